@@ -0,0 +1,62 @@
+"""
+Page sizes and cursors for listings, as in the Rust server
+(routes/util/pagination.rs): every listing has a default and a maximum page
+size, larger requests are clamped, rows sort on a key ending in a unique
+column, and the cursor is the opaque encoding of the last returned row's key.
+
+Clients sending `X-Api-Version: 2` or later get `{items, next_cursor}`;
+older ones keep getting the bare array they were written against.
+"""
+import json
+
+from flask import jsonify, request
+
+
+class InvalidCursor(ValueError):
+    pass
+
+
+def page_limit(default, maximum):
+    """`?limit=` clamped to 1..maximum"""
+    try:
+        limit = int(request.args.get('limit', default))
+    except ValueError:
+        limit = default
+    return min(max(limit, 1), maximum)
+
+
+def encode_cursor(key):
+    """Opaque, URL-safe cursor for a sort key (hex of its JSON)"""
+    return json.dumps(key, separators=(',', ':')).encode().hex()
+
+
+def decode_cursor():
+    """The sort key of `?cursor=`, None without one"""
+    cursor = request.args.get('cursor')
+    if not cursor:
+        return None
+    try:
+        return json.loads(bytes.fromhex(cursor))
+    except ValueError as e:
+        raise InvalidCursor('Invalid cursor') from e
+
+
+def overfetch(rows, limit, key):
+    """(items, next_cursor) from `rows` fetched with `limit + 1`"""
+    items = rows[:limit]
+    next_cursor = encode_cursor(key(items[-1])) if len(rows) > limit and items else None
+    return items, next_cursor
+
+
+def page_response(items, next_cursor):
+    """A listing in the schema the client asked for"""
+    try:
+        version = int(request.headers.get('X-Api-Version', '1'))
+    except ValueError:
+        version = 1
+    if version < 2:
+        return jsonify(items)
+    body = {'items': items}
+    if next_cursor is not None:
+        body['next_cursor'] = next_cursor
+    return jsonify(body)
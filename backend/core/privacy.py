@@ -0,0 +1,68 @@
+"""
+Client IPs under the same policy as the Rust server: TRUST_PROXY /
+TRUSTED_PROXY_HOPS decide which address is the client's, IP_ANONYMIZATION
+(keep | truncate | hash, keyed by IP_HASH_SALT) what of it is logged or
+stored, and PII_RETENTION_DAYS how long stored addresses are kept.
+"""
+import hashlib
+import ipaddress
+import os
+import time
+import uuid
+from datetime import datetime, timedelta
+
+_MODES = {'none': 'keep', 'keep': 'keep', 'truncate': 'truncate', 'hash': 'hash'}
+
+_raw_mode = os.getenv('IP_ANONYMIZATION', 'keep').strip().lower()
+if _raw_mode not in _MODES:
+    # Same as the Rust server: a typo must not silently keep full addresses
+    raise RuntimeError(f"IP_ANONYMIZATION: unknown IP anonymization '{_raw_mode}'")
+IP_ANONYMIZATION = _MODES[_raw_mode]
+# Random per process unless set: hashes then can't be linked across restarts
+IP_HASH_SALT = os.getenv('IP_HASH_SALT') or str(uuid.uuid4())
+TRUST_PROXY = os.getenv('TRUST_PROXY', 'false').strip().lower() == 'true'
+TRUSTED_PROXY_HOPS = max(int(os.getenv('TRUSTED_PROXY_HOPS', '1')), 1)
+PII_RETENTION_DAYS = int(os.getenv('PII_RETENTION_DAYS', '0'))
+
+_RETENTION_INTERVAL = 24 * 60 * 60
+_last_purge = 0.0
+
+
+def client_ip(request):
+    """The peer address, or with TRUST_PROXY the X-Forwarded-For entry added by
+    the outermost trusted proxy (entries further left are client-supplied)."""
+    if TRUST_PROXY:
+        hops = [h.strip() for h in request.headers.get('X-Forwarded-For', '').split(',') if h.strip()]
+        if len(hops) >= TRUSTED_PROXY_HOPS:
+            return hops[-TRUSTED_PROXY_HOPS]
+    return request.remote_addr or 'unknown'
+
+
+def anonymize_ip(ip):
+    """`ip` as it may be logged or stored under IP_ANONYMIZATION."""
+    if IP_ANONYMIZATION == 'keep':
+        return ip
+    if IP_ANONYMIZATION == 'hash':
+        return hashlib.sha256(f"{IP_HASH_SALT}|{ip}".encode()).hexdigest()[:16]
+    try:
+        addr = ipaddress.ip_address(ip)
+    except ValueError:
+        return 'unknown'
+    prefix = 24 if addr.version == 4 else 48
+    return str(ipaddress.ip_network(f"{addr}/{prefix}", strict=False).network_address)
+
+
+def purge_expired(db, models):
+    """At most once a day, blanks the stored addresses and user agents of
+    `models` (User, PublicSearch) not seen for PII_RETENTION_DAYS (0 = kept)."""
+    global _last_purge
+    if PII_RETENTION_DAYS <= 0 or time.time() - _last_purge < _RETENTION_INTERVAL:
+        return
+    _last_purge = time.time()
+    cutoff = datetime.utcnow() - timedelta(days=PII_RETENTION_DAYS)
+    user, public_search = models
+    user.query.filter(user.last_seen < cutoff).update(
+        {'ip_address': '', 'user_agent': None}, synchronize_session=False)
+    public_search.query.filter(public_search.last_searched_at < cutoff).update(
+        {'ip_addresses': [], 'user_agents': [], 'last_ip': None}, synchronize_session=False)
+    db.session.commit()
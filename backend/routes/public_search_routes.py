@@ -1,6 +1,9 @@
 from flask import Blueprint, jsonify, request
 from datetime import datetime
 from models import db, PublicSearch
+from core.pagination import InvalidCursor, decode_cursor, overfetch, page_limit, page_response
+from core.privacy import anonymize_ip, client_ip
+from sqlalchemy import and_, func, or_
 from sqlalchemy.exc import IntegrityError
 
 public_search_bp = Blueprint('public_search', __name__)
@@ -17,9 +20,7 @@ def save_public_search():
     if not query:
         return jsonify({'error': 'Query required'}), 400
     
-    ip_address = request.headers.get('X-Forwarded-For', request.remote_addr)
-    if ip_address and ',' in ip_address:
-        ip_address = ip_address.split(',')[0].strip()
+    ip_address = anonymize_ip(client_ip(request))
     
     user_agent = request.headers.get('User-Agent', 'Unknown')
     
@@ -69,41 +70,82 @@ def save_public_search():
 
 @public_search_bp.route('/searches/public', methods=['GET'])
 def get_public_searches():
+    """Search history, most recent (or with `?sort=popular` most searched) first"""
     sort_by = request.args.get('sort', 'recent')
-    limit = int(request.args.get('limit', 20))
-    
-    if sort_by == 'popular':
-        searches = PublicSearch.query.order_by(PublicSearch.search_count.desc()).limit(limit).all()
-    else:
-        searches = PublicSearch.query.order_by(PublicSearch.last_searched_at.desc()).limit(limit).all()
-    
-    return jsonify([s.to_dict() for s in searches])
+    limit = page_limit(20, 200)
+    column = PublicSearch.search_count if sort_by == 'popular' else PublicSearch.last_searched_at
+    try:
+        after = decode_cursor()
+    except InvalidCursor as e:
+        return jsonify({'error': str(e)}), 400
+
+    query = PublicSearch.query
+    if after is not None:
+        value, last_id = after
+        if sort_by != 'popular':
+            value = datetime.fromisoformat(value)
+        query = query.filter(or_(column < value, and_(column == value, PublicSearch.id < last_id)))
+    rows = query.order_by(column.desc(), PublicSearch.id.desc()).limit(limit + 1).all()
+
+    def key(s):
+        value = s.search_count if sort_by == 'popular' else s.last_searched_at.isoformat()
+        return [value, str(s.id)]
+
+    items, next_cursor = overfetch(rows, limit, key)
+    return page_response([s.to_dict() for s in items], next_cursor)
 
 
 @public_search_bp.route('/searches/by-ip/<ip>', methods=['GET'])
 def get_searches_by_ip(ip):
-    """Admin endpoint to see all searches from a specific IP"""
-    searches = PublicSearch.query.filter(
-        PublicSearch.ip_addresses.contains([ip])
-    ).order_by(PublicSearch.last_searched_at.desc()).all()
-    
-    return jsonify({
+    """Admin endpoint to see all searches from a specific IP, most recent first"""
+    limit = page_limit(100, 1000)
+    try:
+        after = decode_cursor()
+    except InvalidCursor as e:
+        return jsonify({'error': str(e)}), 400
+
+    from_ip = PublicSearch.query.filter(PublicSearch.ip_addresses.contains([ip]))
+    query = from_ip
+    if after is not None:
+        searched_at, last_id = datetime.fromisoformat(after[0]), after[1]
+        query = query.filter(or_(
+            PublicSearch.last_searched_at < searched_at,
+            and_(PublicSearch.last_searched_at == searched_at, PublicSearch.id < last_id),
+        ))
+    rows = query.order_by(PublicSearch.last_searched_at.desc(), PublicSearch.id.desc()).limit(limit + 1).all()
+    items, next_cursor = overfetch(rows, limit, lambda s: [s.last_searched_at.isoformat(), str(s.id)])
+
+    body = {
         'ip': ip,
-        'total_searches': len(searches),
-        'queries': [s.to_dict() for s in searches]
-    })
+        'total_searches': from_ip.count(),
+        'queries': [s.to_dict() for s in items],
+    }
+    if next_cursor is not None:
+        body['next_cursor'] = next_cursor
+    return jsonify(body)
 
 @public_search_bp.route('/searches/stats', methods=['GET'])
 def get_search_stats():
-    """Admin endpoint for abuse detection"""
-    from sqlalchemy import func
-    
-    # Get IPs with most searches
-    heavy_users = db.session.query(
-        PublicSearch.last_ip,
-        func.sum(PublicSearch.search_count).label('total')
-    ).group_by(PublicSearch.last_ip).order_by(func.sum(PublicSearch.search_count).desc()).limit(20).all()
-    
-    return jsonify({
-        'top_ips': [{'ip': ip, 'search_count': total} for ip, total in heavy_users]
-    })
\ No newline at end of file
+    """Admin endpoint for abuse detection: IPs by searches, most first"""
+    limit = page_limit(20, 500)
+    try:
+        after = decode_cursor()
+    except InvalidCursor as e:
+        return jsonify({'error': str(e)}), 400
+
+    # IPs with most searches; ties by IP so the order is total
+    ip = func.coalesce(PublicSearch.last_ip, '')
+    total = func.sum(PublicSearch.search_count)
+    query = db.session.query(ip.label('ip'), total.label('total')).group_by(ip)
+    if after is not None:
+        last_total, last_ip = after
+        query = query.having(or_(total < last_total, and_(total == last_total, ip > last_ip)))
+    rows = query.order_by(total.desc(), ip.asc()).limit(limit + 1).all()
+    items, next_cursor = overfetch(rows, limit, lambda row: [int(row.total), row.ip])
+
+    body = {
+        'top_ips': [{'ip': row.ip or None, 'search_count': int(row.total)} for row in items]
+    }
+    if next_cursor is not None:
+        body['next_cursor'] = next_cursor
+    return jsonify(body)
@@ -14,20 +14,20 @@ from core.ranking import (
 )
 from core.cross_edges import calculate_global_cross_edges
 from core.console import console
+from core.privacy import anonymize_ip, client_ip, purge_expired
 from models import db, PublicSearch, User
 
 search_bp = Blueprint('search', __name__)
 
 def get_client_info():
-    """Extracts IP and User Agent"""
-    ip = request.headers.get('X-Forwarded-For', request.remote_addr)
-    if ip and ',' in ip:
-        ip = ip.split(',')[0].strip()
+    """Extracts IP (anonymized per IP_ANONYMIZATION) and User Agent"""
+    ip = anonymize_ip(client_ip(request))
     ua = request.headers.get('User-Agent', 'Unknown')
     return ip, ua
 
 def get_or_create_user():
     """Identifies a user by a hash of their IP + UserAgent."""
+    purge_expired(db, (User, PublicSearch))
     ip, ua = get_client_info()
     fingerprint_raw = f"{ip}|{ua}"
     fingerprint = hashlib.sha256(fingerprint_raw.encode()).hexdigest()
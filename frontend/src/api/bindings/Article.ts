@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Article = { article_id: number, title: string, pagerank: number | null, pageviews: number | null, backlinks: number | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Cluster = { label: number, size: number, representative_id: number, representative_title: string, members: Array<number>, };
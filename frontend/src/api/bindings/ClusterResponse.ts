@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Cluster } from "./Cluster";
+
+export type ClusterResponse = { clusters: Array<Cluster>, 
+/**
+ * Node ID -> cluster label
+ */
+assignments: { [key in number]?: number }, 
+/**
+ * IDs whose vectors could not be reconstructed
+ */
+unassigned: Array<number>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryCount } from "./CategoryCount";
+
+export type ClusterSummary = { label: number, size: number, 
+/**
+ * Best-connected member, used as the cluster's name
+ */
+representative_id: number, representative_title: string, top_categories: Array<CategoryCount>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EdgeSource } from "./EdgeSource";
+
+export type Connectivity = { threshold: number, 
+/**
+ * Whether searches return cross edges at all: semantic ones, or wikilinks
+ * from a `links` table in fallback
+ */
+enabled: boolean, source: EdgeSource, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionGraph } from "./SessionGraph";
+
+/**
+ * A new session and the token for changing or deleting it, returned only here
+ */
+export type CreatedSession = { edit_token: string, id: string, name: string, corpus: string, graph: SessionGraph, created_at: number, updated_at: number, };
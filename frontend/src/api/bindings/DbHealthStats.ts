@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DbHealthStats = { healthy: boolean, failed_checks: number, reconnects: number, last_error: string | null, };
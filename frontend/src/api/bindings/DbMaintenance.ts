@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DbMaintenance = { 
+/**
+ * `user`, or the name of the corpus whose metadata DB this is
+ */
+name: string, 
+/**
+ * `ANALYZE` (no statistics yet) or `PRAGMA optimize`
+ */
+analyze: string, 
+/**
+ * `incremental`, `full` (a `VACUUM` switching the DB to incremental
+ * auto-vacuum) or `none`
+ */
+vacuum: string, reclaimed_bytes: number, 
+/**
+ * Free pages left after the run
+ */
+freelist_pages: number, error: string | null, };
@@ -0,0 +1,45 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DebugScores = { 
+/**
+ * Raw FAISS similarity (cosine for the lexical backfill)
+ */
+sem_faiss: number, sem_verify: number, 
+/**
+ * `sem_faiss` after score calibration, as used in the ranking formula
+ */
+sem_norm: number, 
+/**
+ * Affinity to the graph context, when the request had one
+ */
+context: number | null, 
+/**
+ * Edit recency, when the request ranked by it
+ */
+recency: number | null, 
+/**
+ * Score factor of a result about an `exclude` term
+ */
+exclusion: number | null, 
+/**
+ * Obscurity penalty of a result with next to no pageviews and pagerank
+ */
+obscurity: number | null, 
+/**
+ * Filter policy factor of a demoted meta page
+ */
+demotion: number | null, 
+/**
+ * Raw `pagerank`, `pageviews` and `backlinks` columns (0 when missing),
+ * each followed by its normalized value
+ */
+pagerank: number, pagerank_norm: number, pageviews: number, pageviews_norm: number, backlinks: number, backlinks_norm: number, title_match: number, 
+/**
+ * Normalized registry signals by name
+ */
+custom: { [key in string]?: number }, 
+/**
+ * Weighted geometric mean of the built-in signals, before penalties and
+ * custom signals, whatever the ranking
+ */
+geometric_mean: number, final_score: number, };
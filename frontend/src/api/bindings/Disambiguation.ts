@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Sense } from "./Sense";
+
+export type Disambiguation = { 
+/**
+ * The disambiguation page the query names, if any
+ */
+page: string | null, senses: Array<Sense>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EdgeOrigin } from "./EdgeOrigin";
+
+export type EdgeResult = { source: string, target: string, 
+/**
+ * Public IDs of the endpoints, unique where titles may not be (within a corpus)
+ */
+source_id: number, target_id: number, score: number, source_corpus: string | null, target_corpus: string | null, origin: EdgeOrigin, 
+/**
+ * Embedding model that produced the score
+ */
+model_version: string, 
+/**
+ * Whether a wikilink exists between the two articles (None when the DB has no `links` table)
+ */
+wikilink: boolean | null, };
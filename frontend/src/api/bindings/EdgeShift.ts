@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type EdgeShift = { source: number, target: number, 
+/**
+ * None when the pair scored below `SNAPSHOT_MIN_SCORE` (or wasn't scored) in that snapshot
+ */
+before: number | null, after: number | null, delta: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a response's cross-edges come from, so clients know whether
+ * semantic edges are available
+ */
+export type EdgeSource = "semantic" | "fallback";
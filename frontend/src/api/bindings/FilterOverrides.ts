@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ListPageMode } from "./ListPageMode";
+
+/**
+ * Per-request changes to the configured policy (`SearchRequest.filters`).
+ * Unset fields keep the configured value.
+ */
+export type FilterOverrides = { deny_prefixes: Array<string> | null, deny_patterns: Array<string> | null, allow_patterns: Array<string> | null, exclude_disambiguation: boolean | null, list_pages: ListPageMode | null, list_demotion: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GraphEdge = { source: number, target: number, score: number, 
+/**
+ * "expansion" (target found by searching the source) or "semantic"
+ */
+kind: string, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComponentStats } from "./ComponentStats";
+
+export type GraphMetrics = { node_count: number, edge_count: number, 
+/**
+ * Edges present over edges possible
+ */
+density: number, 
+/**
+ * Mean local clustering coefficient; nodes below degree 2 count as 0
+ */
+average_clustering: number, components: ComponentStats, 
+/**
+ * Longest shortest path (hops) in the largest component, None for an empty graph
+ */
+diameter: number | null, 
+/**
+ * False when `diameter` is a lower bound (largest component above 2000 nodes)
+ */
+diameter_exact: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GraphNode = { id: number, title: string, 
+/**
+ * 0 for seed articles, n for articles found by the n-th expansion
+ */
+depth: number, score: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryCount } from "./CategoryCount";
+import type { ClusterSummary } from "./ClusterSummary";
+import type { NodeSummary } from "./NodeSummary";
+
+export type GraphSummary = { graph_id: string, name: string, node_count: number, edge_count: number, 
+/**
+ * "embedding" (k-means over vectors) or "components" (index can't reconstruct)
+ */
+clustering: string, clusters: Array<ClusterSummary>, central_nodes: Array<NodeSummary>, bridge_nodes: Array<NodeSummary>, top_categories: Array<CategoryCount>, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Connectivity } from "./Connectivity";
+import type { DbHealthStats } from "./DbHealthStats";
+import type { EmbeddingCacheStats } from "./EmbeddingCacheStats";
+import type { MaintenanceStats } from "./MaintenanceStats";
+import type { PageviewRefreshStats } from "./PageviewRefreshStats";
+import type { QueryStoreStats } from "./QueryStoreStats";
+import type { RankingWeights } from "./RankingWeights";
+import type { SearchParams } from "./SearchParams";
+import type { SemanticCacheStats } from "./SemanticCacheStats";
+import type { SignalCoverage } from "./SignalCoverage";
+
+export type HealthResponse = { status: string, index_path: string, metadata_path: string, total_articles: number, index_total_vectors: number, 
+/**
+ * Non-fatal problems, e.g. index size not matching the metadata DB
+ */
+warnings: Array<string>, search_params: SearchParams, ranking_weights: RankingWeights, connectivity: Connectivity, signal_coverage: SignalCoverage, candidate_pool_size: number, default_results: number, database: DbHealthStats, maintenance: MaintenanceStats, pageview_refresh: PageviewRefreshStats | null, 
+/**
+ * Where query embeddings are computed: cpu, cuda, mps or remote
+ */
+embedding_device: string, embedding_cache: EmbeddingCacheStats | null, query_store: QueryStoreStats | null, semantic_cache: SemanticCacheStats | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether results carry metadata. `Partial` means the metadata DB missed its
+ * budget: results are ordered by FAISS score only and titles must be fetched
+ * from the metadata API.
+ */
+export type Hydration = "full" | "partial";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InterwikiLink } from "./InterwikiLink";
+
+export type InterwikiResponse = { id: number, title: string, lang: string | null, 
+/**
+ * None when the metadata DB has no QID for the article
+ */
+wikidata_qid: string | null, 
+/**
+ * The same Wikidata item in every other language corpus that has it
+ */
+links: Array<InterwikiLink>, };
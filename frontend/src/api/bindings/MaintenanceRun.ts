@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DbMaintenance } from "./DbMaintenance";
+
+export type MaintenanceRun = { finished_at: number, duration_ms: number, reclaimed_bytes: number, 
+/**
+ * The user DB, then each corpus' metadata DB
+ */
+databases: Array<DbMaintenance>, };
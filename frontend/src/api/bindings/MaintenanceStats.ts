@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MaintenanceRun } from "./MaintenanceRun";
+
+export type MaintenanceStats = { enabled: boolean, runs: number, reclaimed_bytes: number, last_run: MaintenanceRun | null, };
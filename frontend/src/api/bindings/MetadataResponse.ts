@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Article } from "./Article";
+
+export type MetadataResponse = { 
+/**
+ * In request order, each ID once
+ */
+articles: Array<Article>, 
+/**
+ * Requested IDs with no row in the metadata DB
+ */
+missing: Array<number>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-request changes to the configured obscurity penalty
+ * (`SearchRequest.obscurity`). Unset fields keep the configured value.
+ */
+export type ObscurityOverrides = { pageviews_below: number | null, pagerank_below: number | null, penalty: number | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One page of a listing.
+ */
+export type Page<T> = { items: Array<T>, 
+/**
+ * Pass as `cursor` for the next page; absent on the last one
+ */
+next_cursor: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PageviewRefreshRun = { 
+/**
+ * RFC 3339
+ */
+finished_at: string, duration_ms: number, 
+/**
+ * First and last day (`YYYYMMDD`) of the views of every updated article
+ */
+window_start: string, window_end: string, updated: number, failed: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PageviewRefreshRun } from "./PageviewRefreshRun";
+
+export type PageviewRefreshStats = { every_secs: number, last_refresh: PageviewRefreshRun | null, };
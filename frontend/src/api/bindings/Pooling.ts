@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the parts of a multi-vector query are combined (`pooling`, default mean).
+ */
+export type Pooling = "mean" | "max";
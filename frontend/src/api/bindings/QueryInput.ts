@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `query` of a search request: one text or several terms.
+ */
+export type QueryInput = string | Array<string>;
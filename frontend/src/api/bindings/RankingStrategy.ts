@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How hydrated candidates are turned into a final score, per request
+ * (`ranking`, default RANKING).
+ */
+export type RankingStrategy = "geometric" | "sum" | "rrf" | "linear";
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RankingWeights = { semantic: number, pagerank: number, pageviews: number, backlinks: number, title_match: number, recency: number, };
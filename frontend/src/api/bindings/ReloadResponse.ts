@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ReloadResponse = { status: string, index_path: string, metadata_path: string, total_vectors: number, can_reconstruct: boolean, elapsed_ms: number, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ResearchGraphRequest = { seeds: Array<string>, depth: number, k_per_seed: number, threshold: number | null, max_nodes: number | null, corpus: string | null, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphEdge } from "./GraphEdge";
+import type { GraphNode } from "./GraphNode";
+
+export type ResearchGraphResponse = { nodes: Array<GraphNode>, edges: Array<GraphEdge>, 
+/**
+ * Seeds that matched no article
+ */
+unresolved_seeds: Array<string>, 
+/**
+ * Seed and expansion searches that failed; the graph is built without them
+ */
+failed_searches: number, truncated: boolean, elapsed_ms: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResolvedTitle } from "./ResolvedTitle";
+
+export type ResolveResponse = { 
+/**
+ * In request order, each title once
+ */
+resolved: Array<ResolvedTitle>, 
+/**
+ * Requested titles with no article
+ */
+missing: Array<string>, };
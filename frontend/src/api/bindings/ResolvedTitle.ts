@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ResolvedTitle = { 
+/**
+ * As requested
+ */
+query: string, id: number, 
+/**
+ * As stored, e.g. `Alan_Turing` for `alan turing`
+ */
+title: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How candidates are retrieved: FAISS only, FTS5 (BM25 over titles) only, or both blended.
+ */
+export type SearchMode = "semantic" | "lexical" | "hybrid";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * FAISS runtime search parameters. Also used as the per-request override.
+ */
+export type SearchParams = { nprobe: number | null, ef_search: number | null, };
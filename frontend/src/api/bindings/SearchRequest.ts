@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FilterOverrides } from "./FilterOverrides";
+import type { ObscurityOverrides } from "./ObscurityOverrides";
+import type { Pooling } from "./Pooling";
+import type { QueryInput } from "./QueryInput";
+import type { RankingStrategy } from "./RankingStrategy";
+import type { SearchMode } from "./SearchMode";
+import type { SearchParams } from "./SearchParams";
+
+export type SearchRequest = { query: QueryInput, anchor_ids: Array<number>, pooling: Pooling | null, exclude: QueryInput | null, context: Array<number>, context_token: string | null, k: number | null, debug: boolean, rescore: number | null, search_params: SearchParams | null, corpus: string | null, offset: number | null, page_token: string | null, include_categories: Array<string>, exclude_categories: Array<string>, filters: FilterOverrides | null, search_mode: SearchMode, lang: string | null, ranking: RankingStrategy | null, diversity: number | null, recency: number | null, obscurity: ObscurityOverrides | null, deep_dive: boolean, features: Array<string>, };
@@ -0,0 +1,57 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Disambiguation } from "./Disambiguation";
+import type { EdgeResult } from "./EdgeResult";
+import type { EdgeSource } from "./EdgeSource";
+import type { Hydration } from "./Hydration";
+import type { SearchResult } from "./SearchResult";
+import type { Timings } from "./Timings";
+
+export type SearchResponse = { results: Array<SearchResult>, cross_edges: Array<EdgeResult>, 
+/**
+ * "fallback" when the index can't reconstruct vectors: no fresh semantic
+ * edges, wikilinks stand in for them
+ */
+edge_source: EdgeSource, 
+/**
+ * Size of the ranked pool; ranking stops at the requested depth, so with
+ * `total_is_lower_bound` more results follow
+ */
+total_results: number, total_is_lower_bound: boolean, 
+/**
+ * Pass back with a higher `offset` to fetch further pages
+ */
+next_page_token: string | null, hydration: Hydration, 
+/**
+ * Cross-edges were cut to the edge budget (CROSS_EDGE_MAX_EDGES / CROSS_EDGE_BUDGET_MS)
+ */
+truncated: boolean, 
+/**
+ * Experimental (`clusters` feature): result ID -> k-means label of its
+ * embedding among this page's results
+ */
+clusters: { [key in number]?: number } | null, 
+/**
+ * Experimental (`centrality` feature): result ID -> sum of the scores of
+ * its cross-edges on this page
+ */
+centrality: { [key in number]?: number } | null, 
+/**
+ * Senses of an ambiguous query ("Mercury"), on the first page only
+ */
+disambiguation: Disambiguation | null, 
+/**
+ * The query with typos corrected, when its best result is a weak match
+ * (DID_YOU_MEAN_THRESHOLD); on the first page only
+ */
+did_you_mean: string | null, 
+/**
+ * Stands for the whole graph context of the request (token and
+ * `context` merged): send it as `context_token` with only the IDs added
+ * since, instead of resending them all
+ */
+context_token: string | null, 
+/**
+ * Time spent per stage, for `debug` requests (zero for stages a cache
+ * or page token skipped)
+ */
+timings: Timings | null, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DebugScores } from "./DebugScores";
+
+/**
+ * One ranked article.
+ */
+export type SearchResult = { id: number, 
+/**
+ * Shared, so cached pools and pages don't copy every title; empty when hydration is partial
+ */
+title: string, score: number, 
+/**
+ * `relevance` on the wire since API v2; the alias reads older cached responses
+ */
+relevance: number, corpus: string | null, 
+/**
+ * `[start, end)` UTF-16 offsets of query-term matches in `title`
+ */
+highlights: Array<[number, number]>, 
+/**
+ * Attached to the returned page only (see `article_images`)
+ */
+thumbnail_url: string | null, debug: DebugScores | null, 
+/**
+ * Added by the keyword backfill rather than found by FAISS
+ */
+backfilled: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SemanticCacheStats = { entries: number, capacity: number, max_distance: number, hits: number, misses: number, 
+/**
+ * Hits where the embedding matched exactly (same query text)
+ */
+exact_hits: number, avg_hit_distance: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Sense = { id: number, title: string, 
+/**
+ * A few of its categories, to tell the senses apart
+ */
+categories: Array<string>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionGraph } from "./SessionGraph";
+
+/**
+ * A saved exploration graph, shared by its UUID.
+ */
+export type Session = { id: string, name: string, corpus: string, graph: SessionGraph, created_at: number, updated_at: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SessionEdge = { source: number, target: number, score: number | null, 
+/**
+ * "expansion" or "semantic", as in research graphs
+ */
+kind: string | null, };
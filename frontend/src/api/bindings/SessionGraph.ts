@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { SessionEdge } from "./SessionEdge";
+import type { SessionNode } from "./SessionNode";
+
+export type SessionGraph = { nodes: Array<SessionNode>, edges: Array<SessionEdge>, 
+/**
+ * Free-form layout hints for the frontend (zoom, camera, algorithm, ...)
+ */
+layout: JsonValue, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Thumbnail } from "./Thumbnail";
+
+/**
+ * A session as shown in listings: metadata plus its thumbnail (None until the
+ * background writer has produced one).
+ */
+export type SessionListing = { id: string, name: string, corpus: string, created_at: number, updated_at: number, thumbnail: Thumbnail | null, };
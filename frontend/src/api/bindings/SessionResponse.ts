@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EdgeResult } from "./EdgeResult";
+import type { SessionGraph } from "./SessionGraph";
+
+export type SessionResponse = { cross_edges: Array<EdgeResult> | null, id: string, name: string, corpus: string, graph: SessionGraph, created_at: number, updated_at: number, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BurnAlert } from "./BurnAlert";
+import type { WindowBurn } from "./WindowBurn";
+
+export type SloStatus = { route: string, latency_ms: number | null, target_percent: number, windows: Array<WindowBurn>, alert: BurnAlert | null, };
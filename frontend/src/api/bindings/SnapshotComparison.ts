@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EdgeShift } from "./EdgeShift";
+
+export type SnapshotComparison = { from: number, to: number, 
+/**
+ * Node ID -> title for every node in `shifts`
+ */
+titles: { [key in number]?: string }, shifts: Array<EdgeShift>, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SnapshotInfo = { id: number, corpus_version: string, model_version: string, edge_count: number, created_at: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Suggestion } from "./Suggestion";
+
+export type SuggestResponse = { query: string, suggestions: Array<Suggestion>, 
+/**
+ * Pass as `cursor` for more suggestions
+ */
+next_cursor: string | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskStatus } from "./TaskStatus";
+
+export type TaskInfo = { id: string, kind: string, status: TaskStatus, progress: number, progress_message: string, error: string | null, artifact_url: string | null, created_at: number, finished_at: number | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Compact preview of a saved graph for gallery/list views, stored alongside
+ * the session so listings don't load full graphs.
+ */
+export type Thumbnail = { 
+/**
+ * Most central titles, centrality weighted by pageviews
+ */
+top_nodes: Array<string>, node_count: number, edge_count: number, 
+/**
+ * Representative titles of the largest connected clusters
+ */
+clusters: Array<string>, };
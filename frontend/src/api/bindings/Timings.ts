@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `timings` of a debug search response.
+ */
+export type Timings = { encode_ms: number, faiss_ms: number, sqlite_ms: number, rank_ms: number, cross_edges_ms: number, 
+/**
+ * The whole request; stages of federated corpora overlap, so theirs can add up to more
+ */
+total_ms: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A saved query whose top-k is re-checked after every index refresh.
+ */
+export type Watch = { id: string, user_id: string, query: string, k: number, created_at: number, last_run_at: number | null, };
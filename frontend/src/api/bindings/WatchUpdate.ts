@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An article that entered a watch's top-k.
+ */
+export type WatchUpdate = { watch_id: string, query: string, article_id: number, title: string, rank: number, detected_at: number, };
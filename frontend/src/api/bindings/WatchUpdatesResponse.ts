@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WatchUpdate } from "./WatchUpdate";
+
+export type WatchUpdatesResponse = { user_id: string, updates: Array<WatchUpdate>, 
+/**
+ * Pass as `cursor` for older updates
+ */
+next_cursor: string | null, };
@@ -0,0 +1,265 @@
+/**
+ * Typed client for the Rust API (rs/). Request and response shapes come from
+ * ./bindings, which `npm run gen:api` generates from the server's own structs,
+ * so only routes and query strings are written by hand here.
+ *
+ * The API has no streaming (SSE/WebSocket) endpoints: long-running work such
+ * as research graphs runs as a task, polled with `waitForTask`.
+ *
+ * Optional response fields are typed `T | null` but are left out of the JSON
+ * when empty, so test them with `== null` rather than `=== null`.
+ */
+import type { Article } from './bindings/Article';
+import type { CancelResponse } from './bindings/CancelResponse';
+import type { ClusterRequest } from './bindings/ClusterRequest';
+import type { ClusterResponse } from './bindings/ClusterResponse';
+import type { CreateSessionRequest } from './bindings/CreateSessionRequest';
+import type { CreatedSession } from './bindings/CreatedSession';
+import type { CreateWatchRequest } from './bindings/CreateWatchRequest';
+import type { ExportRequest } from './bindings/ExportRequest';
+import type { GraphMetrics } from './bindings/GraphMetrics';
+import type { GraphSummary } from './bindings/GraphSummary';
+import type { HealthResponse } from './bindings/HealthResponse';
+import type { InterwikiResponse } from './bindings/InterwikiResponse';
+import type { JobSubmitted } from './bindings/JobSubmitted';
+import type { MetadataRequest } from './bindings/MetadataRequest';
+import type { MetadataResponse } from './bindings/MetadataResponse';
+import type { Page } from './bindings/Page';
+import type { PurgeResponse } from './bindings/PurgeResponse';
+import type { ReloadRequest } from './bindings/ReloadRequest';
+import type { ReloadResponse } from './bindings/ReloadResponse';
+import type { ResearchGraphRequest } from './bindings/ResearchGraphRequest';
+import type { ResearchGraphResponse } from './bindings/ResearchGraphResponse';
+import type { ResolveRequest } from './bindings/ResolveRequest';
+import type { ResolveResponse } from './bindings/ResolveResponse';
+import type { SearchRequest } from './bindings/SearchRequest';
+import type { SearchResponse } from './bindings/SearchResponse';
+import type { Session } from './bindings/Session';
+import type { SessionGraph } from './bindings/SessionGraph';
+import type { SessionListing } from './bindings/SessionListing';
+import type { SessionResponse } from './bindings/SessionResponse';
+import type { SloResponse } from './bindings/SloResponse';
+import type { SnapshotComparison } from './bindings/SnapshotComparison';
+import type { SnapshotInfo } from './bindings/SnapshotInfo';
+import type { SuggestResponse } from './bindings/SuggestResponse';
+import type { TaskInfo } from './bindings/TaskInfo';
+import type { UpdateSessionRequest } from './bindings/UpdateSessionRequest';
+import type { Watch } from './bindings/Watch';
+import type { WatchUpdatesResponse } from './bindings/WatchUpdatesResponse';
+
+export type { Article, SearchResponse, Session, SessionGraph, TaskInfo };
+
+/** Response schema this client is written against (`X-Api-Version`) */
+export const API_VERSION = 2;
+
+/**
+ * A request body: the server fills in every field it marks `#[serde(default)]`,
+ * so only `Required` has to be given.
+ */
+export type Body<T, Required extends keyof T = never> = Pick<T, Required> & Partial<Omit<T, Required>>;
+
+/** Cursor pagination (`limit` up to the endpoint's maximum, `next_cursor` of the previous page) */
+export type PageQuery = {
+  limit?: number;
+  cursor?: string;
+};
+
+/** RFC 7807 problem details, the body of every error response */
+export interface Problem {
+  type: string;
+  title: string;
+  status: number;
+  detail: string;
+  retryable: boolean;
+  /** Machine-readable part: `kind`, plus `message` (4xx) or `retry_after_secs` */
+  details?: { kind: string; message?: string; retry_after_secs?: number };
+  instance?: string;
+  request_id?: string;
+  retry_after_secs?: number;
+}
+
+export class ApiError extends Error {
+  constructor(public problem: Problem) {
+    super(problem.detail || problem.title);
+    this.name = 'ApiError';
+  }
+
+  get status(): number {
+    return this.problem.status;
+  }
+}
+
+type Query = Record<string, string | number | boolean | undefined | null>;
+
+export interface ClientOptions {
+  /** Origin of the API; empty for same-origin (dev proxy, desktop build) */
+  baseUrl?: string;
+  /** ADMIN_TOKEN, for the `admin` endpoints */
+  adminToken?: string;
+  /** Experimental response fields to opt into (`X-Features`), e.g. `clusters`, `centrality` */
+  features?: string[];
+  fetch?: typeof fetch;
+}
+
+export function createClient(options: ClientOptions = {}) {
+  const baseUrl = (options.baseUrl ?? '').replace(/\/$/, '');
+  const fetchImpl = options.fetch ?? fetch.bind(globalThis);
+
+  function url(path: string, query?: Query): string {
+    const params = new URLSearchParams();
+    for (const [key, value] of Object.entries(query ?? {})) {
+      if (value !== undefined && value !== null) params.set(key, String(value));
+    }
+    const search = params.toString();
+    return `${baseUrl}${path}${search ? `?${search}` : ''}`;
+  }
+
+  async function send(method: string, path: string, init: { query?: Query; body?: unknown; admin?: boolean; editToken?: string } = {}) {
+    const headers: Record<string, string> = {
+      Accept: 'application/json',
+      'X-Api-Version': String(API_VERSION),
+    };
+    if (init.body !== undefined) headers['Content-Type'] = 'application/json';
+    if (options.features?.length) headers['X-Features'] = options.features.join(', ');
+    if (init.admin && options.adminToken) headers.Authorization = `Bearer ${options.adminToken}`;
+    if (init.editToken) headers['X-Edit-Token'] = init.editToken;
+
+    const response = await fetchImpl(url(path, init.query), {
+      method,
+      headers,
+      body: init.body === undefined ? undefined : JSON.stringify(init.body),
+    });
+    if (!response.ok) {
+      const problem = await response.json().catch(() => null);
+      throw new ApiError(
+        problem && typeof problem === 'object'
+          ? (problem as Problem)
+          : {
+              type: 'about:blank',
+              title: response.statusText,
+              status: response.status,
+              detail: response.statusText,
+              retryable: response.status >= 500,
+            }
+      );
+    }
+    return response;
+  }
+
+  async function json<T>(method: string, path: string, init?: { query?: Query; body?: unknown; admin?: boolean; editToken?: string }) {
+    return (await (await send(method, path, init)).json()) as T;
+  }
+
+  const segment = encodeURIComponent;
+
+  const tasks = {
+    /** Needs `adminToken` */
+    list: () => json<TaskInfo[]>('GET', '/api/tasks', { admin: true }),
+    get: (id: string) => json<TaskInfo>('GET', `/api/tasks/${segment(id)}`),
+    cancel: (id: string) => json<CancelResponse>('DELETE', `/api/tasks/${segment(id)}`),
+    artifact: async (id: string) => (await send('GET', `/api/tasks/${segment(id)}/artifact`)).blob(),
+  };
+
+  return {
+    health: () => json<HealthResponse>('GET', '/api/health'),
+
+    /**
+     * Semantic search: articles related to `query`, with cross-edges to `context`.
+     * Pass the response's `context_token` back along with only the nodes added since.
+     */
+    related: (body: Body<SearchRequest, 'query'>) =>
+      json<SearchResponse & { api_version: number }>('POST', '/api/related', { body }),
+
+    metadata: (body: Body<MetadataRequest, 'ids'>) => json<MetadataResponse>('POST', '/api/metadata', { body }),
+
+    suggest: (q: string, query: PageQuery & { corpus?: string } = {}) =>
+      json<SuggestResponse>('GET', '/api/suggest', { query: { q, ...query } }),
+
+    resolve: (body: Body<ResolveRequest, 'titles'>) => json<ResolveResponse>('POST', '/api/resolve', { body }),
+
+    clusters: (body: Body<ClusterRequest, 'context'>) => json<ClusterResponse>('POST', '/api/clusters', { body }),
+
+    /** GraphML, GEXF or DOT, as a file */
+    exportGraph: async (body: Body<ExportRequest>) => (await send('POST', '/api/export', { body })).blob(),
+
+    researchGraph: (body: Body<ResearchGraphRequest, 'seeds'>) =>
+      json<ResearchGraphResponse>('POST', '/api/research/graph', { body }),
+
+    /** Starts a research graph as a task; see `waitForTask` */
+    researchGraphJob: (body: Body<ResearchGraphRequest, 'seeds'>) =>
+      json<JobSubmitted>('POST', '/api/research/graph/jobs', { body }),
+
+    graphMetrics: (graph: Body<SessionGraph, 'nodes'>) => json<GraphMetrics>('POST', '/api/graph/metrics', { body: graph }),
+
+    graphSummary: (sessionId: string) => json<GraphSummary>('POST', `/api/graphs/${segment(sessionId)}/summary`),
+
+    /** Wikipedia's page summary, passed through unchanged */
+    articleSummary: (title: string) =>
+      json<Record<string, unknown>>('GET', `/api/summary/${segment(title.replace(/ /g, '_'))}`),
+
+    interwiki: (id: number, query: { corpus?: string; lang?: string } = {}) =>
+      json<InterwikiResponse>('GET', `/api/interwiki/${id}`, { query }),
+
+    sessions: {
+      /** Needs `adminToken` */
+      list: (query: PageQuery = {}) =>
+        json<Page<SessionListing>>('GET', '/api/session', { query: { ...query }, admin: true }),
+      /** Keep `edit_token`: `update` and `delete` need it */
+      create: (body: Body<CreateSessionRequest, 'name' | 'graph'>) =>
+        json<CreatedSession>('POST', '/api/session', { body }),
+      get: (id: string, query: { cross_edges?: boolean; threshold?: number } = {}) =>
+        json<SessionResponse>('GET', `/api/session/${segment(id)}`, { query }),
+      update: (id: string, editToken: string, body: Body<UpdateSessionRequest>) =>
+        json<Session>('PUT', `/api/session/${segment(id)}`, { body, editToken }),
+      delete: (id: string, editToken: string) =>
+        json<Session>('DELETE', `/api/session/${segment(id)}`, { editToken }),
+      snapshots: (id: string) => json<SnapshotInfo[]>('GET', `/api/session/${segment(id)}/snapshots`),
+      compareSnapshots: (id: string, query: { from?: number; to?: number } = {}) =>
+        json<SnapshotComparison>('GET', `/api/session/${segment(id)}/snapshots/compare`, { query }),
+    },
+
+    watches: {
+      list: (userId: string) => json<Watch[]>('GET', `/api/users/${segment(userId)}/watches`),
+      create: (userId: string, body: Body<CreateWatchRequest, 'query'>) =>
+        json<Watch>('POST', `/api/users/${segment(userId)}/watches`, { body }),
+      delete: (userId: string, watchId: string) =>
+        json<Watch>('DELETE', `/api/users/${segment(userId)}/watches/${segment(watchId)}`),
+      /** `since` in Unix seconds */
+      updates: (userId: string, query: PageQuery & { since?: number } = {}) =>
+        json<WatchUpdatesResponse>('GET', `/api/users/${segment(userId)}/watch-updates`, { query: { ...query } }),
+    },
+
+    tasks,
+
+    /** Needs `adminToken` */
+    admin: {
+      reloadIndex: (body: Body<ReloadRequest> = {}) =>
+        json<ReloadResponse>('POST', '/api/admin/reload-index', { body, admin: true }),
+      purgeCache: () => json<PurgeResponse>('POST', '/api/admin/cache/purge', { admin: true }),
+      slo: () => json<SloResponse>('GET', '/api/admin/slo', { admin: true }),
+    },
+
+    /** Polls a task until it is no longer running; rejects if it failed or was cancelled */
+    waitForTask: async (id: string, { intervalMs = 1000, signal }: { intervalMs?: number; signal?: AbortSignal } = {}) => {
+      for (;;) {
+        const task = await tasks.get(id);
+        if (task.status === 'completed') return task;
+        if (task.status !== 'running') {
+          throw new ApiError({
+            type: 'about:blank',
+            title: `Task ${task.status}`,
+            status: 409,
+            detail: task.error ?? `Task ${id} ${task.status}`,
+            retryable: false,
+          });
+        }
+        if (signal?.aborted) throw signal.reason;
+        await new Promise((resolve) => setTimeout(resolve, intervalMs));
+      }
+    },
+  };
+}
+
+export type ApiClient = ReturnType<typeof createClient>;
+
+export const api = createClient({ baseUrl: import.meta.env.VITE_API_URL || '' });
@@ -1,6 +1,6 @@
 import type { WikiArticle, WikiLink, GraphEdge } from '../types';
 
-const API_BASE_URL = import.meta.env.VITE_API_URL || 'http://localhost:5001';
+const API_BASE_URL = import.meta.env.VITE_API_URL || '';
 const BACKEND_API_BASE = `${API_BASE_URL}/api`;
 const WIKI_API_BASE = 'https://en.wikipedia.org/api/rest_v1/page/summary';
 
@@ -105,6 +105,7 @@ export async function fetchArticleLinks(
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
+        'X-Api-Version': '2',
       },
       body: JSON.stringify({
         query: query,
@@ -0,0 +1,418 @@
+use serde::Serialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wikiexplorer_core::config::get_config;
+use wikiexplorer_core::index::manifest::IndexManifest;
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::search::engine::IndexHandle;
+use wikiexplorer_core::search::pipeline::{rank_candidates, POOL_DEPTH};
+use wikiexplorer_core::search::query_store::QueryStore;
+use wikiexplorer_core::signals::SignalRegistry;
+use wikiexplorer_core::utils::sql::placeholders;
+use wikiexplorer_core::{RankOptions, RankedGraph, SearchOptions, WikiExplorer};
+
+use crate::{alloc_stats, BenchmarkArgs, NeighborsArgs, ReconstructArgs, SearchArgs};
+
+/// Components printed by `reconstruct` without `--full`
+const PREVIEW_COMPONENTS: usize = 8;
+
+pub async fn search(args: SearchArgs, json: bool) -> anyhow::Result<()> {
+    let explorer = WikiExplorer::open().await?;
+    let options = SearchOptions {
+        k: args.k,
+        rank: RankOptions {
+            search_mode: args.mode.into(),
+            debug: args.debug,
+            deep_dive: args.deep_dive,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let outcome = explorer.search(&args.query, &options).await?;
+    let elapsed = started.elapsed();
+
+    if json {
+        return print_json(&json!({
+            "results": outcome.results,
+            "cross_edges": outcome.cross_edges,
+            "total_results": outcome.total_results,
+            "hydration": outcome.hydration,
+            "elapsed_ms": elapsed.as_millis(),
+        }));
+    }
+
+    print_graph(&outcome, elapsed);
+    Ok(())
+}
+
+/// Result table and cross edges of one search.
+pub(crate) fn print_graph(graph: &RankedGraph, elapsed: Duration) {
+    println!("{} results ({} ranked) in {:?}", graph.results.len(), graph.total_results, elapsed);
+    for (rank, result) in graph.results.iter().enumerate() {
+        let faiss = result
+            .debug
+            .as_ref()
+            .map(|d| format!("  faiss={:.4}", d.sem_faiss))
+            .unwrap_or_default();
+        println!("{:>3}. {:>8.4}  [{:>8}] {}{}", rank + 1, result.score_float, result.id, result.title, faiss);
+    }
+    if !graph.cross_edges.is_empty() {
+        println!("\n{} cross edges:", graph.cross_edges.len());
+        for edge in &graph.cross_edges {
+            println!("  {:.3}  {} — {}", edge.score, edge.source, edge.target);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Neighbor {
+    id: i64,
+    title: Option<String>,
+    similarity: f32,
+}
+
+pub async fn neighbors(args: NeighborsArgs, json: bool) -> anyhow::Result<()> {
+    let (index, db) = open_index().await?;
+    let article_id = index
+        .ids
+        .article(args.id)
+        .ok_or_else(|| anyhow::anyhow!("Article {} is not in the index", args.id))?;
+    let vector = index.reconstruct(article_id)?;
+
+    // One extra: the article itself comes back first
+    let (dists, ids) = index.search(&vector, args.k + 1, None)?;
+    let found: Vec<(i64, f32)> = ids
+        .into_iter()
+        .zip(dists)
+        .filter(|&(id, _)| id >= 0 && id != article_id)
+        .take(args.k)
+        .collect();
+
+    let ids: Vec<i64> = found.iter().map(|(id, _)| *id).chain([article_id]).collect();
+    let mut titles = fetch_titles(&db, &ids).await?;
+    let neighbors: Vec<Neighbor> = found
+        .into_iter()
+        .map(|(id, similarity)| Neighbor { id: index.ids.public(id), title: titles.remove(&id), similarity })
+        .collect();
+
+    if json {
+        return print_json(&neighbors);
+    }
+
+    let title = titles.remove(&article_id).unwrap_or_else(|| "<no metadata>".to_string());
+    println!("Nearest to [{}] {}:", args.id, title);
+    for (rank, n) in neighbors.iter().enumerate() {
+        println!(
+            "{:>3}. {:>8.4}  [{:>8}] {}",
+            rank + 1,
+            n.similarity,
+            n.id,
+            n.title.as_deref().unwrap_or("<no metadata>")
+        );
+    }
+    Ok(())
+}
+
+pub async fn reconstruct(args: ReconstructArgs, json: bool) -> anyhow::Result<()> {
+    let (index, _) = open_index().await?;
+    if !index.can_reconstruct {
+        anyhow::bail!("Index at {} does not support reconstruction", index.path);
+    }
+    let vector = index.reconstruct(args.id)?;
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if json {
+        return print_json(&json!({ "id": args.id, "dimension": vector.len(), "norm": norm, "vector": vector }));
+    }
+
+    println!("id={} dim={} norm={:.6}", args.id, vector.len(), norm);
+    let shown = if args.full { vector.len() } else { PREVIEW_COMPONENTS.min(vector.len()) };
+    let components: Vec<String> = vector[..shown].iter().map(|x| format!("{:.6}", x)).collect();
+    let more = if shown < vector.len() { ", ..." } else { "" };
+    println!("[{}{}]", components.join(", "), more);
+    Ok(())
+}
+
+pub async fn stats(json: bool) -> anyhow::Result<()> {
+    let (index, db) = open_index().await?;
+    let (vectors, dimension) = {
+        let replica = index.pool.acquire();
+        (replica.ntotal(), replica.dim())
+    };
+    let manifest = IndexManifest::load(&index.path).ok();
+
+    let articles: Option<i64> = sqlx::query_scalar("SELECT COUNT(*) FROM articles")
+        .fetch_one(&db)
+        .await
+        .ok();
+    let max_article_id: Option<i64> = sqlx::query_scalar("SELECT MAX(article_id) FROM articles")
+        .fetch_one(&db)
+        .await
+        .ok()
+        .flatten();
+
+    // Tombstoned positions (see `wikiexplorer index update`) hold no article
+    let tombstoned = index.labels.tombstone_count() as u64;
+    let live = vectors.saturating_sub(tombstoned);
+
+    let mut warnings = Vec::new();
+    if let Some(articles) = articles {
+        if articles as u64 != live {
+            warnings.push(format!("index has {} live vectors but the DB has {} articles", live, articles));
+        }
+    }
+    if let Some(max_id) = max_article_id {
+        if index.labels.position_of(max_id).is_none_or(|p| p >= vectors as i64) {
+            warnings.push(format!("article_id {} has no vector (index positions end at {})", max_id, vectors));
+        }
+    }
+    if !index.can_reconstruct {
+        warnings.push("index does not support reconstruction, cross edges are disabled".to_string());
+    }
+
+    if json {
+        return print_json(&json!({
+            "index_path": index.path,
+            "vectors": vectors,
+            "tombstoned": tombstoned,
+            "dimension": dimension,
+            "can_reconstruct": index.can_reconstruct,
+            "search_params": index.search_params,
+            "manifest": manifest,
+            "metadata_path": get_config().metadata_path,
+            "articles": articles,
+            "warnings": warnings,
+        }));
+    }
+
+    println!("Index:      {} ({} vectors, {} tombstoned, dim={})", index.path, vectors, tombstoned, dimension);
+    match &manifest {
+        Some(m) => println!(
+            "Manifest:   factory={} metric={} model={} nprobe={}",
+            m.factory,
+            m.metric,
+            m.model,
+            m.nprobe.map_or("-".to_string(), |n| n.to_string())
+        ),
+        None => println!("Manifest:   none"),
+    }
+    println!("Reconstruct: {}", if index.can_reconstruct { "yes" } else { "no" });
+    println!(
+        "Metadata:   {} ({} articles)",
+        get_config().metadata_path,
+        articles.map_or("?".to_string(), |n| n.to_string())
+    );
+    for warning in &warnings {
+        println!("⚠ {}", warning);
+    }
+    Ok(())
+}
+
+pub async fn benchmark(args: BenchmarkArgs, json: bool) -> anyhow::Result<()> {
+    if args.replay {
+        return replay(args.queries, args.concurrency, json).await;
+    }
+    let explorer = Arc::new(WikiExplorer::open().await?);
+    let queries: Vec<String> = match &args.queries_file {
+        Some(path) => std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(args.queries)
+            .map(str::to_string)
+            .collect(),
+        None => sqlx::query_scalar("SELECT title FROM articles ORDER BY RANDOM() LIMIT ?")
+            .bind(args.queries as i64)
+            .fetch_all(&explorer.corpus().db)
+            .await?,
+    };
+    if queries.is_empty() {
+        anyhow::bail!("No benchmark queries");
+    }
+    if args.allocations {
+        return allocations(&explorer, &queries, args.max_allocations, json).await;
+    }
+
+    let start = Instant::now();
+    let mut latencies: Vec<Duration> = Vec::with_capacity(queries.len());
+    let mut failures = 0;
+    for chunk in queries.chunks(args.concurrency.max(1)) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .cloned()
+            .map(|query| {
+                let explorer = Arc::clone(&explorer);
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    explorer.search(&query, &SearchOptions::default()).await.map(|_| started.elapsed())
+                })
+            })
+            .collect();
+        for handle in handles {
+            match handle.await? {
+                Ok(elapsed) => latencies.push(elapsed),
+                Err(_) => failures += 1,
+            }
+        }
+    }
+    report_latencies(latencies, failures, start.elapsed(), json)
+}
+
+/// Ranks up to `limit` stored queries with their stored embeddings, so only
+/// retrieval, hydration and scoring are timed.
+async fn replay(limit: usize, concurrency: usize, json: bool) -> anyhow::Result<()> {
+    let config = get_config();
+    let Some(path) = &config.query_store_path else {
+        anyhow::bail!("--replay needs QUERY_STORE_PATH");
+    };
+    let ttl = Duration::from_secs(config.query_store_ttl_days * 86_400);
+    let store = QueryStore::open(path, ttl, true).await?;
+    let stored = store.recent(None, limit, ttl).await?;
+    store.close().await;
+
+    let (index, db) = open_index().await?;
+    let dim = index.dim as usize;
+    let queries: Vec<(String, Vec<f32>)> = stored
+        .into_iter()
+        .filter(|q| q.embedding.len() == dim)
+        .map(|q| (q.query, q.embedding))
+        .collect();
+    if queries.is_empty() {
+        anyhow::bail!("No stored queries with {}-dimensional embeddings in {}", dim, path);
+    }
+    let corpus = Arc::new(Corpus {
+        name: config.corpus_name.clone(),
+        index: Arc::new(index),
+        signals: Arc::new(SignalRegistry::load(&db).await),
+        db,
+        lang: Some(config.corpus_lang.clone()),
+        model: None,
+    });
+
+    let start = Instant::now();
+    let mut latencies: Vec<Duration> = Vec::with_capacity(queries.len());
+    let mut failures = 0;
+    for chunk in queries.chunks(concurrency.max(1)) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .cloned()
+            .map(|(query, vector)| {
+                let corpus = Arc::clone(&corpus);
+                tokio::spawn(async move {
+                    let started = Instant::now();
+                    // Texts are empty when the store was written with QUERY_STORE_KEEP_TEXT off
+                    rank_candidates(&corpus, &server_options(), &query, &vector)
+                        .await
+                        .map(|_| started.elapsed())
+                })
+            })
+            .collect();
+        for handle in handles {
+            match handle.await? {
+                Ok(elapsed) => latencies.push(elapsed),
+                Err(_) => failures += 1,
+            }
+        }
+    }
+    report_latencies(latencies, failures, start.elapsed(), json)
+}
+
+/// What `/api/search` ranks with for a first page without options
+fn server_options() -> RankOptions {
+    RankOptions { limit: Some(POOL_DEPTH), ..Default::default() }
+}
+
+fn report_latencies(mut latencies: Vec<Duration>, failures: usize, elapsed: Duration, json: bool) -> anyhow::Result<()> {
+    if latencies.is_empty() {
+        anyhow::bail!("All {} queries failed", failures);
+    }
+    latencies.sort();
+    let pct = |p: f64| latencies[((latencies.len() - 1) as f64 * p) as usize];
+    let throughput = latencies.len() as f64 / elapsed.as_secs_f64();
+
+    if json {
+        return print_json(&json!({
+            "queries": latencies.len(),
+            "failures": failures,
+            "elapsed_ms": elapsed.as_millis(),
+            "throughput_qps": throughput,
+            "p50_ms": pct(0.50).as_secs_f64() * 1000.0,
+            "p95_ms": pct(0.95).as_secs_f64() * 1000.0,
+            "p99_ms": pct(0.99).as_secs_f64() * 1000.0,
+        }));
+    }
+
+    println!("Completed {} searches ({} failed) in {:?}", latencies.len(), failures, elapsed);
+    println!("  throughput: {:.1} searches/s", throughput);
+    println!("  p50: {:?}  p95: {:?}  p99: {:?}", pct(0.50), pct(0.95), pct(0.99));
+    Ok(())
+}
+
+/// Heap allocations per query of ranking (retrieval, hydration, scoring) and
+/// of the cross edges of the first page; embedding is left out.
+async fn allocations(explorer: &WikiExplorer, queries: &[String], max: Option<u64>, json: bool) -> anyhow::Result<()> {
+    let config = get_config();
+    let mut embedded = Vec::with_capacity(queries.len());
+    for query in queries {
+        let query = query.replace('_', " ");
+        let vector = explorer.embed(&query).await?;
+        embedded.push((query, vector));
+    }
+
+    let corpus = explorer.corpus();
+    let (allocations_before, bytes_before) = alloc_stats::snapshot();
+    for (query, vector) in &embedded {
+        let (ranked, _) = rank_candidates(corpus, &server_options(), query, vector).await?;
+        let ids: Vec<i64> = ranked.iter().take(config.results_to_return).map(|r| r.id).collect();
+        explorer.cross_edges(&ids, &[], config.cross_edge_threshold as f32).await?;
+    }
+    let (allocations_after, bytes_after) = alloc_stats::snapshot();
+    let per_query = (allocations_after - allocations_before) / embedded.len() as u64;
+    let bytes_per_query = (bytes_after - bytes_before) / embedded.len() as u64;
+
+    if json {
+        print_json(&json!({
+            "queries": embedded.len(),
+            "allocations_per_query": per_query,
+            "bytes_per_query": bytes_per_query,
+        }))?;
+    } else {
+        println!("{} queries: {} allocations, {} KiB per query", embedded.len(), per_query, bytes_per_query / 1024);
+    }
+    if let Some(max) = max.filter(|&max| per_query > max) {
+        anyhow::bail!("{} allocations per query, over the limit of {}", per_query, max);
+    }
+    Ok(())
+}
+
+/// The configured index and metadata DB, without loading the model.
+async fn open_index() -> anyhow::Result<(IndexHandle, SqlitePool)> {
+    let config = get_config();
+    let path = config.index_path.clone();
+    let index = tokio::task::spawn_blocking(move || IndexHandle::load(&path)).await??;
+    let db = SqlitePool::connect(&format!("sqlite:{}", config.metadata_path)).await?;
+    Ok((index, db))
+}
+
+async fn fetch_titles(db: &SqlitePool, ids: &[i64]) -> anyhow::Result<HashMap<i64, String>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let params = placeholders(ids.len());
+    let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", params);
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    Ok(query.fetch_all(db).await?.into_iter().collect())
+}
+
+pub(crate) fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! `wikiexplorer-cli`: queries and inspects the configured index and metadata
+//! DB (INDEX_PATH / METADATA_PATH) directly, without the HTTP server.
+
+use clap::{Args, Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+use wikiexplorer_core::search::lexical::SearchMode;
+
+mod alloc_stats;
+mod commands;
+#[cfg(all(feature = "libtorch", feature = "onnx"))]
+mod parity;
+mod repl;
+
+#[global_allocator]
+static ALLOCATOR: alloc_stats::CountingAllocator = alloc_stats::CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "wikiexplorer-cli", about = "Offline WikiExplorer queries and index inspection")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Print machine-readable JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rank articles for a query exactly like `/api/related`
+    Search(SearchArgs),
+    /// Interactive search: expand results into a graph and export it
+    Repl(ReplArgs),
+    /// Nearest articles to an article's own vector
+    Neighbors(NeighborsArgs),
+    /// Print the stored vector of an index position
+    Reconstruct(ReconstructArgs),
+    /// Index, manifest and metadata DB statistics
+    Stats,
+    /// End-to-end latency (encode, FAISS, hydration, ranking) over sample queries
+    Benchmark(BenchmarkArgs),
+    /// Compare an ONNX model export against rust-bert on sample texts
+    #[cfg(all(feature = "libtorch", feature = "onnx"))]
+    Parity(ParityArgs),
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    pub query: String,
+
+    /// Results to print (default: RESULTS_TO_RETURN)
+    #[arg(short, long)]
+    pub k: Option<usize>,
+
+    #[arg(long, value_enum, default_value = "semantic")]
+    pub mode: ModeArg,
+
+    /// Show raw FAISS scores next to final scores
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Rank niche articles on relevance alone (no obscurity penalty)
+    #[arg(long)]
+    pub deep_dive: bool,
+}
+
+#[derive(Args)]
+pub struct ReplArgs {
+    /// Results per search (default: RESULTS_TO_RETURN)
+    #[arg(short, long)]
+    pub k: Option<usize>,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum ModeArg {
+    Semantic,
+    Lexical,
+    Hybrid,
+}
+
+impl From<ModeArg> for SearchMode {
+    fn from(mode: ModeArg) -> Self {
+        match mode {
+            ModeArg::Semantic => SearchMode::Semantic,
+            ModeArg::Lexical => SearchMode::Lexical,
+            ModeArg::Hybrid => SearchMode::Hybrid,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct NeighborsArgs {
+    /// Public ID of the article, as returned by `search` and the HTTP API
+    pub id: i64,
+
+    #[arg(short, long, default_value_t = 10)]
+    pub k: usize,
+}
+
+#[derive(Args)]
+pub struct ReconstructArgs {
+    pub id: i64,
+
+    /// Print every component instead of the first few
+    #[arg(long)]
+    pub full: bool,
+}
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Queries to run, sampled from article titles unless --queries-file is given
+    #[arg(long, default_value_t = 200)]
+    pub queries: usize,
+
+    /// One query per line
+    #[arg(long)]
+    pub queries_file: Option<String>,
+
+    /// Queries in flight at once
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+
+    /// Rank the most recent queries of the query store (QUERY_STORE_PATH)
+    /// with their stored embeddings, without loading the model
+    #[arg(long, conflicts_with_all = ["queries_file", "allocations"])]
+    pub replay: bool,
+
+    /// Count heap allocations of ranking and cross edges per query instead
+    /// (queries run one at a time, embedded beforehand)
+    #[arg(long)]
+    pub allocations: bool,
+
+    /// With --allocations: fail when a query averages more than this, to
+    /// catch hot-path regressions in CI
+    #[arg(long, requires = "allocations")]
+    pub max_allocations: Option<u64>,
+}
+
+#[cfg(all(feature = "libtorch", feature = "onnx"))]
+#[derive(Args)]
+pub struct ParityArgs {
+    /// Directory with tokenizer.json and model_quantized.onnx or model.onnx
+    pub model_dir: String,
+
+    /// One text per line, instead of the built-in samples
+    #[arg(long)]
+    pub texts_file: Option<String>,
+
+    /// Lowest cosine similarity accepted between the two backends' vectors
+    #[arg(long, default_value_t = 0.99)]
+    pub min_cosine: f32,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Logs go to stderr so `--json` output can be piped; RUST_LOG overrides the level
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with_target(false)
+        .with_writer(std::io::stderr)
+        .compact()
+        .init();
+
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Search(args) => commands::search(args, cli.json).await,
+        Command::Repl(args) => repl::run(args).await,
+        Command::Neighbors(args) => commands::neighbors(args, cli.json).await,
+        Command::Reconstruct(args) => commands::reconstruct(args, cli.json).await,
+        Command::Stats => commands::stats(cli.json).await,
+        Command::Benchmark(args) => commands::benchmark(args, cli.json).await,
+        #[cfg(all(feature = "libtorch", feature = "onnx"))]
+        Command::Parity(args) => parity::run(args, cli.json).await,
+    }
+}
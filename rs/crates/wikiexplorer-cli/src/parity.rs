@@ -0,0 +1,93 @@
+//! `wikiexplorer-cli parity`: encodes the same texts with rust-bert and an ONNX
+//! export and fails unless every pair of vectors agrees, so a model swapped in
+//! for EMBEDDER_BACKEND=onnx keeps matching indexes built with rust-bert.
+
+use serde_json::json;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use wikiexplorer_core::search::embedder::Embedder;
+use wikiexplorer_core::search::inference::InferenceWorker;
+use wikiexplorer_core::search::onnx::OnnxEmbedder;
+
+use crate::commands::print_json;
+use crate::ParityArgs;
+
+/// Checked when no --texts-file is given: titles, queries, non-ASCII and a long lead
+const SAMPLES: [&str; 8] = [
+    "Albert Einstein",
+    "theory of general relativity",
+    "History of the Byzantine Empire",
+    "how do vaccines train the immune system",
+    "Zürich",
+    "Ōsaka Castle",
+    "C++ (programming language)",
+    "The mitochondrion is an organelle found in the cells of most eukaryotes. \
+     Mitochondria use aerobic respiration to generate adenosine triphosphate, \
+     which is used throughout the cell as a source of chemical energy.",
+];
+
+pub async fn run(args: ParityArgs, json: bool) -> anyhow::Result<()> {
+    let texts: Vec<String> = match &args.texts_file {
+        Some(path) => std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => SAMPLES.iter().map(|s| s.to_string()).collect(),
+    };
+    if texts.is_empty() {
+        anyhow::bail!("No texts to compare");
+    }
+
+    // The export first: a bad model directory fails before rust-bert downloads its weights
+    let started = Instant::now();
+    let onnx = OnnxEmbedder::load(Path::new(&args.model_dir), 1)?;
+    let load_time = started.elapsed();
+    let reference = InferenceWorker::start(1, Duration::from_millis(5), texts.len())?;
+
+    let expected = reference.encode_batch(texts.clone()).await?;
+    let started = Instant::now();
+    let actual = onnx.encode_batch(texts.clone()).await?;
+    let encode_time = started.elapsed();
+    tokio::task::spawn_blocking(move || reference.shutdown()).await?;
+
+    let mut rows = Vec::with_capacity(texts.len());
+    for ((text, a), b) in texts.iter().zip(&expected).zip(&actual) {
+        if a.len() != b.len() {
+            anyhow::bail!("Dimension mismatch: rust-bert {} vs ONNX {}", a.len(), b.len());
+        }
+        let cosine = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>() / (norm(a) * norm(b)).max(f32::EPSILON);
+        let max_diff = a.iter().zip(b).map(|(x, y)| (x - y).abs()).fold(0.0f32, f32::max);
+        rows.push((text, cosine, max_diff));
+    }
+    let failed = rows.iter().filter(|(_, cosine, _)| *cosine < args.min_cosine).count();
+
+    if json {
+        print_json(&json!({
+            "texts": rows.len(),
+            "failed": failed,
+            "min_cosine": rows.iter().map(|r| r.1).fold(f32::INFINITY, f32::min),
+            "max_abs_diff": rows.iter().map(|r| r.2).fold(0.0f32, f32::max),
+            "onnx_load_ms": load_time.as_millis(),
+            "onnx_encode_ms": encode_time.as_millis(),
+        }))?;
+    } else {
+        println!("ONNX model loaded in {:?}, encoded {} texts in {:?}", load_time, rows.len(), encode_time);
+        println!("{:>8}  {:>9}  text", "cosine", "max diff");
+        for (text, cosine, max_diff) in &rows {
+            let mark = if *cosine < args.min_cosine { "  ✗" } else { "" };
+            let preview: String = text.chars().take(60).collect();
+            println!("{:>8.5}  {:>9.6}  {}{}", cosine, max_diff, preview, mark);
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} texts below cosine {}", failed, rows.len(), args.min_cosine);
+    }
+    Ok(())
+}
+
+fn norm(v: &[f32]) -> f32 {
+    v.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
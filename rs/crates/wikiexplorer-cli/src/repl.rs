@@ -0,0 +1,237 @@
+//! `wikiexplorer-cli repl`: search interactively and grow a graph the way the
+//! frontend does (expand a result, cross edges to what's already there), then
+//! export it for a closer look in Gephi or Graphviz.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::time::Instant;
+use wikiexplorer_core::export::{ExportEdge, ExportFormat, ExportGraph, ExportNode};
+use wikiexplorer_core::search::lexical::SearchMode;
+use wikiexplorer_core::{RankedGraph, SearchOptions, WikiExplorer};
+
+use crate::commands::print_graph;
+use crate::ReplArgs;
+
+const HELP: &str = "\
+  <query>                 search; starts a new graph
+  expand <n>              search for result n and add the results to the graph
+  export <path> [format]  write the graph (graphml, gexf or dot; default from the extension)
+  set k <n>               results per search
+  set mode <semantic|lexical|hybrid>
+  clear                   forget the graph
+  help                    this text
+  quit                    leave (also Ctrl-D)";
+
+struct GraphNode {
+    title: String,
+    depth: usize,
+    score: f64,
+}
+
+/// The graph built so far plus the numbered rows of the last search.
+#[derive(Default)]
+struct Workspace {
+    nodes: HashMap<i64, GraphNode>,
+    /// Insertion order, so exports are stable
+    order: Vec<i64>,
+    edges: Vec<(i64, i64, f32, &'static str)>,
+    /// Undirected endpoints and kind of each edge, so repeated searches don't add it again
+    edge_keys: HashSet<(i64, i64, &'static str)>,
+    rows: Vec<(i64, String)>,
+}
+
+/// One line of input, parsed before anything is run so a typo can't cost the graph.
+enum Command<'a> {
+    Empty,
+    Quit,
+    Help,
+    Clear,
+    SetK(usize),
+    SetMode(SearchMode),
+    Expand(usize),
+    Export(&'a str, Option<&'a str>),
+    Search(&'a str),
+}
+
+impl<'a> Command<'a> {
+    fn parse(line: &'a str) -> anyhow::Result<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        Ok(match words.as_slice() {
+            [] => Self::Empty,
+            ["quit"] | ["exit"] => Self::Quit,
+            ["help"] => Self::Help,
+            ["clear"] => Self::Clear,
+            ["set", "k", n] => match n.parse() {
+                Ok(k) if k > 0 => Self::SetK(k),
+                _ => anyhow::bail!("k must be a positive number"),
+            },
+            ["set", "mode", mode] => Self::SetMode(parse_mode(mode)?),
+            ["set", ..] => anyhow::bail!("usage: set k <n> | set mode <semantic|lexical|hybrid>"),
+            ["expand", n] => Self::Expand(n.parse().map_err(|_| anyhow::anyhow!("usage: expand <n>"))?),
+            ["expand", ..] => anyhow::bail!("usage: expand <n>"),
+            ["export", path] => Self::Export(path, None),
+            ["export", path, format] => Self::Export(path, Some(format)),
+            ["export", ..] => anyhow::bail!("usage: export <path> [format]"),
+            _ => Self::Search(line),
+        })
+    }
+}
+
+impl Workspace {
+    fn add_node(&mut self, id: i64, title: &str, depth: usize, score: f64) {
+        if !self.nodes.contains_key(&id) {
+            self.order.push(id);
+            self.nodes.insert(id, GraphNode { title: title.to_string(), depth, score });
+        }
+    }
+
+    fn add_edge(&mut self, source: i64, target: i64, score: f32, kind: &'static str) {
+        let key = (source.min(target), source.max(target), kind);
+        if source != target && self.edge_keys.insert(key) {
+            self.edges.push((source, target, score, kind));
+        }
+    }
+
+    /// Adds the results (linked from `parent` when expanding) and their cross edges.
+    fn add(&mut self, graph: &RankedGraph, parent: Option<i64>) {
+        let depth = parent.and_then(|p| self.nodes.get(&p)).map_or(0, |n| n.depth + 1);
+        for result in &graph.results {
+            self.add_node(result.id, &result.title, depth, result.score_float);
+            if let Some(parent) = parent {
+                self.add_edge(parent, result.id, result.score_float as f32, "expansion");
+            }
+        }
+        for edge in &graph.cross_edges {
+            if self.nodes.contains_key(&edge.source_id) && self.nodes.contains_key(&edge.target_id) {
+                self.add_edge(edge.source_id, edge.target_id, edge.score, "semantic");
+            }
+        }
+        self.rows = graph.results.iter().map(|r| (r.id, r.title.to_string())).collect();
+    }
+
+    fn export(&self) -> ExportGraph {
+        ExportGraph {
+            nodes: self
+                .order
+                .iter()
+                .map(|id| {
+                    let node = &self.nodes[id];
+                    ExportNode { id: *id, title: node.title.clone(), depth: Some(node.depth), score: Some(node.score) }
+                })
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .map(|&(source, target, score, kind)| ExportEdge { source, target, score, kind: Some(kind.to_string()) })
+                .collect(),
+        }
+    }
+}
+
+pub async fn run(args: ReplArgs) -> anyhow::Result<()> {
+    let explorer = WikiExplorer::open().await?;
+    let mut options = SearchOptions { k: args.k, ..Default::default() };
+    let mut workspace = Workspace::default();
+
+    println!("Type a query, or `help`.");
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+        let line = line?;
+        let command = match Command::parse(line.trim()) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("error: {}", e);
+                continue;
+            }
+        };
+
+        let outcome = match command {
+            Command::Empty => Ok(()),
+            Command::Quit => return Ok(()),
+            Command::Help => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            Command::Clear => {
+                workspace = Workspace::default();
+                Ok(())
+            }
+            Command::SetK(k) => {
+                options.k = Some(k);
+                Ok(())
+            }
+            Command::SetMode(mode) => {
+                options.rank.search_mode = mode;
+                Ok(())
+            }
+            Command::Expand(n) => expand(&explorer, &options, &mut workspace, n).await,
+            Command::Export(path, format) => export(&workspace, path, format),
+            Command::Search(query) => {
+                // The graph is only replaced once the new search succeeded
+                let mut fresh = Workspace::default();
+                let outcome = search(&explorer, &options, &mut fresh, query, None).await;
+                if outcome.is_ok() {
+                    workspace = fresh;
+                }
+                outcome
+            }
+        };
+        if let Err(e) = outcome {
+            println!("error: {}", e);
+        }
+    }
+}
+
+async fn search(
+    explorer: &WikiExplorer,
+    options: &SearchOptions,
+    workspace: &mut Workspace,
+    query: &str,
+    parent: Option<i64>,
+) -> anyhow::Result<()> {
+    let options = SearchOptions { context: workspace.order.clone(), ..options.clone() };
+    let started = Instant::now();
+    let graph = explorer.search(query, &options).await?;
+    print_graph(&graph, started.elapsed());
+    workspace.add(&graph, parent);
+    println!("graph: {} nodes, {} edges", workspace.nodes.len(), workspace.edges.len());
+    Ok(())
+}
+
+/// Searches the title of row `n` (1-based) of the last result table.
+async fn expand(explorer: &WikiExplorer, options: &SearchOptions, workspace: &mut Workspace, n: usize) -> anyhow::Result<()> {
+    let Some((id, title)) = n.checked_sub(1).and_then(|i| workspace.rows.get(i)).cloned() else {
+        anyhow::bail!("no result {} in the last table", n);
+    };
+    if title.is_empty() {
+        anyhow::bail!("result {} has no title (partial results)", n);
+    }
+    search(explorer, options, workspace, &title, Some(id)).await
+}
+
+fn export(workspace: &Workspace, path: &str, format: Option<&str>) -> anyhow::Result<()> {
+    let extension = std::path::Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let format = match format {
+        Some(name) => ExportFormat::parse(name).ok_or_else(|| anyhow::anyhow!("unknown format '{}'", name))?,
+        None => ExportFormat::parse(extension).unwrap_or(ExportFormat::GraphMl),
+    };
+    std::fs::write(path, workspace.export().render(format))?;
+    println!("wrote {} nodes and {} edges to {}", workspace.nodes.len(), workspace.edges.len(), path);
+    Ok(())
+}
+
+fn parse_mode(raw: &str) -> anyhow::Result<SearchMode> {
+    match raw {
+        "semantic" => Ok(SearchMode::Semantic),
+        "lexical" => Ok(SearchMode::Lexical),
+        "hybrid" => Ok(SearchMode::Hybrid),
+        other => anyhow::bail!("unknown mode '{}'", other),
+    }
+}
@@ -0,0 +1,39 @@
+//! Runs `wikiexplorer-cli parity` on the fixture texts against the ONNX export
+//! in PARITY_MODEL_DIR (e.g. ./models/all-MiniLM-L6-v2-onnx). Local runs
+//! without it skip the comparison; under CI (`CI` set) it is required.
+
+#![cfg(all(feature = "libtorch", feature = "onnx"))]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn parity(model_dir: &str) -> Output {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/parity_texts.txt");
+    Command::new(env!("CARGO_BIN_EXE_wikiexplorer-cli"))
+        .args(["--json", "parity", model_dir, "--texts-file"])
+        .arg(&fixture)
+        .output()
+        .expect("wikiexplorer-cli runs")
+}
+
+#[test]
+fn onnx_export_matches_rust_bert() {
+    let Ok(model_dir) = std::env::var("PARITY_MODEL_DIR") else {
+        assert!(std::env::var_os("CI").is_none(), "PARITY_MODEL_DIR must be set in CI");
+        eprintln!("PARITY_MODEL_DIR not set, skipping the model comparison");
+        return;
+    };
+    let output = parity(&model_dir);
+    assert!(output.status.success(), "parity failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report["texts"], 8);
+    assert_eq!(report["failed"], 0);
+    assert!(report["min_cosine"].as_f64().unwrap() >= 0.99);
+}
+
+#[test]
+fn missing_model_fails() {
+    let output = parity("/nonexistent/onnx-model");
+    assert!(!output.status.success());
+}
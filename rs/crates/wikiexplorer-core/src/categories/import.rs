@@ -0,0 +1,65 @@
+use crate::categories::{ensure_category_table, normalize_category};
+use crate::cli::CategoryImportArgs;
+use crate::config::get_config;
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+const BATCH_SIZE: usize = 5_000;
+
+/// Imports `<article_id>,<category>` rows from a CSV into `article_categories`.
+/// An article may appear on several rows; categories are stored normalized.
+pub async fn run(args: CategoryImportArgs) -> anyhow::Result<()> {
+    let config = get_config();
+
+    // 1. Parse the CSV up front so a malformed file changes nothing
+    let mut reader = csv::Reader::from_path(&args.csv)?;
+    let mut rows = Vec::new();
+    let mut skipped = 0usize;
+    for record in reader.records() {
+        let record = record?;
+        let (Some(raw_id), Some(raw_category)) = (record.get(0), record.get(1)) else {
+            skipped += 1;
+            continue;
+        };
+        let (Ok(id), category) = (raw_id.trim().parse::<i64>(), normalize_category(raw_category)) else {
+            skipped += 1;
+            continue;
+        };
+        if category.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        rows.push((id, category));
+    }
+
+    if rows.is_empty() {
+        anyhow::bail!("No usable rows in {}", args.csv);
+    }
+    info!("Parsed {} rows from {} ({} skipped)", rows.len(), args.csv, skipped);
+
+    // 2. Schema + batched inserts
+    let pool = SqlitePool::connect(&format!("sqlite:{}", config.metadata_path)).await?;
+    ensure_category_table(&pool).await?;
+
+    if args.replace {
+        sqlx::query("DELETE FROM article_categories").execute(&pool).await?;
+        warn!("Cleared existing article categories");
+    }
+
+    let mut inserted = 0u64;
+    for chunk in rows.chunks(BATCH_SIZE) {
+        let mut tx = pool.begin().await?;
+        for (id, category) in chunk {
+            inserted += sqlx::query("INSERT OR IGNORE INTO article_categories (article_id, category) VALUES (?, ?)")
+                .bind(id)
+                .bind(category)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+    }
+
+    info!("✓ Imported {} article categories ({} already present)", inserted, rows.len() as u64 - inserted);
+    Ok(())
+}
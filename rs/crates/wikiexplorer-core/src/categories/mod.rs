@@ -0,0 +1,144 @@
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+
+pub mod import;
+
+/// Include/exclude filter on article categories. An article passes when it has
+/// at least one included category (if any are given) and none of the excluded ones.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct CategoryFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl CategoryFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: include.iter().map(|c| normalize_category(c)).filter(|c| !c.is_empty()).collect(),
+            exclude: exclude.iter().map(|c| normalize_category(c)).filter(|c| !c.is_empty()).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// `categories` must already be normalized (as stored by the importer).
+    pub fn allows(&self, categories: &[String]) -> bool {
+        if categories.iter().any(|c| self.exclude.contains(c)) {
+            return false;
+        }
+        self.include.is_empty() || categories.iter().any(|c| self.include.contains(c))
+    }
+}
+
+/// "Category:Quantum_mechanics" -> "quantum mechanics"
+pub fn normalize_category(raw: &str) -> String {
+    let trimmed = raw.trim();
+    let name = trimmed
+        .strip_prefix("Category:")
+        .or_else(|| trimmed.strip_prefix("category:"))
+        .unwrap_or(trimmed);
+    name.replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Categories of the given articles. A DB without the table has no categories.
+pub async fn fetch_categories(
+    pool: &SqlitePool,
+    ids: &[i64],
+) -> Result<HashMap<i64, Vec<String>>, AppError> {
+    let mut categories: HashMap<i64, Vec<String>> = HashMap::new();
+    if ids.is_empty() {
+        return Ok(categories);
+    }
+
+    let unique: Vec<i64> = ids.iter().cloned().collect::<HashSet<_>>().into_iter().collect();
+    let params = placeholders(unique.len());
+    let sql = format!(
+        "SELECT article_id, category FROM article_categories WHERE article_id IN ({})",
+        params
+    );
+
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in &unique {
+        query = query.bind(id);
+    }
+
+    match with_deadline("categories", query.fetch_all(pool)).await {
+        Ok(rows) => {
+            for (id, category) in rows {
+                categories.entry(id).or_default().push(category);
+            }
+            Ok(categories)
+        }
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => {
+            warn!("⚠ Category filter requested but the metadata DB has no article_categories table");
+            Ok(categories)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn ensure_category_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS article_categories (
+            article_id INTEGER NOT NULL,
+            category TEXT NOT NULL,
+            PRIMARY KEY (article_id, category)
+        )",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_article_categories_category ON article_categories (category)")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn normalized_categories_are_clean(raw in "\\PC{0,40}") {
+            let name = normalize_category(&raw);
+            prop_assert!(!name.contains('_'));
+            prop_assert_eq!(name.trim(), name.as_str());
+            prop_assert!(!name.contains("  "));
+        }
+
+        #[test]
+        fn prefix_and_underscores_are_ignored(name in "[A-Za-z]{1,10}(_[A-Za-z]{1,10}){0,3}") {
+            prop_assert_eq!(normalize_category(&format!("Category:{}", name)), normalize_category(&name.replace('_', " ")));
+        }
+
+        #[test]
+        fn filter_follows_include_and_exclude(
+            include in prop::collection::vec("[a-d]{1,2}", 0..3),
+            exclude in prop::collection::vec("[a-d]{1,2}", 0..3),
+            categories in prop::collection::vec("[a-d]{1,2}", 0..4),
+        ) {
+            let filter = CategoryFilter::new(&include, &exclude);
+            let allowed = filter.allows(&categories);
+            if filter.is_empty() {
+                prop_assert!(allowed);
+            }
+            if categories.iter().any(|c| filter.exclude.contains(c)) {
+                prop_assert!(!allowed);
+            } else if !filter.include.is_empty() {
+                prop_assert_eq!(allowed, categories.iter().any(|c| filter.include.contains(c)));
+            }
+        }
+    }
+}
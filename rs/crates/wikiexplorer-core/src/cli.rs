@@ -0,0 +1,355 @@
+//! Arguments of the commands implemented in this crate (index builds,
+//! ingestion, ...). With the `cli` feature they derive `clap::Args`, and the
+//! binaries put them into their own command trees.
+
+#[cfg(feature = "cli")]
+use clap::Args;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct BuildArgs {
+    /// FAISS index factory spec, e.g. "Flat", "IVF4096,Flat", "IVF4096,PQ64", "HNSW32"
+    #[cfg_attr(feature = "cli", arg(long, default_value = "IVF4096,Flat"))]
+    pub factory: String,
+
+    /// Index to read vectors from (must support reconstruction). Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub source: Option<String>,
+
+    /// Embed article titles from the metadata DB instead of copying an existing index
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "source"))]
+    pub from_metadata: bool,
+
+    /// Metadata DB to read articles from. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long, requires = "from_metadata"))]
+    pub metadata: Option<String>,
+
+    /// Append each article's lead paragraph (`lead` column) to its title
+    #[cfg_attr(feature = "cli", arg(long, requires = "from_metadata"))]
+    pub with_lead: bool,
+
+    /// Texts per model call when embedding
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 128))]
+    pub encode_batch: usize,
+
+    /// Where to write the new index. The manifest is written next to it.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub output: String,
+
+    /// Also write the exact vectors to `<output>.vectors.f32`, which the server
+    /// reconstructs from (cross-edges on IVF-PQ layouts)
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub vectors: bool,
+
+    /// Number of vectors sampled for training (ignored for layouts that need no training)
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 200_000))]
+    pub train_sample: usize,
+
+    /// Vectors added per batch while populating the index
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 50_000))]
+    pub batch_size: usize,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct UpdateArgs {
+    /// Index to update in place. Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub index: Option<String>,
+
+    /// Metadata DB to diff against. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    /// Append each article's lead paragraph; must match how the index was built
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub with_lead: bool,
+
+    /// Texts per model call when embedding
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 128))]
+    pub encode_batch: usize,
+
+    /// Vectors added per batch
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 50_000))]
+    pub batch_size: usize,
+
+    /// Only report the diff
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub dry_run: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct ConvertArgs {
+    /// FAISS index to read vectors from (must support reconstruction). Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub source: Option<String>,
+
+    /// Where to write the HNSW index; must end in `.hnsw`. The graph files and
+    /// manifest are written next to it.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub output: String,
+
+    /// Neighbours per node
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 32))]
+    pub m: usize,
+
+    /// Candidate list size while building the graph
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 200))]
+    pub ef_construction: usize,
+
+    /// efSearch stored with the index, used when EF_SEARCH is unset
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 64))]
+    pub ef_search: usize,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct StableIdsArgs {
+    /// Index to write the sidecar for. Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub index: Option<String>,
+
+    /// Metadata DB to read `page_id` from. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct MigrateIdsArgs {
+    /// CSV with a header and `old_id,new_id` rows
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub mapping: String,
+
+    /// Metadata DB holding the edge caches. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    /// User DB holding sessions, their snapshots and watches. Defaults to USER_DB_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub user_db: Option<String>,
+
+    /// Corpus whose sessions to migrate. Defaults to CORPUS_NAME.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub corpus: Option<String>,
+
+    /// Leave the edge caches alone, e.g. for another corpus' sessions (its
+    /// edge caches live in its own metadata DB)
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub sessions_only: bool,
+
+    /// Only report what would change
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub dry_run: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct KnnPrecomputeArgs {
+    /// Index to search. Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub index: Option<String>,
+
+    /// Metadata DB to write `knn_edges` to. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    /// Neighbours stored per article
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 20))]
+    pub k: usize,
+
+    /// Articles searched per batch
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 1024))]
+    pub batch_size: usize,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct CalibrateArgs {
+    /// Validation set: one `query<TAB>relevant article title` per line; repeat
+    /// a query for several relevant articles
+    pub validation: std::path::PathBuf,
+
+    /// `sigmoid` (relevance probability) or `minmax` (range of relevant scores)
+    #[cfg_attr(feature = "cli", arg(long, default_value = "sigmoid"))]
+    pub method: String,
+
+    /// Hits per query labelled relevant or not
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 50))]
+    pub k: usize,
+
+    /// minmax: share of relevant scores left below min and above max
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 0.05))]
+    pub tail: f64,
+
+    /// Index to calibrate. Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub index: Option<String>,
+
+    /// Metadata DB for hit titles. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    /// Print the calibration without writing the manifest
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub dry_run: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct TuneArgs {
+    /// Minimum recall@k (vs exact search) the chosen nprobe must reach
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 0.95))]
+    pub target_recall: f64,
+
+    /// Index to calibrate. Defaults to INDEX_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub index: Option<String>,
+
+    /// Flat index used as ground truth. Defaults to vectors reconstructed from the tuned index.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub exact: Option<String>,
+
+    /// Number of sampled query vectors
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 500))]
+    pub queries: usize,
+
+    /// Neighbors compared per query
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 10))]
+    pub k: usize,
+
+    /// Largest nprobe tried (values double from 1)
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 1024))]
+    pub max_nprobe: usize,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct BenchArgs {
+    /// Number of threads issuing searches at once
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 8))]
+    pub concurrency: usize,
+
+    /// Total searches across all threads
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 2000))]
+    pub queries: usize,
+
+    /// Neighbors per search (the server uses CANDIDATE_POOL_SIZE)
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 1000))]
+    pub k: usize,
+
+    /// Time the cross-edge similarity kernel on an N×N matrix of 384-d vectors
+    /// against the scalar loop instead (no index or model needed)
+    #[cfg_attr(feature = "cli", arg(long, value_name = "N"))]
+    pub similarity: Option<usize>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct SignalImportArgs {
+    /// Column / signal name, e.g. quality_score
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub name: String,
+
+    /// CSV with a header; first column `article_id` or `title`, second the value
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub csv: String,
+
+    /// Exponent of the signal in the geometric-mean score
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 0.10))]
+    pub weight: f64,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct CategoryImportArgs {
+    /// CSV with a header; columns `article_id`, `category` (one row per pair)
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub csv: String,
+
+    /// Remove all existing categories before importing
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub replace: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct PageviewIngestArgs {
+    /// Pageview dump files (plain or .gz); views are summed across them
+    #[cfg_attr(feature = "cli", arg(required = true))]
+    pub files: Vec<String>,
+
+    /// Wiki whose rows count, e.g. "en" (desktop and mobile)
+    #[cfg_attr(feature = "cli", arg(long, default_value = "en"))]
+    pub project: String,
+
+    /// Metadata DB to update. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct LinkIngestArgs {
+    /// Edge list with `source_id target_id` lines (plain or .gz). Defaults to the DB's `links` table.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub file: Option<String>,
+
+    /// Metadata DB to update. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = crate::ingest::links::DEFAULT_DAMPING))]
+    pub damping: f64,
+
+    /// Upper bound on power iterations
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 100))]
+    pub iterations: usize,
+
+    /// Stop once an iteration changes the ranks by less than this (L1)
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 1e-9))]
+    pub tolerance: f64,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct ImageIngestArgs {
+    /// `title<TAB>image` lines (plain or .gz); the image is a URL or a file name
+    pub file: String,
+
+    /// Thumbnail width for images given as file names
+    #[cfg_attr(feature = "cli", arg(long, default_value_t = 320))]
+    pub width: u32,
+
+    /// Metadata DB to update. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+
+    /// Remove all existing thumbnails (including lazily fetched ones) first
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub replace: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct QidIngestArgs {
+    /// `title<TAB>QID` lines (plain or .gz)
+    pub file: String,
+
+    /// Metadata DB to update. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Args))]
+pub struct LastModifiedIngestArgs {
+    /// `title<TAB>timestamp` lines (plain or .gz); ISO 8601 or MediaWiki
+    /// `YYYYMMDDHHMMSS` timestamps, UTC
+    pub file: String,
+
+    /// Metadata DB to update. Defaults to METADATA_PATH.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub metadata: Option<String>,
+}
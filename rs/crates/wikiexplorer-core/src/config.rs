@@ -0,0 +1,609 @@
+use crate::search::calibration::Calibration;
+use crate::search::embedder::{Device, EmbedderBackend};
+use crate::search::ranking::RankingStrategy;
+use crate::search::vector_index::IndexBackend;
+use crate::search::filter_policy::FilterPolicy;
+use crate::utils::anonymize::IpAnonymization;
+use crate::utils::cors::OriginPolicy;
+use crate::utils::slo::SloSpec;
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    // Database
+    pub database_url: String,
+
+    // Weights
+    pub weight_semantic: f64,
+    pub weight_pagerank: f64,
+    pub weight_pageviews: f64,
+    pub weight_title_match: f64,
+    // Log-scaled inbound wikilinks (`backlinks` column); 0 ignores them
+    pub weight_backlinks: f64,
+    // Affinity to the graph being explored (request `context`); 0 ignores it
+    pub weight_context: f64,
+    // Recently edited articles (`last_modified` column, request `recency`);
+    // 0 ignores edit times. An article's recency halves every half-life.
+    pub weight_recency: f64,
+    pub recency_half_life_days: f64,
+    // Obscurity penalty of the geometric ranking: articles with pageviews and
+    // pagerank both below these have their score multiplied by the penalty
+    // (1 = off; requests can override all three, or turn it off with `deep_dive`)
+    pub obscurity_pageviews_below: f64,
+    pub obscurity_pagerank_below: f64,
+    pub obscurity_penalty: f64,
+
+    // Search Params
+    pub cross_edge_threshold: f64,
+    pub cross_edge_max_edges: usize,
+    pub cross_edge_top_m: usize,
+    pub cross_edge_budget_ms: u64,
+    pub epsilon: f64,
+    pub candidate_pool_size: usize,
+    pub results_to_return: usize,
+    pub rescore_top_n: usize,
+    pub mmr_window: usize,
+    pub hydration_budget_ms: u64,
+    pub hydration_chunk_size: usize,
+    pub hybrid_lexical_weight: f32,
+    pub backfill_min_results: usize,
+    // Negative query terms (request `exclude`): similarity above which a
+    // candidate counts as about an excluded term, and its score factor
+    pub exclude_threshold: f32,
+    pub exclude_penalty: f64,
+    // Typo suggestions (`did_you_mean`) for searches whose best result is a
+    // weak match, from the words of titles in at least SPELL_MIN_WORD_COUNT
+    pub did_you_mean_threshold: f64,
+    pub spell_min_word_count: u32,
+    // Raw similarity -> sem_norm (unset = manifest, else none)
+    pub score_calibration: Option<Calibration>,
+    // Default ranking strategy and the coefficients of the linear one
+    pub ranking: RankingStrategy,
+    pub ranking_model_file: Option<String>,
+
+    // Meta-page filtering (FILTER_POLICY_FILE, JSON; default: namespace prefixes + disambiguation)
+    pub filter_policy: FilterPolicy,
+
+    // FAISS search parameters (unset = manifest/index defaults)
+    pub nprobe: Option<usize>,
+    pub ef_search: Option<usize>,
+
+    // Cross-corpus duplicate collapse (cosine similarity of title embeddings)
+    pub dedup_threshold: f32,
+
+    // Query embedding LRU (0 = disabled)
+    pub embedding_cache_size: usize,
+
+    // Query embeddings persisted to a sidecar SQLite file (unset = disabled)
+    pub query_store_path: Option<String>,
+    pub query_store_ttl_days: u64,
+    pub query_store_keep_text: bool,
+
+    // Experimental semantic result cache
+    pub semantic_cache_enabled: bool,
+    pub semantic_cache_capacity: usize,
+    pub semantic_cache_max_distance: f32,
+
+    // Response cache (query + context + options -> full response)
+    pub response_cache_enabled: bool,
+    pub response_cache_ttl_secs: u64,
+    pub response_cache_capacity: usize,
+    pub response_cache_persist: bool,
+
+    // Server-side ranked pools behind page tokens
+    pub page_token_ttl_secs: u64,
+    pub page_pool_capacity: usize,
+
+    // Server-side graph contexts behind context tokens
+    pub context_token_ttl_secs: u64,
+    pub context_token_capacity: usize,
+
+    // Metadata DB liveness probe and reconnect backoff cap
+    pub db_health_interval_secs: u64,
+    pub db_reconnect_max_backoff_secs: u64,
+
+    // Hot-path statement deadline (0 = none) and slow-statement log threshold
+    pub db_query_timeout_ms: u64,
+    pub slow_query_ms: u64,
+
+    // Periodic ANALYZE / incremental vacuum of the metadata DB (0 = off) and pages freed per run
+    pub maintenance_interval_secs: u64,
+    pub maintenance_vacuum_pages: u64,
+
+    // Suggestion learning: rollup interval and weight of usage vs the pagerank prior
+    pub suggest_rollup_secs: u64,
+    pub suggest_usage_weight: f64,
+
+    // Published usage aggregates: queries seen fewer times are hidden, and
+    // counts get Laplace noise of this epsilon (None = exact counts)
+    pub analytics_min_count: u64,
+    pub analytics_epsilon: Option<f64>,
+
+    // Query topics: how often stored query embeddings are clustered (0 = off),
+    // how many of the most recent are, into at most how many topics, and over
+    // how many days topic volumes are counted
+    pub query_topics_interval_secs: u64,
+    pub query_topics_sample: usize,
+    pub query_topics_k: usize,
+    pub query_topics_window_days: u64,
+
+    // Background tasks: artifact directory, how long finished tasks are kept
+    // and how many may run at once
+    pub task_artifact_dir: String,
+    pub task_retention_secs: u64,
+    pub max_running_tasks: usize,
+
+    // Requests per minute per client IP (0 disables limiting)
+    pub rate_limit_per_min: u32,
+    // Take the client IP from X-Forwarded-For (only behind a trusted proxy),
+    // counting this many proxy hops from the right
+    pub trust_proxy: bool,
+    pub trusted_proxy_hops: usize,
+
+    // Privacy: how client IPs appear in logs (none | truncate | hash, keyed by the salt
+    // that hashing requires) and after how many days stored search text is purged (0 = kept)
+    pub ip_anonymization: IpAnonymization,
+    pub ip_hash_salt: String,
+    pub pii_retention_days: u64,
+
+    // LOG_FORMAT=json switches logs to one JSON object per line
+    pub log_json: bool,
+    // GET /metrics in Prometheus format, with tokio runtime probes
+    pub prometheus_metrics: bool,
+    // Per-route objectives (see utils::slo), burn alerts POSTed to the webhook
+    pub slos: Vec<SloSpec>,
+    pub slo_webhook_url: Option<String>,
+
+    // Seconds in-flight requests get to finish after SIGTERM/SIGINT
+    pub shutdown_drain_secs: u64,
+
+    // How often the watch runner checks for an index refresh
+    pub watch_check_secs: u64,
+
+    // Pageview refresh from the Wikimedia REST API: schedule (None = off), articles
+    // refreshed per run, days of views summed (the window of the imported dumps),
+    // wiki, and requests in flight
+    pub pageview_refresh_every: Option<Duration>,
+    pub pageview_refresh_top_n: usize,
+    pub pageview_refresh_days: u32,
+    pub pageview_project: String,
+    pub pageview_refresh_concurrency: usize,
+
+    // Article preview proxy: upstream summary API, how long cached copies
+    // (and cached 404s) are fresh, and how many the user DB keeps
+    pub wiki_summary_api: String,
+    pub summary_cache_ttl_secs: u64,
+    pub summary_cache_max_entries: u64,
+
+    // Thumbnails in search results: look up articles missing from `article_images`
+    // through the MediaWiki action API (PageImages) in the background
+    pub thumbnail_fetch: bool,
+    pub wiki_action_api: String,
+    pub thumbnail_width: u32,
+
+    // Embeddings: in-process rust-bert or ONNX Runtime (model export in
+    // ONNX_MODEL_DIR), or an OpenAI-compatible service (EMBEDDER_URL, with
+    // EMBEDDER_API_KEY as bearer token)
+    pub embedder_backend: EmbedderBackend,
+    // cpu | cuda | mps for the in-process rust-bert model
+    pub device: Device,
+    pub onnx_model_dir: String,
+    pub embedder_url: Option<String>,
+    pub embedder_api_key: Option<String>,
+
+    // auto (by INDEX_PATH extension) | faiss | hnsw | flat (exhaustive, in memory)
+    pub index_backend: IndexBackend,
+
+    // Concurrency
+    pub index_replicas: usize,
+    pub inference_workers: usize,
+    pub inference_batch_window_ms: u64,
+    pub inference_max_batch: usize,
+    // Concurrent inference / FAISS calls per lane (interactive = /api/related)
+    pub interactive_concurrency: usize,
+    pub batch_concurrency: usize,
+
+    // Admin endpoints are disabled unless a token is set
+    pub admin_token: Option<String>,
+
+    // CORS: browser origins allowed on the public API and on admin endpoints
+    pub allowed_origins: OriginPolicy,
+    pub admin_allowed_origins: OriginPolicy,
+
+    // Paths
+    pub index_path: String,
+    pub metadata_path: String,
+    // User-owned data (sessions, watches, ...); never swapped by an index reload
+    pub user_db_path: String,
+
+    // Corpora: the primary one above plus any extra index/DB pairs
+    pub corpus_name: String,
+    /// Language of the primary corpus, matched against a request's `lang`
+    pub corpus_lang: String,
+    /// CORPORA followed by the per-language corpora of LANGUAGES
+    pub extra_corpora: Vec<CorpusSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorpusSpec {
+    pub name: String,
+    pub index_path: String,
+    pub metadata_path: String,
+    /// Requests with this `lang` are routed here
+    pub lang: Option<String>,
+    /// Embedding model of this corpus's index when it isn't the default one
+    pub model: Option<String>,
+}
+
+impl CorpusSpec {
+    /// Parses `CORPORA="simple=/data/simple/index.faiss|/data/simple/metadata.db,private=..."`.
+    /// Malformed entries are skipped.
+    fn parse_list(raw: &str) -> Vec<Self> {
+        raw.split(',')
+            .filter_map(|entry| {
+                let (name, paths) = entry.trim().split_once('=')?;
+                let (index_path, metadata_path) = paths.split_once('|')?;
+                Some(Self {
+                    name: name.trim().to_string(),
+                    index_path: index_path.trim().to_string(),
+                    metadata_path: metadata_path.trim().to_string(),
+                    lang: None,
+                    model: None,
+                })
+            })
+            .filter(|spec| !spec.name.is_empty() && spec.name != "all")
+            .collect()
+    }
+
+    /// One corpus per code of `LANGUAGES="de,fr"`, from `INDEX_PATH_DE`,
+    /// `METADATA_PATH_DE` and optionally `MODEL_DE` (a multilingual model name
+    /// or directory). Malformed or repeated codes and codes missing either
+    /// path are errors.
+    fn parse_languages(raw: &str) -> Result<Vec<Self>, String> {
+        let mut specs: Vec<Self> = Vec::new();
+        for code in raw.split(',').map(|code| code.trim().to_lowercase()).filter(|code| !code.is_empty()) {
+            if !is_language_code(&code) {
+                return Err(format!("'{}' is not a language code", code));
+            }
+            if specs.iter().any(|spec| spec.name == code) {
+                return Err(format!("'{}' is listed twice", code));
+            }
+            let suffix = code.to_uppercase().replace('-', "_");
+            let path = |key: &str| {
+                env::var(format!("{}_{}", key, suffix))
+                    .ok()
+                    .filter(|p| !p.trim().is_empty())
+                    .ok_or_else(|| format!("'{}' needs {}_{}", code, key, suffix))
+            };
+            specs.push(Self {
+                index_path: path("INDEX_PATH")?,
+                metadata_path: path("METADATA_PATH")?,
+                model: env::var(format!("MODEL_{}", suffix)).ok().filter(|m| !m.trim().is_empty()),
+                name: code.clone(),
+                lang: Some(code),
+            });
+        }
+        Ok(specs)
+    }
+}
+
+/// A Wikipedia language code such as `de`, `simple` or `be-x-old`: letters,
+/// optionally followed by `-` separated alphanumeric subtags.
+fn is_language_code(code: &str) -> bool {
+    let mut parts = code.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (2..=12).contains(&primary.len())
+        && primary.bytes().all(|b| b.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.len() <= 8 && part.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+impl Config {
+    pub fn load() -> Self {
+        // We use typical defaults from your python config if env vars are missing
+        let is_macos = cfg!(target_os = "macos");
+        
+        let default_index = if is_macos { "../data/index.faiss" } else { "/opt/we/data/index.faiss" };
+        let default_meta = if is_macos { "../data/metadata.db" } else { "/opt/we/data/metadata.db" };
+        let default_user_db = if is_macos { "../data/user.db" } else { "/opt/we/data/user.db" };
+        // A typo must not silently keep full addresses
+        let ip_anonymization = env_strict("IP_ANONYMIZATION", IpAnonymization::Keep);
+
+        Self {
+            database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
+            
+            weight_semantic: 0.30,
+            weight_pagerank: 0.50,
+            weight_pageviews: 0.15,
+            weight_title_match: 0.05,
+            weight_backlinks: env_or("WEIGHT_BACKLINKS", 0.1),
+            // Off by default: with it, candidates far from the graph rank far lower on every expansion
+            weight_context: env_or("WEIGHT_CONTEXT", 0.0),
+            // Off by default: most queries are about topics, not news
+            weight_recency: env_or("WEIGHT_RECENCY", 0.0),
+            recency_half_life_days: env_or("RECENCY_HALF_LIFE_DAYS", 30.0),
+            // Raw columns: no pageviews at all and next to no pagerank
+            obscurity_pageviews_below: env_or("OBSCURITY_PAGEVIEWS_BELOW", 0.2),
+            obscurity_pagerank_below: env_or("OBSCURITY_PAGERANK_BELOW", 0.1),
+            obscurity_penalty: env_or("OBSCURITY_PENALTY", 0.5),
+            
+            cross_edge_threshold: 0.65,
+            // Search responses keep at most this many cross-edges, the strongest (0 = all)
+            cross_edge_max_edges: env_or("CROSS_EDGE_MAX_EDGES", 300),
+            // ...and per node only its M strongest (0 = all)
+            cross_edge_top_m: env_or("CROSS_EDGE_TOP_M", 10),
+            // Vector math stops after this long and returns what it has (0 = no limit)
+            cross_edge_budget_ms: env_or("CROSS_EDGE_BUDGET_MS", 500),
+            epsilon: 1e-8,
+            
+            candidate_pool_size: 1000,
+            results_to_return: 60,
+            // Exact re-scoring of the top FAISS candidates (0 = off)
+            rescore_top_n: env_or("RESCORE_TOP_N", 0),
+            // Results re-ordered by a request's `diversity` (MMR); the rest keep score order
+            mmr_window: env_or("MMR_WINDOW", 100),
+            // Metadata lookup and vector signal budget before falling back to ID-only results (0 = wait)
+            hydration_budget_ms: env_or("HYDRATION_BUDGET_MS", 750),
+            // Candidates hydrated per round when ranking may stop early (RankOptions::limit)
+            hydration_chunk_size: env_or("HYDRATION_CHUNK_SIZE", 100),
+            // Share of BM25 in the hybrid candidate score (the rest is cosine)
+            hybrid_lexical_weight: env_or("HYBRID_LEXICAL_WEIGHT", 0.3),
+            // Semantic searches left with fewer usable results than their page
+            // (offset + k; this many for callers without one) are topped up from
+            // the FTS5 keyword index (0 = off)
+            backfill_min_results: env_or("BACKFILL_MIN_RESULTS", 60),
+            // Candidates more similar than this to an `exclude` term are penalized...
+            exclude_threshold: env_or("EXCLUDE_THRESHOLD", 0.45),
+            // ...by multiplying their score with this (0 ranks them last)
+            exclude_penalty: env_or("EXCLUDE_PENALTY", 0.2),
+            // Best calibrated semantic score below which a correction is offered (0 = off)
+            did_you_mean_threshold: env_or("DID_YOU_MEAN_THRESHOLD", 0.5),
+            spell_min_word_count: env_or("SPELL_MIN_WORD_COUNT", 3),
+            // `minmax:<min>,<max>` or `sigmoid:<midpoint>,<steepness>`; overrides the
+            // calibration `wikiexplorer calibrate` wrote to the index manifest
+            score_calibration: env_strict_opt("SCORE_CALIBRATION"),
+            // geometric | sum | rrf | linear; requests override it with `ranking`
+            ranking: env_strict("RANKING", RankingStrategy::Geometric),
+            // JSON `{"intercept": .., "coefficients": {"semantic": .., ...}}` for `linear`
+            ranking_model_file: env::var("RANKING_MODEL_FILE").ok().filter(|p| !p.trim().is_empty()),
+
+            filter_policy: env::var("FILTER_POLICY_FILE")
+                .map(|path| load_filter_policy(&path))
+                .unwrap_or_default(),
+
+            nprobe: env_opt("NPROBE"),
+            ef_search: env_opt("EF_SEARCH"),
+
+            dedup_threshold: env_or("DEDUP_THRESHOLD", 0.95),
+
+            embedding_cache_size: env_or("EMBEDDING_CACHE_SIZE", 10_000),
+
+            // e.g. data/query_embeddings.db; survives restarts, unlike the LRU above
+            query_store_path: env_opt::<String>("QUERY_STORE_PATH").filter(|p| !p.is_empty()),
+            // Entries unused for this long are purged
+            query_store_ttl_days: env_or("QUERY_STORE_TTL_DAYS", 30),
+            // On also stores the query text (shown by analytics), blanked after PII_RETENTION_DAYS;
+            // off keeps only hashes and embeddings
+            query_store_keep_text: env_or("QUERY_STORE_KEEP_TEXT", false),
+
+            // Reuses results when a query embedding is within this cosine distance of a cached one
+            semantic_cache_enabled: env_or("SEMANTIC_CACHE", false),
+            semantic_cache_capacity: env_or("SEMANTIC_CACHE_CAPACITY", 512),
+            semantic_cache_max_distance: env_or("SEMANTIC_CACHE_MAX_DISTANCE", 0.02),
+
+            response_cache_enabled: env_or("RESPONSE_CACHE", true),
+            response_cache_ttl_secs: env_or("RESPONSE_CACHE_TTL_SECS", 300),
+            response_cache_capacity: env_or("RESPONSE_CACHE_CAPACITY", 2000),
+            // Mirror entries into the SQLite DB so they survive restarts
+            response_cache_persist: env_or("RESPONSE_CACHE_PERSIST", false),
+
+            // Outlives the response cache so a cached first page never hands out a dead token
+            page_token_ttl_secs: env_or("PAGE_TOKEN_TTL_SECS", 900),
+            page_pool_capacity: env_or("PAGE_POOL_CAPACITY", 500),
+            // Searches that add to a context get a fresh token for the merged one
+            context_token_ttl_secs: env_or("CONTEXT_TOKEN_TTL_SECS", 3600),
+            context_token_capacity: env_or("CONTEXT_TOKEN_CAPACITY", 5000),
+
+            db_health_interval_secs: env_or("DB_HEALTH_INTERVAL_SECS", 10),
+            db_reconnect_max_backoff_secs: env_or("DB_RECONNECT_MAX_BACKOFF_SECS", 30),
+
+            db_query_timeout_ms: env_or("DB_QUERY_TIMEOUT_MS", 5000),
+            slow_query_ms: env_or("SLOW_QUERY_MS", 200),
+
+            maintenance_interval_secs: env_or("MAINTENANCE_INTERVAL_SECS", 6 * 3600),
+            maintenance_vacuum_pages: env_or("MAINTENANCE_VACUUM_PAGES", 10_000),
+
+            suggest_rollup_secs: env_or("SUGGEST_ROLLUP_SECS", 60),
+            suggest_usage_weight: env_or("SUGGEST_USAGE_WEIGHT", 0.5),
+
+            analytics_min_count: env_or("ANALYTICS_MIN_COUNT", 5),
+            // 0 turns the noise off (private instances)
+            analytics_epsilon: Some(env_or("ANALYTICS_EPSILON", 1.0)).filter(|e: &f64| *e > 0.0),
+
+            // Only runs with QUERY_STORE_PATH set
+            query_topics_interval_secs: env_or("QUERY_TOPICS_INTERVAL_SECS", 3600),
+            query_topics_sample: env_or("QUERY_TOPICS_SAMPLE", 5000),
+            query_topics_k: env_or("QUERY_TOPICS_K", 20),
+            query_topics_window_days: env_or("QUERY_TOPICS_WINDOW_DAYS", 7),
+
+            task_artifact_dir: env::var("TASK_ARTIFACT_DIR").unwrap_or_else(|_| {
+                std::env::temp_dir().join("wikiexplorer-tasks").to_string_lossy().into_owned()
+            }),
+            task_retention_secs: env_or("TASK_RETENTION_SECS", 3600),
+            // Submissions beyond this get 429 until a running task finishes
+            max_running_tasks: env_or("MAX_RUNNING_TASKS", 4),
+
+            rate_limit_per_min: env_or("RATE_LIMIT_PER_MIN", 120),
+            trust_proxy: env_or("TRUST_PROXY", false),
+            // 1 = a single reverse proxy in front; entries left of the trusted ones are client-supplied
+            trusted_proxy_hops: env_or("TRUSTED_PROXY_HOPS", 1),
+
+            ip_anonymization,
+            ip_hash_salt: ip_hash_salt(ip_anonymization),
+            pii_retention_days: env_or("PII_RETENTION_DAYS", 0),
+
+            log_json: env::var("LOG_FORMAT").is_ok_and(|f| f.eq_ignore_ascii_case("json")),
+            prometheus_metrics: env_or("PROMETHEUS_METRICS", false),
+            slos: env::var("SLOS").map(|raw| SloSpec::parse_list(&raw)).unwrap_or_default(),
+            slo_webhook_url: env::var("SLO_WEBHOOK_URL").ok().filter(|u| !u.is_empty()),
+
+            shutdown_drain_secs: env_or("SHUTDOWN_DRAIN_SECS", 30),
+
+            watch_check_secs: env_or("WATCH_CHECK_SECS", 60),
+
+            pageview_refresh_every: env::var("PAGEVIEW_REFRESH").ok().and_then(|s| parse_schedule(&s)),
+            pageview_refresh_top_n: env_or("PAGEVIEW_REFRESH_TOP_N", 5000),
+            pageview_refresh_days: env_or("PAGEVIEW_REFRESH_DAYS", 30),
+            pageview_project: env::var("PAGEVIEW_PROJECT").unwrap_or_else(|_| "en.wikipedia".to_string()),
+            pageview_refresh_concurrency: env_or("PAGEVIEW_REFRESH_CONCURRENCY", 4),
+
+            wiki_summary_api: env::var("WIKI_SUMMARY_API")
+                .unwrap_or_else(|_| "https://en.wikipedia.org/api/rest_v1/page/summary".to_string()),
+            summary_cache_ttl_secs: env_or("SUMMARY_CACHE_TTL_SECS", 86_400),
+            // Least recently fetched entries go first
+            summary_cache_max_entries: env_or("SUMMARY_CACHE_MAX_ENTRIES", 50_000),
+
+            thumbnail_fetch: env_or("THUMBNAIL_FETCH", false),
+            wiki_action_api: env::var("WIKI_ACTION_API")
+                .unwrap_or_else(|_| "https://en.wikipedia.org/w/api.php".to_string()),
+            thumbnail_width: env_or("THUMBNAIL_WIDTH", 320),
+
+            embedder_backend: env_or("EMBEDDER_BACKEND", EmbedderBackend::RustBert),
+            device: env_or("DEVICE", Device::Cpu),
+            onnx_model_dir: env::var("ONNX_MODEL_DIR").unwrap_or_else(|_| "./models/all-MiniLM-L6-v2-onnx".to_string()),
+            embedder_url: env::var("EMBEDDER_URL").ok(),
+            embedder_api_key: env::var("EMBEDDER_API_KEY").ok(),
+
+            index_backend: env_strict("INDEX_BACKEND", IndexBackend::Auto),
+            // FAISS replicas each hold a full copy of the index; flat and HNSW ones share one
+            index_replicas: env_or("INDEX_REPLICAS", 2),
+            // Queries arriving within the window are encoded in one model call
+            inference_workers: env_or("INFERENCE_WORKERS", 1),
+            inference_batch_window_ms: env_or("INFERENCE_BATCH_WINDOW_MS", 5),
+            inference_max_batch: env_or("INFERENCE_MAX_BATCH", 32),
+            // Batch traffic (research, exports, jobs) also yields to queued interactive requests
+            interactive_concurrency: env_or("INTERACTIVE_CONCURRENCY", 16),
+            batch_concurrency: env_or("BATCH_CONCURRENCY", 2),
+            
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+
+            // No cross-origin access unless opted in; the Vite dev server proxies /api
+            allowed_origins: OriginPolicy::parse(&env::var("ALLOWED_ORIGINS").unwrap_or_default()),
+            admin_allowed_origins: OriginPolicy::parse(&env::var("ADMIN_ALLOWED_ORIGINS").unwrap_or_default()),
+
+            index_path: env::var("INDEX_PATH").unwrap_or_else(|_| default_index.to_string()),
+            metadata_path: env::var("METADATA_PATH").unwrap_or_else(|_| default_meta.to_string()),
+            user_db_path: env::var("USER_DB_PATH").unwrap_or_else(|_| default_user_db.to_string()),
+
+            corpus_name: env::var("CORPUS_NAME").unwrap_or_else(|_| "enwiki".to_string()),
+            corpus_lang: env::var("CORPUS_LANG").map(|l| l.to_lowercase()).unwrap_or_else(|_| "en".to_string()),
+            extra_corpora: env::var("CORPORA")
+                .map(|raw| CorpusSpec::parse_list(&raw))
+                .unwrap_or_default()
+                .into_iter()
+                .chain(
+                    env::var("LANGUAGES")
+                        .map(|raw| {
+                            CorpusSpec::parse_languages(&raw).unwrap_or_else(|e| panic!("Invalid LANGUAGES='{}': {}", raw, e))
+                        })
+                        .unwrap_or_default(),
+                )
+                .collect(),
+        }
+    }
+}
+
+fn load_filter_policy(path: &str) -> FilterPolicy {
+    let parsed = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()));
+
+    match parsed {
+        Ok(policy) => policy,
+        Err(e) => {
+            tracing::warn!("⚠ Could not load filter policy from {}: {}; using defaults", path, e);
+            FilterPolicy::default()
+        }
+    }
+}
+
+/// Cron-style shorthand: `@hourly`, `@daily`, `@weekly` or `@every <n><s|m|h|d>`
+/// (e.g. `@every 6h`). Anything else, including an empty value, disables the job.
+fn parse_schedule(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    let secs = match raw {
+        "" => return None,
+        "@hourly" => 3600,
+        "@daily" => 86_400,
+        "@weekly" => 7 * 86_400,
+        _ => {
+            let every = raw.strip_prefix("@every").map(str::trim);
+            let parsed = every.and_then(|spec| {
+                let (number, unit) = spec.split_at(spec.find(|c: char| !c.is_ascii_digit())?);
+                let unit_secs = match unit {
+                    "s" => 1,
+                    "m" => 60,
+                    "h" => 3600,
+                    "d" => 86_400,
+                    _ => return None,
+                };
+                number.parse::<u64>().ok().map(|n| n * unit_secs)
+            });
+            match parsed.filter(|secs| *secs > 0) {
+                Some(secs) => secs,
+                None => {
+                    tracing::warn!("⚠ Unrecognized schedule '{}', job disabled", raw);
+                    return None;
+                }
+            }
+        }
+    };
+    Some(Duration::from_secs(secs))
+}
+
+/// IP_HASH_SALT, which stops startup when unset with IP_ANONYMIZATION=hash: a
+/// generated salt would change on every restart, and with it every stored hash.
+fn ip_hash_salt(mode: IpAnonymization) -> String {
+    match env::var("IP_HASH_SALT").ok().filter(|salt| !salt.is_empty()) {
+        Some(salt) => salt,
+        None if mode == IpAnonymization::Hash => panic!("IP_HASH_SALT must be set with IP_ANONYMIZATION=hash"),
+        None => String::new(),
+    }
+}
+
+/// Reads and parses an env var, falling back to `default` when unset or malformed.
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env_opt(key).unwrap_or(default)
+}
+
+/// Like `env_or`, but a set value that doesn't parse stops startup instead of
+/// falling back, for settings where a typo would quietly change behavior.
+fn env_strict<T: std::str::FromStr>(key: &str, default: T) -> T
+where
+    T::Err: std::fmt::Display,
+{
+    match env::var(key) {
+        Ok(raw) => raw.parse().unwrap_or_else(|e| panic!("Invalid {}='{}': {}", key, raw, e)),
+        Err(_) => default,
+    }
+}
+
+/// `env_strict` for settings without a default: unset (or empty) is None.
+fn env_strict_opt<T: std::str::FromStr>(key: &str) -> Option<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let raw = env::var(key).ok().filter(|raw| !raw.trim().is_empty())?;
+    Some(raw.parse().unwrap_or_else(|e| panic!("Invalid {}='{}': {}", key, raw, e)))
+}
+
+fn env_opt<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env::var(key).ok().and_then(|v| v.parse().ok())
+}
+
+pub static CONFIG: OnceLock<Config> = OnceLock::new();
+
+pub fn get_config() -> &'static Config {
+    CONFIG.get_or_init(Config::load)
+}
\ No newline at end of file
@@ -0,0 +1,143 @@
+//! The bundled demo corpus: a few hand-picked articles per topic, with rough
+//! monthly pageviews, so clusters, bridges and cross edges have something to show.
+
+/// `(title, monthly pageviews, category)`; the position is the article ID.
+pub const ARTICLES: &[(&str, i64, &str)] = &[
+    // Ancient history
+    ("Roman Empire", 410_000, "Ancient history"),
+    ("Byzantine Empire", 260_000, "Ancient history"),
+    ("Julius Caesar", 330_000, "Ancient history"),
+    ("Augustus", 190_000, "Ancient history"),
+    ("Ancient Greece", 210_000, "Ancient history"),
+    ("Alexander the Great", 300_000, "Ancient history"),
+    ("Ancient Egypt", 280_000, "Ancient history"),
+    ("Cleopatra", 240_000, "Ancient history"),
+    ("Constantinople", 150_000, "Ancient history"),
+    ("Punic Wars", 70_000, "Ancient history"),
+    // Astronomy
+    ("Solar System", 250_000, "Astronomy"),
+    ("Sun", 180_000, "Astronomy"),
+    ("Moon", 200_000, "Astronomy"),
+    ("Mars", 190_000, "Astronomy"),
+    ("Jupiter", 160_000, "Astronomy"),
+    ("Black hole", 310_000, "Astronomy"),
+    ("Milky Way", 140_000, "Astronomy"),
+    ("Big Bang", 170_000, "Astronomy"),
+    ("Galileo Galilei", 150_000, "Astronomy"),
+    ("Hubble Space Telescope", 90_000, "Astronomy"),
+    // Physics
+    ("General relativity", 130_000, "Physics"),
+    ("Quantum mechanics", 200_000, "Physics"),
+    ("Albert Einstein", 520_000, "Physics"),
+    ("Isaac Newton", 310_000, "Physics"),
+    ("Speed of light", 110_000, "Physics"),
+    ("Electromagnetism", 60_000, "Physics"),
+    ("Thermodynamics", 80_000, "Physics"),
+    ("Higgs boson", 75_000, "Physics"),
+    // Biology
+    ("Evolution", 190_000, "Biology"),
+    ("Charles Darwin", 220_000, "Biology"),
+    ("DNA", 230_000, "Biology"),
+    ("Cell (biology)", 120_000, "Biology"),
+    ("Photosynthesis", 140_000, "Biology"),
+    ("Natural selection", 90_000, "Biology"),
+    ("Genetics", 100_000, "Biology"),
+    ("Dinosaur", 260_000, "Biology"),
+    // Music
+    ("Johann Sebastian Bach", 170_000, "Music"),
+    ("Ludwig van Beethoven", 260_000, "Music"),
+    ("Wolfgang Amadeus Mozart", 280_000, "Music"),
+    ("Symphony", 40_000, "Music"),
+    ("Jazz", 120_000, "Music"),
+    ("The Beatles", 450_000, "Music"),
+    ("Rock music", 150_000, "Music"),
+    ("Opera", 80_000, "Music"),
+    // Computing
+    ("Alan Turing", 290_000, "Computing"),
+    ("Turing machine", 70_000, "Computing"),
+    ("Computer", 160_000, "Computing"),
+    ("Artificial intelligence", 480_000, "Computing"),
+    ("Machine learning", 260_000, "Computing"),
+    ("Internet", 200_000, "Computing"),
+    ("World Wide Web", 110_000, "Computing"),
+    ("Ada Lovelace", 180_000, "Computing"),
+    ("Cryptography", 90_000, "Computing"),
+    ("Enigma machine", 120_000, "Computing"),
+    // Geography
+    ("Mediterranean Sea", 110_000, "Geography"),
+    ("Nile", 130_000, "Geography"),
+    ("Alps", 90_000, "Geography"),
+    ("Istanbul", 170_000, "Geography"),
+    ("Rome", 200_000, "Geography"),
+    ("Amazon rainforest", 120_000, "Geography"),
+    // Meta pages, exercising the filter policy
+    ("List of Roman emperors", 95_000, "Lists"),
+    ("List of planets", 30_000, "Lists"),
+];
+
+/// Wikilinks between demo articles, by title.
+pub const LINKS: &[(&str, &str)] = &[
+    ("Roman Empire", "Julius Caesar"),
+    ("Roman Empire", "Augustus"),
+    ("Roman Empire", "Byzantine Empire"),
+    ("Roman Empire", "Punic Wars"),
+    ("Roman Empire", "Rome"),
+    ("Roman Empire", "Mediterranean Sea"),
+    ("Roman Empire", "List of Roman emperors"),
+    ("Byzantine Empire", "Constantinople"),
+    ("Constantinople", "Istanbul"),
+    ("Julius Caesar", "Cleopatra"),
+    ("Julius Caesar", "Augustus"),
+    ("Cleopatra", "Ancient Egypt"),
+    ("Ancient Egypt", "Nile"),
+    ("Alexander the Great", "Ancient Greece"),
+    ("Alexander the Great", "Ancient Egypt"),
+    ("Ancient Greece", "Mediterranean Sea"),
+    ("Punic Wars", "Alps"),
+    ("Augustus", "List of Roman emperors"),
+    ("Solar System", "Sun"),
+    ("Solar System", "Mars"),
+    ("Solar System", "Jupiter"),
+    ("Solar System", "Moon"),
+    ("Solar System", "List of planets"),
+    ("Solar System", "Milky Way"),
+    ("Milky Way", "Black hole"),
+    ("Black hole", "General relativity"),
+    ("Big Bang", "General relativity"),
+    ("Big Bang", "Hubble Space Telescope"),
+    ("Galileo Galilei", "Jupiter"),
+    ("Galileo Galilei", "Isaac Newton"),
+    ("Isaac Newton", "Albert Einstein"),
+    ("Albert Einstein", "General relativity"),
+    ("Albert Einstein", "Quantum mechanics"),
+    ("Albert Einstein", "Speed of light"),
+    ("Speed of light", "Electromagnetism"),
+    ("Quantum mechanics", "Higgs boson"),
+    ("Thermodynamics", "Black hole"),
+    ("Evolution", "Charles Darwin"),
+    ("Evolution", "Natural selection"),
+    ("Evolution", "Dinosaur"),
+    ("Charles Darwin", "Natural selection"),
+    ("DNA", "Genetics"),
+    ("DNA", "Cell (biology)"),
+    ("Genetics", "Evolution"),
+    ("Photosynthesis", "Cell (biology)"),
+    ("Photosynthesis", "Sun"),
+    ("Amazon rainforest", "Photosynthesis"),
+    ("Johann Sebastian Bach", "Wolfgang Amadeus Mozart"),
+    ("Wolfgang Amadeus Mozart", "Ludwig van Beethoven"),
+    ("Wolfgang Amadeus Mozart", "Opera"),
+    ("Ludwig van Beethoven", "Symphony"),
+    ("Jazz", "Rock music"),
+    ("The Beatles", "Rock music"),
+    ("Alan Turing", "Turing machine"),
+    ("Alan Turing", "Enigma machine"),
+    ("Alan Turing", "Artificial intelligence"),
+    ("Enigma machine", "Cryptography"),
+    ("Ada Lovelace", "Computer"),
+    ("Turing machine", "Computer"),
+    ("Artificial intelligence", "Machine learning"),
+    ("Internet", "World Wide Web"),
+    ("Computer", "Internet"),
+    ("Machine learning", "Genetics"),
+];
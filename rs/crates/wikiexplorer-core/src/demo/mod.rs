@@ -0,0 +1,208 @@
+//! `wikiexplorer --demo`: generates a tiny corpus on first run and points the
+//! config at it, so the server can be tried without downloading a Wikipedia
+//! dump. Titles are embedded with the regular model and EMBEDDER_BACKEND into a
+//! Flat index, an HNSW one in builds without FAISS, or a plain `.flat` matrix
+//! in builds with neither.
+
+mod corpus;
+
+use crate::categories::ensure_category_table;
+use crate::index::manifest::IndexManifest;
+use crate::ingest::links::{pagerank, pagerank_scale, DEFAULT_DAMPING};
+use crate::search::embedder::{start_embedder, EmbeddingModel};
+use crate::search::similarity::{normalize, Metric};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+#[cfg(feature = "faiss")]
+const INDEX_FILE: &str = "index.faiss";
+#[cfg(all(not(feature = "faiss"), feature = "hnsw"))]
+const INDEX_FILE: &str = "index.hnsw";
+#[cfg(all(not(feature = "faiss"), not(feature = "hnsw")))]
+const INDEX_FILE: &str = "index.flat";
+const METADATA_FILE: &str = "metadata.db";
+const USER_DB_FILE: &str = "user.db";
+const PAGERANK_ITERATIONS: usize = 100;
+const PAGERANK_TOLERANCE: f64 = 1e-9;
+
+/// Points the config at the demo corpus in `dir` and enables the optional
+/// features; explicitly set variables still win. Sets environment variables,
+/// so it must run before the first `get_config()` and before the tokio
+/// runtime (or any other thread) is started.
+pub fn configure_env(dir: &Path) {
+    let defaults = [
+        ("INDEX_PATH", dir.join(INDEX_FILE).display().to_string()),
+        ("METADATA_PATH", dir.join(METADATA_FILE).display().to_string()),
+        ("USER_DB_PATH", dir.join(USER_DB_FILE).display().to_string()),
+        ("CORPUS_NAME", "demo".to_string()),
+        ("INDEX_REPLICAS", "1".to_string()),
+        ("SEMANTIC_CACHE", "true".to_string()),
+        ("RESPONSE_CACHE", "true".to_string()),
+        ("RESCORE_TOP_N", "50".to_string()),
+    ];
+    for (key, value) in defaults {
+        if std::env::var_os(key).is_none() {
+            std::env::set_var(key, value);
+        }
+    }
+}
+
+/// Builds the demo metadata DB and index in `dir` unless both already exist.
+pub async fn prepare(dir: &Path) -> anyhow::Result<()> {
+    let index_path = dir.join(INDEX_FILE);
+    let metadata_path = dir.join(METADATA_FILE);
+    if index_path.exists() && metadata_path.exists() {
+        info!("✓ Demo corpus found in {}", dir.display());
+        return Ok(());
+    }
+
+    info!("Generating demo corpus ({} articles) in {}...", corpus::ARTICLES.len(), dir.display());
+    std::fs::create_dir_all(dir)?;
+    // Half-written leftovers from an interrupted run
+    let _ = std::fs::remove_file(&index_path);
+    let _ = std::fs::remove_file(&metadata_path);
+
+    write_metadata(&metadata_path).await?;
+    write_index(&index_path).await?;
+
+    info!("✓ Demo corpus ready");
+    Ok(())
+}
+
+async fn write_metadata(path: &Path) -> anyhow::Result<()> {
+    let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", path.display())).await?;
+
+    sqlx::query(
+        "CREATE TABLE articles (
+            article_id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            pagerank REAL,
+            pageviews INTEGER,
+            backlinks INTEGER,
+            wikidata_qid TEXT
+        )",
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        "CREATE TABLE links (
+            source_id INTEGER NOT NULL,
+            target_id INTEGER NOT NULL,
+            PRIMARY KEY (source_id, target_id)
+        )",
+    )
+    .execute(&pool)
+    .await?;
+    ensure_category_table(&pool).await?;
+
+    let ids: HashMap<&str, i64> = corpus::ARTICLES
+        .iter()
+        .enumerate()
+        .map(|(i, (title, _, _))| (*title, i as i64))
+        .collect();
+    let links: Vec<(i64, i64)> = corpus::LINKS
+        .iter()
+        .map(|(source, target)| {
+            let id = |title: &str| {
+                ids.get(title)
+                    .copied()
+                    .ok_or_else(|| anyhow::anyhow!("Demo link to unknown article '{}'", title))
+            };
+            Ok((id(source)?, id(target)?))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    // The demo links read both ways
+    let n = corpus::ARTICLES.len();
+    let edges: Vec<(u32, u32)> =
+        links.iter().flat_map(|&(a, b)| [(a as u32, b as u32), (b as u32, a as u32)]).collect();
+    let rank = pagerank(&vec![true; n], &edges, DEFAULT_DAMPING, PAGERANK_ITERATIONS, PAGERANK_TOLERANCE);
+    let scale = pagerank_scale(&rank);
+    let mut backlinks = vec![0i64; corpus::ARTICLES.len()];
+    for &(_, target) in &links {
+        backlinks[target as usize] += 1;
+    }
+
+    let mut tx = pool.begin().await?;
+    for (i, (title, pageviews, category)) in corpus::ARTICLES.iter().enumerate() {
+        sqlx::query("INSERT INTO articles (article_id, title, pagerank, pageviews, backlinks) VALUES (?, ?, ?, ?, ?)")
+            .bind(i as i64)
+            .bind(title)
+            .bind(rank[i] * scale)
+            .bind(pageviews)
+            .bind(backlinks[i])
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO article_categories (article_id, category) VALUES (?, ?)")
+            .bind(i as i64)
+            .bind(category)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for (source, target) in &links {
+        sqlx::query("INSERT OR IGNORE INTO links (source_id, target_id) VALUES (?, ?)")
+            .bind(source)
+            .bind(target)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    pool.close().await;
+
+    info!("✓ Wrote {} ({} articles, {} links)", path.display(), corpus::ARTICLES.len(), links.len());
+    Ok(())
+}
+
+/// Embeds every title and writes a Flat index whose positions are the article IDs.
+async fn write_index(path: &Path) -> anyhow::Result<()> {
+    let worker = start_embedder(&EmbeddingModel::default(), 1, Duration::from_millis(5), 32)?;
+    let titles = corpus::ARTICLES.iter().map(|(title, _, _)| title.to_string()).collect();
+    let mut embeddings = worker.encode_batch(titles).await?;
+    for embedding in &mut embeddings {
+        normalize(embedding);
+    }
+    let vectors: Vec<f32> = embeddings.into_iter().flatten().collect();
+    tokio::task::spawn_blocking(move || worker.shutdown()).await?;
+
+    let dim = (vectors.len() / corpus::ARTICLES.len()) as u32;
+    let path = path.display().to_string();
+    let (factory, metric, ntotal) = save_index(vectors, dim, &path)?;
+    IndexManifest::new(factory, dim, metric, ntotal, 0).save(&path)?;
+
+    info!("✓ Wrote {} ({} vectors, dim={})", path, ntotal, dim);
+    Ok(())
+}
+
+#[cfg(feature = "faiss")]
+fn save_index(vectors: Vec<f32>, dim: u32, path: &str) -> anyhow::Result<(&'static str, Metric, u64)> {
+    use crate::utils::errors::AppError;
+    use faiss::{index_factory, Index, MetricType};
+
+    let mut index = index_factory(dim, "Flat", MetricType::InnerProduct).map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    index.add(&vectors).map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))?;
+    faiss::write_index(&index, path).map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    Ok(("Flat", Metric::InnerProduct, index.ntotal()))
+}
+
+#[cfg(all(not(feature = "faiss"), feature = "hnsw"))]
+fn save_index(vectors: Vec<f32>, dim: u32, path: &str) -> anyhow::Result<(&'static str, Metric, u64)> {
+    use crate::search::hnsw::HnswIndex;
+    use crate::search::vector_index::VectorIndex;
+
+    let index = HnswIndex::build(vectors, dim, &Default::default());
+    index.save(path)?;
+    Ok(("hnsw_rs,M=32", index.metric(), index.ntotal()))
+}
+
+#[cfg(all(not(feature = "faiss"), not(feature = "hnsw")))]
+fn save_index(vectors: Vec<f32>, dim: u32, path: &str) -> anyhow::Result<(&'static str, Metric, u64)> {
+    use crate::search::flat::FlatIndex;
+    use crate::search::vector_index::VectorIndex;
+
+    let index = FlatIndex::from_vectors(vectors, dim, Metric::InnerProduct);
+    index.save(path)?;
+    Ok(("Flat", Metric::InnerProduct, index.ntotal()))
+}
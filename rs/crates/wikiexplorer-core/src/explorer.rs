@@ -0,0 +1,164 @@
+//! Embedding + search facade for CLIs, batch jobs and desktop apps that don't
+//! run the HTTP server. Wraps the same engine, ranking pipeline and cross-edge
+//! computation the server uses, configured from the usual environment
+//! variables (INDEX_PATH, METADATA_PATH, ...).
+//!
+//! A [`WikiExplorer`] is one corpus and the engine embedding its queries;
+//! further corpora opened from it share the loaded model. IDs going in and out
+//! are public IDs, the same ones the HTTP API uses (see
+//! [`crate::index::stable_ids`]).
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), wikiexplorer_core::utils::errors::AppError> {
+//! use wikiexplorer_core::{SearchOptions, WikiExplorer};
+//!
+//! let explorer = WikiExplorer::open().await?;
+//! let graph = explorer.search("Byzantine Empire", &SearchOptions::default()).await?;
+//! for result in &graph.results {
+//!     println!("{:.3} {}", result.score_float, result.title);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::{get_config, CorpusSpec};
+use crate::search::corpus::Corpus;
+use crate::search::cross_edges::{calculate_global_cross_edges, EdgeResult};
+use crate::search::engine::SearchEngine;
+use crate::search::pipeline::{rank_candidates, Hydration, RankOptions, SearchResult};
+use crate::signals::SignalRegistry;
+use crate::utils::errors::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// Options for [`WikiExplorer::search`].
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Results to return (default: RESULTS_TO_RETURN)
+    pub k: Option<usize>,
+    /// Skip this many ranked results
+    pub offset: usize,
+    /// Public IDs already on the graph; cross edges connect results to them
+    pub context: Vec<i64>,
+    /// Skip the cross-edge computation entirely
+    pub skip_cross_edges: bool,
+    /// Retrieval, filtering and scoring options (`rank.context` in public IDs too)
+    pub rank: RankOptions,
+}
+
+/// Ranked results for one query and the edges between them: the nodes and
+/// edges a frontend adds to its graph.
+#[derive(Clone, Serialize)]
+pub struct RankedGraph {
+    /// The requested page of results, best first
+    pub results: Vec<SearchResult>,
+    /// Semantic edges among the results and to `SearchOptions::context`
+    pub cross_edges: Vec<EdgeResult>,
+    /// Size of the full ranked pool
+    pub total_results: usize,
+    /// `Partial` when metadata hydration failed and results carry no titles
+    pub hydration: Hydration,
+    /// The query embedding
+    #[serde(skip)]
+    pub query_vector: Vec<f32>,
+}
+
+/// One searchable corpus (index + metadata DB) and the engine whose model
+/// embeds its queries. Cheap to clone.
+#[derive(Clone)]
+pub struct WikiExplorer {
+    engine: Arc<SearchEngine>,
+    corpus: Corpus,
+}
+
+impl WikiExplorer {
+    /// Loads the model, the index at INDEX_PATH and the metadata DB at METADATA_PATH.
+    pub async fn open() -> Result<Self, AppError> {
+        let config = get_config();
+        let engine = tokio::task::spawn_blocking(SearchEngine::new)
+            .await
+            .map_err(|e| AppError::Anyhow(e.into()))??;
+        let db = SqlitePool::connect(&format!("sqlite:{}", config.metadata_path)).await?;
+        let signals = SignalRegistry::load(&db).await;
+
+        let corpus = Corpus {
+            name: config.corpus_name.clone(),
+            index: engine.index(),
+            db,
+            signals: Arc::new(signals),
+            lang: Some(config.corpus_lang.clone()),
+            model: None,
+        };
+        Ok(Self::new(Arc::new(engine), corpus))
+    }
+
+    /// Searches `corpus` with an already loaded engine (which supplies the model).
+    pub fn new(engine: Arc<SearchEngine>, corpus: Corpus) -> Self {
+        Self { engine, corpus }
+    }
+
+    /// Loads another index + metadata DB pair (e.g. one of CORPORA). Its
+    /// queries use the same model unless the spec names one the engine loaded
+    /// (LANGUAGES / MODEL_<LANG>).
+    pub async fn open_corpus(&self, spec: &CorpusSpec) -> Result<Self, AppError> {
+        let corpus = Corpus::load(spec).await?;
+        Ok(Self::new(Arc::clone(&self.engine), corpus))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.corpus.name
+    }
+
+    pub fn engine(&self) -> &Arc<SearchEngine> {
+        &self.engine
+    }
+
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    /// Embeds `text` with the corpus's sentence model (same vectors as the index).
+    pub async fn embed(&self, text: &str) -> Result<Vec<f32>, AppError> {
+        self.engine.encode_query_with(self.corpus.model.as_deref(), text).await
+    }
+
+    /// Ranks the corpus for `query` and computes cross edges for the returned page.
+    pub async fn search(&self, query: &str, options: &SearchOptions) -> Result<RankedGraph, AppError> {
+        let config = get_config();
+        let query_clean = query.replace('_', " ");
+        let query_vector = self.embed(&query_clean).await?;
+
+        let ids = &self.corpus.index.ids;
+        let rank = RankOptions { context: ids.articles_of(&options.rank.context), ..options.rank.clone() };
+        let (ranked, hydration) = rank_candidates(&self.corpus, &rank, &query_clean, &query_vector).await?;
+        let total_results = ranked.len();
+        let k = options.k.unwrap_or(config.results_to_return);
+        let mut results: Vec<SearchResult> = ranked.into_iter().skip(options.offset).take(k).collect();
+        results.iter_mut().for_each(|r| r.id = ids.public(r.id));
+
+        // Edges need titles, which partial results don't have
+        let cross_edges = if options.skip_cross_edges || hydration == Hydration::Partial {
+            vec![]
+        } else {
+            let result_ids: Vec<i64> = results.iter().map(|r| r.id).collect();
+            self.cross_edges(&result_ids, &options.context, config.cross_edge_threshold as f32).await?
+        };
+
+        Ok(RankedGraph {
+            results,
+            cross_edges,
+            total_results,
+            hydration,
+            query_vector,
+        })
+    }
+
+    /// Semantic edges among `ids` and between `ids` and `context` (public IDs)
+    /// scoring at least `threshold` (cosine). IDs this corpus doesn't know are ignored.
+    pub async fn cross_edges(&self, ids: &[i64], context: &[i64], threshold: f32) -> Result<Vec<EdgeResult>, AppError> {
+        let index = &self.corpus.index;
+        let (ids, context) = (index.ids.articles_of(ids), index.ids.articles_of(context));
+        calculate_global_cross_edges(index, &self.corpus.db, &ids, &context, threshold).await
+    }
+}
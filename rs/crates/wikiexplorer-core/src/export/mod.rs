@@ -0,0 +1,188 @@
+use std::fmt::Write;
+
+/// Graph serialization for external tools (Gephi, yEd, Graphviz).
+#[derive(Debug, Default)]
+pub struct ExportGraph {
+    pub nodes: Vec<ExportNode>,
+    pub edges: Vec<ExportEdge>,
+}
+
+#[derive(Debug)]
+pub struct ExportNode {
+    pub id: i64,
+    pub title: String,
+    pub depth: Option<usize>,
+    pub score: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct ExportEdge {
+    pub source: i64,
+    pub target: i64,
+    pub score: f32,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    GraphMl,
+    Gexf,
+    Dot,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "graphml" => Some(Self::GraphMl),
+            "gexf" => Some(Self::Gexf),
+            "dot" | "gv" | "graphviz" => Some(Self::Dot),
+            _ => None,
+        }
+    }
+
+    /// Picks a format from an `Accept` header; `None` if it names none of ours.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',').find_map(|part| {
+            match part.split(';').next().unwrap_or("").trim() {
+                "application/graphml+xml" => Some(Self::GraphMl),
+                "application/gexf+xml" => Some(Self::Gexf),
+                "text/vnd.graphviz" => Some(Self::Dot),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::GraphMl => "application/graphml+xml",
+            Self::Gexf => "application/gexf+xml",
+            Self::Dot => "text/vnd.graphviz",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::GraphMl => "graphml",
+            Self::Gexf => "gexf",
+            Self::Dot => "dot",
+        }
+    }
+}
+
+impl ExportGraph {
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::GraphMl => self.to_graphml(),
+            ExportFormat::Gexf => self.to_gexf(),
+            ExportFormat::Dot => self.to_dot(),
+        }
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n",
+            "  <key id=\"depth\" for=\"node\" attr.name=\"depth\" attr.type=\"int\"/>\n",
+            "  <key id=\"nscore\" for=\"node\" attr.name=\"score\" attr.type=\"double\"/>\n",
+            "  <key id=\"escore\" for=\"edge\" attr.name=\"score\" attr.type=\"double\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <graph id=\"wikiexplorer\" edgedefault=\"undirected\">\n",
+        ));
+
+        for node in &self.nodes {
+            let _ = write!(out, "    <node id=\"n{}\"><data key=\"title\">{}</data>", node.id, xml_escape(&node.title));
+            if let Some(depth) = node.depth {
+                let _ = write!(out, "<data key=\"depth\">{}</data>", depth);
+            }
+            if let Some(score) = node.score {
+                let _ = write!(out, "<data key=\"nscore\">{}</data>", score);
+            }
+            out.push_str("</node>\n");
+        }
+        for edge in &self.edges {
+            let _ = write!(
+                out,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"escore\">{}</data>",
+                edge.source, edge.target, edge.score
+            );
+            if let Some(kind) = &edge.kind {
+                let _ = write!(out, "<data key=\"kind\">{}</data>", xml_escape(kind));
+            }
+            out.push_str("</edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    pub fn to_gexf(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n",
+            "  <graph defaultedgetype=\"undirected\">\n",
+            "    <attributes class=\"node\">\n",
+            "      <attribute id=\"0\" title=\"depth\" type=\"integer\"/>\n",
+            "      <attribute id=\"1\" title=\"score\" type=\"double\"/>\n",
+            "    </attributes>\n",
+            "    <attributes class=\"edge\">\n",
+            "      <attribute id=\"0\" title=\"kind\" type=\"string\"/>\n",
+            "    </attributes>\n",
+            "    <nodes>\n",
+        ));
+
+        for node in &self.nodes {
+            let _ = write!(out, "      <node id=\"{}\" label=\"{}\"><attvalues>", node.id, xml_escape(&node.title));
+            if let Some(depth) = node.depth {
+                let _ = write!(out, "<attvalue for=\"0\" value=\"{}\"/>", depth);
+            }
+            if let Some(score) = node.score {
+                let _ = write!(out, "<attvalue for=\"1\" value=\"{}\"/>", score);
+            }
+            out.push_str("</attvalues></node>\n");
+        }
+        out.push_str("    </nodes>\n    <edges>\n");
+        for (i, edge) in self.edges.iter().enumerate() {
+            let _ = write!(
+                out,
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\" weight=\"{}\">",
+                i, edge.source, edge.target, edge.score
+            );
+            if let Some(kind) = &edge.kind {
+                let _ = write!(out, "<attvalues><attvalue for=\"0\" value=\"{}\"/></attvalues>", xml_escape(kind));
+            }
+            out.push_str("</edge>\n");
+        }
+
+        out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph wikiexplorer {\n");
+        for node in &self.nodes {
+            let _ = writeln!(out, "  n{} [label=\"{}\"];", node.id, dot_escape(&node.title));
+        }
+        for edge in &self.edges {
+            let _ = write!(out, "  n{} -- n{} [weight={:.4}", edge.source, edge.target, edge.score);
+            if let Some(kind) = &edge.kind {
+                let _ = write!(out, ", kind=\"{}\"", dot_escape(kind));
+            }
+            out.push_str("];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn xml_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn dot_escape(raw: &str) -> String {
+    raw.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,210 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Undirected, weighted graph over article IDs, for server-side analysis of
+/// saved graphs.
+pub struct Graph {
+    nodes: Vec<i64>,
+    adjacency: HashMap<i64, Vec<(i64, f32)>>,
+}
+
+impl Graph {
+    /// Edges whose endpoints aren't in `nodes` are ignored; duplicates and
+    /// self-loops are dropped.
+    pub fn new(nodes: &[i64], edges: impl IntoIterator<Item = (i64, i64, f32)>) -> Self {
+        let mut adjacency: HashMap<i64, Vec<(i64, f32)>> = nodes.iter().map(|&id| (id, vec![])).collect();
+        let mut seen = HashSet::new();
+        for (a, b, weight) in edges {
+            let key = if a < b { (a, b) } else { (b, a) };
+            if a == b || !adjacency.contains_key(&a) || !adjacency.contains_key(&b) || !seen.insert(key) {
+                continue;
+            }
+            adjacency.get_mut(&a).expect("checked above").push((b, weight));
+            adjacency.get_mut(&b).expect("checked above").push((a, weight));
+        }
+
+        let mut nodes: Vec<i64> = adjacency.keys().cloned().collect();
+        nodes.sort_unstable();
+        Self { nodes, adjacency }
+    }
+
+    pub fn nodes(&self) -> &[i64] {
+        &self.nodes
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.values().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    pub fn neighbors(&self, id: i64) -> &[(i64, f32)] {
+        self.adjacency.get(&id).map(|n| n.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn degree(&self, id: i64) -> usize {
+        self.neighbors(id).len()
+    }
+
+    /// Sum of incident edge weights
+    pub fn weighted_degree(&self, id: i64) -> f32 {
+        self.neighbors(id).iter().map(|(_, w)| w).sum()
+    }
+
+    /// Edges present over edges possible, 0 below two nodes
+    pub fn density(&self) -> f64 {
+        let n = self.nodes.len() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        2.0 * self.edge_count() as f64 / (n * (n - 1.0))
+    }
+
+    /// Share of a node's neighbor pairs that are linked themselves; 0 below degree 2
+    pub fn clustering_coefficient(&self, id: i64) -> f64 {
+        let neighbors = self.neighbors(id);
+        let degree = neighbors.len();
+        if degree < 2 {
+            return 0.0;
+        }
+        let around: HashSet<i64> = neighbors.iter().map(|(n, _)| *n).collect();
+        // Each triangle is seen from both of its other corners
+        let links: usize = neighbors
+            .iter()
+            .map(|(n, _)| self.neighbors(*n).iter().filter(|(m, _)| around.contains(m)).count())
+            .sum();
+        links as f64 / (degree * (degree - 1)) as f64
+    }
+
+    /// Mean clustering coefficient over all nodes (isolated and leaf nodes count as 0)
+    pub fn average_clustering(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        self.nodes.iter().map(|&id| self.clustering_coefficient(id)).sum::<f64>() / self.nodes.len() as f64
+    }
+
+    /// Hops from `id` to the farthest node it reaches, and that node (BFS)
+    pub fn eccentricity(&self, id: i64) -> (usize, i64) {
+        let mut depth = HashMap::from([(id, 0usize)]);
+        let mut queue = VecDeque::from([id]);
+        let mut farthest = (0, id);
+        while let Some(node) = queue.pop_front() {
+            let d = depth[&node];
+            if d > farthest.0 {
+                farthest = (d, node);
+            }
+            for &(next, _) in self.neighbors(node) {
+                if let Entry::Vacant(entry) = depth.entry(next) {
+                    entry.insert(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        farthest
+    }
+
+    /// Component label per node, labels ordered by component size (largest first).
+    pub fn connected_components(&self) -> HashMap<i64, usize> {
+        let mut components: Vec<Vec<i64>> = Vec::new();
+        let mut visited = HashSet::new();
+        for &start in &self.nodes {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &(next, _) in self.neighbors(node) {
+                    if visited.insert(next) {
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        components
+            .into_iter()
+            .enumerate()
+            .flat_map(|(label, members)| members.into_iter().map(move |id| (id, label)))
+            .collect()
+    }
+
+    /// How much a node connects different communities: the number of distinct
+    /// other communities among its neighbors, plus the share of its edges that
+    /// leave its own community. Nodes with no cross-community edges score 0.
+    pub fn bridge_score(&self, id: i64, communities: &HashMap<i64, usize>) -> f32 {
+        let Some(own) = communities.get(&id) else { return 0.0 };
+        let neighbors = self.neighbors(id);
+        let foreign: Vec<usize> = neighbors
+            .iter()
+            .filter_map(|(n, _)| communities.get(n))
+            .filter(|c| *c != own)
+            .cloned()
+            .collect();
+        if foreign.is_empty() {
+            return 0.0;
+        }
+        let distinct = foreign.iter().collect::<HashSet<_>>().len();
+        distinct as f32 + foreign.len() as f32 / neighbors.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Triangle 1-2-3 with 4 hanging off 3
+    fn triangle_with_pendant() -> Graph {
+        Graph::new(&[1, 2, 3, 4], [(1, 2, 1.0), (2, 3, 1.0), (1, 3, 1.0), (3, 4, 1.0)])
+    }
+
+    fn diameter(g: &Graph) -> Option<usize> {
+        g.nodes().iter().map(|&id| g.eccentricity(id).0).max()
+    }
+
+    #[test]
+    fn triangle_with_pendant_metrics() {
+        let g = triangle_with_pendant();
+        assert_eq!(g.edge_count(), 4);
+        assert!((g.density() - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(g.clustering_coefficient(1), 1.0);
+        assert!((g.clustering_coefficient(3) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(g.clustering_coefficient(4), 0.0);
+        assert!((g.average_clustering() - 7.0 / 12.0).abs() < 1e-9);
+        assert_eq!(g.eccentricity(1), (2, 4));
+        assert_eq!(diameter(&g), Some(2));
+    }
+
+    #[test]
+    fn duplicate_self_loop_and_unknown_edges_are_dropped() {
+        let g = Graph::new(&[1, 2], [(1, 2, 0.5), (2, 1, 0.9), (1, 2, 0.7), (1, 1, 1.0), (1, 9, 1.0)]);
+        assert_eq!(g.edge_count(), 1);
+        assert_eq!(g.neighbors(1), &[(2, 0.5)]);
+        assert_eq!(g.degree(2), 1);
+    }
+
+    #[test]
+    fn empty_graph() {
+        let g = Graph::new(&[], []);
+        assert!(g.connected_components().is_empty());
+        assert_eq!(diameter(&g), None);
+        assert_eq!(g.density(), 0.0);
+        assert_eq!(g.average_clustering(), 0.0);
+    }
+
+    #[test]
+    fn components_are_labelled_largest_first() {
+        // Path 1-2-3, pair 4-5 and isolated 6
+        let g = Graph::new(&[1, 2, 3, 4, 5, 6], [(1, 2, 1.0), (2, 3, 1.0), (4, 5, 1.0)]);
+        let labels = g.connected_components();
+        assert_eq!(labels.values().collect::<HashSet<_>>().len(), 3);
+        let largest: Vec<i64> = g.nodes().iter().filter(|id| labels[*id] == 0).cloned().collect();
+        assert_eq!(largest, vec![1, 2, 3]);
+        assert_eq!(labels[&4], labels[&5]);
+        assert_eq!(labels[&6], 2);
+        let isolated: Vec<i64> = g.nodes().iter().filter(|&&id| g.degree(id) == 0).cloned().collect();
+        assert_eq!(isolated, vec![6]);
+    }
+}
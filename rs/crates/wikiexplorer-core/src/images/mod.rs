@@ -0,0 +1,83 @@
+//! Article thumbnails shown on graph nodes. `article_images` is filled by
+//! `wikiexplorer ingest images` or, with THUMBNAIL_FETCH, lazily by the server
+//! from the Wikimedia API. A row with a NULL URL records that the article has
+//! no image, so it isn't looked up again.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+
+/// Resolves a file name to a thumbnail of the given width on any wiki project
+const FILE_PATH_BASE: &str = "https://commons.wikimedia.org/wiki/Special:FilePath";
+
+/// Stored thumbnails of the given articles: `Some(None)` means the article is
+/// known to have no image, absent IDs were never looked up. A DB without the
+/// table has no thumbnails.
+pub async fn fetch_thumbnails(pool: &SqlitePool, ids: &[i64]) -> Result<HashMap<i64, Option<String>>, AppError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let params = placeholders(ids.len());
+    let sql = format!("SELECT article_id, thumbnail_url FROM article_images WHERE article_id IN ({})", params);
+    let mut query = sqlx::query_as::<_, (i64, Option<String>)>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+
+    match with_deadline("thumbnails", query.fetch_all(pool)).await {
+        Ok(rows) => Ok(rows.into_iter().collect()),
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => Ok(HashMap::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Inserts or replaces `(article_id, thumbnail_url)` rows in one transaction.
+pub async fn store_thumbnails(pool: &SqlitePool, rows: &[(i64, Option<String>)]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for (id, url) in rows {
+        sqlx::query(
+            "INSERT OR REPLACE INTO article_images (article_id, thumbnail_url, fetched_at)
+             VALUES (?, ?, strftime('%s', 'now'))",
+        )
+        .bind(id)
+        .bind(url)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+pub async fn ensure_images_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS article_images (
+            article_id INTEGER PRIMARY KEY,
+            thumbnail_url TEXT,
+            fetched_at INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Thumbnail URL of an image file ("Einstein_1921.jpg" or "File:Einstein 1921.jpg").
+pub fn file_thumbnail_url(file: &str, width: u32) -> String {
+    let name = file.trim();
+    let name = name.strip_prefix("File:").or_else(|| name.strip_prefix("Image:")).unwrap_or(name);
+    format!("{}/{}?width={}", FILE_PATH_BASE, encode_title(name), width)
+}
+
+/// Titles go into a path segment: underscores for spaces, everything else percent-encoded.
+pub fn encode_title(title: &str) -> String {
+    let mut encoded = String::with_capacity(title.len());
+    for byte in title.replace(' ', "_").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,101 @@
+use crate::cli::BenchArgs;
+use crate::search::engine::SearchEngine;
+use crate::search::similarity::{similarity_into, similarity_scalar, unit_matrix, BACKEND};
+use ndarray::Array2;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Hammers `SearchEngine::search_index` from several threads and reports
+/// throughput and latency percentiles. Run once with `INDEX_REPLICAS=1` and
+/// once with more replicas to see what the pool buys on this machine.
+pub fn run(args: BenchArgs) -> anyhow::Result<()> {
+    if let Some(n) = args.similarity {
+        return run_similarity(n.max(1), args.queries.clamp(1, 100));
+    }
+    let engine = SearchEngine::new()?;
+    let concurrency = args.concurrency.max(1);
+    let per_thread = (args.queries / concurrency).max(1);
+
+    info!(
+        "Benchmark: {} threads x {} searches, k={}, replicas={}",
+        concurrency, per_thread, args.k, engine.index().pool.len()
+    );
+
+    let start = Instant::now();
+    let mut latencies: Vec<Duration> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..concurrency)
+            .map(|t| {
+                let engine = &engine;
+                scope.spawn(move || {
+                    let mut timings = Vec::with_capacity(per_thread);
+                    for i in 0..per_thread {
+                        let query = synthetic_query(384, (t * per_thread + i) as u64);
+                        let started = Instant::now();
+                        let _ = engine.search_index(&query, args.k, None);
+                        timings.push(started.elapsed());
+                    }
+                    timings
+                })
+            })
+            .collect();
+
+        handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+    });
+    let elapsed = start.elapsed();
+
+    latencies.sort();
+    let pct = |p: f64| latencies[((latencies.len() - 1) as f64 * p) as usize];
+
+    info!("Completed {} searches in {:?}", latencies.len(), elapsed);
+    info!("  throughput: {:.1} searches/s", latencies.len() as f64 / elapsed.as_secs_f64());
+    info!("  p50: {:?}  p95: {:?}  p99: {:?}", pct(0.50), pct(0.95), pct(0.99));
+    Ok(())
+}
+
+/// Cross-edge similarity of `n` × `n` unit vectors: the kernel (best of
+/// `rounds`) against one pass of the scalar loop, and their largest difference.
+fn run_similarity(n: usize, rounds: usize) -> anyhow::Result<()> {
+    let dim = 384;
+    let a = unit_matrix((0..n as u64).map(|i| synthetic_query(dim, i)).collect());
+    let b = unit_matrix((0..n as u64).map(|i| synthetic_query(dim, i + n as u64)).collect());
+    info!("Similarity benchmark: {}x{} matrix, {}-d, {} backend", n, n, dim, BACKEND);
+
+    let started = Instant::now();
+    let reference = similarity_scalar(a.view(), b.view());
+    let scalar = started.elapsed();
+
+    let mut out = Array2::zeros((0, 0));
+    let mut kernel = Duration::MAX;
+    for _ in 0..rounds {
+        let started = Instant::now();
+        similarity_into(a.view(), b.view(), &mut out);
+        kernel = kernel.min(started.elapsed());
+    }
+
+    let max_error = (&out - &reference).iter().fold(0.0f32, |m, d| m.max(d.abs()));
+    info!("  scalar loop: {:?}", scalar);
+    info!("  kernel:      {:?} (best of {})", kernel, rounds);
+    info!(
+        "  speedup: {:.1}x, max difference {:.2e}",
+        scalar.as_secs_f64() / kernel.as_secs_f64().max(1e-9),
+        max_error
+    );
+    Ok(())
+}
+
+/// Deterministic unit-length pseudo-random vector (xorshift), so runs are comparable.
+fn synthetic_query(dim: usize, seed: u64) -> Vec<f32> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut v: Vec<f32> = (0..dim)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 2000) as f32 / 1000.0 - 1.0
+        })
+        .collect();
+
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+    v.iter_mut().for_each(|x| *x /= norm);
+    v
+}
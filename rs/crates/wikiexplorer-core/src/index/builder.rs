@@ -0,0 +1,112 @@
+use crate::cli::BuildArgs;
+use crate::config::get_config;
+use crate::index::manifest::IndexManifest;
+use crate::index::reconstruct_many;
+use crate::index::labels::LabelMap;
+use crate::index::update::copy_sidecars;
+use crate::index::vectors::{vectors_path, VectorStoreWriter};
+use crate::utils::errors::AppError;
+use faiss::{index_factory, Index};
+use tracing::{info, warn};
+
+/// Rebuilds the vectors of an existing (reconstructable) index into a new
+/// FAISS layout described by a factory string, e.g. `IVF4096,PQ64`.
+///
+/// Vector positions are preserved, so labels returned by the new index still
+/// map to the same `article_id`s in the metadata DB.
+pub fn run(args: BuildArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let source_path = args.source.clone().unwrap_or_else(|| config.index_path.clone());
+
+    info!("Loading source index from {}...", source_path);
+    let source = faiss::read_index(&source_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+
+    let dim = source.d();
+    let ntotal = source.ntotal();
+    info!("✓ Source index: {} vectors, dim={}", ntotal, dim);
+
+    if ntotal == 0 {
+        anyhow::bail!("Source index is empty, nothing to build");
+    }
+    if source.reconstruct(0).is_err() {
+        anyhow::bail!("Source index does not support reconstruction; rebuild from a Flat index");
+    }
+
+    info!("Creating index with factory spec '{}'", args.factory);
+    // Keeps the source's metric: an L2 source holds vectors that may not be unit length
+    let mut target = index_factory(dim, &args.factory, source.metric_type())
+        .map_err(|e| AppError::Faiss(format!("Invalid factory spec '{}': {:?}", args.factory, e)))?;
+
+    // 1. Train on an evenly strided sample (IVF/PQ layouts need this, Flat/HNSW don't)
+    let mut trained_on = 0;
+    if !target.is_trained() {
+        let sample_ids = strided_sample(ntotal, args.train_sample);
+        info!("Training on {} sampled vectors...", sample_ids.len());
+
+        let sample = reconstruct_many(&source, &sample_ids)?;
+        target.train(&sample)
+            .map_err(|e| AppError::Faiss(format!("Training failed: {:?}", e)))?;
+        trained_on = sample_ids.len();
+        info!("✓ Training complete");
+    }
+
+    // 2. Populate in batches, keeping positions aligned with the source
+    let labels = LabelMap::load(&source_path)?;
+    let mut sidecar = if args.vectors { Some(VectorStoreWriter::create(&args.output, dim)?) } else { None };
+    let batch_size = args.batch_size.max(1) as u64;
+    let mut start = 0u64;
+    while start < ntotal {
+        let end = (start + batch_size).min(ntotal);
+        let ids: Vec<u64> = (start..end).collect();
+        let batch = reconstruct_many(&source, &ids)?;
+
+        target.add(&batch)
+            .map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))?;
+        if let Some(sidecar) = sidecar.as_mut() {
+            // Rows are article IDs, so positions go through the label map
+            for (&position, vector) in ids.iter().zip(batch.chunks_exact(dim as usize)) {
+                if let Some(article_id) = labels.article_at(position as i64) {
+                    sidecar.put(article_id, vector)?;
+                }
+            }
+        }
+
+        info!("  added {}/{}", end, ntotal);
+        start = end;
+    }
+
+    if target.ntotal() != ntotal {
+        warn!("⚠ Built index has {} vectors, source had {}", target.ntotal(), ntotal);
+    }
+
+    // 3. Write index + manifest (tmp file then rename so readers never see a partial index)
+    let tmp_path = format!("{}.tmp", args.output);
+    faiss::write_index(&target, &tmp_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    std::fs::rename(&tmp_path, &args.output)?;
+
+    match sidecar {
+        Some(sidecar) => {
+            sidecar.finish()?;
+            info!("✓ Wrote exact vectors next to {}", args.output);
+        }
+        // Rows are article IDs, so the source's still apply
+        None if vectors_path(&source_path).exists() => {
+            std::fs::copy(vectors_path(&source_path), vectors_path(&args.output))?;
+        }
+        None => {}
+    }
+    IndexManifest::new(&args.factory, dim, target.metric_type().into(), target.ntotal(), trained_on).save(&args.output)?;
+    // Positions are unchanged, so the source's label map, entries snapshot and stable IDs still apply
+    copy_sidecars(&source_path, &args.output)?;
+
+    info!("✓ Wrote {} ({} vectors, factory={})", args.output, target.ntotal(), args.factory);
+    Ok(())
+}
+
+pub(crate) fn strided_sample(ntotal: u64, sample_size: usize) -> Vec<u64> {
+    let sample_size = (sample_size as u64).min(ntotal).max(1);
+    let stride = ntotal / sample_size;
+    (0..sample_size).map(|i| i * stride).collect()
+}
@@ -0,0 +1,130 @@
+use crate::cli::CalibrateArgs;
+use crate::config::get_config;
+use crate::index::manifest::IndexManifest;
+use crate::search::calibration::Calibration;
+use crate::search::embedder::{start_embedder, EmbeddingModel};
+use crate::search::engine::IndexHandle;
+use crate::search::similarity::normalize;
+use crate::utils::sql::placeholders;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Queries embedded per model call
+const ENCODE_BATCH: usize = 64;
+
+/// Searches every validation query, labels the top `k` hits relevant or not
+/// by title, fits a calibration to the (similarity, relevant) samples and
+/// records it in the index manifest.
+pub async fn run(args: CalibrateArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let index_path = args.index.clone().unwrap_or_else(|| config.index_path.clone());
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+
+    let validation = read_validation(&args.validation)?;
+    if validation.is_empty() {
+        anyhow::bail!("No `query<TAB>title` lines in {}", args.validation.display());
+    }
+    info!("✓ {} validation queries", validation.len());
+
+    let path = index_path.clone();
+    let index = tokio::task::spawn_blocking(move || IndexHandle::load(&path)).await??;
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    // 1. Embed the queries the way search does (unit length)
+    let queries: Vec<String> = validation.keys().cloned().collect();
+    let worker = start_embedder(&EmbeddingModel::default(), config.inference_workers, Duration::from_millis(5), ENCODE_BATCH)?;
+    let mut embeddings = Vec::with_capacity(queries.len());
+    for chunk in queries.chunks(ENCODE_BATCH) {
+        embeddings.extend(worker.encode_batch(chunk.to_vec()).await?);
+    }
+    tokio::task::spawn_blocking(move || worker.shutdown()).await?;
+
+    // 2. Label the hits
+    let mut samples: Vec<(f32, bool)> = Vec::new();
+    let mut missed = 0usize;
+    for (query, mut embedding) in queries.iter().zip(embeddings) {
+        normalize(&mut embedding);
+        let (scores, ids) = index.search(&embedding, args.k, None)?;
+        let titles = hit_titles(&pool, &ids).await?;
+        let relevant = &validation[query];
+
+        let before = samples.iter().filter(|(_, hit)| *hit).count();
+        for (score, id) in scores.into_iter().zip(ids) {
+            if let Some(title) = titles.get(&id) {
+                samples.push((score, relevant.contains(&title_key(title))));
+            }
+        }
+        if samples.iter().filter(|(_, hit)| *hit).count() == before {
+            missed += 1;
+        }
+    }
+    let positives: Vec<f32> = samples.iter().filter(|(_, hit)| *hit).map(|(score, _)| *score).collect();
+    info!("✓ {} labelled hits, {} relevant", samples.len(), positives.len());
+    if missed > 0 {
+        warn!("⚠ {} queries had no relevant article in their top {}", missed, args.k);
+    }
+
+    // 3. Fit
+    let calibration = match args.method.as_str() {
+        "sigmoid" => Calibration::fit_sigmoid(&samples),
+        "minmax" => Calibration::fit_min_max(&positives, args.tail),
+        other => anyhow::bail!("Unknown method '{}', expected sigmoid or minmax", other),
+    };
+    let Some(calibration) = calibration else {
+        anyhow::bail!("Not enough relevant and irrelevant hits to fit a {} calibration", args.method);
+    };
+    info!("✓ Fitted calibration: {}", calibration);
+    for raw in [0.2f32, 0.4, 0.6, 0.8] {
+        info!("  {:.2} -> {:.3}", raw, calibration.apply(raw));
+    }
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    // 4. Record
+    let mut manifest = IndexManifest::load(&index_path).unwrap_or_else(|_| {
+        warn!("No manifest found for {}, creating one", index_path);
+        let ntotal = index.ntotal;
+        IndexManifest::new("unknown", index.dim, index.metric, ntotal, 0)
+    });
+    manifest.calibration = Some(calibration);
+    manifest.save(&index_path)?;
+
+    info!("✓ Calibration written to manifest (SCORE_CALIBRATION overrides it)");
+    Ok(())
+}
+
+/// Relevant titles (normalized) by query.
+fn read_validation(path: &std::path::Path) -> anyhow::Result<HashMap<String, HashSet<String>>> {
+    let mut validation: HashMap<String, HashSet<String>> = HashMap::new();
+    for line in std::fs::read_to_string(path)?.lines() {
+        let Some((query, title)) = line.split_once('\t') else {
+            continue;
+        };
+        let (query, title) = (query.trim(), title.trim());
+        if !query.is_empty() && !title.is_empty() {
+            validation.entry(query.to_string()).or_default().insert(title_key(title));
+        }
+    }
+    Ok(validation)
+}
+
+fn title_key(title: &str) -> String {
+    title.to_lowercase().replace('_', " ")
+}
+
+async fn hit_titles(pool: &SqlitePool, ids: &[i64]) -> anyhow::Result<HashMap<i64, String>> {
+    let ids: Vec<i64> = ids.iter().copied().filter(|id| *id >= 0).collect();
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", placeholders(ids.len()));
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    Ok(query.fetch_all(pool).await?.into_iter().collect())
+}
@@ -0,0 +1,56 @@
+use crate::cli::ConvertArgs;
+use crate::config::get_config;
+use crate::index::manifest::IndexManifest;
+use crate::index::reconstruct_many;
+use crate::index::update::copy_sidecars;
+use crate::search::hnsw::{is_hnsw_path, HnswIndex, HnswParams};
+use crate::search::vector_index::VectorIndex;
+use crate::utils::errors::AppError;
+use faiss::Index;
+use tracing::info;
+
+/// Vectors reconstructed per call while copying
+const BATCH_SIZE: u64 = 50_000;
+
+/// Copies every vector of a FAISS index into an HNSW index, keeping positions,
+/// so the metadata DB, label map and entries snapshot still apply.
+pub fn run(args: ConvertArgs) -> anyhow::Result<()> {
+    if !is_hnsw_path(&args.output) {
+        anyhow::bail!("--output must end in .hnsw, got {}", args.output);
+    }
+    let config = get_config();
+    let source_path = args.source.clone().unwrap_or_else(|| config.index_path.clone());
+
+    info!("Loading source index from {}...", source_path);
+    let source = faiss::read_index(&source_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    let dim = source.d();
+    let ntotal = source.ntotal();
+    info!("✓ Source index: {} vectors, dim={}", ntotal, dim);
+
+    if ntotal > 0 && source.reconstruct(0).is_err() {
+        anyhow::bail!("Source index does not support reconstruction; convert a Flat index");
+    }
+
+    let mut vectors = Vec::with_capacity(ntotal as usize * dim as usize);
+    let mut start = 0u64;
+    while start < ntotal {
+        let end = (start + BATCH_SIZE).min(ntotal);
+        let ids: Vec<u64> = (start..end).collect();
+        vectors.extend(reconstruct_many(&source, &ids)?);
+        info!("  copied {}/{}", end, ntotal);
+        start = end;
+    }
+    drop(source);
+
+    let params = HnswParams { m: args.m, ef_construction: args.ef_construction, ef_search: args.ef_search };
+    info!("Building HNSW graph (M={}, efConstruction={})...", params.m, params.ef_construction);
+    let index = HnswIndex::build(vectors, dim, &params);
+    index.save(&args.output)?;
+
+    IndexManifest::new(&format!("hnsw_rs,M={}", params.m), dim, index.metric(), index.ntotal(), 0).save(&args.output)?;
+    copy_sidecars(&source_path, &args.output)?;
+
+    info!("✓ Wrote {} ({} vectors)", args.output, index.ntotal());
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use crate::cli::BuildArgs;
+use crate::config::get_config;
+use crate::index::builder::strided_sample;
+use crate::index::labels::labels_path;
+use crate::index::manifest::IndexManifest;
+use crate::index::stable_ids::StableIds;
+use crate::index::update::record_entries;
+use crate::index::vectors::VectorStoreWriter;
+use crate::search::embedder::{start_embedder, Embedder, EmbeddingModel, MODEL_VERSION};
+use crate::search::similarity::{normalize, Metric};
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use faiss::{index_factory, Index, MetricType};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// SQLite's default bound-parameter limit is 999
+const IN_CLAUSE_CHUNK: usize = 900;
+
+/// Lead paragraphs are cut to roughly what the model reads anyway (256 word pieces)
+const MAX_LEAD_CHARS: usize = 1000;
+
+/// Builds an index from scratch: embeds article titles (optionally with their
+/// lead paragraphs) from the metadata DB and adds them to a new FAISS layout.
+///
+/// Index positions are article IDs, as the server expects. IDs without a row
+/// (gaps) get zero vectors, which hydration later drops.
+pub async fn run(args: BuildArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    info!("Reading articles from {}...", metadata_path);
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    if args.with_lead && !has_lead_column(&pool).await? {
+        anyhow::bail!("--with-lead needs a `lead` column in the articles table");
+    }
+    let (count, max_id): (i64, Option<i64>) = sqlx::query_as("SELECT COUNT(*), MAX(article_id) FROM articles")
+        .fetch_one(&pool)
+        .await?;
+    let Some(max_id) = max_id.filter(|id| *id >= 0) else {
+        anyhow::bail!("No articles to embed");
+    };
+    let ntotal = max_id as u64 + 1;
+    if count as u64 != ntotal {
+        warn!("⚠ {} articles over {} positions, {} gaps get zero vectors", count, ntotal, ntotal - count as u64);
+    }
+
+    let encode_batch = args.encode_batch.max(1);
+    let worker = start_embedder(&EmbeddingModel::default(), config.inference_workers, Duration::from_millis(5), encode_batch)?;
+    let embedder = ArticleEncoder { pool: &pool, worker: worker.as_ref(), with_lead: args.with_lead, encode_batch };
+
+    // Dimension comes from the model, not a constant
+    let dim = embedder.encode(&["dimension probe".to_string()]).await?[0].len() as u32;
+    info!("✓ Model {} ready (dim={})", MODEL_VERSION, dim);
+
+    info!("Creating index with factory spec '{}'", args.factory);
+    let mut target = index_factory(dim, &args.factory, MetricType::InnerProduct)
+        .map_err(|e| AppError::Faiss(format!("Invalid factory spec '{}': {:?}", args.factory, e)))?;
+
+    // 1. Train on an evenly strided sample of articles, kept so they aren't embedded again below
+    let mut trained_on = 0;
+    let mut sample_vectors: HashMap<i64, Vec<f32>> = HashMap::new();
+    if !target.is_trained() {
+        let sample_ids: Vec<i64> = strided_sample(ntotal, args.train_sample).into_iter().map(|id| id as i64).collect();
+        info!("Embedding {} sampled articles for training...", sample_ids.len());
+        let sample = embedder.vectors_for(&sample_ids, dim).await?;
+        target.train(&sample)
+            .map_err(|e| AppError::Faiss(format!("Training failed: {:?}", e)))?;
+        trained_on = sample_ids.len();
+        sample_vectors = sample_ids.into_iter().zip(sample.chunks_exact(dim as usize).map(<[f32]>::to_vec)).collect();
+        info!("✓ Training complete");
+    }
+
+    // 2. Embed and add position ranges in order
+    let mut sidecar = if args.vectors { Some(VectorStoreWriter::create(&args.output, dim)?) } else { None };
+    let started = Instant::now();
+    let batch_size = args.batch_size.max(1) as u64;
+    let mut start = 0u64;
+    while start < ntotal {
+        let end = (start + batch_size).min(ntotal);
+        let ids: Vec<i64> = (start as i64..end as i64).collect();
+        let batch = embedder.vectors_with(&ids, dim, &mut sample_vectors).await?;
+
+        target.add(&batch)
+            .map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))?;
+        if let Some(sidecar) = sidecar.as_mut() {
+            for (&id, vector) in ids.iter().zip(batch.chunks_exact(dim as usize)) {
+                sidecar.put(id, vector)?;
+            }
+        }
+
+        let rate = end as f64 / started.elapsed().as_secs_f64().max(f64::EPSILON);
+        info!("  embedded {}/{} ({:.0}/s)", end, ntotal, rate);
+        start = end;
+    }
+    tokio::task::spawn_blocking(move || worker.shutdown()).await?;
+
+    // 3. Write vectors + manifest, then the index (tmp file then rename so readers
+    // never see a partial index, nor a new index without its manifest)
+    if let Some(sidecar) = sidecar {
+        sidecar.finish()?;
+        info!("✓ Wrote exact vectors next to {}", args.output);
+    }
+    IndexManifest::new(&args.factory, dim, Metric::InnerProduct, target.ntotal(), trained_on).save(&args.output)?;
+    let tmp_path = format!("{}.tmp", args.output);
+    faiss::write_index(&target, &tmp_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    std::fs::rename(&tmp_path, &args.output)?;
+
+    // Baseline for `index update`; a stale label map from an older index would be wrong here
+    let _ = std::fs::remove_file(labels_path(&args.output));
+    record_entries(&args.output, &metadata_path).await?;
+    StableIds::write(&args.output, &pool).await?;
+
+    info!("✓ Wrote {} ({} vectors, factory={}) in {:?}", args.output, target.ntotal(), args.factory, started.elapsed());
+    Ok(())
+}
+
+pub(crate) struct ArticleEncoder<'a> {
+    pub(crate) pool: &'a SqlitePool,
+    pub(crate) worker: &'a dyn Embedder,
+    pub(crate) with_lead: bool,
+    pub(crate) encode_batch: usize,
+}
+
+impl ArticleEncoder<'_> {
+    /// Row-major vectors for `ids`, zeros for IDs without an article.
+    pub(crate) async fn vectors_for(&self, ids: &[i64], dim: u32) -> anyhow::Result<Vec<f32>> {
+        let texts = self.texts(ids).await?;
+        let present: Vec<(usize, String)> = ids
+            .iter()
+            .enumerate()
+            .filter_map(|(i, id)| texts.get(id).map(|text| (i, text.clone())))
+            .collect();
+
+        let mut flat = vec![0.0f32; ids.len() * dim as usize];
+        for chunk in present.chunks(self.encode_batch) {
+            let chunk_texts: Vec<String> = chunk.iter().map(|(_, text)| text.clone()).collect();
+            let embeddings = self.encode(&chunk_texts).await?;
+            for ((i, _), embedding) in chunk.iter().zip(embeddings) {
+                if embedding.len() != dim as usize {
+                    anyhow::bail!("Model returned dim {} (expected {})", embedding.len(), dim);
+                }
+                flat[i * dim as usize..(i + 1) * dim as usize].copy_from_slice(&embedding);
+            }
+        }
+        Ok(flat)
+    }
+
+    /// [`Self::vectors_for`], taking the vectors of IDs already in `embedded`
+    /// (and dropping them there) instead of encoding them again.
+    pub(crate) async fn vectors_with(
+        &self,
+        ids: &[i64],
+        dim: u32,
+        embedded: &mut HashMap<i64, Vec<f32>>,
+    ) -> anyhow::Result<Vec<f32>> {
+        if embedded.is_empty() {
+            return self.vectors_for(ids, dim).await;
+        }
+        let missing: Vec<i64> = ids.iter().copied().filter(|id| !embedded.contains_key(id)).collect();
+        let fresh = self.vectors_for(&missing, dim).await?;
+        let mut fresh = fresh.chunks_exact(dim as usize);
+
+        let mut flat = Vec::with_capacity(ids.len() * dim as usize);
+        for id in ids {
+            match embedded.remove(id) {
+                Some(vector) => flat.extend_from_slice(&vector),
+                None => flat.extend_from_slice(fresh.next().expect("one vector per missing ID")),
+            }
+        }
+        Ok(flat)
+    }
+
+    /// Submitted together so the backend encodes them in as few model calls as it can.
+    /// Embeddings come back unit length, as the inner-product index expects.
+    pub(crate) async fn encode(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        let mut embeddings = self.worker.encode_batch(texts.to_vec()).await?;
+        for embedding in &mut embeddings {
+            normalize(embedding);
+        }
+        Ok(embeddings)
+    }
+
+    async fn texts(&self, ids: &[i64]) -> anyhow::Result<HashMap<i64, String>> {
+        let (Some(&first), Some(&last)) = (ids.iter().min(), ids.iter().max()) else {
+            return Ok(HashMap::new());
+        };
+        let columns = if self.with_lead { "article_id, title, lead" } else { "article_id, title, NULL" };
+
+        // Position ranges read as one range scan; the strided training sample in IN chunks
+        let mut rows: Vec<(i64, String, Option<String>)> = Vec::new();
+        if (last - first + 1) as usize == ids.len() {
+            let sql = format!("SELECT {} FROM articles WHERE article_id BETWEEN ? AND ?", columns);
+            rows = sqlx::query_as(&sql).bind(first).bind(last).fetch_all(self.pool).await?;
+        } else {
+            for chunk in ids.chunks(IN_CLAUSE_CHUNK) {
+                let params = placeholders(chunk.len());
+                let sql = format!("SELECT {} FROM articles WHERE article_id IN ({})", columns, params);
+                let mut query = sqlx::query_as(&sql);
+                for id in chunk {
+                    query = query.bind(id);
+                }
+                rows.extend(query.fetch_all(self.pool).await?);
+            }
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, title, lead)| (id, article_text(&title, lead.as_deref())))
+            .collect())
+    }
+}
+
+/// What gets embedded: the title as the server encodes queries, plus the lead if given.
+fn article_text(title: &str, lead: Option<&str>) -> String {
+    let title = title.replace('_', " ");
+    match lead.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(lead) => {
+            let lead: String = lead.chars().take(MAX_LEAD_CHARS).collect();
+            format!("{}. {}", title, lead)
+        }
+        None => title,
+    }
+}
+
+async fn has_lead_column(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name = 'lead'")
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
@@ -0,0 +1,188 @@
+//! Precomputed nearest neighbours (`knn precompute`): the top-k articles of
+//! every article in the index, in the metadata DB's `knn_edges` table, keyed by
+//! embedding model and public IDs like `cached_edges`.
+//!
+//! Cross-edges read them before doing any vector math. An article's list is
+//! taken as complete for a threshold below its k-th score: nothing outside the
+//! list should score higher. That holds exactly for Flat indexes; on IVF and
+//! HNSW the lists come from the same approximate search as queries and can miss
+//! a neighbour the search missed. `index update` drops the lists, since added
+//! articles can belong in any of them.
+
+use crate::cli::KnnPrecomputeArgs;
+use crate::config::get_config;
+use crate::search::embedder::MODEL_VERSION;
+use crate::search::engine::IndexHandle;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+pub async fn ensure_knn_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS knn_edges (
+            source_id INTEGER NOT NULL,
+            target_id INTEGER NOT NULL,
+            score REAL NOT NULL,
+            rank INTEGER NOT NULL,
+            model_version TEXT NOT NULL,
+            PRIMARY KEY (model_version, source_id, target_id)
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    // Tables created before lists were keyed by model hold one model's rows
+    let keyed: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('knn_edges') WHERE name = 'model_version' AND pk > 0")
+            .fetch_optional(pool)
+            .await?;
+    if keyed.is_none() {
+        let mut tx = pool.begin().await?;
+        sqlx::query("ALTER TABLE knn_edges RENAME TO knn_edges_old").execute(&mut *tx).await?;
+        sqlx::query(
+            "CREATE TABLE knn_edges (
+                source_id INTEGER NOT NULL,
+                target_id INTEGER NOT NULL,
+                score REAL NOT NULL,
+                rank INTEGER NOT NULL,
+                model_version TEXT NOT NULL,
+                PRIMARY KEY (model_version, source_id, target_id)
+            )",
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("INSERT INTO knn_edges SELECT source_id, target_id, score, rank, model_version FROM knn_edges_old")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DROP TABLE knn_edges_old").execute(&mut *tx).await?;
+        tx.commit().await?;
+    }
+    Ok(())
+}
+
+/// Drops this model's lists, e.g. after the index changed under them.
+pub async fn clear_knn_edges(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    ensure_knn_table(pool).await?;
+    let deleted = sqlx::query("DELETE FROM knn_edges WHERE model_version = ?").bind(MODEL_VERSION).execute(pool).await?;
+    Ok(deleted.rows_affected())
+}
+
+/// This model's neighbour lists of `public_ids` (best first), as (target,
+/// score, model version). Articles without a list are absent; so is
+/// everything when there is no table.
+pub async fn load_knn_edges(
+    pool: &SqlitePool,
+    public_ids: &[i64],
+) -> Result<HashMap<i64, Vec<(i64, f32, String)>>, AppError> {
+    let mut lists: HashMap<i64, Vec<(i64, f32, String)>> = HashMap::new();
+    if public_ids.is_empty() {
+        return Ok(lists);
+    }
+    let params = placeholders(public_ids.len());
+    let sql = format!(
+        "SELECT source_id, target_id, score, model_version FROM knn_edges
+         WHERE model_version = ? AND source_id IN ({}) ORDER BY source_id, rank",
+        params
+    );
+    let mut query = sqlx::query_as::<_, (i64, i64, f32, String)>(&sql).bind(MODEL_VERSION);
+    for id in public_ids {
+        query = query.bind(id);
+    }
+    let rows = match with_deadline("knn edges", query.fetch_all(pool)).await {
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => return Ok(lists),
+        other => other?,
+    };
+    for (source, target, score, model_version) in rows {
+        lists.entry(source).or_default().push((target, score, model_version));
+    }
+    Ok(lists)
+}
+
+/// Recomputes this model's `knn_edges` for the whole index in one
+/// transaction, so readers see either the old lists or all of the new ones.
+pub async fn run(args: KnnPrecomputeArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let index_path = args.index.clone().unwrap_or_else(|| config.index_path.clone());
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+
+    let path = index_path.clone();
+    let index = Arc::new(tokio::task::spawn_blocking(move || IndexHandle::load(&path)).await??);
+    if !index.can_reconstruct {
+        anyhow::bail!("{} can't reconstruct vectors; build it with --vectors or use a Flat/HNSW index", index_path);
+    }
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+    ensure_knn_table(&pool).await?;
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM knn_edges WHERE model_version = ?").bind(MODEL_VERSION).execute(&mut *tx).await?;
+
+    let started = Instant::now();
+    let ntotal = index.ntotal as i64;
+    let articles: Vec<i64> = (0..ntotal).filter_map(|position| index.labels.article_at(position)).collect();
+    info!("Precomputing {} neighbours for {} articles...", args.k, articles.len());
+
+    let mut written = 0usize;
+    for (n, batch) in articles.chunks(args.batch_size.max(1)).enumerate() {
+        let batch = batch.to_vec();
+        let k = args.k;
+        let handle = Arc::clone(&index);
+        let rows = tokio::task::spawn_blocking(move || neighbours(&handle, &batch, k)).await??;
+
+        for (source, target, score, rank) in &rows {
+            sqlx::query(
+                "INSERT OR REPLACE INTO knn_edges (source_id, target_id, score, rank, model_version)
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(index.ids.public(*source))
+            .bind(index.ids.public(*target))
+            .bind(score)
+            .bind(*rank as i64)
+            .bind(MODEL_VERSION)
+            .execute(&mut *tx)
+            .await?;
+        }
+        written += rows.len();
+
+        if (n + 1) % 10 == 0 {
+            info!("  {} / {} articles, {} edges", ((n + 1) * args.batch_size).min(articles.len()), articles.len(), written);
+        }
+    }
+    tx.commit().await?;
+
+    info!("✓ Wrote {} kNN edges for {} articles in {:?}", written, articles.len(), started.elapsed());
+    Ok(())
+}
+
+/// (source, target, score, rank) for every article of `batch`. Scores are
+/// the index's similarities, like search scores.
+fn neighbours(index: &IndexHandle, batch: &[i64], k: usize) -> Result<Vec<(i64, i64, f32, usize)>, AppError> {
+    let mut sources = Vec::with_capacity(batch.len());
+    let mut queries = Vec::new();
+    for &id in batch {
+        // Gaps in the ID range hold zero vectors
+        if let Ok(v) = index.reconstruct(id).map_err(|e| debug!("Skipping article {}: {}", id, e)) {
+            if v.iter().any(|x| *x != 0.0) {
+                queries.extend(v);
+                sources.push(id);
+            }
+        }
+    }
+    if sources.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // One extra for the article itself
+    let results = index.search_many(&queries, k + 1)?;
+    let mut rows = Vec::with_capacity(sources.len() * k);
+    for (&source, (scores, labels)) in sources.iter().zip(results) {
+        let hits = scores.into_iter().zip(labels).filter(|&(_, target)| target >= 0 && target != source);
+        for (rank, (score, target)) in hits.take(k).enumerate() {
+            rows.push((source, target, score, rank));
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Exceptions to "index position == article ID", written by `wikiexplorer index
+/// update`. Lives next to the index as `<name>.labels.json`; an index without
+/// one maps every position to itself.
+///
+/// FAISS can only append, so a re-embedded article gets a new position at the
+/// end (`remapped`) and its old vector is tombstoned, as are vectors of deleted
+/// articles and the zero-vector padding of ID gaps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelMap {
+    /// Positions whose vector belongs to no article
+    #[serde(default)]
+    tombstones: HashSet<i64>,
+    /// Appended position -> article ID
+    #[serde(default)]
+    remapped: HashMap<i64, i64>,
+    /// Article ID -> appended position (inverse of `remapped`)
+    #[serde(skip)]
+    positions: HashMap<i64, i64>,
+}
+
+impl LabelMap {
+    /// A missing sidecar is an identity mapping.
+    pub fn load(index_path: &str) -> anyhow::Result<Self> {
+        let path = labels_path(index_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let mut labels: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        labels.positions = labels.remapped.iter().map(|(&position, &id)| (id, position)).collect();
+        Ok(labels)
+    }
+
+    pub fn save(&self, index_path: &str) -> anyhow::Result<()> {
+        let path = labels_path(index_path);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tombstones.is_empty() && self.remapped.is_empty()
+    }
+
+    pub fn tombstone_count(&self) -> usize {
+        self.tombstones.len()
+    }
+
+    /// Article stored at a FAISS position; None for tombstones.
+    pub fn article_at(&self, position: i64) -> Option<i64> {
+        if self.tombstones.contains(&position) {
+            return None;
+        }
+        Some(self.remapped.get(&position).copied().unwrap_or(position))
+    }
+
+    /// FAISS position holding an article's vector; None if it has none.
+    pub fn position_of(&self, article_id: i64) -> Option<i64> {
+        if let Some(&position) = self.positions.get(&article_id) {
+            return Some(position);
+        }
+        // Its natural position is dead, or holds another (appended) article
+        if self.tombstones.contains(&article_id) || self.remapped.contains_key(&article_id) {
+            return None;
+        }
+        Some(article_id)
+    }
+
+    /// Records that `position` now holds `article_id`, tombstoning its previous vector.
+    pub fn assign(&mut self, position: i64, article_id: i64) {
+        if let Some(old) = self.position_of(article_id) {
+            self.tombstone(old);
+        }
+        if position != article_id {
+            self.remapped.insert(position, article_id);
+            self.positions.insert(article_id, position);
+        }
+    }
+
+    pub fn tombstone(&mut self, position: i64) {
+        if let Some(id) = self.remapped.remove(&position) {
+            self.positions.remove(&id);
+        }
+        self.tombstones.insert(position);
+    }
+}
+
+pub fn labels_path(index_path: &str) -> PathBuf {
+    Path::new(index_path).with_extension("labels.json")
+}
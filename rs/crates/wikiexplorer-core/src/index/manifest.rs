@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::search::calibration::Calibration;
+use crate::search::embedder::MODEL_VERSION;
+use crate::search::similarity::Metric;
+use crate::utils::time::unix_now;
+
+/// Sidecar JSON describing how an index file was produced.
+/// Lives next to the index as `<name>.manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexManifest {
+    pub factory: String,
+    pub dimension: u32,
+    pub metric: String,
+    pub ntotal: u64,
+    pub trained_on: usize,
+    pub model: String,
+    pub created_at: u64,
+    /// Recommended IVF nprobe, written by `wikiexplorer tune`
+    #[serde(default)]
+    pub nprobe: Option<usize>,
+    /// Semantic score calibration, written by `wikiexplorer calibrate`
+    #[serde(default)]
+    pub calibration: Option<Calibration>,
+    /// Last `wikiexplorer index update`, if any
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+}
+
+impl IndexManifest {
+    pub fn new(factory: &str, dimension: u32, metric: Metric, ntotal: u64, trained_on: usize) -> Self {
+        Self {
+            factory: factory.to_string(),
+            dimension,
+            metric: metric.name().to_string(),
+            ntotal,
+            trained_on,
+            model: MODEL_VERSION.to_string(),
+            created_at: unix_now(),
+            nprobe: None,
+            calibration: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn load(index_path: &str) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(manifest_path(index_path))?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+
+    pub fn save(&self, index_path: &str) -> anyhow::Result<()> {
+        let path = manifest_path(index_path);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+pub fn manifest_path(index_path: &str) -> PathBuf {
+    Path::new(index_path).with_extension("manifest.json")
+}
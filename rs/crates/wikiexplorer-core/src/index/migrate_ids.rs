@@ -0,0 +1,584 @@
+//! `index migrate-ids`: rewrites data saved under the old ID scheme (article
+//! IDs, i.e. index positions) to stable IDs, after the index got a
+//! `.stable_ids` sidecar (see [`crate::index::stable_ids`]).
+//!
+//! The mapping is a CSV with a header and `old_id,new_id` rows. It applies to
+//! every table holding article IDs:
+//!
+//! - the metadata DB's caches, `cached_edges` (with `cached_edge_coverage`)
+//!   and `knn_edges`, where rows with an unmapped endpoint are dropped (they
+//!   are recomputed on demand);
+//! - the user DB's data of one corpus: `sessions`, their `session_snapshots`
+//!   and, for the primary corpus, `watches` and `watch_updates`. These can't
+//!   be recomputed, so a single unmapped ID fails the whole migration.
+//!
+//! Each DB is rewritten in one transaction, and nothing is committed unless
+//! both succeed. `--dry-run` reports what would change and rolls back. Rows
+//! already in the current ID space (`id_space`) are left alone, and a mapping
+//! is recorded once applied (`id_migrations`), so running the same one twice
+//! can't map IDs a second time.
+
+use crate::cli::MigrateIdsArgs;
+use crate::config::get_config;
+use crate::index::knn::ensure_knn_table;
+use crate::index::stable_ids::ID_SPACE_STABLE;
+use crate::search::cross_edges::ensure_edge_cache_table;
+use crate::sessions::snapshots::ensure_snapshots_table;
+use crate::sessions::{ensure_sessions_table, SessionGraph};
+use sha2::{Digest, Sha256};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Unmapped IDs listed in the error of a failed migration
+const SHOWN_UNMAPPED: usize = 10;
+
+#[derive(Debug, Default)]
+struct Report {
+    edges: usize,
+    edges_mapped: usize,
+    // Rows with an endpoint missing from the mapping, deleted
+    edges_dropped: usize,
+    // Rows that collapsed onto the same pair
+    edges_merged: usize,
+    coverage: usize,
+    // Rows of nodes missing from the mapping, deleted
+    coverage_dropped: usize,
+    knn: usize,
+    knn_mapped: usize,
+    knn_dropped: usize,
+    sessions: usize,
+    nodes_mapped: usize,
+    corrupt_sessions: usize,
+    snapshots: usize,
+    watches: usize,
+    watch_updates: usize,
+    // IDs of user data missing from the mapping; any fails the migration
+    unmapped: BTreeSet<i64>,
+}
+
+impl Report {
+    /// `id` through `mapping`, noting it when the mapping lacks it
+    fn map(&mut self, mapping: &HashMap<i64, i64>, id: i64) -> i64 {
+        match mapping.get(&id) {
+            Some(&new) => new,
+            None => {
+                self.unmapped.insert(id);
+                id
+            }
+        }
+    }
+}
+
+pub async fn run(args: MigrateIdsArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let user_db_path = args.user_db.clone().unwrap_or_else(|| config.user_db_path.clone());
+    let corpus = args.corpus.clone().unwrap_or_else(|| config.corpus_name.clone());
+
+    // 1. Parse the mapping up front so a malformed file changes nothing
+    let raw = std::fs::read(&args.mapping)?;
+    let digest: String = Sha256::digest(&raw).iter().take(16).map(|b| format!("{:02x}", b)).collect();
+    let mapping = parse_mapping(&raw)?;
+    info!("Parsed {} ID pairs from {}", mapping.len(), args.mapping);
+
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+    ensure_migrations_table(&pool).await?;
+    let applied: Option<i64> =
+        sqlx::query_scalar("SELECT applied_at FROM id_migrations WHERE mapping = ? AND corpus = ?")
+            .bind(&digest)
+            .bind(&corpus)
+            .fetch_optional(&pool)
+            .await?;
+    if let Some(applied_at) = applied {
+        anyhow::bail!("{} was already applied to corpus '{}' (at {})", args.mapping, corpus, applied_at);
+    }
+
+    // A server that never ran has no user DB, and nothing in it to migrate
+    let user_db = if Path::new(&user_db_path).exists() {
+        let user_db = SqlitePool::connect(&format!("sqlite:{}", user_db_path)).await?;
+        ensure_sessions_table(&user_db).await?;
+        ensure_snapshots_table(&user_db).await?;
+        Some(user_db)
+    } else {
+        warn!("⚠ No user DB at {}, only the caches are migrated", user_db_path);
+        None
+    };
+
+    // 2. Rewrite each DB in one transaction
+    ensure_edge_cache_table(&pool).await?;
+    ensure_knn_table(&pool).await?;
+    let mut report = Report::default();
+    let mut tx = pool.begin().await?;
+    if !args.sessions_only {
+        migrate_edges(&mut tx, &mapping, &mut report).await?;
+        migrate_coverage(&mut tx, &mapping, &mut report).await?;
+        migrate_knn(&mut tx, &mapping, &mut report).await?;
+    }
+    let mut user_tx = match &user_db {
+        Some(user_db) => {
+            let mut user_tx = user_db.begin().await?;
+            migrate_sessions(&mut user_tx, &corpus, &mapping, &mut report).await?;
+            // Watches always run against the primary corpus
+            if corpus == config.corpus_name {
+                migrate_watches(&mut user_tx, &mapping, &mut report).await?;
+            }
+            Some(user_tx)
+        }
+        None => None,
+    };
+
+    info!(
+        "cached_edges: {} rows, {} mapped, {} dropped (unmapped endpoint), {} merged",
+        report.edges, report.edges_mapped, report.edges_dropped, report.edges_merged
+    );
+    info!("cached_edge_coverage: {} rows, {} dropped (unmapped node)", report.coverage, report.coverage_dropped);
+    info!("knn_edges: {} rows, {} mapped, {} dropped (unmapped endpoint)", report.knn, report.knn_mapped, report.knn_dropped);
+    info!(
+        "sessions of '{}': {} migrated ({} nodes), {} snapshots, {} watches, {} watch updates",
+        corpus, report.sessions, report.nodes_mapped, report.snapshots, report.watches, report.watch_updates
+    );
+    if report.corrupt_sessions > 0 {
+        warn!("⚠ {} sessions have an unreadable graph and were left alone", report.corrupt_sessions);
+    }
+
+    // 3. Commit only a complete migration
+    if !report.unmapped.is_empty() {
+        let shown: Vec<String> = report.unmapped.iter().take(SHOWN_UNMAPPED).map(|id| id.to_string()).collect();
+        anyhow::bail!(
+            "{} IDs of saved data are missing from the mapping (e.g. {}); nothing was written",
+            report.unmapped.len(),
+            shown.join(", ")
+        );
+    }
+    if args.dry_run {
+        info!("Dry run, nothing written");
+        return Ok(());
+    }
+    sqlx::query("INSERT INTO id_migrations (mapping, corpus) VALUES (?, ?)")
+        .bind(&digest)
+        .bind(&corpus)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(user_tx) = user_tx.take() {
+        user_tx.commit().await?;
+    }
+    tx.commit().await?;
+    info!("✓ Migrated {} and {} to stable IDs", metadata_path, user_db_path);
+    Ok(())
+}
+
+fn parse_mapping(raw: &[u8]) -> anyhow::Result<HashMap<i64, i64>> {
+    let mut reader = csv::Reader::from_reader(raw);
+    let mut mapping = HashMap::new();
+    let mut skipped = 0usize;
+    for record in reader.records() {
+        let record = record?;
+        let parse = |i: usize| record.get(i).and_then(|v| v.trim().parse::<i64>().ok());
+        let (Some(old), Some(new)) = (parse(0), parse(1)) else {
+            skipped += 1;
+            continue;
+        };
+        if let Some(previous) = mapping.insert(old, new) {
+            if previous != new {
+                anyhow::bail!("ID {} maps to both {} and {}", old, previous, new);
+            }
+        }
+    }
+    if mapping.is_empty() {
+        anyhow::bail!("No usable old_id,new_id rows in the mapping");
+    }
+    if skipped > 0 {
+        warn!("⚠ Skipped {} malformed mapping rows", skipped);
+    }
+    Ok(mapping)
+}
+
+async fn ensure_migrations_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS id_migrations (
+            mapping TEXT NOT NULL,
+            corpus TEXT NOT NULL,
+            applied_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            PRIMARY KEY (mapping, corpus)
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Re-keys the cached edges saved under the old IDs; pairs stay ordered
+/// (smaller id, larger id) per model. Edges already cached under stable IDs win.
+async fn migrate_edges(
+    tx: &mut Transaction<'_, Sqlite>,
+    mapping: &HashMap<i64, i64>,
+    report: &mut Report,
+) -> anyhow::Result<()> {
+    let rows: Vec<(i64, i64, f32, String, i64)> = sqlx::query_as(
+        "SELECT source_id, target_id, score, model_version, created_at FROM cached_edges WHERE id_space != ?",
+    )
+    .bind(ID_SPACE_STABLE)
+    .fetch_all(&mut **tx)
+    .await?;
+    report.edges = rows.len();
+
+    let mut migrated: HashMap<(i64, i64, String), (f32, i64)> = HashMap::new();
+    for (source, target, score, model_version, created_at) in rows {
+        let (Some(&a), Some(&b)) = (mapping.get(&source), mapping.get(&target)) else {
+            report.edges_dropped += 1;
+            continue;
+        };
+        report.edges_mapped += 1;
+        if migrated.insert((a.min(b), a.max(b), model_version), (score, created_at)).is_some() {
+            report.edges_merged += 1;
+        }
+    }
+
+    sqlx::query("DELETE FROM cached_edges WHERE id_space != ?")
+        .bind(ID_SPACE_STABLE)
+        .execute(&mut **tx)
+        .await?;
+    for ((source, target, model_version), (score, created_at)) in migrated {
+        sqlx::query(
+            "INSERT OR IGNORE INTO cached_edges (source_id, target_id, score, model_version, created_at, id_space)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(source)
+        .bind(target)
+        .bind(score)
+        .bind(model_version)
+        .bind(created_at)
+        .bind(ID_SPACE_STABLE)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Re-keys the cache coverage saved under the old IDs. Unmapped IDs leave the
+/// covered graph, since their edges were dropped above.
+async fn migrate_coverage(
+    tx: &mut Transaction<'_, Sqlite>,
+    mapping: &HashMap<i64, i64>,
+    report: &mut Report,
+) -> anyhow::Result<()> {
+    let rows: Vec<(i64, f32, String, String, i64)> = sqlx::query_as(
+        "SELECT node_id, threshold, node_ids, model_version, created_at FROM cached_edge_coverage WHERE id_space != ?",
+    )
+    .bind(ID_SPACE_STABLE)
+    .fetch_all(&mut **tx)
+    .await?;
+    report.coverage = rows.len();
+
+    sqlx::query("DELETE FROM cached_edge_coverage WHERE id_space != ?")
+        .bind(ID_SPACE_STABLE)
+        .execute(&mut **tx)
+        .await?;
+    for (node, threshold, node_ids, model_version, created_at) in rows {
+        let Some(&node) = mapping.get(&node) else {
+            report.coverage_dropped += 1;
+            continue;
+        };
+        let mut mapped: Vec<i64> = node_ids
+            .split(',')
+            .filter_map(|id| mapping.get(&id.parse().ok()?).copied())
+            .collect();
+        mapped.sort_unstable();
+        let node_ids = mapped.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+        sqlx::query(
+            "INSERT OR IGNORE INTO cached_edge_coverage (node_id, threshold, node_ids, model_version, created_at, id_space)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(node)
+        .bind(threshold)
+        .bind(node_ids)
+        .bind(model_version)
+        .bind(created_at)
+        .bind(ID_SPACE_STABLE)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Re-keys the neighbour lists. A list that loses an entry is no longer the
+/// article's top-k, so the whole list goes and is recomputed by the next
+/// `knn precompute`.
+async fn migrate_knn(
+    tx: &mut Transaction<'_, Sqlite>,
+    mapping: &HashMap<i64, i64>,
+    report: &mut Report,
+) -> anyhow::Result<()> {
+    let rows: Vec<(i64, i64, f32, i64, String)> =
+        sqlx::query_as("SELECT source_id, target_id, score, rank, model_version FROM knn_edges")
+            .fetch_all(&mut **tx)
+            .await?;
+    report.knn = rows.len();
+
+    // Lists are per model
+    let incomplete: BTreeSet<(&str, i64)> = rows
+        .iter()
+        .filter(|(source, target, ..)| !mapping.contains_key(source) || !mapping.contains_key(target))
+        .map(|(source, _, _, _, model_version)| (model_version.as_str(), *source))
+        .collect();
+
+    sqlx::query("DELETE FROM knn_edges").execute(&mut **tx).await?;
+    for (source, target, score, rank, model_version) in &rows {
+        if incomplete.contains(&(model_version.as_str(), *source)) {
+            report.knn_dropped += 1;
+            continue;
+        }
+        sqlx::query(
+            "INSERT OR IGNORE INTO knn_edges (source_id, target_id, score, rank, model_version) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(mapping[source])
+        .bind(mapping[target])
+        .bind(score)
+        .bind(rank)
+        .bind(model_version)
+        .execute(&mut **tx)
+        .await?;
+        report.knn_mapped += 1;
+    }
+    Ok(())
+}
+
+/// Maps node and edge IDs of the corpus' sessions saved under the old IDs,
+/// and the edges of their snapshots.
+async fn migrate_sessions(
+    tx: &mut Transaction<'_, Sqlite>,
+    corpus: &str,
+    mapping: &HashMap<i64, i64>,
+    report: &mut Report,
+) -> anyhow::Result<()> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, graph FROM sessions WHERE corpus = ? AND id_space != ?")
+        .bind(corpus)
+        .bind(ID_SPACE_STABLE)
+        .fetch_all(&mut **tx)
+        .await?;
+
+    for (id, raw) in rows {
+        let Ok(mut graph) = serde_json::from_str::<SessionGraph>(&raw) else {
+            report.corrupt_sessions += 1;
+            continue;
+        };
+        for node in &mut graph.nodes {
+            node.id = report.map(mapping, node.id);
+        }
+        for edge in &mut graph.edges {
+            edge.source = report.map(mapping, edge.source);
+            edge.target = report.map(mapping, edge.target);
+        }
+        report.nodes_mapped += graph.nodes.len();
+
+        // The thumbnail only holds titles, so it stays valid
+        sqlx::query("UPDATE sessions SET graph = ?, id_space = ? WHERE id = ?")
+            .bind(serde_json::to_string(&graph)?)
+            .bind(ID_SPACE_STABLE)
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+        report.sessions += 1;
+
+        let snapshots: Vec<(i64, String)> = sqlx::query_as("SELECT id, edges FROM session_snapshots WHERE session_id = ?")
+            .bind(&id)
+            .fetch_all(&mut **tx)
+            .await?;
+        for (snapshot_id, raw) in snapshots {
+            let edges: Vec<(i64, i64, f32)> = serde_json::from_str(&raw)
+                .map_err(|e| anyhow::anyhow!("Snapshot {} of session {} is corrupt: {}", snapshot_id, id, e))?;
+            let mut edges: Vec<(i64, i64, f32)> = edges
+                .into_iter()
+                .map(|(a, b, score)| {
+                    let (a, b) = (report.map(mapping, a), report.map(mapping, b));
+                    (a.min(b), a.max(b), score)
+                })
+                .collect();
+            edges.sort_by_key(|&(a, b, _)| (a, b));
+            sqlx::query("UPDATE session_snapshots SET edges = ? WHERE id = ?")
+                .bind(serde_json::to_string(&edges)?)
+                .bind(snapshot_id)
+                .execute(&mut **tx)
+                .await?;
+            report.snapshots += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Maps the last known top-k of every watch and the articles of their updates.
+async fn migrate_watches(
+    tx: &mut Transaction<'_, Sqlite>,
+    mapping: &HashMap<i64, i64>,
+    report: &mut Report,
+) -> anyhow::Result<()> {
+    let has_tables: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('watches', 'watch_updates')",
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+    if has_tables < 2 {
+        return Ok(());
+    }
+
+    let watches: Vec<(String, String)> = sqlx::query_as("SELECT id, last_results FROM watches")
+        .fetch_all(&mut **tx)
+        .await?;
+    for (id, raw) in watches {
+        let results: Vec<i64> = serde_json::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("Watch {} has corrupt results: {}", id, e))?;
+        let results: Vec<i64> = results.into_iter().map(|r| report.map(mapping, r)).collect();
+        sqlx::query("UPDATE watches SET last_results = ? WHERE id = ?")
+            .bind(serde_json::to_string(&results)?)
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+        report.watches += 1;
+    }
+
+    let updates: Vec<(i64, i64)> = sqlx::query_as("SELECT id, article_id FROM watch_updates")
+        .fetch_all(&mut **tx)
+        .await?;
+    for (id, article_id) in updates {
+        sqlx::query("UPDATE watch_updates SET article_id = ? WHERE id = ?")
+            .bind(report.map(mapping, article_id))
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        report.watch_updates += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::stable_ids::ID_SPACE_ARTICLE;
+    use crate::sessions::snapshots::{insert_snapshot, load_snapshot_edges, CorpusVersion};
+    use crate::sessions::{fetch_session, insert_session, SessionEdge, SessionNode};
+
+    fn graph(ids: &[i64], edges: &[(i64, i64)]) -> SessionGraph {
+        SessionGraph {
+            nodes: ids
+                .iter()
+                .map(|&id| SessionNode { id, title: String::new(), x: None, y: None, depth: None })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|&(source, target)| SessionEdge { source, target, score: None, kind: None })
+                .collect(),
+            layout: serde_json::Value::Null,
+        }
+    }
+
+    fn edges_of(graph: &SessionGraph) -> Vec<(i64, i64)> {
+        graph.edges.iter().map(|e| (e.source, e.target)).collect()
+    }
+
+    async fn create(path: &Path) -> SqlitePool {
+        SqlitePool::connect(&format!("sqlite:{}?mode=rwc", path.display())).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn migration_round_trips_and_skips_migrated_rows() {
+        let dir = std::env::temp_dir().join(format!("wikiexplorer-migrate-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (metadata_path, user_db_path, mapping_path) =
+            (dir.join("metadata.db"), dir.join("user.db"), dir.join("mapping.csv"));
+        // Article 3 has no page ID, so it is published as its fallback, -(3 + 1)
+        std::fs::write(&mapping_path, "old_id,new_id\n1,100\n2,200\n3,-4\n").unwrap();
+
+        let metadata = create(&metadata_path).await;
+        ensure_edge_cache_table(&metadata).await.unwrap();
+        for (source, target, id_space) in [(1, 2, ID_SPACE_ARTICLE), (2, 5, ID_SPACE_ARTICLE), (100, 300, ID_SPACE_STABLE)] {
+            sqlx::query("INSERT INTO cached_edges (source_id, target_id, score, id_space) VALUES (?, ?, 0.9, ?)")
+                .bind(source)
+                .bind(target)
+                .bind(id_space)
+                .execute(&metadata)
+                .await
+                .unwrap();
+        }
+        for (node, node_ids) in [(1, "1,2,5"), (5, "1,5")] {
+            sqlx::query(
+                "INSERT INTO cached_edge_coverage (node_id, threshold, node_ids, model_version, id_space)
+                 VALUES (?, 0.5, ?, 'm', ?)",
+            )
+            .bind(node)
+            .bind(node_ids)
+            .bind(ID_SPACE_ARTICLE)
+            .execute(&metadata)
+            .await
+            .unwrap();
+        }
+
+        let user_db = create(&user_db_path).await;
+        ensure_sessions_table(&user_db).await.unwrap();
+        ensure_snapshots_table(&user_db).await.unwrap();
+        insert_session(&user_db, "old", "old", "test", &graph(&[1, 2, 3], &[(1, 2), (2, 3)]), ID_SPACE_ARTICLE, "t")
+            .await
+            .unwrap();
+        // Already migrated: neither its page IDs nor its fallback are in the mapping
+        insert_session(&user_db, "new", "new", "test", &graph(&[100, -4, 7], &[(100, -4)]), ID_SPACE_STABLE, "t")
+            .await
+            .unwrap();
+        let version = CorpusVersion { version: "v1".to_string(), model_version: "m".to_string() };
+        insert_snapshot(&user_db, "old", &version, &[(1, 2, 0.9), (2, 3, 0.5)]).await.unwrap();
+
+        let args = || MigrateIdsArgs {
+            mapping: mapping_path.display().to_string(),
+            metadata: Some(metadata_path.display().to_string()),
+            user_db: Some(user_db_path.display().to_string()),
+            corpus: Some("test".to_string()),
+            sessions_only: false,
+            dry_run: false,
+        };
+        run(args()).await.unwrap();
+
+        let old = fetch_session(&user_db, "old").await.unwrap();
+        assert_eq!(old.graph.node_ids(), vec![100, 200, -4]);
+        assert_eq!(edges_of(&old.graph), vec![(100, 200), (200, -4)]);
+        let new = fetch_session(&user_db, "new").await.unwrap();
+        assert_eq!(new.graph.node_ids(), vec![100, -4, 7]);
+        assert_eq!(edges_of(&new.graph), vec![(100, -4)]);
+        let spaces: Vec<String> = sqlx::query_scalar("SELECT id_space FROM sessions ORDER BY id")
+            .fetch_all(&user_db)
+            .await
+            .unwrap();
+        assert_eq!(spaces, vec![ID_SPACE_STABLE, ID_SPACE_STABLE]);
+
+        let snapshot_id: i64 = sqlx::query_scalar("SELECT id FROM session_snapshots").fetch_one(&user_db).await.unwrap();
+        let snapshot = load_snapshot_edges(&user_db, "old", snapshot_id).await.unwrap();
+        assert_eq!(snapshot, vec![(-4, 200, 0.5), (100, 200, 0.9)]);
+
+        // The edge with an unmapped endpoint is dropped, the stable one kept
+        let cached: Vec<(i64, i64, String)> =
+            sqlx::query_as("SELECT source_id, target_id, id_space FROM cached_edges ORDER BY source_id, target_id")
+                .fetch_all(&metadata)
+                .await
+                .unwrap();
+        assert_eq!(
+            cached,
+            vec![(100, 200, ID_SPACE_STABLE.to_string()), (100, 300, ID_SPACE_STABLE.to_string())]
+        );
+
+        // Unmapped IDs leave the covered graph; an unmapped node loses its row
+        let coverage: Vec<(i64, String, String)> =
+            sqlx::query_as("SELECT node_id, node_ids, id_space FROM cached_edge_coverage")
+                .fetch_all(&metadata)
+                .await
+                .unwrap();
+        assert_eq!(coverage, vec![(100, "100,200".to_string(), ID_SPACE_STABLE.to_string())]);
+
+        // The same mapping can't map the migrated IDs a second time
+        assert!(run(args()).await.is_err());
+        let old_again = fetch_session(&user_db, "old").await.unwrap();
+        assert_eq!(old_again.graph.node_ids(), vec![100, 200, -4]);
+
+        metadata.close().await;
+        user_db.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
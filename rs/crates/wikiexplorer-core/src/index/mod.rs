@@ -0,0 +1,38 @@
+#[cfg(feature = "faiss")]
+use crate::utils::errors::AppError;
+#[cfg(feature = "faiss")]
+use faiss::Index;
+
+// Building, tuning and updating indexes needs FAISS; serving an HNSW index doesn't
+pub mod bench;
+pub mod calibrate;
+#[cfg(feature = "faiss")]
+pub mod builder;
+#[cfg(all(feature = "faiss", feature = "hnsw"))]
+pub mod convert;
+#[cfg(feature = "faiss")]
+pub mod embed;
+pub mod knn;
+pub mod labels;
+pub mod manifest;
+pub mod migrate_ids;
+#[cfg(feature = "faiss")]
+pub mod params;
+pub mod stable_ids;
+#[cfg(feature = "faiss")]
+pub mod tune;
+#[cfg(feature = "faiss")]
+pub mod update;
+pub mod vectors;
+
+/// Reconstructs the vectors at `ids` into one row-major buffer.
+#[cfg(feature = "faiss")]
+pub fn reconstruct_many<I: Index + ?Sized>(index: &I, ids: &[u64]) -> Result<Vec<f32>, AppError> {
+    let mut flat = Vec::with_capacity(ids.len() * index.d() as usize);
+    for &id in ids {
+        let v = index.reconstruct(id)
+            .map_err(|e| AppError::Faiss(format!("Reconstruct {} failed: {:?}", id, e)))?;
+        flat.extend_from_slice(&v);
+    }
+    Ok(flat)
+}
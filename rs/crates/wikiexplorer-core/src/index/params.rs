@@ -0,0 +1,13 @@
+use crate::utils::errors::AppError;
+use faiss::{Index, ParameterSpace};
+
+/// Sets a runtime search parameter (`nprobe`, `efSearch`, ...) through FAISS's
+/// ParameterSpace, which unwraps IDMap/PreTransform wrappers for us.
+/// Fails if the index type has no such parameter.
+pub fn set_search_parameter<I: Index + ?Sized>(index: &mut I, name: &str, value: f64) -> Result<(), AppError> {
+    let space = ParameterSpace::new()
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    space
+        .set_index_parameter(index, name, value)
+        .map_err(|e| AppError::Faiss(format!("Cannot set {}={}: {:?}", name, value, e)))
+}
@@ -0,0 +1,194 @@
+//! Public article IDs that survive index rebuilds.
+//!
+//! Internally an article is its `article_id`, the metadata DB row that index
+//! positions map to, and a rebuild from a newer dump renumbers those. Clients,
+//! saved graphs and the edge cache instead see a stable ID (Wikipedia's
+//! `page_id`), recorded per article next to the index as `<name>.stable_ids`:
+//! a raw little-endian i64 per article ID, -1 where the article has none.
+//! Articles without a page ID are published as `-(article_id + 1)`: negative,
+//! so they can never collide with a page ID.
+//!
+//! Written at `index build --from-metadata` / `index update` from the
+//! metadata DB's `page_id` column, or for an existing index with `index
+//! stable-ids`. Without the sidecar both ID spaces are the same.
+
+use crate::cli::StableIdsArgs;
+use crate::config::get_config;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Column of `articles` holding the stable ID
+pub const STABLE_ID_COLUMN: &str = "page_id";
+
+const NONE: i64 = -1;
+
+/// `id_space` of IDs saved while public IDs were article IDs (no sidecar);
+/// they only stay valid for the index build they came from
+pub const ID_SPACE_ARTICLE: &str = "article_id";
+/// `id_space` of IDs saved under this sidecar scheme (page IDs, negative
+/// fallbacks). Bumped whenever the mapping changes meaning.
+pub const ID_SPACE_STABLE: &str = "page_id/2";
+
+pub fn stable_ids_path(index_path: &str) -> PathBuf {
+    Path::new(index_path).with_extension("stable_ids")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StableIds {
+    // Indexed by article ID
+    stable: Vec<i64>,
+    // (stable ID, article ID), sorted
+    articles: Vec<(i64, i64)>,
+}
+
+impl StableIds {
+    /// The sidecar of `index_path`; identity when there is none.
+    pub fn load(index_path: &str) -> anyhow::Result<Self> {
+        let path = stable_ids_path(index_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read(&path)?;
+        if raw.len() % 8 != 0 {
+            anyhow::bail!("{} is truncated", path.display());
+        }
+        let stable: Vec<i64> = raw
+            .chunks_exact(8)
+            .map(|b| i64::from_le_bytes(b.try_into().expect("8-byte chunk")))
+            .collect();
+        Ok(Self::from_stable(stable))
+    }
+
+    fn from_stable(stable: Vec<i64>) -> Self {
+        let mut articles: Vec<(i64, i64)> = stable
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s != NONE)
+            .map(|(article_id, &s)| (s, article_id as i64))
+            .collect();
+        articles.sort_unstable();
+        let before = articles.len();
+        articles.dedup_by_key(|(s, _)| *s);
+        if articles.len() != before {
+            warn!("⚠ {} articles share a stable ID with another; the lowest article ID wins", before - articles.len());
+        }
+        Self { stable, articles }
+    }
+
+    /// Reads `articles.page_id` of the metadata DB; None when the column is missing.
+    pub async fn from_metadata(pool: &SqlitePool) -> anyhow::Result<Option<Self>> {
+        let has_column: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name = ?")
+            .bind(STABLE_ID_COLUMN)
+            .fetch_one(pool)
+            .await?;
+        if has_column == 0 {
+            return Ok(None);
+        }
+        let sql = format!("SELECT article_id, {} FROM articles WHERE article_id >= 0", STABLE_ID_COLUMN);
+        let rows: Vec<(i64, Option<i64>)> = sqlx::query_as(&sql).fetch_all(pool).await?;
+
+        let len = rows.iter().map(|(id, _)| *id + 1).max().unwrap_or(0) as usize;
+        let mut stable = vec![NONE; len];
+        for (article_id, page_id) in rows {
+            stable[article_id as usize] = page_id.filter(|p| *p >= 0).unwrap_or(NONE);
+        }
+        Ok(Some(Self::from_stable(stable)))
+    }
+
+    /// Rewrites the sidecar of `index_path` from the metadata DB; without a
+    /// `page_id` column any old sidecar is removed, as it would be stale.
+    pub async fn write(index_path: &str, pool: &SqlitePool) -> anyhow::Result<()> {
+        match Self::from_metadata(pool).await? {
+            Some(ids) => {
+                ids.save(index_path)?;
+                info!("✓ Wrote stable IDs for {} articles ({} without one)", ids.mapped(), ids.missing());
+            }
+            None => {
+                let _ = std::fs::remove_file(stable_ids_path(index_path));
+                warn!("⚠ Metadata DB has no articles.{} column; public IDs are article IDs", STABLE_ID_COLUMN);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, index_path: &str) -> anyhow::Result<()> {
+        let path = stable_ids_path(index_path);
+        let tmp = path.with_extension("stable_ids.tmp");
+        let raw: Vec<u8> = self.stable.iter().flat_map(|s| s.to_le_bytes()).collect();
+        std::fs::write(&tmp, raw)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// True when public and internal IDs are the same (no sidecar)
+    pub fn is_identity(&self) -> bool {
+        self.stable.is_empty()
+    }
+
+    /// Articles with a stable ID
+    pub fn mapped(&self) -> usize {
+        self.articles.len()
+    }
+
+    /// Articles of the DB without one (published under a negative fallback ID)
+    pub fn missing(&self) -> usize {
+        self.stable.iter().filter(|s| **s == NONE).count()
+    }
+
+    /// The ID scheme public IDs are in, stored next to saved IDs
+    pub fn id_space(&self) -> &'static str {
+        if self.is_identity() {
+            ID_SPACE_ARTICLE
+        } else {
+            ID_SPACE_STABLE
+        }
+    }
+
+    /// Public ID of an article
+    pub fn public(&self, article_id: i64) -> i64 {
+        if self.is_identity() || article_id < 0 {
+            return article_id;
+        }
+        match self.stable.get(article_id as usize) {
+            Some(&stable) if stable != NONE => stable,
+            _ => fallback(article_id),
+        }
+    }
+
+    /// Article behind a public ID; None for IDs this index doesn't know
+    pub fn article(&self, public_id: i64) -> Option<i64> {
+        if self.is_identity() {
+            return Some(public_id);
+        }
+        if public_id < 0 {
+            // Articles without a stable ID go by their fallback
+            let article_id = fallback(public_id);
+            return (self.stable.get(article_id as usize) == Some(&NONE)).then_some(article_id);
+        }
+        self.articles
+            .binary_search_by_key(&public_id, |(s, _)| *s)
+            .ok()
+            .map(|i| self.articles[i].1)
+    }
+
+    /// Articles behind `public_ids`, unknown ones dropped
+    pub fn articles_of(&self, public_ids: &[i64]) -> Vec<i64> {
+        public_ids.iter().filter_map(|&id| self.article(id)).collect()
+    }
+}
+
+/// Public ID of an article without a stable one, and back (the map is its own inverse)
+fn fallback(id: i64) -> i64 {
+    -id - 1
+}
+
+/// `index stable-ids`: (re)writes the sidecar of an existing index. The server
+/// picks it up on restart or `POST /api/admin/reload-index`.
+pub async fn run(args: StableIdsArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let index_path = args.index.unwrap_or_else(|| config.index_path.clone());
+    let metadata_path = args.metadata.unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+    StableIds::write(&index_path, &pool).await
+}
@@ -0,0 +1,115 @@
+use crate::cli::TuneArgs;
+use crate::config::get_config;
+use crate::index::manifest::IndexManifest;
+use crate::index::params::set_search_parameter;
+use crate::index::reconstruct_many;
+use crate::utils::errors::AppError;
+use faiss::{index_factory, Index};
+use std::collections::HashSet;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// Measures recall@k of an IVF index against exact search for doubling nprobe
+/// values and records the smallest nprobe that reaches the target recall.
+pub fn run(args: TuneArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let index_path = args.index.clone().unwrap_or_else(|| config.index_path.clone());
+
+    info!("Loading index from {}...", index_path);
+    let mut index = faiss::read_index(&index_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    let ntotal = index.ntotal();
+    let dim = index.d();
+
+    if ntotal == 0 {
+        anyhow::bail!("Index is empty, nothing to tune");
+    }
+    if set_search_parameter(&mut index, "nprobe", 1.0).is_err() {
+        anyhow::bail!("Index at {} has no nprobe parameter (not an IVF index)", index_path);
+    }
+
+    // 1. Ground truth
+    let mut exact = match &args.exact {
+        Some(path) => {
+            info!("Loading exact reference index from {}...", path);
+            faiss::read_index(path).map_err(|e| AppError::Faiss(format!("{:?}", e)))?
+        }
+        None => {
+            info!("Building exact reference from {} reconstructed vectors...", ntotal);
+            // Same metric as the index, so the ground truth ranks the same way
+            let mut flat = index_factory(dim, "Flat", index.metric_type())
+                .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+            let all_ids: Vec<u64> = (0..ntotal).collect();
+            for chunk in all_ids.chunks(50_000) {
+                flat.add(&reconstruct_many(&index, chunk)?)
+                    .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+            }
+            flat
+        }
+    };
+
+    // 2. Query set: evenly strided stored vectors
+    let query_count = (args.queries as u64).min(ntotal).max(1);
+    let stride = ntotal / query_count;
+    let query_ids: Vec<u64> = (0..query_count).map(|i| i * stride).collect();
+    let queries = reconstruct_many(&exact, &query_ids)?;
+
+    let truth = exact.search(&queries, args.k)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    let truth_labels: Vec<i64> = truth.labels.iter().map(|l| l.get_u64() as i64).collect();
+
+    // 3. Sweep nprobe
+    info!("{:>8} {:>10} {:>12}", "nprobe", "recall@k", "ms/query");
+    let mut recommended = None;
+    let mut nprobe = 1;
+    while nprobe <= args.max_nprobe {
+        set_search_parameter(&mut index, "nprobe", nprobe as f64)?;
+
+        let start = Instant::now();
+        let result = index.search(&queries, args.k)
+            .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+        let per_query_ms = start.elapsed().as_secs_f64() * 1000.0 / query_count as f64;
+
+        let labels: Vec<i64> = result.labels.iter().map(|l| l.get_u64() as i64).collect();
+        let recall = recall_at_k(&truth_labels, &labels, args.k);
+        info!("{:>8} {:>10.4} {:>12.3}", nprobe, recall, per_query_ms);
+
+        if recall >= args.target_recall {
+            recommended = Some(nprobe);
+            break;
+        }
+        nprobe *= 2;
+    }
+
+    // 4. Record
+    let Some(nprobe) = recommended else {
+        warn!("⚠ No nprobe up to {} reached recall {:.2}", args.max_nprobe, args.target_recall);
+        return Ok(());
+    };
+
+    let mut manifest = IndexManifest::load(&index_path).unwrap_or_else(|_| {
+        warn!("No manifest found for {}, creating one", index_path);
+        IndexManifest::new("unknown", dim, index.metric_type().into(), ntotal, 0)
+    });
+    manifest.nprobe = Some(nprobe);
+    manifest.save(&index_path)?;
+
+    info!("✓ Recommended nprobe={} (target recall {:.2}) written to manifest", nprobe, args.target_recall);
+    Ok(())
+}
+
+fn recall_at_k(truth: &[i64], found: &[i64], k: usize) -> f64 {
+    let mut hits = 0usize;
+    let mut total = 0usize;
+
+    for (expected, actual) in truth.chunks(k).zip(found.chunks(k)) {
+        let expected: HashSet<i64> = expected.iter().filter(|&&l| l >= 0).cloned().collect();
+        hits += actual.iter().filter(|l| expected.contains(l)).count();
+        total += expected.len();
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+    hits as f64 / total as f64
+}
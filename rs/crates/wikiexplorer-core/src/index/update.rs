@@ -0,0 +1,260 @@
+use crate::cli::UpdateArgs;
+use crate::config::get_config;
+use crate::index::embed::ArticleEncoder;
+use crate::index::knn::clear_knn_edges;
+use crate::index::labels::{labels_path, LabelMap};
+use crate::index::manifest::IndexManifest;
+use crate::index::stable_ids::{stable_ids_path, StableIds};
+use crate::index::vectors::{vectors_path, VectorStoreWriter};
+use crate::search::embedder::{start_embedder, EmbeddingModel};
+use crate::utils::errors::AppError;
+use crate::utils::time::unix_now;
+use faiss::Index;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const ENTRIES_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS entries (
+    article_id INTEGER PRIMARY KEY,
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL
+)";
+
+/// Brings an existing index up to date with the metadata DB without a rebuild.
+///
+/// The articles an index holds are recorded in `<name>.entries.db` (written by
+/// `index build --from-metadata` and by every update). Diffing that against
+/// the metadata DB gives:
+/// - new articles past the end of the index: appended at position == ID, ID
+///   gaps padded with tombstoned zero vectors
+/// - other new or re-titled articles: appended at the end and remapped in the
+///   label map, their old vector tombstoned
+/// - removed articles: tombstoned
+///
+/// Precomputed kNN lists are dropped. The server picks the result up on restart or `POST /api/admin/reload-index`.
+pub async fn run(args: UpdateArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let index_path = args.index.clone().unwrap_or_else(|| config.index_path.clone());
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+
+    info!("Loading index from {}...", index_path);
+    let mut index = faiss::read_index(&index_path)
+        .map_err(|e| AppError::Faiss(format!("Could not load index at {}: {:?}", index_path, e)))?;
+    let mut labels = LabelMap::load(&index_path)?;
+    let ntotal = index.ntotal() as i64;
+    let dim = index.d();
+    info!("✓ Index: {} vectors, dim={}, {} tombstoned", ntotal, dim, labels.tombstone_count());
+
+    let mut conn = open_entries(&index_path, &metadata_path).await?;
+    let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entries").fetch_one(&mut conn).await?;
+    if recorded == 0 {
+        // Index built before entries were tracked: assume it matches the DB up to ntotal
+        warn!("⚠ No entries snapshot for {}, assuming positions 0..{} hold the current titles", index_path, ntotal);
+        sqlx::query("INSERT INTO entries SELECT article_id, article_id, title FROM meta.articles WHERE article_id < ?")
+            .bind(ntotal)
+            .execute(&mut conn)
+            .await?;
+    }
+
+    // 1. Diff
+    let new: Vec<i64> = sqlx::query_scalar(
+        "SELECT a.article_id FROM meta.articles a
+         LEFT JOIN entries e ON e.article_id = a.article_id
+         WHERE e.article_id IS NULL AND a.article_id >= 0
+         ORDER BY a.article_id",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+    let retitled: Vec<i64> = sqlx::query_scalar(
+        "SELECT a.article_id FROM meta.articles a
+         JOIN entries e ON e.article_id = a.article_id
+         WHERE a.title != e.title
+         ORDER BY a.article_id",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+    let removed: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT e.article_id, e.position FROM entries e
+         LEFT JOIN meta.articles a ON a.article_id = e.article_id
+         WHERE a.article_id IS NULL",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    info!("Diff: {} new, {} re-titled, {} removed", new.len(), retitled.len(), removed.len());
+    if args.dry_run || (new.is_empty() && retitled.is_empty() && removed.is_empty()) {
+        if !args.dry_run {
+            info!("✓ Index is up to date");
+        }
+        return Ok(());
+    }
+
+    // 2. Plan positions: IDs past the end keep position == ID, the rest go after them
+    let (tail, moved): (Vec<i64>, Vec<i64>) = new.into_iter().partition(|id| *id >= ntotal);
+    let moved: Vec<i64> = moved.into_iter().chain(retitled).collect();
+    let tail_end = tail.last().map_or(ntotal, |id| id + 1);
+
+    for (_, position) in &removed {
+        labels.tombstone(*position);
+    }
+    // The exact-vector sidecar, if the index has one, follows the same changes
+    let mut sidecar = if vectors_path(&index_path).exists() {
+        Some(VectorStoreWriter::update(&index_path, dim)?)
+    } else {
+        None
+    };
+    if let Some(sidecar) = sidecar.as_mut() {
+        for (id, _) in &removed {
+            sidecar.clear(*id)?;
+        }
+    }
+
+    let encode_batch = args.encode_batch.max(1);
+    let worker = start_embedder(&EmbeddingModel::default(), config.inference_workers, Duration::from_millis(5), encode_batch)?;
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+    let embedder = ArticleEncoder { pool: &pool, worker: worker.as_ref(), with_lead: args.with_lead, encode_batch };
+    let probe = embedder.encode(&["dimension probe".to_string()]).await?;
+    if probe[0].len() != dim as usize {
+        anyhow::bail!("Model dim {} does not match index dim {}", probe[0].len(), dim);
+    }
+
+    // 3. Embed and append, tail range first so its positions line up with IDs
+    let started = Instant::now();
+    let batch_size = args.batch_size.max(1) as i64;
+    let mut placed: Vec<(i64, i64)> = Vec::with_capacity(tail.len() + moved.len());
+
+    let present: HashSet<i64> = tail.iter().copied().collect();
+    let mut start = ntotal;
+    while start < tail_end {
+        let end = (start + batch_size).min(tail_end);
+        let ids: Vec<i64> = (start..end).collect();
+        let batch = embedder.vectors_for(&ids, dim).await?;
+        index.add(&batch)
+            .map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))?;
+        put_rows(sidecar.as_mut(), &ids, &batch, dim)?;
+        for id in ids {
+            if present.contains(&id) {
+                placed.push((id, id));
+            } else {
+                labels.tombstone(id);
+            }
+        }
+        start = end;
+    }
+
+    for chunk in moved.chunks(batch_size as usize) {
+        let first = index.ntotal() as i64;
+        let batch = embedder.vectors_for(chunk, dim).await?;
+        index.add(&batch)
+            .map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))?;
+        put_rows(sidecar.as_mut(), chunk, &batch, dim)?;
+        for (offset, &id) in chunk.iter().enumerate() {
+            let position = first + offset as i64;
+            labels.assign(position, id);
+            placed.push((id, position));
+        }
+    }
+    tokio::task::spawn_blocking(move || worker.shutdown()).await?;
+    info!("✓ Embedded {} articles in {:?}", placed.len(), started.elapsed());
+
+    // 4. Write labels, index and manifest (tmp file then rename), then record the new entries.
+    // Labels go first: the old index under new labels only misses the appended
+    // vectors, while a new index under old labels would serve them as other articles.
+    let tmp_path = format!("{}.tmp", index_path);
+    faiss::write_index(&index, &tmp_path)
+        .map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+    labels.save(&index_path)?;
+    std::fs::rename(&tmp_path, &index_path)?;
+    if let Some(sidecar) = sidecar {
+        sidecar.finish()?;
+    }
+
+    match IndexManifest::load(&index_path) {
+        Ok(mut manifest) => {
+            manifest.ntotal = index.ntotal();
+            manifest.updated_at = Some(unix_now());
+            manifest.save(&index_path)?;
+        }
+        Err(e) => warn!("⚠ No manifest updated for {}: {}", index_path, e),
+    }
+
+    let mut tx = conn.begin().await?;
+    for (id, _) in &removed {
+        sqlx::query("DELETE FROM entries WHERE article_id = ?").bind(id).execute(&mut *tx).await?;
+    }
+    for (id, position) in &placed {
+        sqlx::query(
+            "INSERT OR REPLACE INTO entries (article_id, position, title)
+             SELECT article_id, ?, title FROM meta.articles WHERE article_id = ?",
+        )
+        .bind(position)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    // New and renamed articles may have new stable IDs
+    StableIds::write(&index_path, &pool).await?;
+    // New vectors can outrank any precomputed neighbour
+    let dropped = clear_knn_edges(&pool).await?;
+    if dropped > 0 {
+        info!("Dropped {} kNN edges; rerun `knn precompute` to restore them", dropped);
+    }
+
+    info!(
+        "✓ Updated {} ({} vectors, {} tombstoned)",
+        index_path,
+        index.ntotal(),
+        labels.tombstone_count()
+    );
+    Ok(())
+}
+
+fn put_rows(sidecar: Option<&mut VectorStoreWriter>, ids: &[i64], batch: &[f32], dim: u32) -> std::io::Result<()> {
+    if let Some(sidecar) = sidecar {
+        for (&id, vector) in ids.iter().zip(batch.chunks_exact(dim as usize)) {
+            sidecar.put(id, vector)?;
+        }
+    }
+    Ok(())
+}
+
+/// Records every article of a freshly embedded index as the baseline for `run`.
+pub(crate) async fn record_entries(index_path: &str, metadata_path: &str) -> anyhow::Result<()> {
+    let _ = std::fs::remove_file(entries_path(index_path));
+    let mut conn = open_entries(index_path, metadata_path).await?;
+    sqlx::query("INSERT INTO entries SELECT article_id, article_id, title FROM meta.articles WHERE article_id >= 0")
+        .execute(&mut conn)
+        .await?;
+    Ok(())
+}
+
+/// Copies the label map, entries snapshot and stable IDs of `source` to `output`, for
+/// rebuilds that keep positions.
+pub(crate) fn copy_sidecars(source: &str, output: &str) -> anyhow::Result<()> {
+    for (from, to) in [
+        (labels_path(source), labels_path(output)),
+        (entries_path(source), entries_path(output)),
+        (stable_ids_path(source), stable_ids_path(output)),
+    ] {
+        if from.exists() {
+            std::fs::copy(from, to)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn entries_path(index_path: &str) -> PathBuf {
+    Path::new(index_path).with_extension("entries.db")
+}
+
+/// The entries snapshot with the metadata DB attached as `meta`.
+async fn open_entries(index_path: &str, metadata_path: &str) -> anyhow::Result<SqliteConnection> {
+    let url = format!("sqlite:{}?mode=rwc", entries_path(index_path).display());
+    let mut conn = SqliteConnection::connect(&url).await?;
+    sqlx::query(ENTRIES_SCHEMA).execute(&mut conn).await?;
+    sqlx::query("ATTACH DATABASE ? AS meta").bind(metadata_path).execute(&mut conn).await?;
+    Ok(conn)
+}
@@ -0,0 +1,116 @@
+//! Exact vectors by article ID, next to the index as `<name>.vectors.f32`: a
+//! raw little-endian f32 matrix whose row `r` is article `r`'s vector (all
+//! zeros: none). The server memory-maps it and reconstructs from it first, so
+//! lossy layouts (IVF-PQ) still get cross-edges and exact rescoring.
+//!
+//! Written by `index build --vectors` and kept in sync by `index update`.
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+pub fn vectors_path(index_path: &str) -> PathBuf {
+    Path::new(index_path).with_extension("vectors.f32")
+}
+
+pub struct VectorStore {
+    map: Mmap,
+    dim: usize,
+}
+
+impl VectorStore {
+    /// The sidecar of `index_path`, if there is one. `dim` is the index's.
+    pub fn open(index_path: &str, dim: u32) -> anyhow::Result<Option<Self>> {
+        let path = vectors_path(index_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = File::open(&path)?;
+        // SAFETY: the sidecar is only ever replaced by rename (see
+        // `VectorStoreWriter::finish`), never modified in place, so the mapped
+        // pages stay valid for the life of the map
+        let map = unsafe { Mmap::map(&file)? };
+        let row_bytes = dim as usize * 4;
+        if row_bytes == 0 || map.len() % row_bytes != 0 {
+            anyhow::bail!("{} is not a {}-d f32 matrix ({} bytes)", path.display(), dim, map.len());
+        }
+        Ok(Some(Self { map, dim: dim as usize }))
+    }
+
+    pub fn rows(&self) -> u64 {
+        (self.map.len() / (self.dim * 4)) as u64
+    }
+
+    /// Article `article_id`'s vector; None past the end and for zero rows.
+    pub fn get(&self, article_id: i64) -> Option<Vec<f32>> {
+        if article_id < 0 || article_id as u64 >= self.rows() {
+            return None;
+        }
+        let start = article_id as usize * self.dim * 4;
+        let vector: Vec<f32> = self.map[start..start + self.dim * 4]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        vector.iter().any(|v| *v != 0.0).then_some(vector)
+    }
+}
+
+/// Writes a sidecar into a temporary file, moved into place by `finish`.
+pub struct VectorStoreWriter {
+    out: BufWriter<File>,
+    // Byte offset `out` is at, so sequential rows don't seek (which flushes)
+    offset: u64,
+    tmp: PathBuf,
+    path: PathBuf,
+    dim: usize,
+}
+
+impl VectorStoreWriter {
+    /// An empty sidecar for the index at `index_path`.
+    pub fn create(index_path: &str, dim: u32) -> std::io::Result<Self> {
+        let path = vectors_path(index_path);
+        let tmp = path.with_extension("f32.tmp");
+        let out = BufWriter::new(File::create(&tmp)?);
+        Ok(Self { out, offset: 0, tmp, path, dim: dim as usize })
+    }
+
+    /// A copy of the current sidecar of `index_path` to change rows in.
+    pub fn update(index_path: &str, dim: u32) -> std::io::Result<Self> {
+        let path = vectors_path(index_path);
+        let tmp = path.with_extension("f32.tmp");
+        std::fs::copy(&path, &tmp)?;
+        let out = BufWriter::new(File::options().write(true).open(&tmp)?);
+        Ok(Self { out, offset: 0, tmp, path, dim: dim as usize })
+    }
+
+    /// Sets row `article_id`; rows skipped over read as zeros (no vector).
+    pub fn put(&mut self, article_id: i64, vector: &[f32]) -> std::io::Result<()> {
+        if article_id < 0 || vector.len() != self.dim {
+            return Ok(());
+        }
+        let start = article_id as u64 * self.dim as u64 * 4;
+        if start != self.offset {
+            self.out.seek(SeekFrom::Start(start))?;
+        }
+        for value in vector {
+            self.out.write_all(&value.to_le_bytes())?;
+        }
+        self.offset = start + self.dim as u64 * 4;
+        Ok(())
+    }
+
+    /// Zeroes row `article_id`, e.g. for a deleted article.
+    pub fn clear(&mut self, article_id: i64) -> std::io::Result<()> {
+        let len = self.out.get_ref().metadata()?.len().max(self.offset);
+        if article_id >= 0 && (article_id as u64 + 1) * self.dim as u64 * 4 <= len {
+            self.put(article_id, &vec![0.0; self.dim])?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> std::io::Result<()> {
+        self.out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(self.tmp, self.path)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::cli::ImageIngestArgs;
+use crate::config::get_config;
+use crate::images::{ensure_images_table, file_thumbnail_url, store_thumbnails};
+use crate::ingest::{open_lines, BATCH_SIZE};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tracing::{info, warn};
+
+/// Loads article thumbnails from a `title<TAB>image` file into `article_images`.
+/// The image is a URL or a file name (e.g. the `page_image_free` values of a
+/// `page_props` dump), which becomes a Special:FilePath thumbnail URL. Rows
+/// for titles not in the DB are skipped.
+pub async fn run(args: ImageIngestArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    let titles: HashMap<String, i64> = sqlx::query_as("SELECT title, article_id FROM articles")
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    // 1. Parse the whole file first so a malformed one changes nothing
+    let mut images: HashMap<i64, Option<String>> = HashMap::new();
+    let (mut lines, mut malformed) = (0u64, 0u64);
+    for line in open_lines(&args.file)?.lines() {
+        let line = line?;
+        lines += 1;
+        let Some((title, image)) = line.split_once('\t') else {
+            malformed += 1;
+            continue;
+        };
+        let image = image.trim();
+        if image.is_empty() {
+            malformed += 1;
+            continue;
+        }
+        let Some(&id) = titles.get(&title.trim().replace(' ', "_")) else {
+            continue;
+        };
+        let url = if image.starts_with("https://") || image.starts_with("http://") {
+            image.to_string()
+        } else {
+            file_thumbnail_url(image, args.width)
+        };
+        images.insert(id, Some(url));
+    }
+    if malformed > 0 {
+        warn!("⚠ Skipped {} malformed lines", malformed);
+    }
+    info!("✓ {} lines read, {} articles have an image", lines, images.len());
+
+    // 2. Write
+    ensure_images_table(&pool).await?;
+    if args.replace {
+        sqlx::query("DELETE FROM article_images").execute(&pool).await?;
+        warn!("Cleared existing article images");
+    }
+    let mut rows: Vec<(i64, Option<String>)> = images.into_iter().collect();
+    rows.sort_unstable();
+    for chunk in rows.chunks(BATCH_SIZE) {
+        store_thumbnails(&pool, chunk).await?;
+    }
+
+    info!("✓ Wrote thumbnails for {} articles", rows.len());
+    Ok(())
+}
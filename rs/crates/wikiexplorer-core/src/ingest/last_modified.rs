@@ -0,0 +1,90 @@
+use crate::cli::LastModifiedIngestArgs;
+use crate::config::get_config;
+use crate::ingest::{ensure_column, open_lines, write_column};
+use chrono::{DateTime, NaiveDateTime};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tracing::{info, warn};
+
+/// Stores each article's last edit in `articles.last_modified` (unix seconds)
+/// from a `title<TAB>timestamp` file, e.g. `page_title` and `rev_timestamp`
+/// of each page's latest revision. Titles listed more than once keep their
+/// latest timestamp; articles absent from the file keep their value.
+pub async fn run(args: LastModifiedIngestArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    let titles: HashMap<String, i64> = sqlx::query_as("SELECT title, article_id FROM articles")
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut edits: HashMap<i64, i64> = HashMap::new();
+    let (mut lines, mut malformed) = (0u64, 0u64);
+    for line in open_lines(&args.file)?.lines() {
+        let line = line?;
+        lines += 1;
+        let Some((title, timestamp)) = line.split_once('\t').map(|(t, ts)| (t.trim(), ts.trim())) else {
+            malformed += 1;
+            continue;
+        };
+        let Some(timestamp) = parse_timestamp(timestamp) else {
+            malformed += 1;
+            continue;
+        };
+        if let Some(&id) = titles.get(&title.replace(' ', "_")) {
+            let latest = edits.entry(id).or_insert(timestamp);
+            *latest = (*latest).max(timestamp);
+        }
+    }
+    if malformed > 0 {
+        warn!("⚠ Skipped {} malformed lines", malformed);
+    }
+    info!("✓ {} lines read, {} articles matched", lines, edits.len());
+
+    ensure_column(&pool, "last_modified", "INTEGER").await?;
+    let mut values: Vec<(i64, i64)> = edits.into_iter().collect();
+    values.sort_unstable();
+    let updated = write_column(&pool, "last_modified", &values).await?;
+
+    info!("✓ Wrote last edit times for {} articles", updated);
+    Ok(())
+}
+
+/// Unix seconds of an ISO 8601 (`2024-05-01T12:00:00Z`) or MediaWiki
+/// (`20240501120000`, UTC) timestamp.
+fn parse_timestamp(raw: &str) -> Option<i64> {
+    if raw.len() == 14 && raw.bytes().all(|b| b.is_ascii_digit()) {
+        return NaiveDateTime::parse_from_str(raw, "%Y%m%d%H%M%S").ok().map(|t| t.and_utc().timestamp());
+    }
+    DateTime::parse_from_rfc3339(raw).ok().map(|t| t.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn both_formats_agree(secs in 0i64..4_102_444_800) {
+            let time = DateTime::from_timestamp(secs, 0).unwrap();
+            prop_assert_eq!(parse_timestamp(&time.format("%Y%m%d%H%M%S").to_string()), Some(secs));
+            prop_assert_eq!(parse_timestamp(&time.to_rfc3339()), Some(secs));
+        }
+
+        #[test]
+        fn garbage_is_rejected_without_panicking(raw in "\\PC{0,30}") {
+            let _ = parse_timestamp(&raw);
+        }
+
+        #[test]
+        fn invalid_mediawiki_dates_are_none(month in 13u32..100, day in 32u32..100) {
+            prop_assert_eq!(parse_timestamp(&format!("2024{:02}01120000", month)), None);
+            prop_assert_eq!(parse_timestamp(&format!("202401{:02}120000", day)), None);
+        }
+    }
+}
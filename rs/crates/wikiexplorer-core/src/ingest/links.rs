@@ -0,0 +1,163 @@
+use crate::cli::LinkIngestArgs;
+use crate::config::get_config;
+use crate::ingest::{ensure_column, open_lines, write_column};
+use futures::TryStreamExt;
+use sqlx::SqlitePool;
+use std::io::BufRead;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// The ranking divides stored pagerank by 100, so the top article gets 100.
+const PAGERANK_SCALE: f64 = 100.0;
+pub const DEFAULT_DAMPING: f64 = 0.85;
+
+/// Computes `articles.pagerank` (power iteration over the directed link graph)
+/// and `articles.backlinks` (distinct linking articles) from an edge list of
+/// `source_id target_id` lines, or from the DB's `links` table without one.
+/// Links from or to IDs without an article are ignored.
+pub async fn run(args: LinkIngestArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT article_id FROM articles WHERE article_id >= 0 ORDER BY article_id")
+        .fetch_all(&pool)
+        .await?;
+    let Some(&max_id) = ids.last() else {
+        anyhow::bail!("No articles in {}", metadata_path);
+    };
+    if max_id > u32::MAX as i64 {
+        anyhow::bail!("article_id {} does not fit the in-memory graph", max_id);
+    }
+    let n = max_id as usize + 1;
+    let mut live = vec![false; n];
+    for &id in &ids {
+        live[id as usize] = true;
+    }
+
+    // 1. Edges, deduplicated, between existing articles only
+    let started = Instant::now();
+    let raw = match &args.file {
+        Some(path) => read_edge_file(path)?,
+        None => read_links_table(&pool).await?,
+    };
+    let total = raw.len();
+    let mut edges: Vec<(u32, u32)> = raw
+        .into_iter()
+        .filter(|&(s, t)| s != t && s >= 0 && t >= 0 && s <= max_id && t <= max_id && live[s as usize] && live[t as usize])
+        .map(|(s, t)| (s as u32, t as u32))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    info!("✓ {} links ({} read, rest dangling or duplicate) in {:?}", edges.len(), total, started.elapsed());
+
+    // 2. Signals
+    let mut backlinks = vec![0i64; n];
+    for &(_, target) in &edges {
+        backlinks[target as usize] += 1;
+    }
+
+    let started = Instant::now();
+    let rank = pagerank(&live, &edges, args.damping, args.iterations, args.tolerance);
+    info!("✓ PageRank computed in {:?}", started.elapsed());
+    let scale = pagerank_scale(&rank);
+
+    // 3. Write
+    ensure_column(&pool, "pagerank", "REAL").await?;
+    ensure_column(&pool, "backlinks", "INTEGER").await?;
+    let pagerank_values: Vec<(i64, f64)> = ids.iter().map(|&id| (id, rank[id as usize] * scale)).collect();
+    let backlink_values: Vec<(i64, i64)> = ids.iter().map(|&id| (id, backlinks[id as usize])).collect();
+    write_column(&pool, "pagerank", &pagerank_values).await?;
+    let updated = write_column(&pool, "backlinks", &backlink_values).await?;
+
+    info!("✓ Wrote pagerank and backlinks for {} articles", updated);
+    info!("Restart the server or POST /api/admin/reload-index to rank with the new values");
+    Ok(())
+}
+
+/// Factor taking `rank` to the stored scale, where the top article has 100.
+pub fn pagerank_scale(rank: &[f64]) -> f64 {
+    let top = rank.iter().copied().fold(0.0f64, f64::max);
+    if top > 0.0 {
+        PAGERANK_SCALE / top
+    } else {
+        0.0
+    }
+}
+
+/// Power iteration over `live` nodes; the rank of pages without outgoing links
+/// is spread evenly, as is the teleport share. Stops once the L1 change of an
+/// iteration drops below `tolerance`. Ranks of live nodes sum to 1.
+pub fn pagerank(live: &[bool], edges: &[(u32, u32)], damping: f64, max_iterations: usize, tolerance: f64) -> Vec<f64> {
+    let n = live.len();
+    let live_count = live.iter().filter(|l| **l).count();
+    if live_count == 0 {
+        return vec![0.0; n];
+    }
+    let share = 1.0 / live_count as f64;
+
+    let mut out_degree = vec![0u32; n];
+    for &(source, _) in edges {
+        out_degree[source as usize] += 1;
+    }
+
+    let mut rank: Vec<f64> = live.iter().map(|&l| if l { share } else { 0.0 }).collect();
+    let mut next = vec![0.0f64; n];
+    for iteration in 1..=max_iterations {
+        let dangling: f64 = (0..n).filter(|&i| live[i] && out_degree[i] == 0).map(|i| rank[i]).sum();
+        let base = (1.0 - damping + damping * dangling) * share;
+        for (i, value) in next.iter_mut().enumerate() {
+            *value = if live[i] { base } else { 0.0 };
+        }
+        for &(source, target) in edges {
+            let s = source as usize;
+            next[target as usize] += damping * rank[s] / out_degree[s] as f64;
+        }
+
+        let delta: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        std::mem::swap(&mut rank, &mut next);
+        if delta < tolerance {
+            info!("  converged after {} iterations (delta {:.2e})", iteration, delta);
+            return rank;
+        }
+        if iteration % 10 == 0 {
+            info!("  iteration {} (delta {:.2e})", iteration, delta);
+        }
+    }
+    warn!("⚠ PageRank did not converge in {} iterations", max_iterations);
+    rank
+}
+
+/// `source_id target_id` per line, separated by whitespace, tab or comma.
+/// Blank lines, `#` comments and a non-numeric header are skipped.
+fn read_edge_file(path: &str) -> anyhow::Result<Vec<(i64, i64)>> {
+    info!("Reading links from {}...", path);
+    let mut edges = Vec::new();
+    let mut malformed = 0u64;
+    for line in open_lines(path)?.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(|c: char| c.is_whitespace() || c == ',').filter(|f| !f.is_empty());
+        match (fields.next().map(str::parse), fields.next().map(str::parse)) {
+            (Some(Ok(source)), Some(Ok(target))) => edges.push((source, target)),
+            _ => malformed += 1,
+        }
+    }
+    if malformed > 1 {
+        warn!("⚠ Skipped {} malformed lines", malformed);
+    }
+    Ok(edges)
+}
+
+async fn read_links_table(pool: &SqlitePool) -> anyhow::Result<Vec<(i64, i64)>> {
+    info!("Reading links from the links table...");
+    let mut rows = sqlx::query_as::<_, (i64, i64)>("SELECT source_id, target_id FROM links").fetch(pool);
+    let mut edges = Vec::new();
+    while let Some(edge) = rows.try_next().await? {
+        edges.push(edge);
+    }
+    Ok(edges)
+}
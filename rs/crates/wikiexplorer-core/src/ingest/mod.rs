@@ -0,0 +1,60 @@
+//! `wikiexplorer ingest`: computes the built-in ranking signals (`pageviews`,
+//! `pagerank`, `backlinks`, `last_modified`) from Wikipedia dumps and writes
+//! them into the metadata DB's `articles` table, along with Wikidata QIDs, and
+//! article thumbnails into `article_images`.
+
+use flate2::read::MultiGzDecoder;
+use sqlx::{Sqlite, SqlitePool};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use tracing::info;
+
+pub mod images;
+pub mod last_modified;
+pub mod links;
+pub mod pageviews;
+pub mod qids;
+
+/// Rows per write transaction
+pub(crate) const BATCH_SIZE: usize = 5_000;
+
+/// Opens a dump file, transparently decompressing `.gz`.
+fn open_lines(path: &str) -> anyhow::Result<Box<dyn BufRead>> {
+    let file = File::open(path).map_err(|e| anyhow::anyhow!("Could not open {}: {}", path, e))?;
+    if path.ends_with(".gz") {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Adds `column` to `articles` if an older DB lacks it.
+async fn ensure_column(pool: &SqlitePool, column: &'static str, sql_type: &'static str) -> anyhow::Result<()> {
+    let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('articles')")
+        .fetch_all(pool)
+        .await?;
+    if !columns.iter().any(|(c,)| c == column) {
+        sqlx::query(&format!("ALTER TABLE articles ADD COLUMN {} {}", column, sql_type))
+            .execute(pool)
+            .await?;
+        info!("✓ Added column articles.{}", column);
+    }
+    Ok(())
+}
+
+/// Writes `(article_id, value)` pairs into `column` in batched transactions.
+async fn write_column<T>(pool: &SqlitePool, column: &'static str, values: &[(i64, T)]) -> anyhow::Result<u64>
+where
+    T: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Clone + Send + Sync,
+{
+    let sql = format!("UPDATE articles SET {} = ? WHERE article_id = ?", column);
+    let mut updated = 0u64;
+    for chunk in values.chunks(BATCH_SIZE) {
+        let mut tx = pool.begin().await?;
+        for (id, value) in chunk {
+            updated += sqlx::query(&sql).bind(value.clone()).bind(id).execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+    }
+    Ok(updated)
+}
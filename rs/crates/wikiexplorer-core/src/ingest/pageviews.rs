@@ -0,0 +1,80 @@
+use crate::cli::PageviewIngestArgs;
+use crate::config::get_config;
+use crate::ingest::{ensure_column, open_lines, write_column};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tracing::{info, warn};
+
+/// Sums the views of every article over the given pageview dumps and stores
+/// the totals in `articles.pageviews`. Articles absent from the dumps get 0.
+///
+/// Both dump layouts from dumps.wikimedia.org are understood:
+/// - `pageviews-*`: `domain_code page_title count_views total_response_size`
+/// - `pageviews-*-user` (complete): `wiki_code page_title page_id access count_views hourly`
+pub async fn run(args: PageviewIngestArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    // Only titles we have are counted, which bounds memory by the corpus, not the dumps
+    let titles: HashMap<String, i64> = sqlx::query_as("SELECT title, article_id FROM articles")
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .collect();
+    info!("Matching pageviews against {} articles", titles.len());
+
+    let mut views: HashMap<i64, i64> = HashMap::with_capacity(titles.len());
+    let (mut lines, mut matched, mut malformed) = (0u64, 0u64, 0u64);
+    for path in &args.files {
+        info!("Reading {}...", path);
+        for line in open_lines(path)?.lines() {
+            let line = line?;
+            lines += 1;
+            let Some((domain, title, count)) = parse_line(&line) else {
+                malformed += 1;
+                continue;
+            };
+            if !matches_project(domain, &args.project) {
+                continue;
+            }
+            if let Some(&id) = titles.get(title) {
+                *views.entry(id).or_insert(0) += count;
+                matched += 1;
+            }
+        }
+    }
+    if malformed > 0 {
+        warn!("⚠ Skipped {} malformed lines", malformed);
+    }
+    info!("✓ {} lines read, {} matched {} articles", lines, matched, views.len());
+
+    ensure_column(&pool, "pageviews", "INTEGER").await?;
+    let mut values: Vec<(i64, i64)> = titles.values().map(|id| (*id, views.get(id).copied().unwrap_or(0))).collect();
+    values.sort_unstable();
+    let updated = write_column(&pool, "pageviews", &values).await?;
+
+    info!("✓ Wrote pageviews for {} articles", updated);
+    info!("Restart the server or POST /api/admin/reload-index to rank with the new values");
+    Ok(())
+}
+
+/// `(domain, title, views)` from either dump layout.
+fn parse_line(line: &str) -> Option<(&str, &str, i64)> {
+    let fields: Vec<&str> = line.split(' ').collect();
+    let count = match fields.len() {
+        4 => fields[2],
+        6 => fields[4],
+        _ => return None,
+    };
+    Some((fields[0], fields[1], count.parse().ok()?))
+}
+
+/// `en` matches desktop (`en`, `en.wikipedia`) and mobile (`en.m`) rows of that wiki.
+fn matches_project(domain: &str, project: &str) -> bool {
+    match domain.strip_prefix(project) {
+        Some(rest) => rest.is_empty() || rest == ".m" || rest == ".wikipedia",
+        None => false,
+    }
+}
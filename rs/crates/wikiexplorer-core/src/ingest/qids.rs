@@ -0,0 +1,61 @@
+use crate::cli::QidIngestArgs;
+use crate::config::get_config;
+use crate::ingest::{ensure_column, open_lines, write_column};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tracing::{info, warn};
+
+/// Stores each article's Wikidata item in `articles.wikidata_qid` from a
+/// `title<TAB>Q42` file (e.g. the `wikibase_item` rows of a `page_props`
+/// dump). The QID links the same concept across language corpora
+/// (`/api/interwiki/:id`). Articles absent from the file keep their value.
+pub async fn run(args: QidIngestArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    let metadata_path = args.metadata.clone().unwrap_or_else(|| config.metadata_path.clone());
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+
+    let titles: HashMap<String, i64> = sqlx::query_as("SELECT title, article_id FROM articles")
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut qids: HashMap<i64, String> = HashMap::new();
+    let (mut lines, mut malformed) = (0u64, 0u64);
+    for line in open_lines(&args.file)?.lines() {
+        let line = line?;
+        lines += 1;
+        let Some((title, qid)) = line.split_once('\t').map(|(t, q)| (t.trim(), q.trim())) else {
+            malformed += 1;
+            continue;
+        };
+        if !is_qid(qid) {
+            malformed += 1;
+            continue;
+        }
+        if let Some(&id) = titles.get(&title.replace(' ', "_")) {
+            qids.insert(id, qid.to_string());
+        }
+    }
+    if malformed > 0 {
+        warn!("⚠ Skipped {} malformed lines", malformed);
+    }
+    info!("✓ {} lines read, {} articles matched", lines, qids.len());
+
+    ensure_column(&pool, "wikidata_qid", "TEXT").await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_articles_wikidata_qid ON articles (wikidata_qid)")
+        .execute(&pool)
+        .await?;
+    let mut values: Vec<(i64, String)> = qids.into_iter().collect();
+    values.sort_unstable();
+    let updated = write_column(&pool, "wikidata_qid", &values).await?;
+
+    info!("✓ Wrote Wikidata QIDs for {} articles", updated);
+    Ok(())
+}
+
+/// "Q" followed by digits
+fn is_qid(raw: &str) -> bool {
+    raw.len() > 1 && raw.starts_with('Q') && raw[1..].bytes().all(|b| b.is_ascii_digit())
+}
@@ -0,0 +1,28 @@
+//! Search, ranking and storage for WikiExplorer: index loading, query encoding,
+//! multi-signal ranking, cross edges, categories, signals (and their ingestion
+//! from dumps) and saved sessions.
+//! The HTTP server lives in `wikiexplorer-server`; [`WikiExplorer`] is the
+//! entry point for using search without it.
+
+pub mod categories;
+pub mod cli;
+pub mod config;
+pub mod demo;
+pub mod explorer;
+pub mod export;
+pub mod graph;
+pub mod images;
+pub mod index;
+pub mod ingest;
+pub mod models;
+pub mod search;
+pub mod sessions;
+pub mod signals;
+pub mod utils;
+
+// Links the BLAS implementation ndarray's `blas` feature calls into
+#[cfg(feature = "blas")]
+extern crate blas_src;
+
+pub use explorer::{RankedGraph, SearchOptions, WikiExplorer};
+pub use search::pipeline::{Hydration, RankOptions, SearchResult};
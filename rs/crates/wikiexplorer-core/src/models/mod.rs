@@ -4,6 +4,7 @@ use uuid::Uuid;
 use chrono::NaiveDateTime;
 
 #[derive(Debug, Serialize, FromRow)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct Article {
     pub article_id: i64,
     pub title: String,
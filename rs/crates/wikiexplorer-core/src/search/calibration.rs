@@ -0,0 +1,215 @@
+//! Calibration of semantic similarity before it enters the ranking formula.
+//!
+//! Search scores are cosines in [0, 1], but where relevant articles land in
+//! that range depends on the model and the index layout (IVF/PQ compress the
+//! spread). The geometric mean weights assume `sem_norm` spans [0, 1] the way
+//! the other signals do, so the raw score is mapped through a calibration
+//! first: SCORE_CALIBRATION, else the one `wikiexplorer calibrate` learned
+//! from a validation set and wrote to the index manifest, else none.
+//!
+//! Every calibration is non-decreasing, so ranking by semantic score and the
+//! early-termination bound (`ScoreCutoff`) keep their meaning.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// L2 penalty on the logistic fit's parameters
+const RIDGE: f64 = 1e-3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Calibration {
+    /// The raw similarity as is
+    #[default]
+    None,
+    /// `min` maps to 0 and `max` to 1, linearly in between
+    MinMax { min: f32, max: f32 },
+    /// Logistic curve: 0.5 at `midpoint`, `steepness` its slope factor
+    Sigmoid { midpoint: f32, steepness: f32 },
+}
+
+impl Calibration {
+    /// `raw` (a similarity in [0, 1]) as the calibrated `sem_norm` in [0, 1].
+    pub fn apply(&self, raw: f32) -> f32 {
+        let calibrated = match *self {
+            Calibration::None => raw,
+            Calibration::MinMax { min, max } => (raw - min) / (max - min).max(f32::EPSILON),
+            Calibration::Sigmoid { midpoint, steepness } => 1.0 / (1.0 + (-steepness * (raw - midpoint)).exp()),
+        };
+        if calibrated.is_nan() { 0.0 } else { calibrated.clamp(0.0, 1.0) }
+    }
+
+    /// Linear range from the scores of relevant hits: their `tail` quantile
+    /// maps to 0 and their `1 - tail` quantile to 1.
+    pub fn fit_min_max(relevant: &[f32], tail: f64) -> Option<Self> {
+        let mut scores: Vec<f32> = relevant.iter().copied().filter(|s| s.is_finite()).collect();
+        if scores.len() < 2 {
+            return None;
+        }
+        scores.sort_by(f32::total_cmp);
+        let quantile = |q: f64| scores[((scores.len() - 1) as f64 * q.clamp(0.0, 1.0)).round() as usize];
+        let (min, max) = (quantile(tail), quantile(1.0 - tail));
+        (max > min).then_some(Calibration::MinMax { min, max })
+    }
+
+    /// Logistic regression of "relevant" on the raw score, by Newton's method:
+    /// the fitted curve is the probability that a hit with that score is relevant.
+    /// None without both relevant and irrelevant samples, or if the fit diverges.
+    pub fn fit_sigmoid(samples: &[(f32, bool)]) -> Option<Self> {
+        let positives = samples.iter().filter(|(_, relevant)| *relevant).count();
+        if positives == 0 || positives == samples.len() {
+            return None;
+        }
+
+        // p = σ(a·x + b)
+        let (mut a, mut b) = (1.0f64, 0.0f64);
+        for _ in 0..50 {
+            let (mut ga, mut gb, mut haa, mut hab, mut hbb) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for &(x, relevant) in samples {
+                let x = x as f64;
+                let p = 1.0 / (1.0 + (-(a * x + b)).exp());
+                let residual = p - if relevant { 1.0 } else { 0.0 };
+                let w = (p * (1.0 - p)).max(1e-9);
+                ga += residual * x;
+                gb += residual;
+                haa += w * x * x;
+                hab += w * x;
+                hbb += w;
+            }
+            // Small ridge penalty keeps separable samples from running off to infinity
+            ga += RIDGE * a;
+            gb += RIDGE * b;
+            haa += RIDGE;
+            hbb += RIDGE;
+            let det = haa * hbb - hab * hab;
+            if det.abs() < 1e-12 {
+                break;
+            }
+            let (da, db) = ((hbb * ga - hab * gb) / det, (haa * gb - hab * ga) / det);
+            a -= da;
+            b -= db;
+            if da.abs() < 1e-6 && db.abs() < 1e-6 {
+                break;
+            }
+        }
+
+        // A higher score must never mean less relevant
+        if !(a.is_finite() && b.is_finite()) || a <= 0.0 {
+            return None;
+        }
+        Some(Calibration::Sigmoid { midpoint: (-b / a) as f32, steepness: a as f32 })
+    }
+}
+
+impl std::fmt::Display for Calibration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Calibration::None => write!(f, "none"),
+            Calibration::MinMax { min, max } => write!(f, "minmax:{:.4},{:.4}", min, max),
+            Calibration::Sigmoid { midpoint, steepness } => write!(f, "sigmoid:{:.4},{:.4}", midpoint, steepness),
+        }
+    }
+}
+
+/// `none`, `minmax:<min>,<max>` or `sigmoid:<midpoint>,<steepness>` (the
+/// `Display` form, so a logged calibration can be pasted into SCORE_CALIBRATION).
+impl FromStr for Calibration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("none") {
+            return Ok(Calibration::None);
+        }
+        let (kind, params) = s.split_once(':').ok_or_else(|| format!("'{}' has no parameters", s))?;
+        let (x, y) = params.split_once(',').ok_or_else(|| format!("'{}' needs two parameters", s))?;
+        let parse = |v: &str| v.trim().parse::<f32>().map_err(|e| format!("bad parameter in '{}': {}", s, e));
+        let (x, y) = (parse(x)?, parse(y)?);
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(format!("'{}': parameters must be finite", s));
+        }
+        match kind.trim().to_lowercase().as_str() {
+            "minmax" if y > x => Ok(Calibration::MinMax { min: x, max: y }),
+            "minmax" => Err(format!("'{}': max must be above min", s)),
+            "sigmoid" if y > 0.0 => Ok(Calibration::Sigmoid { midpoint: x, steepness: y }),
+            "sigmoid" => Err(format!("'{}': steepness must be positive", s)),
+            other => Err(format!("unknown calibration '{}'", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_maps_the_tails_to_the_ends() {
+        let scores: Vec<f32> = (0..=100).map(|i| i as f32 / 100.0).collect();
+        let calibration = Calibration::fit_min_max(&scores, 0.1).unwrap();
+        assert_eq!(calibration, Calibration::MinMax { min: 0.1, max: 0.9 });
+        assert_eq!(calibration.apply(0.1), 0.0);
+        assert_eq!(calibration.apply(0.9), 1.0);
+        assert!((calibration.apply(0.5) - 0.5).abs() < 1e-6);
+        assert_eq!(calibration.apply(0.05), 0.0);
+        assert_eq!(calibration.apply(0.95), 1.0);
+    }
+
+    #[test]
+    fn min_max_needs_a_spread() {
+        assert_eq!(Calibration::fit_min_max(&[], 0.05), None);
+        assert_eq!(Calibration::fit_min_max(&[0.4], 0.05), None);
+        assert_eq!(Calibration::fit_min_max(&[0.4, 0.4, 0.4], 0.05), None);
+        assert_eq!(Calibration::fit_min_max(&[f32::NAN, 0.3], 0.0), None);
+        assert_eq!(Calibration::fit_min_max(&[0.6, f32::NAN, 0.2], 0.0), Some(Calibration::MinMax { min: 0.2, max: 0.6 }));
+    }
+
+    #[test]
+    fn sigmoid_separates_relevant_from_irrelevant_scores() {
+        // Relevant hits score around 0.7, irrelevant ones around 0.3, with some overlap
+        let mut samples = Vec::new();
+        for i in 0..50 {
+            let jitter = (i % 10) as f32 / 50.0;
+            samples.push((0.6 + jitter, true));
+            samples.push((0.2 + jitter, false));
+        }
+        samples.push((0.35, true));
+        samples.push((0.65, false));
+
+        let calibration = Calibration::fit_sigmoid(&samples).unwrap();
+        let Calibration::Sigmoid { midpoint, steepness } = calibration else {
+            panic!("expected a sigmoid, got {:?}", calibration);
+        };
+        assert!(steepness > 0.0);
+        assert!((0.35..0.65).contains(&midpoint), "midpoint {}", midpoint);
+        assert!(calibration.apply(0.8) > 0.5 && calibration.apply(0.2) < 0.5);
+        assert!(calibration.apply(0.3) <= calibration.apply(0.4));
+    }
+
+    #[test]
+    fn sigmoid_needs_both_classes_and_a_positive_slope() {
+        assert_eq!(Calibration::fit_sigmoid(&[]), None);
+        assert_eq!(Calibration::fit_sigmoid(&[(0.4, true), (0.9, true)]), None);
+        assert_eq!(Calibration::fit_sigmoid(&[(0.4, false), (0.9, false)]), None);
+        // Higher scores less relevant: no calibration may invert the ranking
+        let inverted: Vec<(f32, bool)> = (0..40).map(|i| (i as f32 / 40.0, i < 20)).collect();
+        assert_eq!(Calibration::fit_sigmoid(&inverted), None);
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for calibration in [
+            Calibration::None,
+            Calibration::MinMax { min: 0.25, max: 0.75 },
+            Calibration::Sigmoid { midpoint: 0.5, steepness: 12.0 },
+        ] {
+            assert_eq!(calibration.to_string().parse::<Calibration>(), Ok(calibration));
+        }
+    }
+
+    #[test]
+    fn malformed_values_are_rejected() {
+        for raw in ["minmax", "minmax:0.5", "minmax:0.8,0.2", "sigmoid:0.5,0", "sigmoid:0.5,NaN", "linear:0,1", "minmax:a,b"] {
+            assert!(raw.parse::<Calibration>().is_err(), "{} parsed", raw);
+        }
+    }
+}
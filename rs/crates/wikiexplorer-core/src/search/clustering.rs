@@ -0,0 +1,148 @@
+/// Spherical k-means (cosine similarity) over L2-normalized vectors.
+///
+/// Seeding is k-means++ with a fixed-seed xorshift, so the same context always
+/// yields the same labels and the frontend's colors don't flicker between calls.
+pub struct Clustering {
+    /// Cluster label per input vector
+    pub labels: Vec<usize>,
+    /// Index (into the input) of the member closest to each centroid
+    pub representatives: Vec<usize>,
+    pub sizes: Vec<usize>,
+}
+
+const MAX_ITERATIONS: usize = 50;
+
+pub fn kmeans_cosine(vectors: &[Vec<f32>], k: usize) -> Clustering {
+    let n = vectors.len();
+    let k = k.clamp(1, n.max(1));
+    if n == 0 {
+        return Clustering { labels: vec![], representatives: vec![], sizes: vec![] };
+    }
+
+    let points: Vec<Vec<f32>> = vectors.iter().map(|v| normalized(v)).collect();
+    let mut centroids = seed_plus_plus(&points, k);
+    let mut labels = vec![0usize; n];
+
+    for iteration in 0..MAX_ITERATIONS {
+        // Assign
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let best = nearest(&centroids, p).0;
+            if best != labels[i] {
+                labels[i] = best;
+                changed = true;
+            }
+        }
+        if !changed && iteration > 0 {
+            break;
+        }
+
+        // Update: mean direction of members; empty clusters keep their centroid
+        let dim = points[0].len();
+        let mut sums = vec![vec![0.0f32; dim]; k];
+        for (p, &label) in points.iter().zip(&labels) {
+            for (s, x) in sums[label].iter_mut().zip(p) {
+                *s += x;
+            }
+        }
+        for (centroid, sum) in centroids.iter_mut().zip(sums) {
+            if sum.iter().any(|x| *x != 0.0) {
+                *centroid = normalized(&sum);
+            }
+        }
+    }
+
+    let mut sizes = vec![0usize; k];
+    let mut representatives = vec![usize::MAX; k];
+    let mut best_similarity = vec![f32::NEG_INFINITY; k];
+    for (i, (p, &label)) in points.iter().zip(&labels).enumerate() {
+        sizes[label] += 1;
+        let similarity = dot(p, &centroids[label]);
+        if similarity > best_similarity[label] {
+            best_similarity[label] = similarity;
+            representatives[label] = i;
+        }
+    }
+
+    // Drop empty clusters and relabel densely, largest cluster first
+    let mut order: Vec<usize> = (0..k).filter(|&c| sizes[c] > 0).collect();
+    order.sort_by(|a, b| sizes[*b].cmp(&sizes[*a]).then(a.cmp(b)));
+    let mut relabel = vec![0usize; k];
+    for (new, &old) in order.iter().enumerate() {
+        relabel[old] = new;
+    }
+
+    Clustering {
+        labels: labels.iter().map(|&l| relabel[l]).collect(),
+        representatives: order.iter().map(|&c| representatives[c]).collect(),
+        sizes: order.iter().map(|&c| sizes[c]).collect(),
+    }
+}
+
+/// Heuristic cluster count when the caller doesn't choose one.
+pub fn default_k(n: usize) -> usize {
+    ((n as f64 / 2.0).sqrt().round() as usize).clamp(1, 12).min(n.max(1))
+}
+
+fn seed_plus_plus(points: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+    let mut centroids = vec![points[rng.next() as usize % points.len()].clone()];
+
+    while centroids.len() < k {
+        // Pick proportional to cosine distance from the nearest chosen centroid
+        let weights: Vec<f64> = points
+            .iter()
+            .map(|p| (1.0 - nearest(&centroids, p).1).max(0.0) as f64)
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            break; // every point coincides with a centroid
+        }
+
+        let mut target = rng.next_f64() * total;
+        let mut chosen = points.len() - 1;
+        for (i, w) in weights.iter().enumerate() {
+            if target < *w {
+                chosen = i;
+                break;
+            }
+            target -= w;
+        }
+        centroids.push(points[chosen].clone());
+    }
+    centroids
+}
+
+/// (index, similarity) of the most similar centroid
+fn nearest(centroids: &[Vec<f32>], p: &[f32]) -> (usize, f32) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(p, c)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0))
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+    v.iter().map(|x| x / norm).collect()
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
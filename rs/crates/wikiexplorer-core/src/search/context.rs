@@ -0,0 +1,117 @@
+//! The graph being explored as a ranking signal: candidates close to what is
+//! already on it rank higher, so expanding a node continues the exploration
+//! instead of restarting it.
+//!
+//! A candidate's affinity is its cosine to the centroid of the context
+//! articles' vectors. A wikilink to or from a context article counts as at
+//! least CROSS_EDGE_THRESHOLD, the similarity at which the graph draws an
+//! edge, which also keeps the signal alive on indexes that can't reconstruct.
+//! Rankers see it as one more signal named [`CONTEXT_SIGNAL`] with weight
+//! WEIGHT_CONTEXT.
+
+use crate::config::get_config;
+use crate::search::corpus::Corpus;
+use crate::search::engine::IndexHandle;
+use crate::search::similarity::{cosine_similarity, normalize};
+use crate::utils::cancel::run_blocking;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Name of the signal, e.g. for its coefficient in RANKING_MODEL_FILE
+pub const CONTEXT_SIGNAL: &str = "context";
+
+/// Context articles considered; the rest of a very large graph is ignored
+const MAX_CONTEXT: usize = 200;
+
+pub struct GraphContext {
+    /// Unit-length mean of the context vectors; None when none could be reconstructed
+    centroid: Option<Vec<f32>>,
+    /// Articles linking to or linked from a context article
+    linked: HashSet<i64>,
+}
+
+impl GraphContext {
+    /// None without context articles or with WEIGHT_CONTEXT at 0.
+    pub async fn load(corpus: &Corpus, context: &[i64]) -> Result<Option<Self>, AppError> {
+        let config = get_config();
+        if context.is_empty() || config.weight_context == 0.0 || !config.weight_context.is_finite() {
+            return Ok(None);
+        }
+        let context: Vec<i64> = context.iter().copied().take(MAX_CONTEXT).collect();
+
+        let index = Arc::clone(&corpus.index);
+        let ids = context.clone();
+        let centroid = run_blocking(move |_| Ok(centroid(&index, &ids))).await?;
+        let linked = linked_articles(&corpus.db, &context).await?;
+        if centroid.is_none() && linked.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self { centroid, linked }))
+    }
+
+    /// Affinity in [0, 1] of each of `candidates` to the context.
+    pub async fn affinities(self: &Arc<Self>, index: &Arc<IndexHandle>, candidates: &[i64]) -> Result<HashMap<i64, f64>, AppError> {
+        let (context, index, candidates) = (Arc::clone(self), Arc::clone(index), candidates.to_vec());
+        run_blocking(move |cancel| {
+            let floor = get_config().cross_edge_threshold.clamp(0.0, 1.0);
+            let mut affinities = HashMap::with_capacity(candidates.len());
+            for (n, id) in candidates.into_iter().enumerate() {
+                if n % 256 == 0 {
+                    cancel.check()?;
+                }
+                let semantic = match &context.centroid {
+                    Some(centroid) if index.can_reconstruct => {
+                        index.reconstruct(id).map_or(0.0, |v| cosine_similarity(centroid, &v) as f64)
+                    }
+                    _ => 0.0,
+                };
+                let affinity = if context.linked.contains(&id) { semantic.max(floor) } else { semantic };
+                affinities.insert(id, affinity);
+            }
+            Ok(affinities)
+        })
+        .await
+    }
+}
+
+fn centroid(index: &IndexHandle, ids: &[i64]) -> Option<Vec<f32>> {
+    if !index.can_reconstruct {
+        return None;
+    }
+    let mut sum: Option<Vec<f32>> = None;
+    for &id in ids {
+        let Ok(mut v) = index.reconstruct(id) else {
+            continue;
+        };
+        if !normalize(&mut v) {
+            continue;
+        }
+        match &mut sum {
+            Some(sum) => sum.iter_mut().zip(&v).for_each(|(s, x)| *s += x),
+            None => sum = Some(v),
+        }
+    }
+    let mut centroid = sum?;
+    normalize(&mut centroid).then_some(centroid)
+}
+
+/// Neighbors of `context` in the `links` table; none without the table.
+async fn linked_articles(pool: &sqlx::SqlitePool, context: &[i64]) -> Result<HashSet<i64>, AppError> {
+    let params = placeholders(context.len());
+    let sql = format!(
+        "SELECT target_id FROM links WHERE source_id IN ({0}) UNION SELECT source_id FROM links WHERE target_id IN ({0})",
+        params
+    );
+    let mut query = sqlx::query_scalar::<_, i64>(&sql);
+    for id in context.iter().chain(context) {
+        query = query.bind(id);
+    }
+    match with_deadline("context links", query.fetch_all(pool)).await {
+        Ok(ids) => Ok(ids.into_iter().collect()),
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => Ok(HashSet::new()),
+        Err(e) => Err(e),
+    }
+}
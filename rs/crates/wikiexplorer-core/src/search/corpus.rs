@@ -0,0 +1,49 @@
+use crate::config::CorpusSpec;
+use crate::search::engine::IndexHandle;
+use crate::signals::SignalRegistry;
+use crate::utils::errors::AppError;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::info;
+
+/// A named index + metadata DB pair that can be searched independently.
+///
+/// The primary corpus (INDEX_PATH / METADATA_PATH) is assembled on demand from
+/// the hot-reloadable state; extra corpora from `CORPORA` are loaded once at startup.
+/// Corpora share the default embedding model unless their spec names one
+/// (per-language indexes built with a multilingual model).
+#[derive(Clone)]
+pub struct Corpus {
+    pub name: String,
+    pub index: Arc<IndexHandle>,
+    pub db: SqlitePool,
+    pub signals: Arc<SignalRegistry>,
+    /// Language served, for requests selecting a corpus by `lang`
+    pub lang: Option<String>,
+    /// Embedding model of the index; None for the default one
+    pub model: Option<String>,
+}
+
+impl Corpus {
+    pub async fn load(spec: &CorpusSpec) -> Result<Self, AppError> {
+        info!("Loading corpus '{}'...", spec.name);
+
+        let path = spec.index_path.clone();
+        let index = tokio::task::spawn_blocking(move || IndexHandle::load(&path))
+            .await
+            .map_err(|e| AppError::Anyhow(e.into()))??;
+
+        let db = SqlitePool::connect(&format!("sqlite:{}", spec.metadata_path)).await?;
+        let signals = SignalRegistry::load(&db).await;
+
+        info!("✓ Corpus '{}' ready", spec.name);
+        Ok(Self {
+            name: spec.name.clone(),
+            index: Arc::new(index),
+            db,
+            signals: Arc::new(signals),
+            lang: spec.lang.clone(),
+            model: spec.model.clone(),
+        })
+    }
+}
@@ -0,0 +1,834 @@
+use crate::config::get_config;
+use crate::index::knn::load_knn_edges;
+use crate::index::stable_ids::StableIds;
+use crate::search::engine::IndexHandle;
+use crate::search::embedder::MODEL_VERSION;
+use crate::search::lanes::{lanes, Resource};
+use crate::search::similarity::{normalize, similarity_into, unit_matrix};
+use crate::utils::cancel::{run_blocking, CancelToken};
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use ndarray::{Array2, Axis};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct EdgeResult {
+    // Titles, shared by every edge of an article
+    pub source: Arc<str>,
+    pub target: Arc<str>,
+    /// Public IDs of the endpoints, unique where titles may not be (within a corpus)
+    #[serde(default)]
+    pub source_id: i64,
+    #[serde(default)]
+    pub target_id: i64,
+    pub score: f32,
+    // Set for edges between results of different corpora (federated search)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_corpus: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_corpus: Option<String>,
+    // Provenance, so the frontend can show where a score came from
+    #[serde(default)]
+    pub origin: EdgeOrigin,
+    /// Embedding model that produced the score
+    #[serde(default)]
+    pub model_version: String,
+    /// Whether a wikilink exists between the two articles (None when the DB has no `links` table)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wikilink: Option<bool>,
+}
+
+/// `score` of wikilink edges: below every similarity threshold, so they never
+/// pass for a similarity and are the first pruned
+pub const WIKILINK_SCORE: f32 = 0.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeOrigin {
+    /// Read from the `cached_edges` table
+    Cache,
+    /// Read from the precomputed `knn_edges` table
+    Knn,
+    /// Computed from index vectors for this request
+    #[default]
+    Computed,
+    /// A wikilink, standing in for similarity when the index can't reconstruct
+    /// vectors; scored [`WIKILINK_SCORE`], as it measures no similarity
+    Wikilink,
+}
+
+/// Where a response's cross-edges come from, so clients know whether
+/// semantic edges are available
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeSource {
+    /// Similarities from index vectors, cached and precomputed edges
+    Semantic,
+    /// No vectors to compare: cached and precomputed edges, then wikilinks
+    Fallback,
+}
+
+impl EdgeSource {
+    pub fn of(index: &IndexHandle) -> Self {
+        if index.can_reconstruct {
+            EdgeSource::Semantic
+        } else {
+            EdgeSource::Fallback
+        }
+    }
+}
+
+/// Limits on the cross-edges of one response
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EdgeBudget {
+    /// Strongest edges kept overall (0 = all)
+    pub max_edges: usize,
+    /// Strongest edges kept per node; an edge stays if it is among either
+    /// endpoint's top M (0 = all)
+    pub top_m: usize,
+    /// Wall-clock limit for the vector math
+    pub time: Option<Duration>,
+}
+
+impl EdgeBudget {
+    /// CROSS_EDGE_MAX_EDGES / CROSS_EDGE_TOP_M / CROSS_EDGE_BUDGET_MS, for interactive responses
+    pub fn from_config() -> Self {
+        let config = get_config();
+        Self {
+            max_edges: config.cross_edge_max_edges,
+            top_m: config.cross_edge_top_m,
+            time: (config.cross_edge_budget_ms > 0).then(|| Duration::from_millis(config.cross_edge_budget_ms)),
+        }
+    }
+}
+
+/// Every edge at or above `threshold` among `new_node_ids` and from them to
+/// `existing_node_ids`, for exports and offline jobs.
+pub async fn calculate_global_cross_edges(
+    index: &Arc<IndexHandle>,
+    pool: &SqlitePool,
+    new_node_ids: &[i64],
+    existing_node_ids: &[i64],
+    threshold: f32,
+) -> Result<Vec<EdgeResult>, AppError> {
+    let budget = EdgeBudget::default();
+    let (edges, _) = calculate_cross_edges(index, pool, new_node_ids, existing_node_ids, threshold, &budget).await?;
+    Ok(edges)
+}
+
+/// Cross-edges within `budget`; true when edges were cut by the edge count or
+/// left uncomputed when time ran out.
+pub async fn calculate_cross_edges(
+    index: &Arc<IndexHandle>,
+    pool: &SqlitePool,
+    new_node_ids: &[i64],
+    existing_node_ids: &[i64],
+    threshold: f32,
+    budget: &EdgeBudget,
+) -> Result<(Vec<EdgeResult>, bool), AppError> {
+    if new_node_ids.is_empty() {
+        return Ok((vec![], false));
+    }
+
+    let start_time = std::time::Instant::now();
+
+    // 1. Normalize Inputs
+    let new_ids_set: HashSet<i64> = new_node_ids.iter().cloned().collect();
+    // Ensure existing IDs don't overlap with new ones to avoid self-loops/dupes
+    let existing_ids_set: HashSet<i64> = existing_node_ids
+        .iter()
+        .filter(|id| !new_ids_set.contains(id))
+        .cloned()
+        .collect();
+
+    let mut combined_edges: HashMap<(i64, i64), f32> = HashMap::new();
+    let mut resolved_nodes: HashSet<i64> = HashSet::new();
+    // Cached edge key -> model version it was computed with
+    let mut cached: HashMap<(i64, i64), String> = HashMap::new();
+
+    // 2. Query Cache (DB Lookup)
+    // Only nodes whose cached edges were computed at this threshold or a lower
+    // one, against every node of the current graph, are complete in the cache
+    let graph: HashSet<i64> = existing_ids_set.union(&new_ids_set).cloned().collect();
+    let new_ids_vec: Vec<i64> = new_ids_set.iter().cloned().collect();
+    let covered = match covered_nodes(pool, &index.ids, &new_ids_vec, &graph, threshold).await {
+        Ok(covered) => covered,
+        Err(e) => {
+            warn!("⚠ Edge cache coverage lookup failed: {}", e);
+            vec![]
+        }
+    };
+    match load_cached_edges(pool, &index.ids, &covered, threshold).await {
+        Ok(rows) => {
+            for (src, tgt, score, model_version) in rows {
+                if graph.contains(&src) && graph.contains(&tgt) {
+                    let key = if src < tgt { (src, tgt) } else { (tgt, src) };
+                    combined_edges.insert(key, score);
+                    cached.insert(key, model_version);
+                }
+            }
+            resolved_nodes.extend(covered);
+        }
+        Err(e) => warn!("⚠ Edge cache lookup failed: {}", e),
+    }
+
+    // 2b. Precomputed neighbours: a node whose k-th neighbour scores below the
+    // threshold has every edge above it in its list (up to ANN recall)
+    let mut from_knn: HashMap<(i64, i64), String> = HashMap::new();
+    let unresolved: Vec<i64> = new_ids_set.difference(&resolved_nodes).map(|&id| index.ids.public(id)).collect();
+    match load_knn_edges(pool, &unresolved).await {
+        Ok(lists) => {
+            for (source, neighbours) in lists {
+                let complete = neighbours.last().is_some_and(|(_, score, _)| *score < threshold);
+                let Some(node) = index.ids.article(source).filter(|_| complete) else {
+                    continue;
+                };
+                for (target, score, model_version) in neighbours {
+                    let Some(other) = index.ids.article(target) else {
+                        continue;
+                    };
+                    if score >= threshold && (existing_ids_set.contains(&other) || new_ids_set.contains(&other)) {
+                        let key = if node < other { (node, other) } else { (other, node) };
+                        if !cached.contains_key(&key) {
+                            combined_edges.insert(key, score);
+                            from_knn.insert(key, model_version);
+                        }
+                    }
+                }
+                resolved_nodes.insert(node);
+            }
+        }
+        Err(e) => warn!("⚠ kNN edge lookup failed: {}", e),
+    }
+
+    // 3. Compute Missing (Vector Math)
+    // Identify nodes that weren't resolved by DB cache
+    let nodes_to_compute: Vec<i64> = new_ids_set
+        .difference(&resolved_nodes)
+        .cloned()
+        .collect();
+
+    let mut truncated = false;
+    let mut from_links: HashSet<(i64, i64)> = HashSet::new();
+    if !index.can_reconstruct && !nodes_to_compute.is_empty() {
+        // 3a. Fallback: wikilinks connect what vectors can't
+        let others: Vec<i64> = existing_ids_set.union(&new_ids_set).cloned().collect();
+        match link_edges(pool, &nodes_to_compute, &others).await {
+            Ok(keys) => {
+                for key in keys {
+                    if let Entry::Vacant(entry) = combined_edges.entry(key) {
+                        entry.insert(WIKILINK_SCORE);
+                        from_links.insert(key);
+                    }
+                }
+            }
+            Err(e) => warn!("⚠ Wikilink edge lookup failed: {}", e),
+        }
+    } else if !nodes_to_compute.is_empty() {
+        // Reconstruction and matmuls are CPU bound; abandoned if the request is dropped
+        let handle = Arc::clone(index);
+        let nodes = nodes_to_compute.clone();
+        let context_pool: Vec<i64> = existing_ids_set.union(&resolved_nodes).cloned().collect();
+        let deadline = budget.time.map(|time| start_time + time);
+        let _permit = lanes().acquire(Resource::Index).await;
+        let (computed, out_of_time) = run_blocking(move |cancel| {
+            compute_edges(&handle, &nodes, &context_pool, threshold, deadline, cancel)
+        })
+        .await?;
+        truncated |= out_of_time;
+
+        let fresh: Vec<(i64, i64, f32)> = computed
+            .iter()
+            .map(|(&(src, tgt), &score)| (index.ids.public(src), index.ids.public(tgt), score))
+            .collect();
+        for (key, score) in computed {
+            combined_edges
+                .entry(key)
+                .and_modify(|e| *e = score.max(*e))
+                .or_insert(score);
+        }
+
+        // Persist freshly computed edges so later requests (and restored sessions) skip the math.
+        // Rows cut short by the deadline leave their nodes' edges incomplete.
+        if let Err(e) = store_cached_edges(pool, &fresh, index.ids.id_space()).await {
+            warn!("⚠ Could not cache {} edges: {}", fresh.len(), e);
+        } else if !out_of_time {
+            let nodes: Vec<i64> = nodes_to_compute.iter().map(|&id| index.ids.public(id)).collect();
+            let context: Vec<i64> = graph.iter().map(|&id| index.ids.public(id)).collect();
+            if let Err(e) = store_edge_coverage(pool, &nodes, &context, threshold, index.ids.id_space()).await {
+                warn!("⚠ Could not record edge cache coverage of {} nodes: {}", nodes.len(), e);
+            }
+        }
+    }
+
+    // 3b. Keep the response readable: each node's strongest edges, then the strongest overall
+    let before = combined_edges.len();
+    prune_edges(&mut combined_edges, budget);
+    truncated |= budget.max_edges > 0 && before > budget.max_edges;
+
+    // 4. Resolve Titles (Final DB Lookup)
+    // Collect all unique IDs involved in edges
+    let mut needed_ids = HashSet::new();
+    for (src, tgt) in combined_edges.keys() {
+        needed_ids.insert(*src);
+        needed_ids.insert(*tgt);
+    }
+
+    if needed_ids.is_empty() {
+        return Ok((vec![], truncated));
+    }
+
+    // Resolve titles
+    let mut id_to_title: HashMap<i64, Arc<str>> = HashMap::with_capacity(needed_ids.len());
+    let params = placeholders(needed_ids.len());
+    let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", params);
+    
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in &needed_ids {
+        query = query.bind(id);
+    }
+    
+    let rows = with_deadline("edge titles", query.fetch_all(pool)).await?;
+    for (id, title) in rows {
+        id_to_title.insert(id, title.into());
+    }
+
+    let keys: Vec<(i64, i64)> = combined_edges.keys().cloned().collect();
+    let wikilinks = match linked_pairs(pool, &keys).await {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            warn!("⚠ Wikilink lookup failed: {}", e);
+            None
+        }
+    };
+
+    // Format output
+    let mut final_output = Vec::new();
+    for ((src_id, tgt_id), score) in combined_edges {
+        if let (Some(src_title), Some(tgt_title)) = (id_to_title.get(&src_id), id_to_title.get(&tgt_id)) {
+            let key = (src_id, tgt_id);
+            let (origin, model_version) = match (cached.get(&key), from_knn.get(&key)) {
+                (Some(model), _) => (EdgeOrigin::Cache, model.clone()),
+                (None, Some(model)) => (EdgeOrigin::Knn, model.clone()),
+                (None, None) if from_links.contains(&key) => (EdgeOrigin::Wikilink, String::new()),
+                (None, None) => (EdgeOrigin::Computed, MODEL_VERSION.to_string()),
+            };
+            final_output.push(EdgeResult {
+                source: Arc::clone(src_title),
+                target: Arc::clone(tgt_title),
+                source_id: index.ids.public(src_id),
+                target_id: index.ids.public(tgt_id),
+                score,
+                source_corpus: None,
+                target_corpus: None,
+                origin,
+                model_version,
+                wikilink: wikilinks.as_ref().map(|pairs| pairs.contains(&(src_id, tgt_id))),
+            });
+        }
+    }
+
+    info!(
+        "Cross-edges: {} returned ({} from cache, {} from kNN, {} from wikilinks{}) in {:?}",
+        final_output.len(),
+        cached.len(),
+        from_knn.len(),
+        from_links.len(),
+        if truncated { ", truncated" } else { "" },
+        start_time.elapsed()
+    );
+    Ok((final_output, truncated))
+}
+
+/// Drops edges outside both endpoints' top `top_m`, then all but the
+/// `max_edges` strongest.
+fn prune_edges(edges: &mut HashMap<(i64, i64), f32>, budget: &EdgeBudget) {
+    if budget.top_m > 0 {
+        let mut by_node: HashMap<i64, Vec<f32>> = HashMap::new();
+        for (&(a, b), &score) in edges.iter() {
+            by_node.entry(a).or_default().push(score);
+            by_node.entry(b).or_default().push(score);
+        }
+        // Score of each node's M-th strongest edge
+        let cutoff: HashMap<i64, f32> = by_node
+            .into_iter()
+            .map(|(node, mut scores)| {
+                scores.sort_by(|x, y| y.total_cmp(x));
+                (node, scores.get(budget.top_m - 1).copied().unwrap_or(f32::NEG_INFINITY))
+            })
+            .collect();
+        edges.retain(|(a, b), score| *score >= cutoff[a] || *score >= cutoff[b]);
+    }
+
+    if budget.max_edges > 0 && edges.len() > budget.max_edges {
+        let mut ranked: Vec<((i64, i64), f32)> = edges.drain().collect();
+        ranked.sort_by(|x, y| y.1.total_cmp(&x.1));
+        ranked.truncate(budget.max_edges);
+        edges.extend(ranked);
+    }
+}
+
+// --- Edge cache ---
+
+const EDGE_CACHE_SCHEMA: &str = "(
+    source_id INTEGER NOT NULL,
+    target_id INTEGER NOT NULL,
+    score REAL NOT NULL,
+    model_version TEXT NOT NULL DEFAULT 'all-MiniLM-L6-v2',
+    id_space TEXT NOT NULL DEFAULT 'legacy',
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (model_version, source_id, target_id)
+)";
+
+/// Per node, the threshold and graph (public IDs, comma-separated) its cached
+/// edges were last computed at and against
+const EDGE_COVERAGE_SCHEMA: &str = "(
+    node_id INTEGER NOT NULL,
+    threshold REAL NOT NULL,
+    node_ids TEXT NOT NULL,
+    model_version TEXT NOT NULL,
+    id_space TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (model_version, id_space, node_id)
+)";
+
+pub async fn ensure_edge_cache_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("CREATE TABLE IF NOT EXISTS cached_edges {}", EDGE_CACHE_SCHEMA))
+        .execute(pool)
+        .await?;
+    sqlx::query(&format!("CREATE TABLE IF NOT EXISTS cached_edge_coverage {}", EDGE_COVERAGE_SCHEMA))
+        .execute(pool)
+        .await?;
+
+    // Tables created before provenance was tracked
+    let has_model_version: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('cached_edges') WHERE name = 'model_version'")
+            .fetch_optional(pool)
+            .await?;
+    if has_model_version.is_none() {
+        sqlx::query("ALTER TABLE cached_edges ADD COLUMN model_version TEXT NOT NULL DEFAULT 'all-MiniLM-L6-v2'")
+            .execute(pool)
+            .await?;
+    }
+    // Tables created before IDs were versioned: their rows are in an unknown
+    // ID space and never match again
+    let has_id_space: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('cached_edges') WHERE name = 'id_space'")
+            .fetch_optional(pool)
+            .await?;
+    if has_id_space.is_none() {
+        sqlx::query("ALTER TABLE cached_edges ADD COLUMN id_space TEXT NOT NULL DEFAULT 'legacy'")
+            .execute(pool)
+            .await?;
+    }
+    // Tables created before edges were keyed by model hold one score per pair,
+    // whichever model wrote it last
+    let keyed: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('cached_edges') WHERE name = 'model_version' AND pk > 0")
+            .fetch_optional(pool)
+            .await?;
+    if keyed.is_none() {
+        let mut tx = pool.begin().await?;
+        sqlx::query("ALTER TABLE cached_edges RENAME TO cached_edges_old").execute(&mut *tx).await?;
+        sqlx::query(&format!("CREATE TABLE cached_edges {}", EDGE_CACHE_SCHEMA))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO cached_edges (source_id, target_id, score, model_version, id_space, created_at)
+             SELECT source_id, target_id, score, model_version, id_space, created_at FROM cached_edges_old",
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DROP TABLE cached_edges_old").execute(&mut *tx).await?;
+        tx.commit().await?;
+    }
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_cached_edges_target ON cached_edges (target_id)")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// This model's cached edges touching any of `article_ids` with a score at or
+/// above `threshold`, as article IDs (the table is keyed by stable IDs so it
+/// survives rebuilds; edges to articles this index lacks, or saved under
+/// another [`StableIds::id_space`], are dropped).
+/// A missing table (read-only DB) is treated as an empty cache.
+async fn load_cached_edges(
+    pool: &SqlitePool,
+    stable_ids: &StableIds,
+    article_ids: &[i64],
+    threshold: f32,
+) -> Result<Vec<(i64, i64, f32, String)>, AppError> {
+    if article_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let ids: Vec<i64> = article_ids.iter().map(|&id| stable_ids.public(id)).collect();
+    let params = placeholders(ids.len());
+    let sql = format!(
+        "SELECT source_id, target_id, score, model_version FROM cached_edges
+         WHERE (source_id IN ({0}) OR target_id IN ({0})) AND score >= ? AND id_space = ? AND model_version = ?",
+        params
+    );
+    let mut query = sqlx::query_as::<_, (i64, i64, f32, String)>(&sql);
+    for id in ids.iter().chain(&ids) {
+        query = query.bind(id);
+    }
+    let query = query.bind(threshold).bind(stable_ids.id_space()).bind(MODEL_VERSION);
+    let rows = match with_deadline("cached edges", query.fetch_all(pool)).await {
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => return Ok(vec![]),
+        other => other?,
+    };
+    Ok(rows
+        .into_iter()
+        .filter_map(|(src, tgt, score, model)| Some((stable_ids.article(src)?, stable_ids.article(tgt)?, score, model)))
+        .collect())
+}
+
+/// Stores this model's edges keyed by (smaller id, larger id). IDs are public ones, in `id_space`.
+pub async fn store_cached_edges(
+    pool: &SqlitePool,
+    edges: &[(i64, i64, f32)],
+    id_space: &str,
+) -> Result<(), sqlx::Error> {
+    if edges.is_empty() {
+        return Ok(());
+    }
+    let mut tx = pool.begin().await?;
+    for &(src, tgt, score) in edges {
+        let (src, tgt) = if src < tgt { (src, tgt) } else { (tgt, src) };
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO cached_edges (source_id, target_id, score, model_version, id_space)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(src)
+        .bind(tgt)
+        .bind(score)
+        .bind(MODEL_VERSION)
+        .bind(id_space)
+        .execute(&mut *tx)
+        .await;
+        match result {
+            Err(e) if e.to_string().contains("no such table") => return Ok(()),
+            other => other?,
+        };
+    }
+    tx.commit().await
+}
+
+/// Articles of `article_ids` whose cached edges are complete for `graph`: last
+/// computed at `threshold` or below, against every node of `graph`.
+/// A missing table (read-only DB) covers nothing.
+async fn covered_nodes(
+    pool: &SqlitePool,
+    stable_ids: &StableIds,
+    article_ids: &[i64],
+    graph: &HashSet<i64>,
+    threshold: f32,
+) -> Result<Vec<i64>, AppError> {
+    if article_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let ids: Vec<i64> = article_ids.iter().map(|&id| stable_ids.public(id)).collect();
+    let sql = format!(
+        "SELECT node_id, threshold, node_ids FROM cached_edge_coverage
+         WHERE node_id IN ({}) AND id_space = ? AND model_version = ?",
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query_as::<_, (i64, f32, String)>(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let query = query.bind(stable_ids.id_space()).bind(MODEL_VERSION);
+    let rows = match with_deadline("edge cache coverage", query.fetch_all(pool)).await {
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => return Ok(vec![]),
+        other => other?,
+    };
+    let graph: Vec<i64> = graph.iter().map(|&id| stable_ids.public(id)).collect();
+    Ok(rows
+        .into_iter()
+        .filter(|(_, stored, _)| threshold >= *stored)
+        .filter(|(node, _, node_ids)| {
+            let covered: HashSet<i64> = node_ids.split(',').filter_map(|id| id.parse().ok()).collect();
+            graph.iter().all(|id| id == node || covered.contains(id))
+        })
+        .filter_map(|(node, _, _)| stable_ids.article(node))
+        .collect())
+}
+
+/// Records that the edges of `nodes` scoring at least `threshold` against
+/// every node of `graph` are cached. IDs are public ones, in `id_space`.
+pub async fn store_edge_coverage(
+    pool: &SqlitePool,
+    nodes: &[i64],
+    graph: &[i64],
+    threshold: f32,
+    id_space: &str,
+) -> Result<(), sqlx::Error> {
+    if nodes.is_empty() {
+        return Ok(());
+    }
+    let mut sorted = graph.to_vec();
+    sorted.sort_unstable();
+    let node_ids = sorted.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    let mut tx = pool.begin().await?;
+    for &node in nodes {
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO cached_edge_coverage (node_id, threshold, node_ids, model_version, id_space)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(node)
+        .bind(threshold)
+        .bind(&node_ids)
+        .bind(MODEL_VERSION)
+        .bind(id_space)
+        .execute(&mut *tx)
+        .await;
+        match result {
+            Err(e) if e.to_string().contains("no such table") => return Ok(()),
+            other => other?,
+        };
+    }
+    tx.commit().await
+}
+
+/// Pairs (smaller id, larger id) among `pairs` joined by a wikilink in either
+/// direction. None when the DB has no `links (source_id, target_id)` table.
+async fn linked_pairs(pool: &SqlitePool, pairs: &[(i64, i64)]) -> Result<Option<HashSet<(i64, i64)>>, AppError> {
+    let mut linked = HashSet::new();
+    // Four bound values per pair; 200 pairs stays well under SQLite's variable limit
+    for chunk in pairs.chunks(200) {
+        let clauses = vec!["(source_id = ? AND target_id = ?) OR (source_id = ? AND target_id = ?)"; chunk.len()];
+        let sql = format!("SELECT source_id, target_id FROM links WHERE {}", clauses.join(" OR "));
+        let mut query = sqlx::query_as::<_, (i64, i64)>(&sql);
+        for &(a, b) in chunk {
+            query = query.bind(a).bind(b).bind(b).bind(a);
+        }
+        match with_deadline("wikilinks", query.fetch_all(pool)).await {
+            Ok(rows) => linked.extend(rows.into_iter().map(|(a, b)| if a < b { (a, b) } else { (b, a) })),
+            Err(AppError::Database(e)) if e.to_string().contains("no such table") => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(Some(linked))
+}
+
+/// Wikilinks (either direction) between `nodes` and `others`, as (smaller id,
+/// larger id). Empty when the DB has no `links` table.
+async fn link_edges(pool: &SqlitePool, nodes: &[i64], others: &[i64]) -> Result<HashSet<(i64, i64)>, AppError> {
+    if others.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let (node_params, other_params) = (placeholders(nodes.len()), placeholders(others.len()));
+    let sql = format!(
+        "SELECT source_id, target_id FROM links
+         WHERE (source_id IN ({0}) AND target_id IN ({1})) OR (target_id IN ({0}) AND source_id IN ({1}))",
+        node_params, other_params
+    );
+    let mut query = sqlx::query_as::<_, (i64, i64)>(&sql);
+    for id in nodes.iter().chain(others).chain(nodes).chain(others) {
+        query = query.bind(id);
+    }
+    let rows = match with_deadline("wikilink edges", query.fetch_all(pool)).await {
+        Err(AppError::Database(e)) if e.to_string().contains("no such table") => return Ok(HashSet::new()),
+        other => other?,
+    };
+    Ok(rows
+        .into_iter()
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| if a < b { (a, b) } else { (b, a) })
+        .collect())
+}
+
+// --- Helpers ---
+
+/// Scores of ordered `(source, target)` article pairs
+type PairScores = HashMap<(i64, i64), f32>;
+
+/// Vectors and the article IDs they belong to, in the same order
+type IdVectors = (Vec<Vec<f32>>, Vec<i64>);
+
+/// Rows of the new-node matrix multiplied per step between deadline and
+/// cancellation checks; a page of results spans several steps
+const MATMUL_CHUNK_ROWS: usize = 8;
+
+/// Edges among `new_ids` and from `new_ids` to `context_ids` scoring at least
+/// `threshold`. Past `deadline` the remaining rows are skipped (true).
+fn compute_edges(
+    index: &IndexHandle,
+    new_ids: &[i64],
+    context_ids: &[i64],
+    threshold: f32,
+    deadline: Option<Instant>,
+    cancel: &CancelToken,
+) -> Result<(PairScores, bool), AppError> {
+    let mut edges = HashMap::new();
+
+    // A. Get Vectors for New Nodes
+    let Some((new_vecs, new_valid_ids)) = get_vectors(index, new_ids, deadline, cancel)? else {
+        return Ok((edges, true));
+    };
+    if new_vecs.is_empty() {
+        return Ok((edges, false));
+    }
+
+    // B. Get Vectors for Context (Existing) Nodes
+    let Some((ctx_vecs, ctx_valid_ids)) = get_vectors(index, context_ids, deadline, cancel)? else {
+        return Ok((edges, true));
+    };
+
+    let new_matrix = unit_matrix(new_vecs);
+    let ctx_matrix = (!ctx_vecs.is_empty()).then(|| unit_matrix(ctx_vecs));
+    let mut similarity_matrix = Array2::zeros((0, 0));
+
+    for (chunk_idx, rows) in new_matrix.axis_chunks_iter(Axis(0), MATMUL_CHUNK_ROWS).enumerate() {
+        cancel.check()?;
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok((edges, true));
+        }
+        let row_ids = &new_valid_ids[chunk_idx * MATMUL_CHUNK_ROWS..][..rows.nrows()];
+
+        // C. Calculate: New vs New
+        similarity_into(rows, new_matrix.view(), &mut similarity_matrix);
+        extract_edges(row_ids, &new_valid_ids, &similarity_matrix, threshold, &mut edges);
+
+        // D. Calculate: New vs Context
+        if let Some(ctx_matrix) = &ctx_matrix {
+            similarity_into(rows, ctx_matrix.view(), &mut similarity_matrix);
+            extract_edges(row_ids, &ctx_valid_ids, &similarity_matrix, threshold, &mut edges);
+        }
+    }
+    Ok((edges, false))
+}
+
+/// Unit-length vectors of `ids`; articles without one (or with a zero vector)
+/// are left out. None when `deadline` passes first.
+fn get_vectors(
+    index: &IndexHandle,
+    ids: &[i64],
+    deadline: Option<Instant>,
+    cancel: &CancelToken,
+) -> Result<Option<IdVectors>, AppError> {
+    let mut vecs = Vec::new();
+    let mut valid = Vec::new();
+
+    for (n, &id) in ids.iter().enumerate() {
+        if n % 256 == 0 {
+            cancel.check()?;
+        }
+        // Reconstruction can read from disk, so every vector counts against the budget
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok(None);
+        }
+        if let Ok(mut v) = index.reconstruct(id) {
+            if normalize(&mut v) {
+                vecs.push(v);
+                valid.push(id);
+            }
+        }
+    }
+    Ok(Some((vecs, valid)))
+}
+
+fn extract_edges(
+    row_ids: &[i64],
+    col_ids: &[i64],
+    matrix: &Array2<f32>,
+    threshold: f32,
+    accumulator: &mut HashMap<(i64, i64), f32>
+) {
+    for (row_idx, row) in matrix.outer_iter().enumerate() {
+        for (col_idx, &score) in row.iter().enumerate() {
+            let src = row_ids[row_idx];
+            let tgt = col_ids[col_idx];
+
+            if src == tgt { continue; }
+            if score < threshold { continue; }
+
+            // Normalize key order to prevent duplicates (A-B vs B-A)
+            let key = if src < tgt { (src, tgt) } else { (tgt, src) };
+            
+            // Only keep the edge if it's new or this score is higher
+            accumulator
+                .entry(key)
+                .and_modify(|e| *e = score.max(*e))
+                .or_insert(score);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::flat::FlatIndex;
+    use crate::search::similarity::Metric;
+
+    /// Articles 0-2 of a flat index: 1 scores 0.9 against 0, and 2 scores 0.6
+    fn index() -> Arc<IndexHandle> {
+        let vectors = vec![1.0, 0.0, 0.9, 0.19f32.sqrt(), 0.6, 0.8];
+        let flat = FlatIndex::from_vectors(vectors, 2, Metric::InnerProduct);
+        Arc::new(IndexHandle::from_replicas(vec![Box::new(flat)], ""))
+    }
+
+    async fn metadata() -> SqlitePool {
+        let dir = std::env::temp_dir().join(format!("wikiexplorer-edges-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", dir.join("metadata.db").display()))
+            .await
+            .unwrap();
+        ensure_edge_cache_table(&pool).await.unwrap();
+        sqlx::query("CREATE TABLE articles (article_id INTEGER PRIMARY KEY, title TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO articles VALUES (0, 'A'), (1, 'B'), (2, 'C')").execute(&pool).await.unwrap();
+        pool
+    }
+
+    async fn edges(pool: &SqlitePool, new: &[i64], existing: &[i64], threshold: f32) -> Vec<(i64, i64, EdgeOrigin)> {
+        let mut edges: Vec<(i64, i64, EdgeOrigin)> = calculate_global_cross_edges(&index(), pool, new, existing, threshold)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.source_id, e.target_id, e.origin))
+            .collect();
+        edges.sort_by_key(|&(source, target, _)| (source, target));
+        edges
+    }
+
+    #[tokio::test]
+    async fn lower_threshold_than_cached_recomputes() {
+        let pool = metadata().await;
+        assert_eq!(edges(&pool, &[0], &[1, 2], 0.8).await, vec![(0, 1, EdgeOrigin::Computed)]);
+        // The cache only holds the edges above 0.8
+        assert_eq!(
+            edges(&pool, &[0], &[1, 2], 0.5).await,
+            vec![(0, 1, EdgeOrigin::Computed), (0, 2, EdgeOrigin::Computed)]
+        );
+        assert_eq!(edges(&pool, &[0], &[1, 2], 0.8).await, vec![(0, 1, EdgeOrigin::Cache)]);
+    }
+
+    #[tokio::test]
+    async fn nodes_outside_the_cached_graph_are_computed() {
+        let pool = metadata().await;
+        assert_eq!(edges(&pool, &[0], &[1], 0.5).await, vec![(0, 1, EdgeOrigin::Computed)]);
+        // 0 was resolved against {0, 1} only, so its edge to 2 was never cached
+        assert_eq!(
+            edges(&pool, &[0], &[1, 2], 0.5).await,
+            vec![(0, 1, EdgeOrigin::Computed), (0, 2, EdgeOrigin::Computed)]
+        );
+        assert_eq!(
+            edges(&pool, &[0], &[1, 2], 0.5).await,
+            vec![(0, 1, EdgeOrigin::Cache), (0, 2, EdgeOrigin::Cache)]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+/// Content fingerprint of a result for cross-corpus duplicate detection: the
+/// same topic loaded from enwiki, simplewiki and a private wiki has different
+/// IDs but a near-identical title embedding (and often the same title).
+#[derive(Debug, Clone)]
+pub struct ArticleFingerprint {
+    pub corpus: String,
+    pub article_id: i64,
+    /// Lowercased title with underscores/whitespace collapsed
+    pub title_key: String,
+    /// L2-normalized embedding, so a dot product is the cosine similarity
+    pub embedding: Vec<f32>,
+}
+
+impl ArticleFingerprint {
+    pub fn new(corpus: &str, article_id: i64, title: &str, embedding: &[f32]) -> Self {
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+        Self {
+            corpus: corpus.to_string(),
+            article_id,
+            title_key: title
+                .replace('_', " ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase(),
+            embedding: embedding.iter().map(|x| x / norm).collect(),
+        }
+    }
+
+    fn similarity(&self, other: &ArticleFingerprint) -> f32 {
+        self.embedding
+            .iter()
+            .zip(other.embedding.iter())
+            .map(|(a, b)| a * b)
+            .sum()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DedupOutcome {
+    /// Indices (into the input) of the results to keep, in input order
+    pub kept: Vec<usize>,
+    /// `(duplicate index, kept index it collapses into)`
+    pub duplicates: Vec<(usize, usize)>,
+}
+
+/// Greedy duplicate collapse over results already sorted best-first.
+///
+/// A result is a duplicate of an earlier kept result from a *different* corpus
+/// when their titles normalize to the same key or their embeddings have cosine
+/// similarity >= `threshold`. Results within one corpus are never collapsed;
+/// that corpus's own ranking already decided they are distinct articles.
+pub fn dedup_across_corpora(items: &[ArticleFingerprint], threshold: f32) -> DedupOutcome {
+    let mut outcome = DedupOutcome::default();
+
+    for (i, item) in items.iter().enumerate() {
+        let duplicate_of = outcome.kept.iter().copied().find(|&k| {
+            let kept = &items[k];
+            kept.corpus != item.corpus
+                && ((!item.title_key.is_empty() && kept.title_key == item.title_key)
+                    || kept.similarity(item) >= threshold)
+        });
+
+        match duplicate_of {
+            Some(k) => outcome.duplicates.push((i, k)),
+            None => outcome.kept.push(i),
+        }
+    }
+
+    outcome
+}
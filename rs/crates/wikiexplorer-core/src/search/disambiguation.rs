@@ -0,0 +1,149 @@
+//! Ambiguous queries: "Mercury" is a planet, an element, a god and a
+//! singer. Expanding a graph node from the wrong one derails the exploration,
+//! so search responses list the senses and the frontend can ask first.
+//!
+//! A query is ambiguous when it names a disambiguation page (`Mercury`
+//! categorized as one, or `Mercury_(disambiguation)`), or when several of the
+//! best results share it as a title prefix (`Mercury (planet)`, `Mercury
+//! (element)`). The senses are those results followed by the articles the
+//! disambiguation page links to, most central first.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::categories::fetch_categories;
+use crate::search::corpus::Corpus;
+use crate::search::filter_policy::{default_policy, FilterVerdict};
+use crate::search::pipeline::SearchResult;
+use crate::search::titles::articles_by_title;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+
+/// Best results checked for titles sharing the query as a prefix
+const PREFIX_WINDOW: usize = 20;
+/// Results sharing the prefix that make a query ambiguous
+const MIN_PREFIX_SENSES: usize = 2;
+const MAX_SENSES: usize = 12;
+/// Categories listed per sense
+const MAX_SENSE_CATEGORIES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Disambiguation {
+    /// The disambiguation page the query names, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    pub senses: Vec<Sense>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Sense {
+    pub id: i64,
+    pub title: String,
+    /// A few of its categories, to tell the senses apart
+    pub categories: Vec<String>,
+}
+
+/// The senses of `query` given its ranked `results` (article IDs of
+/// `corpus`); None when the query isn't ambiguous.
+pub async fn disambiguate(
+    corpus: &Corpus,
+    query: &str,
+    results: &[SearchResult],
+) -> Result<Option<Disambiguation>, AppError> {
+    let query = fold(query);
+    if query.is_empty() {
+        return Ok(None);
+    }
+
+    let prefixed: Vec<(i64, String)> = results
+        .iter()
+        .filter(|r| r.corpus.as_deref().is_none_or(|c| c == corpus.name))
+        .take(PREFIX_WINDOW)
+        .filter(|r| shares_prefix(&fold(&r.title), &query))
+        .map(|r| (r.id, r.title.to_string()))
+        .collect();
+    let page = disambiguation_page(corpus, &query).await?;
+    if page.is_none() && prefixed.len() < MIN_PREFIX_SENSES {
+        return Ok(None);
+    }
+
+    let linked = match &page {
+        Some((id, _)) => linked_articles(corpus, *id).await?,
+        None => vec![],
+    };
+    let policy = default_policy();
+    let mut seen = HashSet::new();
+    let senses: Vec<(i64, String)> = prefixed
+        .into_iter()
+        .chain(linked)
+        .filter(|(_, title)| !matches!(policy.verdict(title), FilterVerdict::Exclude))
+        .filter(|(id, _)| seen.insert(*id))
+        .take(MAX_SENSES)
+        .collect();
+    if senses.len() < MIN_PREFIX_SENSES {
+        return Ok(None);
+    }
+
+    let ids: Vec<i64> = senses.iter().map(|(id, _)| *id).collect();
+    let mut categories = fetch_categories(&corpus.db, &ids).await?;
+    let senses = senses
+        .into_iter()
+        .map(|(id, title)| {
+            let mut categories = categories.remove(&id).unwrap_or_default();
+            // Maintenance categories say nothing about the sense
+            categories.retain(|c| !c.contains("articles") && !c.contains("pages"));
+            categories.sort();
+            categories.truncate(MAX_SENSE_CATEGORIES);
+            Sense { id, title, categories }
+        })
+        .collect();
+    Ok(Some(Disambiguation { page: page.map(|(_, title)| title), senses }))
+}
+
+/// Lowercase with spaces, as queries are typed.
+fn fold(title: &str) -> String {
+    title.replace('_', " ").trim().to_lowercase()
+}
+
+/// "mercury (planet)" and "mercury, california" share "mercury"; "mercury
+/// prize" doesn't name a sense of it.
+fn shares_prefix(title: &str, query: &str) -> bool {
+    title
+        .strip_prefix(query)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(" (") || rest.starts_with(','))
+}
+
+/// `query`'s own page when it is a disambiguation page, else `<query>
+/// (disambiguation)`, as (article ID, title).
+async fn disambiguation_page(corpus: &Corpus, query: &str) -> Result<Option<(i64, String)>, AppError> {
+    let title = query.replace(' ', "_");
+    let candidate = format!("{}_(disambiguation)", title);
+    let rows = articles_by_title(&corpus.db, &[&title, &candidate], "disambiguation page").await?;
+
+    if let Some(row) = rows.iter().find(|(_, t)| t.to_lowercase().ends_with("_(disambiguation)")) {
+        return Ok(Some(row.clone()));
+    }
+    let ids: Vec<i64> = rows.iter().map(|(id, _)| *id).collect();
+    let categories = fetch_categories(&corpus.db, &ids).await?;
+    Ok(rows.into_iter().find(|(id, _)| {
+        categories
+            .get(id)
+            .is_some_and(|c| c.iter().any(|c| c.contains("disambiguation")))
+    }))
+}
+
+/// Articles `page` links to, highest PageRank first; none without the `links` table.
+async fn linked_articles(corpus: &Corpus, page: i64) -> Result<Vec<(i64, String)>, AppError> {
+    if !corpus.signals.has_links {
+        return Ok(vec![]);
+    }
+    let query = sqlx::query_as::<_, (i64, String)>(
+        "SELECT a.article_id, a.title FROM links l JOIN articles a ON a.article_id = l.target_id
+         WHERE l.source_id = ? ORDER BY a.pagerank DESC LIMIT ?",
+    )
+    .bind(page)
+    .bind(MAX_SENSES as i64);
+    with_deadline("disambiguation links", query.fetch_all(&corpus.db)).await
+}
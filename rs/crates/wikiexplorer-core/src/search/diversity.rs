@@ -0,0 +1,130 @@
+//! Maximal marginal relevance: re-orders the head of a ranked pool so that
+//! near-duplicates ("Quantum mechanics", "Introduction to quantum mechanics",
+//! "History of quantum mechanics") don't crowd out other subtopics.
+//!
+//! Each pick maximizes `(1 - diversity) * relevance - diversity * redundancy`,
+//! where relevance is the result's score rescaled to [0, 1] within the window
+//! and redundancy its highest cosine to a result already picked. Scores are
+//! left as they are, so a diversified page is no longer sorted by score.
+
+use crate::search::engine::IndexHandle;
+use crate::search::pipeline::SearchResult;
+use crate::search::similarity::normalize;
+use crate::utils::cancel::CancelToken;
+use crate::utils::errors::AppError;
+
+/// Order in which to take items: `relevance` best first, `vectors` unit length
+/// (None counts as redundant with nothing). `diversity` 0 (or NaN) keeps the input order.
+pub fn mmr_order(relevance: &[f64], vectors: &[Option<Vec<f32>>], diversity: f32) -> Vec<usize> {
+    let n = relevance.len().min(vectors.len());
+    if n == 0 || diversity.is_nan() || diversity <= 0.0 {
+        return (0..n).collect();
+    }
+    let diversity = diversity.min(1.0) as f64;
+
+    let (lo, hi) = relevance[..n]
+        .iter()
+        .filter(|r| r.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &r| (lo.min(r), hi.max(r)));
+    let span = (hi - lo).max(f64::EPSILON);
+    let rescaled = |i: usize| if relevance[i].is_finite() { (relevance[i] - lo) / span } else { 0.0 };
+
+    // Highest similarity of each remaining item to the picked ones, updated per pick
+    let mut redundancy = vec![0.0f64; n];
+    let mut picked = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    while order.len() < n {
+        let next = (0..n)
+            .filter(|&i| !picked[i])
+            .map(|i| (i, (1.0 - diversity) * rescaled(i) - diversity * redundancy[i]))
+            // Ties go to the better-ranked item
+            .fold(None, |best: Option<(usize, f64)>, (i, v)| match best {
+                Some((_, b)) if b >= v => best,
+                _ => Some((i, v)),
+            })
+            .map(|(i, _)| i)
+            .expect("an item is left");
+        picked[next] = true;
+        order.push(next);
+
+        let Some(chosen) = &vectors[next] else {
+            continue;
+        };
+        for i in (0..n).filter(|&i| !picked[i]) {
+            if let Some(v) = &vectors[i] {
+                let similarity: f32 = chosen.iter().zip(v).map(|(a, b)| a * b).sum();
+                redundancy[i] = redundancy[i].max(similarity.max(0.0) as f64);
+            }
+        }
+    }
+    order
+}
+
+/// Re-orders the first `window` of `results` (sorted best first) by MMR, from
+/// their vectors in `index`. The rest of the pool keeps its place.
+pub fn diversify(
+    index: &IndexHandle,
+    results: &mut [SearchResult],
+    diversity: f32,
+    window: usize,
+    cancel: &CancelToken,
+) -> Result<(), AppError> {
+    let n = window.min(results.len());
+    if n < 2 || diversity <= 0.0 || !index.can_reconstruct {
+        return Ok(());
+    }
+
+    let mut vectors = Vec::with_capacity(n);
+    for (i, result) in results[..n].iter().enumerate() {
+        if i % 64 == 0 {
+            cancel.check()?;
+        }
+        vectors.push(index.reconstruct(result.id).ok().and_then(|mut v| normalize(&mut v).then_some(v)));
+    }
+    let relevance: Vec<f64> = results[..n].iter().map(|r| r.score_float).collect();
+    let order = mmr_order(&relevance, &vectors, diversity);
+
+    let head: Vec<SearchResult> = order.iter().map(|&i| results[i].clone()).collect();
+    for (slot, result) in results.iter_mut().zip(head) {
+        *slot = result;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(x: f32, y: f32) -> Option<Vec<f32>> {
+        let norm = (x * x + y * y).sqrt();
+        Some(vec![x / norm, y / norm])
+    }
+
+    #[test]
+    fn zero_diversity_keeps_the_input_order() {
+        let vectors = vec![unit(1.0, 0.0), unit(1.0, 0.01), unit(0.0, 1.0)];
+        assert_eq!(mmr_order(&[0.9, 0.8, 0.7], &vectors, 0.0), vec![0, 1, 2]);
+        assert_eq!(mmr_order(&[0.9, 0.8, 0.7], &vectors, f32::NAN), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn near_duplicate_drops_below_a_distinct_result() {
+        // 1 is almost 0 again; 2 is less relevant but about something else
+        let vectors = vec![unit(1.0, 0.0), unit(1.0, 0.01), unit(0.0, 1.0)];
+        assert_eq!(mmr_order(&[0.9, 0.85, 0.8], &vectors, 0.5), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn missing_vectors_are_redundant_with_nothing() {
+        let vectors = vec![unit(1.0, 0.0), unit(1.0, 0.0), None];
+        assert_eq!(mmr_order(&[0.9, 0.85, 0.8], &vectors, 0.5), vec![0, 2, 1]);
+        assert_eq!(mmr_order(&[0.9, 0.85, 0.8], &[None, None, None], 0.5), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn non_finite_relevance_counts_as_the_lowest() {
+        // Ties with the lowest finite score go to the better-ranked item
+        let relevance = [f64::NAN, 0.9, f64::INFINITY, 0.7, f64::NEG_INFINITY, 0.5];
+        assert_eq!(mmr_order(&relevance, &vec![None; 6], 0.3), vec![1, 3, 0, 2, 4, 5]);
+    }
+}
@@ -0,0 +1,244 @@
+//! Sentence-embedding backends. The engine, index builds and the demo only see
+//! [`Embedder`]; EMBEDDER_BACKEND picks the implementation:
+//! - `rust-bert` (default): the model runs in-process on libtorch
+//!   (needs the `libtorch` feature)
+//! - `onnx`: ONNX Runtime on an exported model in ONNX_MODEL_DIR
+//!   (needs the `onnx` feature, see [`crate::search::onnx`])
+//! - `http`: an OpenAI-compatible `/embeddings` endpoint at EMBEDDER_URL
+//!   (text-embeddings-inference, Ollama, vLLM, ...), for deployments without libtorch
+//!
+//! Whatever the backend, the index must have been built with the same model
+//! that embeds the queries.
+
+use futures::future::{try_join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::get_config;
+use crate::utils::errors::AppError;
+
+/// Default embedding model name, recorded with index manifests and cached edges
+pub const MODEL_VERSION: &str = "all-MiniLM-L6-v2";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub trait Embedder: Send + Sync {
+    /// Embedding of one text. Concurrent calls may be batched by the backend.
+    fn encode(&self, text: String) -> BoxFuture<'_, Result<Vec<f32>, AppError>>;
+
+    /// Embeddings of several texts, in order.
+    fn encode_batch(&self, texts: Vec<String>) -> BoxFuture<'_, Result<Vec<Vec<f32>>, AppError>> {
+        Box::pin(try_join_all(texts.into_iter().map(|text| self.encode(text))))
+    }
+
+    /// Where the model runs, for `/api/health`: `cpu`, `cuda`, `mps`, or
+    /// `remote` for a service.
+    fn device(&self) -> String {
+        Device::Cpu.to_string()
+    }
+
+    /// Stops the backend, letting queued work finish. Blocking; later calls fail.
+    fn shutdown(&self) {}
+}
+
+/// Which model a backend runs: a model name, or for rust-bert also the
+/// directory of a converted model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EmbeddingModel(pub String);
+
+impl EmbeddingModel {
+    pub fn parse(raw: &str) -> Self {
+        Self(raw.trim().to_string())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for EmbeddingModel {
+    fn default() -> Self {
+        Self(MODEL_VERSION.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedderBackend {
+    RustBert,
+    Onnx,
+    Http,
+}
+
+impl FromStr for EmbedderBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rust-bert" | "rustbert" | "libtorch" => Ok(Self::RustBert),
+            "onnx" | "ort" => Ok(Self::Onnx),
+            "http" | "remote" => Ok(Self::Http),
+            other => Err(format!("unknown embedder backend '{}'", other)),
+        }
+    }
+}
+
+/// Hardware the in-process model runs on (DEVICE). Unavailable devices fall
+/// back to the CPU with a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Device {
+    Cpu,
+    Cuda,
+    /// Apple Metal
+    Mps,
+}
+
+impl FromStr for Device {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cpu" => Ok(Self::Cpu),
+            "cuda" | "gpu" => Ok(Self::Cuda),
+            "mps" | "metal" => Ok(Self::Mps),
+            other => Err(format!("unknown device '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Device::Cpu => "cpu",
+            Device::Cuda => "cuda",
+            Device::Mps => "mps",
+        })
+    }
+}
+
+/// Starts the configured backend for `model`. `workers`, `batch_window` and
+/// `max_batch` size the in-process worker pool; remote backends batch per call.
+pub fn start_embedder(
+    model: &EmbeddingModel,
+    workers: usize,
+    batch_window: Duration,
+    max_batch: usize,
+) -> Result<Box<dyn Embedder>, AppError> {
+    let config = get_config();
+    match config.embedder_backend {
+        #[cfg(feature = "libtorch")]
+        EmbedderBackend::RustBert => Ok(Box::new(crate::search::inference::InferenceWorker::start_with(
+            model,
+            config.device,
+            workers,
+            batch_window,
+            max_batch,
+        )?)),
+        #[cfg(not(feature = "libtorch"))]
+        EmbedderBackend::RustBert => {
+            let _ = (workers, batch_window, max_batch);
+            Err(AppError::Config(
+                "Built without libtorch, set EMBEDDER_BACKEND=http and EMBEDDER_URL".to_string(),
+            ))
+        }
+        #[cfg(feature = "onnx")]
+        EmbedderBackend::Onnx => {
+            if config.device != Device::Cpu {
+                tracing::warn!("⚠ The ONNX backend runs on the CPU, ignoring DEVICE={}", config.device);
+            }
+            let dir = crate::search::onnx::OnnxEmbedder::model_dir(model.name(), &config.onnx_model_dir);
+            Ok(Box::new(crate::search::onnx::OnnxEmbedder::load(&dir, workers)?))
+        }
+        #[cfg(not(feature = "onnx"))]
+        EmbedderBackend::Onnx => Err(AppError::Config("Built without the onnx feature".to_string())),
+        EmbedderBackend::Http => {
+            let url = config
+                .embedder_url
+                .clone()
+                .ok_or_else(|| AppError::Config("EMBEDDER_BACKEND=http needs EMBEDDER_URL".to_string()))?;
+            Ok(Box::new(HttpEmbedder::new(url, model.name().to_string(), config.embedder_api_key.clone())?))
+        }
+    }
+}
+
+/// Client of an OpenAI-compatible embeddings endpoint.
+pub struct HttpEmbedder {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+impl HttpEmbedder {
+    pub fn new(url: String, model: String, api_key: Option<String>) -> Result<Self, AppError> {
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Config(format!("Embedding client: {}", e)))?;
+        Ok(Self { client, url, model, api_key })
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, AppError> {
+        let mut request = self.client.post(&self.url).json(&EmbeddingRequest { model: &self.model, input: &texts });
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response: EmbeddingResponse = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| AppError::Inference(format!("Embedding service: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Inference(format!("Embedding service response: {}", e)))?;
+
+        let mut data = response.data;
+        if data.len() != texts.len() {
+            return Err(AppError::Inference(format!(
+                "Embedding service returned {} embeddings for {} texts",
+                data.len(),
+                texts.len()
+            )));
+        }
+        data.sort_by_key(|d| d.index.unwrap_or(0));
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn encode(&self, text: String) -> BoxFuture<'_, Result<Vec<f32>, AppError>> {
+        Box::pin(async move {
+            self.embed(vec![text])
+                .await?
+                .pop()
+                .ok_or_else(|| AppError::Inference("Embedding service returned nothing".to_string()))
+        })
+    }
+
+    fn encode_batch(&self, texts: Vec<String>) -> BoxFuture<'_, Result<Vec<Vec<f32>>, AppError>> {
+        Box::pin(self.embed(texts))
+    }
+
+    fn device(&self) -> String {
+        "remote".to_string()
+    }
+}
@@ -0,0 +1,602 @@
+use crate::config::get_config;
+use crate::index::labels::LabelMap;
+use crate::index::manifest::IndexManifest;
+use crate::index::stable_ids::StableIds;
+use crate::index::vectors::VectorStore;
+use crate::utils::errors::AppError;
+use crate::search::index_pool::IndexPool;
+use crate::search::vector_index::{empty_index, load_index, Hits, VectorIndex, FALLBACK_DIM};
+use crate::search::embedder::{start_embedder, Embedder, EmbeddingModel};
+use crate::search::lanes::{lanes, Resource};
+use crate::search::calibration::Calibration;
+use crate::search::query_store::QueryStore;
+use crate::search::ranking::check_default_ranking;
+use crate::search::similarity::{cosine_similarity, norm, normalize, Metric, UNIT_NORM_TOLERANCE};
+use arc_swap::ArcSwap;
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// nprobe used when the index manifest has no tuned value
+const DEFAULT_NPROBE: usize = 32;
+
+pub struct SearchEngine {
+    // Swapped atomically by `reload_index`; in-flight searches keep the handle they loaded
+    index: ArcSwap<IndexHandle>,
+    // EMBEDDER_BACKEND running the default model
+    pub inference: Box<dyn Embedder>,
+    // Backends for the other models named by corpora (MODEL_<LANG>), keyed by name
+    corpus_models: HashMap<String, Box<dyn Embedder>>,
+    // Normalized query text -> embedding, so repeated queries skip the model
+    embedding_cache: Option<Mutex<LruCache<String, Vec<f32>>>>,
+    embedding_cache_hits: AtomicU64,
+    embedding_cache_misses: AtomicU64,
+    // Embeddings persisted across restarts (QUERY_STORE_PATH), behind the LRU
+    query_store: Option<QueryStore>,
+    // Bumped on every swap so background jobs can tell the index changed
+    index_generation: AtomicU64,
+    pub available_signals: AvailableSignals,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct EmbeddingCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A loaded index plus what we learned about it at load time.
+pub struct IndexHandle {
+    // Searching requires a mutable reference, so concurrent searches are spread
+    // across independently loaded replicas (see INDEX_REPLICAS)
+    pub pool: IndexPool,
+    pub path: String,
+    /// Vector dimension of the replicas, so callers needn't lock one to ask
+    pub dim: u32,
+    /// Vector count of the replicas, fixed once loaded (updates load a new handle)
+    pub ntotal: u64,
+    pub can_reconstruct: bool,
+    /// What the replicas' search scores are; `search` returns similarities either way
+    pub metric: Metric,
+    /// Maps search similarities to the `sem_norm` used in ranking
+    pub calibration: Calibration,
+    /// Effective index-wide search parameters (None where the index type has no such knob)
+    pub search_params: SearchParams,
+    /// Tombstones and remapped positions left by `wikiexplorer index update`
+    pub labels: LabelMap,
+    /// Memory-mapped exact vectors by article ID (`<index>.vectors.f32`), tried
+    /// before the index itself when reconstructing
+    pub vectors: Option<VectorStore>,
+    /// Public (stable) IDs of the articles; requests and responses use these
+    pub ids: StableIds,
+}
+
+/// FAISS runtime search parameters. Also used as the per-request override.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SearchParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nprobe: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ef_search: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AvailableSignals {
+    pub pagerank: bool,
+    pub pageviews: bool,
+    pub backlinks: bool,
+}
+
+impl IndexHandle {
+    /// Loads the index at `path` once, adds replicas up to `INDEX_REPLICAS`
+    /// and applies search parameters.
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let config = get_config();
+
+        info!("Loading index from {}...", path);
+        let first = load_index(path, config.index_backend)?;
+        info!("✓ Index loaded: {} vectors", first.ntotal());
+
+        let mut replicas: Vec<Box<dyn VectorIndex>> = vec![first];
+        for n in 1..config.index_replicas.max(1) {
+            // Shared when the backend allows it, otherwise another read of the file
+            let replica = match replicas[0].replica() {
+                Some(replica) => replica,
+                None => load_index(path, config.index_backend)
+                    .map_err(|e| AppError::Faiss(format!("Replica {} failed to load: {}", n, e)))?,
+            };
+            replicas.push(replica);
+        }
+        if replicas.len() > 1 {
+            info!("✓ {} index replicas loaded for concurrent search", replicas.len());
+        }
+
+        Ok(Self::from_replicas(replicas, path))
+    }
+
+    /// Empty flat index used when the index file is missing at startup
+    /// (prevents crash, matches Python fallback logic)
+    fn empty() -> Self {
+        Self::from_replicas(vec![empty_index(FALLBACK_DIM)], "")
+    }
+
+    pub(crate) fn from_replicas(mut replicas: Vec<Box<dyn VectorIndex>>, path: &str) -> Self {
+        let config = get_config();
+
+        let manifest = IndexManifest::load(path).ok();
+
+        // IVF probe count: NPROBE env, else the value calibrated by `wikiexplorer tune`,
+        // else the historical default. efSearch only applies to HNSW indexes.
+        let nprobe = config.nprobe
+            .or_else(|| manifest.as_ref().and_then(|m| m.nprobe))
+            .unwrap_or(DEFAULT_NPROBE);
+        let mut search_params = SearchParams { nprobe: Some(nprobe), ef_search: config.ef_search };
+
+        for replica in replicas.iter_mut() {
+            if replica.set_search_parameter("nprobe", nprobe as f64).is_err() {
+                search_params.nprobe = None;
+            }
+            if let Some(ef) = config.ef_search {
+                if replica.set_search_parameter("efSearch", ef as f64).is_err() {
+                    search_params.ef_search = None;
+                }
+            }
+        }
+        if let Some(nprobe) = search_params.nprobe {
+            info!("✓ IVF index configured (nprobe={})", nprobe);
+        }
+        if let Some(ef) = search_params.ef_search {
+            info!("✓ HNSW index configured (efSearch={})", ef);
+        }
+
+        let labels = LabelMap::load(path).unwrap_or_else(|e| {
+            warn!("⚠ Ignoring unreadable label map for {}: {}", path, e);
+            LabelMap::default()
+        });
+        if !labels.is_empty() {
+            info!("✓ Label map loaded ({} tombstoned positions)", labels.tombstone_count());
+        }
+
+        let ids = StableIds::load(path).unwrap_or_else(|e| {
+            warn!("⚠ Ignoring unreadable stable IDs for {}: {}", path, e);
+            StableIds::default()
+        });
+        if !ids.is_identity() {
+            info!("✓ Stable IDs loaded ({} articles, {} without one)", ids.mapped(), ids.missing());
+        }
+
+        let metric = replicas[0].metric();
+        info!("✓ Index metric: {}", metric.name());
+
+        // Same precedence as nprobe: SCORE_CALIBRATION, then `wikiexplorer calibrate`
+        let calibration = config.score_calibration
+            .or_else(|| manifest.as_ref().and_then(|m| m.calibration))
+            .unwrap_or_default();
+        if calibration != Calibration::None {
+            info!("✓ Semantic score calibration: {}", calibration);
+        }
+
+        let vectors = VectorStore::open(path, replicas[0].dim()).unwrap_or_else(|e| {
+            warn!("⚠ Ignoring exact-vector sidecar of {}: {}", path, e);
+            None
+        });
+
+        // We try to reconstruct vector 0 to see if the index supports reconstruction (needed for cross-edges)
+        let can_reconstruct = match (&vectors, replicas[0].reconstruct(0)) {
+            (Some(store), _) => {
+                info!("✓ Exact vectors memory-mapped ({} rows) - cross-edges enabled", store.rows());
+                true
+            }
+            (None, Ok(_)) => {
+                info!("✓ Direct map initialized - cross-edges enabled");
+                true
+            }
+            (None, Err(_)) => {
+                warn!("⚠ Reconstruction not available - cross-edges disabled");
+                false
+            }
+        };
+
+        if metric == Metric::L2 && can_reconstruct {
+            check_unit_norms(replicas[0].as_ref(), vectors.as_ref());
+        }
+
+        Self {
+            dim: replicas[0].dim(),
+            ntotal: replicas[0].ntotal(),
+            pool: IndexPool::new(replicas),
+            path: path.to_string(),
+            can_reconstruct,
+            metric,
+            calibration,
+            search_params,
+            labels,
+            vectors,
+            ids,
+        }
+    }
+
+    /// `overrides` temporarily replaces the index-wide search parameters on the
+    /// replica used for this search; they are restored before the replica is released.
+    /// Returns (similarity in [0, 1], article ID) pairs, most similar first.
+    /// Tombstoned positions are dropped and remapped ones translated (see `LabelMap`).
+    pub fn search(
+        &self,
+        query_vec: &[f32],
+        k: usize,
+        overrides: Option<&SearchParams>,
+    ) -> Result<Hits, AppError> {
+        let mut index = self.pool.acquire(); // Any free replica
+
+        let overridden = match overrides {
+            Some(params) if *params != self.search_params => {
+                apply_params(index.as_mut(), &self.search_params, params)?;
+                true
+            }
+            _ => false,
+        };
+
+        // Over-fetch so dropping tombstones still leaves k hits
+        let fetch_k = k + self.labels.tombstone_count().min(k);
+
+        let result = index.search(query_vec, fetch_k);
+
+        if overridden {
+            apply_params(index.as_mut(), &self.search_params, &self.search_params)?;
+        }
+        let (distances, labels) = result?;
+        Ok(self.to_articles(distances, labels, k))
+    }
+
+    /// `search` for several row-major queries at once (index-wide parameters),
+    /// for offline jobs; one result per query.
+    pub fn search_many(&self, queries: &[f32], k: usize) -> Result<Vec<Hits>, AppError> {
+        let fetch_k = k + self.labels.tombstone_count().min(k);
+        let results = self.pool.acquire().search_batch(queries, fetch_k)?;
+        Ok(results.into_iter().map(|(distances, labels)| self.to_articles(distances, labels, k)).collect())
+    }
+
+    /// Raw scores to similarities and index positions to article IDs, keeping
+    /// the first `k` live ones.
+    fn to_articles(&self, distances: Vec<f32>, labels: Vec<i64>, k: usize) -> (Vec<f32>, Vec<i64>) {
+        let similarities = distances.into_iter().map(|d| self.metric.similarity(d));
+        if self.labels.is_empty() {
+            return similarities.zip(labels).unzip();
+        }
+        // Negative labels (FAISS "no result") pass through untouched
+        similarities
+            .zip(labels)
+            .filter_map(|(d, label)| match label {
+                label if label < 0 => Some((d, label)),
+                label => self.labels.article_at(label).map(|id| (d, id)),
+            })
+            .take(k)
+            .unzip()
+    }
+
+    /// Recomputes exact similarities for the first `top_n` candidates from their
+    /// reconstructed vectors and re-sorts that prefix. Recovers precision lost to
+    /// IVF/PQ quantization. Candidates that can't be reconstructed keep their
+    /// approximate similarity.
+    pub fn rescore_exact(&self, query_vec: &[f32], dists: &mut [f32], ids: &mut [i64], top_n: usize) {
+        if !self.can_reconstruct || top_n == 0 {
+            return;
+        }
+        let n = top_n.min(ids.len());
+
+        {
+            // Only taken for candidates the sidecar doesn't cover
+            let mut index = None;
+            for i in 0..n {
+                let stored = self.vectors.as_ref().and_then(|store| store.get(ids[i]));
+                let v = match stored {
+                    Some(v) => v,
+                    None => {
+                        let Some(position) = self.labels.position_of(ids[i]).filter(|p| *p >= 0) else {
+                            continue;
+                        };
+                        let index = index.get_or_insert_with(|| self.pool.acquire());
+                        match index.reconstruct(position as u64) {
+                            Ok(v) => v,
+                            Err(_) => continue,
+                        }
+                    }
+                };
+                dists[i] = cosine_similarity(query_vec, &v);
+            }
+        }
+
+        let mut prefix: Vec<(f32, i64)> = dists[..n].iter().cloned().zip(ids[..n].iter().cloned()).collect();
+        prefix.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (i, (d, id)) in prefix.into_iter().enumerate() {
+            dists[i] = d;
+            ids[i] = id;
+        }
+    }
+
+    /// Used for cross-edges: Reconstructs the vector of an article ID
+    pub fn reconstruct(&self, id: i64) -> Result<Vec<f32>, AppError> {
+        if let Some(v) = self.vectors.as_ref().and_then(|store| store.get(id)) {
+            return Ok(v);
+        }
+        let index = self.pool.acquire();
+        // Client-supplied IDs: keep negative / out-of-range / tombstoned positions away from FAISS
+        let position = match self.labels.position_of(id) {
+            Some(position) if position >= 0 && (position as u64) < index.ntotal() => position,
+            _ => return Err(AppError::NotFound(format!("No vector for article {}", id))),
+        };
+        index.reconstruct(position as u64)
+    }
+}
+
+impl SearchEngine {
+    pub fn new() -> Result<Self, AppError> {
+        let config = get_config();
+        
+        info!("================================================================================");
+        info!("WIKIPEDIA SEMANTIC SEARCH API (Rust Backend)");
+        info!("================================================================================");
+
+        // Before the model loads: a misconfigured default ranking would fail every search
+        check_default_ranking()?;
+
+        // 1. Load Model
+        // rust-bert downloads "all-MiniLM-L6-v2" automatically if not present in cache
+        info!("Loading sentence transformer model (all-MiniLM-L6-v2, {:?} backend)...", config.embedder_backend);
+        let inference = start_embedder(
+            &EmbeddingModel::default(),
+            config.inference_workers,
+            Duration::from_millis(config.inference_batch_window_ms),
+            config.inference_max_batch,
+        )?;
+
+        // 1b. Models of per-language corpora, loaded once each
+        let mut corpus_models = HashMap::new();
+        for name in config.extra_corpora.iter().filter_map(|spec| spec.model.clone()) {
+            if corpus_models.contains_key(&name) {
+                continue;
+            }
+            info!("Loading sentence transformer model ({})...", name);
+            let worker = start_embedder(
+                &EmbeddingModel::parse(&name),
+                config.inference_workers,
+                Duration::from_millis(config.inference_batch_window_ms),
+                config.inference_max_batch,
+            )?;
+            corpus_models.insert(name, worker);
+        }
+        
+        // 2. Load the vector index
+        let handle = match IndexHandle::load(&config.index_path) {
+            Ok(handle) => handle,
+            Err(e) => {
+                warn!("CRITICAL ERROR: {}", e);
+                warn!("Falling back to empty flat index");
+                IndexHandle::empty()
+            }
+        };
+
+        let embedding_cache = NonZeroUsize::new(config.embedding_cache_size)
+            .map(|capacity| Mutex::new(LruCache::new(capacity)));
+
+        Ok(Self {
+            index: ArcSwap::from_pointee(handle),
+            inference,
+            corpus_models,
+            embedding_cache,
+            embedding_cache_hits: AtomicU64::new(0),
+            embedding_cache_misses: AtomicU64::new(0),
+            query_store: None,
+            index_generation: AtomicU64::new(0),
+            available_signals: AvailableSignals::default(), // Will be updated by state init
+        })
+    }
+
+    /// The currently active index. Hold on to the returned handle for the duration of
+    /// a multi-step operation so a concurrent reload can't change it midway.
+    pub fn index(&self) -> Arc<IndexHandle> {
+        self.index.load_full()
+    }
+
+    /// Atomically replaces the active index. The old handle is dropped once the last
+    /// in-flight search holding it finishes.
+    pub fn swap_index(&self, handle: IndexHandle) -> Arc<IndexHandle> {
+        info!("Swapping active index to {}", handle.path);
+        let old = self.index.swap(Arc::new(handle));
+        self.index_generation.fetch_add(1, Ordering::Relaxed);
+        old
+    }
+
+    /// Number of index swaps since startup
+    pub fn index_generation(&self) -> u64 {
+        self.index_generation.load(Ordering::Relaxed)
+    }
+
+    pub fn can_reconstruct(&self) -> bool {
+        self.index.load().can_reconstruct
+    }
+
+    /// Vector count of the active index (reflects reloads); never waits for a replica
+    pub fn total_vectors(&self) -> u64 {
+        self.index.load().ntotal
+    }
+
+    /// True when serving the empty placeholder index because none could be loaded
+    pub fn is_fallback_index(&self) -> bool {
+        self.index.load().path.is_empty()
+    }
+
+    pub fn search_params(&self) -> SearchParams {
+        self.index.load().search_params.clone()
+    }
+
+    /// Encodes with the embedding backend (may be batched with concurrent queries),
+    /// or returns the cached embedding of an earlier identical query.
+    pub async fn encode_query(&self, query: &str) -> Result<Vec<f32>, AppError> {
+        self.encode_query_with(None, query).await
+    }
+
+    /// `encode_query` with a corpus's own model (`Corpus::model`); None is the
+    /// default model. Embeddings are unit length, like the indexed ones.
+    pub async fn encode_query_with(&self, model: Option<&str>, query: &str) -> Result<Vec<f32>, AppError> {
+        let worker = match model {
+            None => &self.inference,
+            Some(name) => self
+                .corpus_models
+                .get(name)
+                .ok_or_else(|| AppError::Inference(format!("Model '{}' is not loaded", name)))?,
+        };
+        let clean_query = query.replace('_', " ");
+
+        // Other models embed the same text differently, and may be cased
+        let key = match model {
+            None => normalize_query_key(&clean_query),
+            Some(name) => format!("{}\u{1f}{}", name, clean_query.split_whitespace().collect::<Vec<_>>().join(" ")),
+        };
+        if let Some(cache) = &self.embedding_cache {
+            if let Some(embedding) = cache.lock().get(&key) {
+                self.embedding_cache_hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(embedding.clone());
+            }
+            self.embedding_cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let embedding = match self.stored_embedding(model, &key).await {
+            Some(embedding) => embedding,
+            None => {
+                let mut embedding = {
+                    let _permit = lanes().acquire(Resource::Inference).await;
+                    worker.encode(clean_query.clone()).await?
+                };
+                normalize(&mut embedding);
+                if let Some(store) = &self.query_store {
+                    let (store, key, model, embedding) = (store.clone(), key.clone(), model.map(str::to_string), embedding.clone());
+                    tokio::spawn(async move { store.record(&key, model.as_deref(), &clean_query, &embedding).await });
+                }
+                embedding
+            }
+        };
+        if let Some(cache) = &self.embedding_cache {
+            cache.lock().put(key, embedding.clone());
+        }
+        Ok(embedding)
+    }
+
+    /// Embedding of `key` from the query store, if it has one of the right size.
+    async fn stored_embedding(&self, model: Option<&str>, key: &str) -> Option<Vec<f32>> {
+        let store = self.query_store.as_ref()?;
+        let embedding = store.get(key).await?;
+        // Written while another default model (of another size) was configured
+        if model.is_none() && embedding.len() != self.index().dim as usize {
+            return None;
+        }
+        let (store, key) = (store.clone(), key.to_string());
+        tokio::spawn(async move { store.touch(&key).await });
+        Some(embedding)
+    }
+
+    /// Persists query embeddings to `store` from now on (see `QueryStore`).
+    pub fn set_query_store(&mut self, store: QueryStore) {
+        self.query_store = Some(store);
+    }
+
+    pub fn query_store(&self) -> Option<&QueryStore> {
+        self.query_store.as_ref()
+    }
+
+    /// Stops the backends of every model (see `Embedder::shutdown`).
+    pub fn shutdown_inference(&self) {
+        self.inference.shutdown();
+        for worker in self.corpus_models.values() {
+            worker.shutdown();
+        }
+    }
+
+    pub fn embedding_cache_stats(&self) -> Option<EmbeddingCacheStats> {
+        self.embedding_cache.as_ref().map(|cache| {
+            let cache = cache.lock();
+            EmbeddingCacheStats {
+                entries: cache.len(),
+                capacity: cache.cap().get(),
+                hits: self.embedding_cache_hits.load(Ordering::Relaxed),
+                misses: self.embedding_cache_misses.load(Ordering::Relaxed),
+            }
+        })
+    }
+
+    /// Searches the active index (see `IndexHandle::search`)
+    pub fn search_index(
+        &self,
+        query_vec: &[f32],
+        k: usize,
+        overrides: Option<&SearchParams>,
+    ) -> Result<Hits, AppError> {
+        self.index.load().search(query_vec, k, overrides)
+    }
+
+    pub fn rescore_exact(&self, query_vec: &[f32], dists: &mut [f32], ids: &mut [i64], top_n: usize) {
+        self.index.load().rescore_exact(query_vec, dists, ids, top_n)
+    }
+
+    /// Used for cross-edges: Reconstructs a vector for a given ID
+    pub fn reconstruct(&self, id: i64) -> Result<Vec<f32>, AppError> {
+        self.index.load().reconstruct(id)
+    }
+}
+
+/// Applies the overridable parameters the index actually supports (`supported` is the
+/// handle's effective parameter set, where unsupported knobs are None).
+fn apply_params(index: &mut dyn VectorIndex, supported: &SearchParams, params: &SearchParams) -> Result<(), AppError> {
+    if let (Some(_), Some(nprobe)) = (supported.nprobe, params.nprobe) {
+        index.set_search_parameter("nprobe", nprobe.max(1) as f64)?;
+    }
+    if let (Some(_), Some(ef)) = (supported.ef_search, params.ef_search) {
+        index.set_search_parameter("efSearch", ef.max(1) as f64)?;
+    }
+    Ok(())
+}
+
+/// Cache key for a query: MiniLM's tokenizer is uncased and whitespace-insensitive,
+/// so these variants produce the same embedding.
+fn normalize_query_key(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Vectors sampled by [`check_unit_norms`]
+const NORM_SAMPLES: u64 = 32;
+
+/// Squared L2 distances only read as cosines between unit vectors. Indexes
+/// built before normalization stored raw embeddings, so a sample of the stored
+/// vectors is measured and a warning logged when they aren't unit length.
+fn check_unit_norms(index: &dyn VectorIndex, vectors: Option<&VectorStore>) {
+    let total = index.ntotal();
+    if total == 0 {
+        return;
+    }
+    let step = (total / NORM_SAMPLES).max(1);
+    let worst = (0..total)
+        .step_by(step as usize)
+        .take(NORM_SAMPLES as usize)
+        .filter_map(|position| match vectors {
+            Some(store) => store.get(position as i64),
+            None => index.reconstruct(position).ok(),
+        })
+        .map(|v| norm(&v))
+        .filter(|n| *n > 0.0)
+        .max_by(|a, b| (a - 1.0).abs().total_cmp(&(b - 1.0).abs()));
+    if let Some(worst) = worst.filter(|n| (n - 1.0).abs() > UNIT_NORM_TOLERANCE) {
+        warn!(
+            "⚠ L2 index holds vectors that are not unit length (norm {:.3}); similarities will be off, rebuild it with `wikiexplorer index build`",
+            worst
+        );
+    }
+}
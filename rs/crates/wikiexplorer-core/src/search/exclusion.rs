@@ -0,0 +1,78 @@
+//! Negative query terms: `"exclude": "planet"` on a search for "mercury"
+//! pushes the planet down and leaves the element and the god on top.
+//!
+//! Each excluded term is embedded like a query. A candidate whose similarity
+//! to any of them exceeds EXCLUDE_THRESHOLD has its score multiplied by
+//! EXCLUDE_PENALTY, like a demoted meta page; the rest are untouched, so terms
+//! unrelated to the query change nothing. Indexes that can't reconstruct
+//! vectors are searched around each excluded term instead, which only sees the
+//! CANDIDATE_POOL_SIZE articles closest to it.
+
+use crate::config::get_config;
+use crate::search::engine::IndexHandle;
+use crate::search::similarity::cosine_similarity;
+use crate::utils::cancel::run_blocking;
+use crate::utils::errors::AppError;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct Exclusion {
+    /// Embeddings of the excluded terms
+    vectors: Vec<Vec<f32>>,
+}
+
+impl Exclusion {
+    /// None without excluded terms.
+    pub fn new(vectors: &[Vec<f32>]) -> Option<Self> {
+        if vectors.is_empty() {
+            return None;
+        }
+        Some(Self { vectors: vectors.to_vec() })
+    }
+
+    /// Score factor of each of `candidates` that is about an excluded term;
+    /// candidates left out keep their score.
+    pub async fn factors(self: &Arc<Self>, index: &Arc<IndexHandle>, candidates: &[i64]) -> Result<HashMap<i64, f64>, AppError> {
+        let (exclusion, index, candidates) = (Arc::clone(self), Arc::clone(index), candidates.to_vec());
+        run_blocking(move |cancel| {
+            let config = get_config();
+            let similarities = if index.can_reconstruct {
+                let mut similarities = HashMap::with_capacity(candidates.len());
+                for (n, id) in candidates.into_iter().enumerate() {
+                    if n % 256 == 0 {
+                        cancel.check()?;
+                    }
+                    let Ok(v) = index.reconstruct(id) else {
+                        continue;
+                    };
+                    let closest = exclusion.vectors.iter().map(|e| cosine_similarity(e, &v)).fold(f32::MIN, f32::max);
+                    similarities.insert(id, closest);
+                }
+                similarities
+            } else {
+                exclusion.neighbors(&index, config.candidate_pool_size)?
+            };
+
+            let penalty = config.exclude_penalty.clamp(0.0, 1.0);
+            Ok(similarities
+                .into_iter()
+                .filter(|(_, similarity)| *similarity > config.exclude_threshold)
+                .map(|(id, _)| (id, penalty))
+                .collect())
+        })
+        .await
+    }
+
+    /// Highest similarity to an excluded term of the `k` nearest articles of each.
+    fn neighbors(&self, index: &IndexHandle, k: usize) -> Result<HashMap<i64, f32>, AppError> {
+        let queries: Vec<f32> = self.vectors.concat();
+        let mut similarities: HashMap<i64, f32> = HashMap::new();
+        for (scores, ids) in index.search_many(&queries, k)? {
+            for (id, score) in ids.into_iter().zip(scores).filter(|(id, _)| *id >= 0) {
+                let closest = similarities.entry(id).or_insert(score);
+                *closest = closest.max(score);
+            }
+        }
+        Ok(similarities)
+    }
+}
@@ -0,0 +1,292 @@
+use lru::LruCache;
+use parking_lot::Mutex;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::config::get_config;
+use crate::utils::errors::AppError;
+
+/// Which titles are dropped (or demoted) before ranking.
+///
+/// Titles are matched case-insensitively with underscores read as spaces.
+/// Allow patterns win over every deny rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterPolicy {
+    /// Namespace-style prefixes, e.g. "template:"
+    pub deny_prefixes: Vec<String>,
+    /// Regexes matched against the normalized title
+    pub deny_patterns: Vec<String>,
+    /// Regexes that keep a title even if a deny rule matches
+    pub allow_patterns: Vec<String>,
+    pub exclude_disambiguation: bool,
+    pub list_pages: ListPageMode,
+    /// Score multiplier for "List of ..." pages in `demote` mode, in [0, 1]
+    pub list_demotion: f64,
+}
+
+/// Patterns per list, and characters per pattern
+const MAX_PATTERNS: usize = 32;
+const MAX_PATTERN_LEN: usize = 256;
+/// Compiled size of one pattern (regex's default is 10 MB)
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+/// Distinct patterns kept compiled across requests
+const REGEX_CACHE_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ListPageMode {
+    Keep,
+    Demote,
+    Exclude,
+}
+
+impl Default for FilterPolicy {
+    fn default() -> Self {
+        Self {
+            deny_prefixes: [
+                "wikipedia:", "template:", "category:", "portal:", "help:",
+                "user:", "talk:", "file:", "mediawiki:", "draft:",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+            deny_patterns: vec![],
+            allow_patterns: vec![],
+            exclude_disambiguation: true,
+            list_pages: ListPageMode::Keep,
+            list_demotion: 0.5,
+        }
+    }
+}
+
+/// Per-request changes to the configured policy (`SearchRequest.filters`).
+/// Unset fields keep the configured value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct FilterOverrides {
+    pub deny_prefixes: Option<Vec<String>>,
+    pub deny_patterns: Option<Vec<String>>,
+    pub allow_patterns: Option<Vec<String>>,
+    pub exclude_disambiguation: Option<bool>,
+    pub list_pages: Option<ListPageMode>,
+    pub list_demotion: Option<f64>,
+}
+
+pub enum FilterVerdict {
+    Keep,
+    /// Keep with the score multiplied by this factor
+    Demote(f64),
+    Exclude,
+}
+
+/// A policy with its regexes compiled.
+pub struct CompiledFilterPolicy {
+    policy: FilterPolicy,
+    deny: Vec<Regex>,
+    allow: Vec<Regex>,
+}
+
+impl CompiledFilterPolicy {
+    pub fn compile(policy: FilterPolicy) -> Result<Self, AppError> {
+        if !(0.0..=1.0).contains(&policy.list_demotion) {
+            return Err(AppError::BadRequest(format!("list_demotion must be in [0, 1], got {}", policy.list_demotion)));
+        }
+        let compile_all = |patterns: &[String]| {
+            if patterns.len() > MAX_PATTERNS {
+                return Err(AppError::BadRequest(format!("At most {} filter patterns per list", MAX_PATTERNS)));
+            }
+            patterns.iter().map(|p| compile_pattern(p)).collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            deny: compile_all(&policy.deny_patterns)?,
+            allow: compile_all(&policy.allow_patterns)?,
+            policy: FilterPolicy {
+                deny_prefixes: policy.deny_prefixes.iter().map(|p| normalize_title(p)).collect(),
+                ..policy
+            },
+        })
+    }
+
+    /// The configured policy merged with per-request overrides.
+    pub fn with_overrides(&self, overrides: &FilterOverrides) -> Result<Self, AppError> {
+        let base = &self.policy;
+        Self::compile(FilterPolicy {
+            deny_prefixes: overrides.deny_prefixes.clone().unwrap_or_else(|| base.deny_prefixes.clone()),
+            deny_patterns: overrides.deny_patterns.clone().unwrap_or_else(|| base.deny_patterns.clone()),
+            allow_patterns: overrides.allow_patterns.clone().unwrap_or_else(|| base.allow_patterns.clone()),
+            exclude_disambiguation: overrides.exclude_disambiguation.unwrap_or(base.exclude_disambiguation),
+            list_pages: overrides.list_pages.unwrap_or(base.list_pages),
+            list_demotion: overrides.list_demotion.unwrap_or(base.list_demotion),
+        })
+    }
+
+    /// Largest factor a verdict can multiply a score by; demotions are at most 1
+    pub fn max_factor(&self) -> f64 {
+        1.0
+    }
+
+    pub fn verdict(&self, title: &str) -> FilterVerdict {
+        let title = normalize_title(title);
+
+        if self.allow.iter().any(|re| re.is_match(&title)) {
+            return FilterVerdict::Keep;
+        }
+        if self.policy.deny_prefixes.iter().any(|p| title.starts_with(p.as_str()))
+            || (self.policy.exclude_disambiguation && title.contains("(disambiguation)"))
+            || self.deny.iter().any(|re| re.is_match(&title))
+        {
+            return FilterVerdict::Exclude;
+        }
+
+        if title.starts_with("list of ") {
+            return match self.policy.list_pages {
+                ListPageMode::Keep => FilterVerdict::Keep,
+                ListPageMode::Demote => FilterVerdict::Demote(self.policy.list_demotion),
+                ListPageMode::Exclude => FilterVerdict::Exclude,
+            };
+        }
+        FilterVerdict::Keep
+    }
+}
+
+fn normalize_title(title: &str) -> String {
+    title.replace('_', " ").to_lowercase()
+}
+
+static DEFAULT_POLICY: OnceLock<CompiledFilterPolicy> = OnceLock::new();
+
+/// The policy from config (`FILTER_POLICY_FILE`), compiled once. Invalid
+/// values there are dropped with a warning rather than failing every search.
+pub fn default_policy() -> &'static CompiledFilterPolicy {
+    DEFAULT_POLICY.get_or_init(|| {
+        let mut policy = get_config().filter_policy.clone();
+        policy.deny_patterns.retain(|p| valid_pattern(p));
+        policy.allow_patterns.retain(|p| valid_pattern(p));
+        for patterns in [&mut policy.deny_patterns, &mut policy.allow_patterns] {
+            if patterns.len() > MAX_PATTERNS {
+                warn!("⚠ Ignoring filter patterns past the first {}", MAX_PATTERNS);
+                patterns.truncate(MAX_PATTERNS);
+            }
+        }
+        if !(0.0..=1.0).contains(&policy.list_demotion) {
+            warn!("⚠ Ignoring list_demotion {}, must be in [0, 1]", policy.list_demotion);
+            policy.list_demotion = FilterPolicy::default().list_demotion;
+        }
+        CompiledFilterPolicy::compile(policy).expect("policy validated above")
+    })
+}
+
+fn valid_pattern(pattern: &str) -> bool {
+    match compile_pattern(pattern) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("⚠ Ignoring filter pattern: {}", e);
+            false
+        }
+    }
+}
+
+/// `pattern` compiled case-insensitively within the size limits, from a
+/// cache shared by all requests.
+fn compile_pattern(pattern: &str) -> Result<Regex, AppError> {
+    static CACHE: OnceLock<Mutex<LruCache<String, Regex>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(LruCache::new(NonZeroUsize::new(REGEX_CACHE_SIZE).unwrap())));
+    if let Some(regex) = cache.lock().get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    if pattern.chars().count() > MAX_PATTERN_LEN {
+        return Err(AppError::BadRequest(format!("Filter pattern longer than {} characters", MAX_PATTERN_LEN)));
+    }
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| AppError::BadRequest(format!("Invalid filter pattern '{}': {}", pattern, e)))?;
+    cache.lock().put(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn policy() -> CompiledFilterPolicy {
+        CompiledFilterPolicy::compile(FilterPolicy::default()).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_patterns_are_bad_requests_not_panics(patterns in prop::collection::vec("\\PC{0,12}", 0..4), title in "\\PC{0,30}") {
+            let overrides = FilterOverrides { deny_patterns: Some(patterns), ..FilterOverrides::default() };
+            match policy().with_overrides(&overrides) {
+                Ok(policy) => {
+                    policy.verdict(&title);
+                }
+                Err(e) => prop_assert!(matches!(e, AppError::BadRequest(_))),
+            }
+        }
+
+        #[test]
+        fn denied_prefixes_are_excluded(name in "[A-Za-z ]{0,20}") {
+            let title = format!("Template:{}", name);
+            prop_assert!(matches!(policy().verdict(&title), FilterVerdict::Exclude));
+        }
+
+        #[test]
+        fn allow_patterns_win(name in "[a-z]{1,20}") {
+            let overrides = FilterOverrides {
+                allow_patterns: Some(vec![format!("^template:{}$", name)]),
+                ..FilterOverrides::default()
+            };
+            let policy = policy().with_overrides(&overrides).unwrap();
+            let title = format!("Template:{}", name);
+            prop_assert!(matches!(policy.verdict(&title), FilterVerdict::Keep));
+        }
+
+        #[test]
+        fn demotion_never_exceeds_max_factor(demotion in 0.0..=1.0f64, name in "[a-z ]{0,20}") {
+            let overrides = FilterOverrides {
+                list_pages: Some(ListPageMode::Demote),
+                list_demotion: Some(demotion),
+                ..FilterOverrides::default()
+            };
+            let policy = policy().with_overrides(&overrides).unwrap();
+            if let FilterVerdict::Demote(factor) = policy.verdict(&format!("List of {}", name)) {
+                prop_assert!(factor <= policy.max_factor());
+            }
+        }
+
+        #[test]
+        fn demotion_outside_unit_range_is_rejected(demotion in prop_oneof![-4.0..-0.001f64, 1.001..4.0f64]) {
+            let overrides = FilterOverrides { list_demotion: Some(demotion), ..FilterOverrides::default() };
+            prop_assert!(matches!(policy().with_overrides(&overrides), Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[test]
+    fn oversized_patterns_are_rejected() {
+        let deny = |patterns: Vec<String>| FilterOverrides { deny_patterns: Some(patterns), ..FilterOverrides::default() };
+        for patterns in [
+            vec!["a".repeat(MAX_PATTERN_LEN + 1)],
+            vec!["a".to_string(); MAX_PATTERNS + 1],
+            vec!["(\\w{1000}){1000}".to_string()],
+        ] {
+            assert!(matches!(policy().with_overrides(&deny(patterns)), Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[test]
+    fn non_finite_demotion_is_rejected() {
+        let overrides = FilterOverrides { list_demotion: Some(f64::NAN), ..FilterOverrides::default() };
+        assert!(matches!(policy().with_overrides(&overrides), Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,153 @@
+//! Brute-force backend (INDEX_BACKEND=flat): every vector in one in-memory
+//! matrix, searched exhaustively. Exact and dependency-free; fine up to a few
+//! hundred thousand vectors, and the placeholder index when none can be loaded.
+//!
+//! `<name>.flat` files hold the matrix as is (a small header, then the raw
+//! vectors), for builds with neither FAISS nor HNSW such as the demo's.
+
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::search::similarity::Metric;
+use crate::search::vector_index::{Hits, VectorIndex};
+use crate::utils::errors::AppError;
+
+const MAGIC: &[u8; 8] = b"WEFLAT01";
+pub const EXTENSION: &str = "flat";
+
+pub fn is_flat_path(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+pub struct FlatIndex {
+    // Row-major, position order; shared by replicas until one adds vectors
+    vectors: Arc<Vec<f32>>,
+    dim: u32,
+    metric: Metric,
+}
+
+impl FlatIndex {
+    pub fn new(dim: u32) -> Self {
+        Self { vectors: Arc::new(Vec::new()), dim, metric: Metric::L2 }
+    }
+
+    /// Copies every vector out of `source`, which must support reconstruction,
+    /// and searches with its metric.
+    pub fn copy_of(source: &dyn VectorIndex) -> Result<Self, AppError> {
+        let mut vectors = Vec::with_capacity(source.ntotal() as usize * source.dim() as usize);
+        for position in 0..source.ntotal() {
+            vectors.extend(source.reconstruct(position)?);
+        }
+        Ok(Self { vectors: Arc::new(vectors), dim: source.dim(), metric: source.metric() })
+    }
+
+    /// Searches row-major `vectors` (`dim` wide) with `metric`.
+    pub fn from_vectors(vectors: Vec<f32>, dim: u32, metric: Metric) -> Self {
+        Self { vectors: Arc::new(vectors), dim, metric }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), AppError> {
+        let tmp = format!("{}.tmp", path);
+        let mut out = BufWriter::new(std::fs::File::create(&tmp)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&self.dim.to_le_bytes())?;
+        out.write_all(&[matches!(self.metric, Metric::InnerProduct) as u8])?;
+        out.write_all(&self.ntotal().to_le_bytes())?;
+        for value in self.vectors.iter() {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let mut input = BufReader::new(std::fs::File::open(path)?);
+        let mut header = [0u8; 21];
+        input.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(AppError::Faiss(format!("{} is not a wikiexplorer flat index", path)));
+        }
+        let dim = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        let metric = if header[12] == 1 { Metric::InnerProduct } else { Metric::L2 };
+        let n = u64::from_le_bytes(header[13..21].try_into().unwrap()) as usize;
+        let mut raw = vec![0u8; n * dim as usize * 4];
+        input.read_exact(&mut raw)?;
+        let vectors = raw.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+        Ok(Self::from_vectors(vectors, dim, metric))
+    }
+
+    /// The row-major matrix, positions in order
+    pub fn into_vectors(self) -> Vec<f32> {
+        Arc::unwrap_or_clone(self.vectors)
+    }
+}
+
+impl VectorIndex for FlatIndex {
+    fn dim(&self) -> u32 {
+        self.dim
+    }
+
+    fn ntotal(&self) -> u64 {
+        (self.vectors.len() / self.dim.max(1) as usize) as u64
+    }
+
+    fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    fn search(&mut self, query: &[f32], k: usize) -> Result<Hits, AppError> {
+        if query.len() != self.dim as usize {
+            return Err(AppError::BadRequest(format!("Query has dimension {}, index {}", query.len(), self.dim)));
+        }
+        let mut scored: Vec<(f32, i64)> = self
+            .vectors
+            .chunks_exact(self.dim as usize)
+            .zip(0..)
+            .map(|(v, position)| {
+                let score = match self.metric {
+                    Metric::L2 => v.iter().zip(query).map(|(a, b)| (a - b) * (a - b)).sum(),
+                    Metric::InnerProduct => v.iter().zip(query).map(|(a, b)| a * b).sum(),
+                };
+                (score, position)
+            })
+            .collect();
+
+        let k = k.min(scored.len());
+        if k == 0 {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        // Nearest first: smallest distance, largest product
+        let metric = self.metric;
+        let nearer = |a: &(f32, i64), b: &(f32, i64)| match metric {
+            Metric::L2 => a.0.total_cmp(&b.0),
+            Metric::InnerProduct => b.0.total_cmp(&a.0),
+        };
+        scored.select_nth_unstable_by(k - 1, nearer);
+        scored.truncate(k);
+        scored.sort_by(nearer);
+        Ok(scored.into_iter().unzip())
+    }
+
+    fn reconstruct(&self, position: u64) -> Result<Vec<f32>, AppError> {
+        let dim = self.dim as usize;
+        let start = position as usize * dim;
+        self.vectors
+            .get(start..start + dim)
+            .map(<[f32]>::to_vec)
+            .ok_or_else(|| AppError::Faiss(format!("Position {} out of range", position)))
+    }
+
+    fn add(&mut self, vectors: &[f32]) -> Result<(), AppError> {
+        if !vectors.len().is_multiple_of(self.dim as usize) {
+            return Err(AppError::BadRequest(format!("{} values are not whole {}-d vectors", vectors.len(), self.dim)));
+        }
+        Arc::make_mut(&mut self.vectors).extend_from_slice(vectors);
+        Ok(())
+    }
+
+    fn replica(&self) -> Option<Box<dyn VectorIndex>> {
+        Some(Box::new(Self { vectors: Arc::clone(&self.vectors), dim: self.dim, metric: self.metric }))
+    }
+}
@@ -0,0 +1,263 @@
+//! Pure-Rust HNSW index (`hnsw` feature), for builds without libfaiss and a
+//! C++ toolchain. `wikiexplorer index convert` produces one from a FAISS Flat
+//! index; point INDEX_PATH at the `.hnsw` file to serve it.
+//!
+//! On disk, `<name>.hnsw` holds a small header and the raw vectors (for
+//! reconstruction, which cross-edges and exact rescoring need), and hnsw_rs
+//! writes the graph next to it as `<stem>.hnsw.graph` / `<stem>.hnsw.data`.
+
+use hnsw_rs::hnswio::HnswIo;
+use hnsw_rs::prelude::*;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::search::vector_index::{Hits, VectorIndex};
+use crate::utils::errors::AppError;
+
+const MAGIC: &[u8; 8] = b"WEHNSW01";
+/// Magic, dim (u32), efSearch (u64) and vector count (u64)
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8 + 8;
+pub const EXTENSION: &str = "hnsw";
+
+/// hnsw_rs' maximum layer count
+const MAX_LAYERS: usize = 16;
+
+pub fn is_hnsw_path(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+/// Graph parameters, named as in FAISS' IndexHNSW
+#[derive(Debug, Clone)]
+pub struct HnswParams {
+    /// Neighbours per node
+    pub m: usize,
+    pub ef_construction: usize,
+    /// Default for EF_SEARCH when unset
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self { m: 32, ef_construction: 200, ef_search: 64 }
+    }
+}
+
+/// Replicas share one graph and only keep their own `ef_search`.
+pub struct HnswIndex {
+    graph: Arc<Graph>,
+    dim: u32,
+    ef_search: usize,
+}
+
+/// A graph with the vectors it indexes and, when read from disk, the loader
+/// it borrows from.
+struct Graph {
+    hnsw: ManuallyDrop<Hnsw<'static, f32, DistL2>>,
+    // Row-major, position order
+    vectors: Vec<f32>,
+    loader: Option<NonNull<HnswIo>>,
+}
+
+// SAFETY: the loader is only reached through `hnsw`, which hnsw_rs searches
+// from several threads, and freed in `drop`.
+unsafe impl Send for Graph {}
+unsafe impl Sync for Graph {}
+
+impl Graph {
+    fn new(hnsw: Hnsw<'static, f32, DistL2>, vectors: Vec<f32>) -> Self {
+        Self { hnsw: ManuallyDrop::new(hnsw), vectors, loader: None }
+    }
+}
+
+impl Drop for Graph {
+    fn drop(&mut self) {
+        // SAFETY: the graph goes first, it may borrow from the loader; the
+        // loader came from `Box::into_raw` in `HnswIndex::load`
+        unsafe {
+            ManuallyDrop::drop(&mut self.hnsw);
+            if let Some(loader) = self.loader.take() {
+                drop(Box::from_raw(loader.as_ptr()));
+            }
+        }
+    }
+}
+
+impl HnswIndex {
+    /// Indexes `vectors` (row-major, `dim` wide); positions follow row order.
+    pub fn build(vectors: Vec<f32>, dim: u32, params: &HnswParams) -> Self {
+        let n = vectors.len() / dim.max(1) as usize;
+        let mut hnsw = Hnsw::new(params.m, n.max(1), MAX_LAYERS, params.ef_construction, DistL2 {});
+        let rows: Vec<(&[f32], usize)> = vectors.chunks_exact(dim as usize).zip(0..).collect();
+        hnsw.parallel_insert_slice(&rows);
+        hnsw.set_searching_mode(true);
+        Self { graph: Arc::new(Graph::new(hnsw, vectors)), dim, ef_search: params.ef_search }
+    }
+
+    /// Writes the header file at `path` and the graph next to it.
+    pub fn save(&self, path: &str) -> Result<(), AppError> {
+        let (dir, stem) = graph_location(path);
+        self.graph
+            .hnsw
+            .file_dump(&dir, &stem)
+            .map_err(|e| AppError::Faiss(format!("Writing HNSW graph for {}: {}", path, e)))?;
+
+        let tmp = format!("{}.tmp", path);
+        let mut out = BufWriter::new(std::fs::File::create(&tmp)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&self.dim.to_le_bytes())?;
+        out.write_all(&(self.ef_search as u64).to_le_bytes())?;
+        out.write_all(&self.ntotal().to_le_bytes())?;
+        for value in &self.graph.vectors {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let mut input = BufReader::new(std::fs::File::open(path)?);
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(AppError::Faiss(format!("{} is not a wikiexplorer HNSW index", path)));
+        }
+        let dim = read_u32(&mut input)?;
+        let ef_search = read_u64(&mut input)? as usize;
+        let n = read_u64(&mut input)? as usize;
+        // A corrupt header must not overflow or allocate more than the file holds
+        let file_len = input.get_ref().metadata()?.len();
+        let len = n
+            .checked_mul(dim as usize)
+            .and_then(|values| values.checked_mul(4))
+            .filter(|len| len.checked_add(HEADER_LEN).is_some_and(|total| total as u64 == file_len));
+        let Some(len) = len else {
+            return Err(AppError::Faiss(format!(
+                "{}: header ({} vectors of dim {}) doesn't match the file size ({} bytes)",
+                path, n, dim, file_len
+            )));
+        };
+        let mut raw = vec![0u8; len];
+        input.read_exact(&mut raw)?;
+        let vectors = raw.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+
+        // Hnsw borrows from its loader, so the graph owns the loader and frees
+        // it after itself, when the last replica of this load is dropped
+        let (dir, stem) = graph_location(path);
+        let loader = NonNull::from(Box::leak(Box::new(HnswIo::new(&dir, &stem))));
+        // SAFETY: the loader outlives the graph (see `Graph::drop`) and is not
+        // otherwise used
+        let loaded: Result<Hnsw<'static, f32, DistL2>, _> = unsafe { &mut *loader.as_ptr() }.load_hnsw();
+        let hnsw = match loaded {
+            Ok(hnsw) => hnsw,
+            Err(e) => {
+                // SAFETY: nothing borrows from the loader without a graph
+                drop(unsafe { Box::from_raw(loader.as_ptr()) });
+                return Err(AppError::Faiss(format!("Reading HNSW graph for {}: {}", path, e)));
+            }
+        };
+        let mut graph = Graph { hnsw: ManuallyDrop::new(hnsw), vectors, loader: Some(loader) };
+        if graph.hnsw.get_nb_point() != n {
+            return Err(AppError::Faiss(format!(
+                "{}: graph has {} points, header {}",
+                path,
+                graph.hnsw.get_nb_point(),
+                n
+            )));
+        }
+        graph.hnsw.set_searching_mode(true);
+        info!("✓ HNSW index loaded ({} vectors, efSearch={})", n, ef_search);
+        Ok(Self { graph: Arc::new(graph), dim, ef_search })
+    }
+}
+
+impl VectorIndex for HnswIndex {
+    fn dim(&self) -> u32 {
+        self.dim
+    }
+
+    fn ntotal(&self) -> u64 {
+        (self.graph.vectors.len() / self.dim.max(1) as usize) as u64
+    }
+
+    fn search(&mut self, query: &[f32], k: usize) -> Result<Hits, AppError> {
+        if self.ntotal() == 0 || k == 0 {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        // DistL2 is the plain Euclidean distance; the L2 metric is its square.
+        // Vectors are unit length, so an inner-product source ranks the same
+        Ok(self
+            .graph
+            .hnsw
+            .search(query, k, self.ef_search.max(k))
+            .into_iter()
+            .map(|n| (n.distance * n.distance, n.d_id as i64))
+            .unzip())
+    }
+
+    fn reconstruct(&self, position: u64) -> Result<Vec<f32>, AppError> {
+        let dim = self.dim as usize;
+        let start = position as usize * dim;
+        self.graph
+            .vectors
+            .get(start..start + dim)
+            .map(<[f32]>::to_vec)
+            .ok_or_else(|| AppError::Faiss(format!("Position {} out of range", position)))
+    }
+
+    /// Inserts into the existing graph; positions continue after the last vector.
+    /// Fails once the graph is shared with replicas.
+    fn add(&mut self, vectors: &[f32]) -> Result<(), AppError> {
+        let dim = self.dim as usize;
+        if !vectors.len().is_multiple_of(dim) {
+            return Err(AppError::BadRequest(format!("{} values are not whole {}-d vectors", vectors.len(), dim)));
+        }
+        let first = self.ntotal() as usize;
+        let graph = Arc::get_mut(&mut self.graph)
+            .ok_or_else(|| AppError::Faiss("Cannot add to an HNSW graph shared by replicas".to_string()))?;
+        graph.hnsw.set_searching_mode(false);
+        let rows: Vec<(&[f32], usize)> = vectors.chunks_exact(dim).zip(first..).collect();
+        graph.hnsw.parallel_insert_slice(&rows);
+        graph.hnsw.set_searching_mode(true);
+        graph.vectors.extend_from_slice(vectors);
+        Ok(())
+    }
+
+    fn replica(&self) -> Option<Box<dyn VectorIndex>> {
+        Some(Box::new(Self { graph: Arc::clone(&self.graph), dim: self.dim, ef_search: self.ef_search }))
+    }
+
+    fn set_search_parameter(&mut self, name: &str, value: f64) -> Result<(), AppError> {
+        match name {
+            "efSearch" => {
+                self.ef_search = value as usize;
+                Ok(())
+            }
+            _ => Err(AppError::Faiss(format!("HNSW index has no parameter {}={}", name, value))),
+        }
+    }
+}
+
+/// hnsw_rs names its files `<basename>.hnsw.graph` and `<basename>.hnsw.data`
+fn graph_location(path: &str) -> (PathBuf, String) {
+    let path = Path::new(path);
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    (dir.to_path_buf(), stem)
+}
+
+fn read_u32(input: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(input: &mut impl Read) -> std::io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,45 @@
+use crate::search::vector_index::VectorIndex;
+use parking_lot::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A set of independently loaded copies of the same index.
+///
+/// Searching needs `&mut` (the `faiss` crate requires it), so a single index serializes every
+/// request behind one lock. Spreading requests across replicas lets N searches
+/// run at once, at the cost of N times the index memory.
+pub struct IndexPool {
+    replicas: Vec<Mutex<Box<dyn VectorIndex>>>,
+    next: AtomicUsize,
+}
+
+impl IndexPool {
+    pub fn new(replicas: Vec<Box<dyn VectorIndex>>) -> Self {
+        assert!(!replicas.is_empty(), "IndexPool needs at least one replica");
+        Self {
+            replicas: replicas.into_iter().map(Mutex::new).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.replicas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+
+    /// Returns the first free replica, starting from a round-robin cursor.
+    /// Blocks on the cursor's replica only if every replica is busy.
+    pub fn acquire(&self) -> MutexGuard<'_, Box<dyn VectorIndex>> {
+        let n = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        for offset in 0..n {
+            if let Some(guard) = self.replicas[(start + offset) % n].try_lock() {
+                return guard;
+            }
+        }
+        self.replicas[start % n].lock()
+    }
+}
@@ -0,0 +1,230 @@
+use crate::search::embedder::{Device, Embedder, EmbeddingModel};
+use crate::utils::errors::AppError;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use rust_bert::pipelines::sentence_embeddings::{
+    SentenceEmbeddingsBuilder, SentenceEmbeddingsModel, SentenceEmbeddingsModelType,
+};
+use rust_bert::RustBertError;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, warn};
+
+/// Loads `model`: a name rust-bert knows is downloaded on first use, anything
+/// else is a converted model directory (e.g. paraphrase-multilingual-MiniLM-L12-v2).
+fn load_model(model: &EmbeddingModel, device: tch::Device) -> Result<SentenceEmbeddingsModel, RustBertError> {
+    let kind = match model.name() {
+        "all-MiniLM-L6-v2" => SentenceEmbeddingsModelType::AllMiniLmL6V2,
+        "all-MiniLM-L12-v2" => SentenceEmbeddingsModelType::AllMiniLmL12V2,
+        "distiluse-base-multilingual-cased" => SentenceEmbeddingsModelType::DistiluseBaseMultilingualCased,
+        path => {
+            return SentenceEmbeddingsBuilder::local(PathBuf::from(path)).with_device(device).create_model()
+        }
+    };
+    SentenceEmbeddingsBuilder::remote(kind).with_device(device).create_model()
+}
+
+/// `requested` if libtorch can use it here, else the CPU.
+fn available_device(requested: Device) -> Device {
+    let available = match requested {
+        Device::Cpu => true,
+        Device::Cuda => tch::Cuda::is_available(),
+        Device::Mps => tch::utils::has_mps(),
+    };
+    if available {
+        requested
+    } else {
+        warn!("⚠ DEVICE={} is not available, running the model on the CPU", requested);
+        Device::Cpu
+    }
+}
+
+fn tch_device(device: Device) -> tch::Device {
+    match device {
+        Device::Cpu => tch::Device::Cpu,
+        Device::Cuda => tch::Device::Cuda(0),
+        Device::Mps => tch::Device::Mps,
+    }
+}
+
+struct EncodeRequest {
+    text: String,
+    reply: oneshot::Sender<Result<Vec<f32>, AppError>>,
+}
+
+/// Runs sentence-embedding inference on dedicated OS threads so the model never
+/// blocks a tokio worker.
+///
+/// Each worker owns its own model. A worker takes the first queued query, keeps
+/// collecting for `batch_window` (up to `max_batch` queries), then encodes them
+/// in a single model call.
+pub struct InferenceWorker {
+    // Taken on shutdown; workers exit once the channel is closed
+    tx: Mutex<Option<Sender<EncodeRequest>>>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+    workers: usize,
+    // Where the models actually run, after any fallback
+    device: Device,
+}
+
+impl InferenceWorker {
+    /// Workers running the default model (MODEL_VERSION) on the CPU.
+    pub fn start(workers: usize, batch_window: Duration, max_batch: usize) -> Result<Self, AppError> {
+        Self::start_with(&EmbeddingModel::default(), Device::Cpu, workers, batch_window, max_batch)
+    }
+
+    /// Workers running `model` on `device`, or on the CPU if the device is
+    /// missing or the model fails to load there.
+    pub fn start_with(
+        model: &EmbeddingModel,
+        device: Device,
+        workers: usize,
+        batch_window: Duration,
+        max_batch: usize,
+    ) -> Result<Self, AppError> {
+        let workers = workers.max(1);
+        let max_batch = max_batch.max(1);
+        let (tx, rx) = mpsc::channel::<EncodeRequest>();
+        let rx = Arc::new(Mutex::new(rx));
+        let mut threads = Vec::with_capacity(workers);
+        let mut device = available_device(device);
+
+        for worker_id in 0..workers {
+            let rx = Arc::clone(&rx);
+            let (ready_tx, ready_rx) = mpsc::channel();
+            let model_source = model.clone();
+
+            let thread = std::thread::Builder::new()
+                .name(format!("inference-{}", worker_id))
+                .spawn(move || {
+                    // The model is built on the thread that uses it, libtorch handles stay put
+                    let loaded = match load_model(&model_source, tch_device(device)) {
+                        Err(e) if device != Device::Cpu => {
+                            warn!("⚠ Loading the model on {} failed ({}), falling back to the CPU", device, e);
+                            load_model(&model_source, tch::Device::Cpu).map(|model| (model, Device::Cpu))
+                        }
+                        loaded => loaded.map(|model| (model, device)),
+                    };
+                    let model = match loaded {
+                        Ok((model, loaded_on)) => {
+                            let _ = ready_tx.send(Ok(loaded_on));
+                            model
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                            return;
+                        }
+                    };
+
+                    while let Some(batch) = next_batch(&rx, batch_window, max_batch) {
+                        let texts: Vec<&str> = batch.iter().map(|r| r.text.as_str()).collect();
+                        let started = Instant::now();
+
+                        match model.encode(&texts) {
+                            Ok(embeddings) => {
+                                debug!("Encoded batch of {} in {:?}", batch.len(), started.elapsed());
+                                for (request, embedding) in batch.into_iter().zip(embeddings) {
+                                    let _ = request.reply.send(Ok(embedding));
+                                }
+                            }
+                            Err(e) => {
+                                error!("Batch encode failed: {:?}", e);
+                                for request in batch {
+                                    let _ = request.reply.send(Err(AppError::Inference(e.to_string())));
+                                }
+                            }
+                        }
+                    }
+                })?;
+            threads.push(thread);
+
+            // Later workers skip a device the first one could not use
+            device = ready_rx
+                .recv()
+                .map_err(|_| AppError::Inference("Inference worker exited during startup".to_string()))?
+                .map_err(AppError::Model)?;
+        }
+
+        info!(
+            "✓ {} inference worker(s) ready on {} (batch window {:?}, max batch {})",
+            workers, device, batch_window, max_batch
+        );
+        Ok(Self { tx: Mutex::new(Some(tx)), threads: Mutex::new(threads), workers, device })
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    pub async fn encode(&self, text: String) -> Result<Vec<f32>, AppError> {
+        let (reply, response) = oneshot::channel();
+        self.tx
+            .lock()
+            .as_ref()
+            .ok_or_else(|| AppError::Inference("Inference workers are shut down".to_string()))?
+            .send(EncodeRequest { text, reply })
+            .map_err(|_| AppError::Inference("Inference workers are not running".to_string()))?;
+
+        response
+            .await
+            .map_err(|_| AppError::Inference("Inference worker dropped the request".to_string()))?
+    }
+
+    /// Closes the queue, lets workers finish queued batches and waits for them to
+    /// exit (dropping their models). Blocking; later `encode` calls fail.
+    pub fn shutdown(&self) {
+        self.tx.lock().take();
+        let threads = std::mem::take(&mut *self.threads.lock());
+        for thread in threads {
+            if thread.join().is_err() {
+                error!("Inference worker panicked during shutdown");
+            }
+        }
+        info!("✓ Inference workers stopped");
+    }
+}
+
+impl Embedder for InferenceWorker {
+    fn encode(&self, text: String) -> BoxFuture<'_, Result<Vec<f32>, AppError>> {
+        Box::pin(InferenceWorker::encode(self, text))
+    }
+
+    fn device(&self) -> String {
+        self.device.to_string()
+    }
+
+    fn shutdown(&self) {
+        InferenceWorker::shutdown(self)
+    }
+}
+
+/// Blocks for the first request, then gathers whatever else arrives within the window.
+/// Returns `None` once every sender is gone.
+fn next_batch(
+    rx: &Mutex<Receiver<EncodeRequest>>,
+    window: Duration,
+    max_batch: usize,
+) -> Option<Vec<EncodeRequest>> {
+    let rx = rx.lock();
+    let first = rx.recv().ok()?;
+
+    let mut batch = vec![first];
+    let deadline = Instant::now() + window;
+
+    while batch.len() < max_batch {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(remaining) {
+            Ok(request) => batch.push(request),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Some(batch)
+}
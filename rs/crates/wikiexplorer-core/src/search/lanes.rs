@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::config::get_config;
+
+/// How long a batch caller backs off while interactive callers are queued
+const BATCH_BACKOFF: Duration = Duration::from_millis(5);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lane {
+    Interactive,
+    Batch,
+}
+
+/// Shared, expensive resources gated by the lanes
+#[derive(Debug, Clone, Copy)]
+pub enum Resource {
+    Inference,
+    Index,
+}
+
+tokio::task_local! {
+    static LANE: Lane;
+}
+
+/// The lane of the current request. Code outside a request (spawned jobs,
+/// background runners) is batch.
+pub fn current_lane() -> Lane {
+    LANE.try_with(|lane| *lane).unwrap_or(Lane::Batch)
+}
+
+/// Runs `future` in `lane`, e.g. a request the server serves interactively.
+pub async fn in_lane<F: Future>(lane: Lane, future: F) -> F::Output {
+    LANE.scope(lane, future).await
+}
+
+struct LaneSemaphores {
+    interactive: Semaphore,
+    batch: Semaphore,
+    // Interactive callers currently waiting for a permit
+    interactive_waiting: AtomicUsize,
+}
+
+/// One interactive caller waiting for a permit, for as long as it is alive.
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Two-tier admission for inference and FAISS: each resource has a wide
+/// interactive semaphore and a narrow batch one, and batch callers yield
+/// while any interactive caller is queued on the same resource.
+pub struct PriorityLanes {
+    inference: LaneSemaphores,
+    index: LaneSemaphores,
+}
+
+impl PriorityLanes {
+    fn new(interactive: usize, batch: usize) -> Self {
+        let lanes = || LaneSemaphores {
+            interactive: Semaphore::new(interactive.max(1)),
+            batch: Semaphore::new(batch.max(1)),
+            interactive_waiting: AtomicUsize::new(0),
+        };
+        Self { inference: lanes(), index: lanes() }
+    }
+
+    /// Waits for a permit on `resource` in the current request's lane.
+    pub async fn acquire(&self, resource: Resource) -> SemaphorePermit<'_> {
+        let lanes = match resource {
+            Resource::Inference => &self.inference,
+            Resource::Index => &self.index,
+        };
+
+        match current_lane() {
+            Lane::Interactive => {
+                // Counted until the wait ends, even if the caller is dropped mid-wait
+                let _waiting = Waiting::new(&lanes.interactive_waiting);
+                lanes.interactive.acquire().await.expect("lane semaphores are never closed")
+            }
+            Lane::Batch => {
+                let permit = lanes.batch.acquire().await.expect("lane semaphores are never closed");
+                while lanes.interactive_waiting.load(Ordering::Relaxed) > 0 {
+                    tokio::time::sleep(BATCH_BACKOFF).await;
+                }
+                permit
+            }
+        }
+    }
+}
+
+static LANES: OnceLock<PriorityLanes> = OnceLock::new();
+
+pub fn lanes() -> &'static PriorityLanes {
+    LANES.get_or_init(|| {
+        let config = get_config();
+        PriorityLanes::new(config.interactive_concurrency, config.batch_concurrency)
+    })
+}
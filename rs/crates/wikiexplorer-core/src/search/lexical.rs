@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::search::engine::IndexHandle;
+use crate::search::similarity::cosine_similarity;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+
+/// How candidates are retrieved: FAISS only, FTS5 (BM25 over titles) only, or both blended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    #[default]
+    Semantic,
+    Lexical,
+    Hybrid,
+}
+
+/// Creates the external-content FTS5 table over `articles.title` and fills it
+/// the first time. Failures (read-only DB, SQLite without FTS5) leave lexical
+/// search unavailable but don't affect semantic search.
+pub async fn ensure_fts_table(pool: &SqlitePool) {
+    let exists: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'articles_fts'",
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None);
+    if exists.is_some() {
+        return;
+    }
+
+    let result = async {
+        sqlx::query(
+            "CREATE VIRTUAL TABLE articles_fts USING fts5(
+                title, content = 'articles', content_rowid = 'article_id'
+            )",
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query("INSERT INTO articles_fts (articles_fts) VALUES ('rebuild')")
+            .execute(pool)
+            .await?;
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => info!("✓ FTS5 title index built for lexical search"),
+        Err(e) => warn!("⚠ Could not build FTS5 title index, lexical search unavailable: {:?}", e),
+    }
+}
+
+/// Top `limit` articles for `query` by BM25, as `(article_id, score)` with the
+/// best match scaled to 1.0.
+pub async fn lexical_search(pool: &SqlitePool, query: &str, limit: usize) -> Result<Vec<(i64, f32)>, AppError> {
+    let Some(expression) = match_expression(query) else {
+        return Ok(vec![]);
+    };
+
+    let query = sqlx::query_as::<_, (i64, f64)>(
+        "SELECT rowid, bm25(articles_fts) FROM articles_fts WHERE articles_fts MATCH ? ORDER BY rank LIMIT ?",
+    )
+    .bind(expression)
+    .bind(limit as i64)
+    .fetch_all(pool);
+    let rows = with_deadline("lexical search", query).await.map_err(|e| match e {
+        AppError::Database(e) if e.to_string().contains("no such table") => {
+            AppError::BadRequest("Lexical search is not available for this corpus".to_string())
+        }
+        other => other,
+    })?;
+
+    // bm25() is lower-is-better and unbounded
+    let best = rows.iter().map(|(_, s)| -s).fold(0.0, f64::max);
+    Ok(rows
+        .into_iter()
+        .map(|(id, s)| (id, if best > 0.0 { (-s / best) as f32 } else { 0.0 }))
+        .collect())
+}
+
+/// Each word as a quoted term, OR-ed, so user input can't inject FTS5 syntax.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("\"{}\"", w))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// Merges FAISS and BM25 candidates into one best-first list.
+///
+/// `Lexical` scores are BM25 only. `Hybrid` scores are
+/// `(1 - lexical_weight) * cosine + lexical_weight * bm25`, both min-max scaled
+/// to [0, 1] over their candidates so neither dominates by its range alone;
+/// lexical-only hits get their cosine from the reconstructed vector on the
+/// semantic candidates' scale (0 when the index can't reconstruct).
+pub fn blend_candidates(
+    index: &IndexHandle,
+    query_vec: &[f32],
+    semantic: (&[f32], &[i64]),
+    lexical: &[(i64, f32)],
+    mode: SearchMode,
+    lexical_weight: f32,
+) -> (Vec<f32>, Vec<i64>) {
+    let mut scores: HashMap<i64, f32> = HashMap::new();
+
+    match mode {
+        SearchMode::Semantic => return (semantic.0.to_vec(), semantic.1.to_vec()),
+        SearchMode::Lexical => {
+            scores.extend(lexical.iter().cloned());
+        }
+        SearchMode::Hybrid => {
+            let cosine: HashMap<i64, f32> = semantic.1.iter().cloned().zip(semantic.0.iter().cloned()).collect();
+            let bm25: HashMap<i64, f32> = lexical.iter().cloned().collect();
+            let scale_sem = MinMax::of(semantic.0);
+            let scale_lex = MinMax::of(lexical.iter().map(|(_, s)| s));
+
+            for &id in cosine.keys().chain(bm25.keys()) {
+                let sem = match cosine.get(&id) {
+                    Some(&sem) => scale_sem.apply(sem),
+                    None if index.can_reconstruct => scale_sem.apply(reconstructed_cosine(index, id, query_vec)),
+                    None => 0.0,
+                };
+                let lex = bm25.get(&id).map_or(0.0, |&lex| scale_lex.apply(lex));
+                scores.insert(id, (1.0 - lexical_weight) * sem + lexical_weight * lex);
+            }
+        }
+    }
+
+    let mut ranked: Vec<(i64, f32)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.into_iter().map(|(id, s)| (s, id)).unzip()
+}
+
+/// Min-max scaling of one kind of candidate score to [0, 1]; values outside
+/// the observed range are clamped, and a range of one value maps it to 1.
+struct MinMax {
+    min: f32,
+    max: f32,
+}
+
+impl MinMax {
+    fn of<'a>(scores: impl IntoIterator<Item = &'a f32>) -> Self {
+        let (min, max) = scores
+            .into_iter()
+            .filter(|s| s.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &s| (min.min(s), max.max(s)));
+        Self { min, max }
+    }
+
+    fn apply(&self, score: f32) -> f32 {
+        if !score.is_finite() || self.min > self.max {
+            return 0.0;
+        }
+        if self.max - self.min <= f32::EPSILON {
+            return if score >= self.max { 1.0 } else { 0.0 };
+        }
+        ((score - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+}
+
+/// Similarity of `id`'s stored vector and the query, on the scale of search
+/// scores; 0 when it can't be reconstructed.
+pub(crate) fn reconstructed_cosine(index: &IndexHandle, id: i64, query_vec: &[f32]) -> f32 {
+    if !index.can_reconstruct {
+        return 0.0;
+    }
+    index.reconstruct(id).map_or(0.0, |v| cosine_similarity(&v, query_vec))
+}
@@ -0,0 +1,35 @@
+pub mod calibration;
+pub mod clustering;
+pub mod context;
+pub mod corpus;
+pub mod cross_edges;
+pub mod dedup;
+pub mod disambiguation;
+pub mod diversity;
+pub mod embedder;
+pub mod engine;
+pub mod exclusion;
+pub mod filter_policy;
+pub mod flat;
+#[cfg(feature = "hnsw")]
+pub mod hnsw;
+pub mod index_pool;
+#[cfg(feature = "libtorch")]
+pub mod inference;
+pub mod lanes;
+pub mod lexical;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod pipeline;
+pub mod query_expansion;
+pub mod query_store;
+pub mod ranking;
+pub mod recency;
+pub mod response_cache;
+pub mod result_pool;
+pub mod semantic_cache;
+pub mod similarity;
+pub mod spelling;
+pub mod timings;
+pub mod titles;
+pub mod vector_index;
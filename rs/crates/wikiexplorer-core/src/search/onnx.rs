@@ -0,0 +1,163 @@
+//! ONNX Runtime embedder (EMBEDDER_BACKEND=onnx, `onnx` feature): a few MB of
+//! runtime instead of libtorch, and a model that loads in well under a second.
+//!
+//! The model directory holds a sentence-transformers export, e.g. the files of
+//! `Xenova/all-MiniLM-L6-v2` on the Hugging Face Hub: `tokenizer.json` and
+//! `model_quantized.onnx` (preferred) or `model.onnx`. Pooling and
+//! normalization follow the sentence-transformers pipeline, so vectors match
+//! the rust-bert backend within quantization error; `wikiexplorer-cli parity`
+//! checks a model against it.
+
+use futures::future::BoxFuture;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use tracing::info;
+
+use crate::search::embedder::Embedder;
+use crate::utils::errors::AppError;
+
+/// Files tried in order inside the model directory
+const MODEL_FILES: [&str; 2] = ["model_quantized.onnx", "model.onnx"];
+const TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Word pieces the model reads (sentence-transformers' max_seq_length)
+const MAX_SEQ_LENGTH: usize = 256;
+
+pub struct OnnxEmbedder {
+    inner: Arc<OnnxModel>,
+}
+
+struct OnnxModel {
+    session: Session,
+    tokenizer: Tokenizer,
+    // Older exports take no token_type_ids input
+    wants_token_types: bool,
+}
+
+impl OnnxEmbedder {
+    /// Loads the model in `dir`; `threads` bounds ONNX Runtime's intra-op pool.
+    pub fn load(dir: &Path, threads: usize) -> Result<Self, AppError> {
+        let model_path = MODEL_FILES
+            .iter()
+            .map(|file| dir.join(file))
+            .find(|path| path.exists())
+            .ok_or_else(|| AppError::Config(format!("No {} in {}", MODEL_FILES.join(" or "), dir.display())))?;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join(TOKENIZER_FILE))
+            .map_err(|e| AppError::Config(format!("Tokenizer in {}: {}", dir.display(), e)))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams { max_length: MAX_SEQ_LENGTH, ..Default::default() }))
+            .map_err(|e| AppError::Config(format!("Tokenizer truncation: {}", e)))?;
+
+        let session = Session::builder()
+            .and_then(|b| b.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|b| b.with_intra_threads(threads.max(1)))
+            .and_then(|b| b.commit_from_file(&model_path))
+            .map_err(|e| AppError::Config(format!("ONNX model {}: {}", model_path.display(), e)))?;
+        let wants_token_types = session.inputs.iter().any(|input| input.name == "token_type_ids");
+
+        info!("✓ ONNX embedder ready ({})", model_path.display());
+        Ok(Self { inner: Arc::new(OnnxModel { session, tokenizer, wants_token_types }) })
+    }
+
+    /// Model directory of `model`: ONNX_MODEL_DIR for the default model, else
+    /// the name itself as a path.
+    pub fn model_dir(model: &str, default_dir: &str) -> PathBuf {
+        if model == crate::search::embedder::MODEL_VERSION {
+            PathBuf::from(default_dir)
+        } else {
+            PathBuf::from(model)
+        }
+    }
+}
+
+impl OnnxModel {
+    /// Mean of the token embeddings under the attention mask, L2-normalized.
+    fn encode(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, AppError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| AppError::Inference(format!("Tokenization failed: {}", e)))?;
+        let batch = encodings.len();
+        let seq_len = encodings[0].get_ids().len();
+
+        let flat = |field: fn(&tokenizers::Encoding) -> &[u32]| -> Vec<i64> {
+            encodings.iter().flat_map(|e| field(e).iter().map(|&v| v as i64)).collect()
+        };
+        let ids = flat(tokenizers::Encoding::get_ids);
+        let mask = flat(tokenizers::Encoding::get_attention_mask);
+        let tensor = |values: Vec<i64>| {
+            Tensor::from_array(([batch, seq_len], values.into_boxed_slice()))
+                .map_err(|e| AppError::Inference(format!("ONNX input: {}", e)))
+        };
+
+        let mut inputs = vec![
+            ("input_ids", tensor(ids)?.into_dyn()),
+            ("attention_mask", tensor(mask.clone())?.into_dyn()),
+        ];
+        if self.wants_token_types {
+            inputs.push(("token_type_ids", tensor(flat(tokenizers::Encoding::get_type_ids))?.into_dyn()));
+        }
+        let outputs = self
+            .session
+            .run(inputs)
+            .map_err(|e| AppError::Inference(format!("ONNX inference failed: {}", e)))?;
+        let (shape, hidden) = outputs[0]
+            .try_extract_raw_tensor::<f32>()
+            .map_err(|e| AppError::Inference(format!("ONNX output: {}", e)))?;
+        let dim = *shape.last().unwrap_or(&0) as usize;
+        if shape.len() != 3 || dim == 0 {
+            return Err(AppError::Inference(format!("Unexpected ONNX output shape {:?}", shape)));
+        }
+
+        Ok((0..batch)
+            .map(|b| {
+                let mut pooled = vec![0.0f32; dim];
+                let mut tokens = 0.0f32;
+                for t in 0..seq_len {
+                    if mask[b * seq_len + t] == 0 {
+                        continue;
+                    }
+                    let offset = (b * seq_len + t) * dim;
+                    for (sum, value) in pooled.iter_mut().zip(&hidden[offset..offset + dim]) {
+                        *sum += value;
+                    }
+                    tokens += 1.0;
+                }
+                pooled.iter_mut().for_each(|v| *v /= tokens.max(1.0));
+                let norm = pooled.iter().map(|v| v * v).sum::<f32>().sqrt().max(f32::EPSILON);
+                pooled.iter_mut().for_each(|v| *v /= norm);
+                pooled
+            })
+            .collect())
+    }
+}
+
+impl Embedder for OnnxEmbedder {
+    fn encode(&self, text: String) -> BoxFuture<'_, Result<Vec<f32>, AppError>> {
+        Box::pin(async move {
+            self.encode_batch(vec![text])
+                .await?
+                .pop()
+                .ok_or_else(|| AppError::Inference("ONNX model returned nothing".to_string()))
+        })
+    }
+
+    /// One session run per call, off the async workers.
+    fn encode_batch(&self, texts: Vec<String>) -> BoxFuture<'_, Result<Vec<Vec<f32>>, AppError>> {
+        let model = Arc::clone(&self.inner);
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || model.encode(texts))
+                .await
+                .map_err(|e| AppError::Inference(format!("ONNX worker failed: {}", e)))?
+        })
+    }
+}
@@ -0,0 +1,782 @@
+//! Candidate retrieval and ranking for one corpus, independent of any transport:
+//! FAISS (and/or FTS5) candidates, metadata hydration, filter policy, category
+//! filters and multi-signal scoring.
+
+use crate::categories::{fetch_categories, CategoryFilter};
+use crate::config::get_config;
+use crate::models::Article;
+use crate::search::calibration::Calibration;
+use crate::search::context::{GraphContext, CONTEXT_SIGNAL};
+use crate::search::corpus::Corpus;
+use crate::search::diversity::diversify;
+use crate::search::engine::SearchParams;
+use crate::search::exclusion::Exclusion;
+use crate::search::filter_policy::{default_policy, CompiledFilterPolicy, FilterOverrides, FilterVerdict};
+use crate::search::lanes::{lanes, Resource};
+use crate::search::lexical::{blend_candidates, lexical_search, reconstructed_cosine, SearchMode};
+use crate::search::ranking::{
+    calculate_title_match_score, custom_factor, geometric_mean, normalize_backlinks, normalize_pagerank,
+    normalize_pageviews, ranker, title_match_spans, ObscurityOverrides, ObscurityPenalty, RankSignals, Ranker,
+    RankingStrategy,
+};
+use crate::search::recency::{fetch_last_modified, recency, recency_weight, RECENCY_SIGNAL};
+use crate::search::timings::{Stage, StageTimings};
+use crate::utils::anonymize::query_digest;
+use crate::utils::cancel::run_blocking;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// One ranked article.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SearchResult {
+    pub id: i64,
+    /// Shared, so cached pools and pages don't copy every title; empty when hydration is partial
+    #[serde(default, skip_serializing_if = "str::is_empty")]
+    pub title: Arc<str>,
+    pub score: i32,
+    /// `relevance` on the wire since API v2; the alias reads older cached responses
+    #[serde(rename = "relevance", alias = "score_float")]
+    #[cfg_attr(feature = "ts", ts(rename = "relevance"))]
+    pub score_float: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corpus: Option<String>, // Set in federated results
+    /// `[start, end)` UTF-16 offsets of query-term matches in `title`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<[usize; 2]>,
+    /// Attached to the returned page only (see `article_images`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<DebugScores>,
+    /// Added by the keyword backfill rather than found by FAISS
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub backfilled: bool,
+    /// Inputs of the score, for rankers that rescore the whole pool
+    #[serde(skip)]
+    pub signals: RankSignals,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct DebugScores {
+    /// Raw FAISS similarity (cosine for the lexical backfill)
+    pub sem_faiss: f32,
+    pub sem_verify: f32,
+    /// `sem_faiss` after score calibration, as used in the ranking formula
+    #[serde(default)]
+    pub sem_norm: f32,
+    /// Affinity to the graph context, when the request had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<f64>,
+    /// Edit recency, when the request ranked by it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recency: Option<f64>,
+    /// Score factor of a result about an `exclude` term
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusion: Option<f64>,
+    /// Obscurity penalty of a result with next to no pageviews and pagerank
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obscurity: Option<f64>,
+    /// Filter policy factor of a demoted meta page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub demotion: Option<f64>,
+    /// Raw `pagerank`, `pageviews` and `backlinks` columns (0 when missing),
+    /// each followed by its normalized value
+    #[serde(default)]
+    pub pagerank: f64,
+    #[serde(default)]
+    pub pagerank_norm: f64,
+    #[serde(default)]
+    pub pageviews: i64,
+    #[serde(default)]
+    pub pageviews_norm: f64,
+    #[serde(default)]
+    pub backlinks: i64,
+    #[serde(default)]
+    pub backlinks_norm: f64,
+    #[serde(default)]
+    pub title_match: f64,
+    /// Normalized registry signals by name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub custom: HashMap<String, f64>,
+    /// Weighted geometric mean of the built-in signals, before penalties and
+    /// custom signals, whatever the ranking
+    #[serde(default)]
+    pub geometric_mean: f64,
+    pub final_score: f64,
+}
+
+/// Whether results carry metadata. `Partial` means the metadata DB missed its
+/// budget: results are ordered by FAISS score only and titles must be fetched
+/// from the metadata API.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum Hydration {
+    #[default]
+    Full,
+    Partial,
+}
+
+/// Results the server ranks at least (per corpus in federated mode), so a few
+/// pages can be served from the token pool; ranking stops once these are certain
+pub const POOL_DEPTH: usize = 300;
+
+/// Per-query ranking options. The defaults match a plain `/api/search` request,
+/// except that the server also sets `limit` to `POOL_DEPTH` (or deeper pages).
+#[derive(Debug, Clone, Default)]
+pub struct RankOptions {
+    /// Overrides RESCORE_TOP_N
+    pub rescore: Option<usize>,
+    /// Per-query nprobe / efSearch
+    pub search_params: Option<SearchParams>,
+    /// Keep only articles in at least one of these categories
+    pub include_categories: Vec<String>,
+    /// Drop articles in any of these categories
+    pub exclude_categories: Vec<String>,
+    /// Overrides of the configured meta-page policy
+    pub filters: Option<FilterOverrides>,
+    pub search_mode: SearchMode,
+    /// Attach per-signal scores to each result
+    pub debug: bool,
+    /// Stop once the best `limit` results are certain: the rest of the pool is
+    /// neither hydrated nor returned. None ranks every candidate.
+    pub limit: Option<usize>,
+    /// Results the request shows (`offset + k`): semantic searches left with
+    /// fewer are topped up from the keyword index. None uses BACKFILL_MIN_RESULTS.
+    pub page_end: Option<usize>,
+    /// Overrides RANKING
+    pub ranking: Option<RankingStrategy>,
+    /// MMR trade-off in [0, 1] for the first MMR_WINDOW results: 0 (or None)
+    /// keeps the score order, higher favors results unlike those above them
+    pub diversity: Option<f32>,
+    /// Article IDs on the client's graph, ranked towards with WEIGHT_CONTEXT
+    pub context: Vec<i64>,
+    /// Embeddings of negative query terms, penalized with EXCLUDE_PENALTY
+    pub exclude: Vec<Vec<f32>>,
+    /// Overrides WEIGHT_RECENCY, the weight of recently edited articles
+    pub recency: Option<f64>,
+    /// Overrides of the configured obscurity penalty
+    pub obscurity: Option<ObscurityOverrides>,
+    /// Exploring a niche: no obscurity penalty at all
+    pub deep_dive: bool,
+    /// Collects the time spent per stage (debug requests)
+    pub timings: Option<Arc<StageTimings>>,
+}
+
+impl RankOptions {
+    fn record(&self, stage: Stage, started: Instant) {
+        if let Some(timings) = &self.timings {
+            timings.since(stage, started);
+        }
+    }
+}
+
+/// FAISS candidate search, SQLite hydration and multi-signal ranking.
+/// Returns every surviving candidate sorted by final score (not yet truncated to k),
+/// or with `RankOptions::limit` possibly just the top `limit` of them.
+/// If hydration misses `HYDRATION_BUDGET_MS`, returns ID-only results in FAISS order.
+pub async fn rank_candidates(
+    corpus: &Corpus,
+    options: &RankOptions,
+    query_clean: &str,
+    query_vec: &[f32],
+) -> Result<(Vec<SearchResult>, Hydration), AppError> {
+    let config = get_config();
+
+    // 3. FAISS Search (Pool Size)
+    // We request more candidates than needed because the verification step drops many.
+    // 3b. Optional exact re-scoring of the head of the pool (IVF/PQ quantization error)
+    // Both hold an index replica; run off the runtime and stop early if the client is gone.
+    let started = Instant::now();
+    let (mut dists, mut ids) = if options.search_mode == SearchMode::Lexical {
+        (vec![], vec![])
+    } else {
+        let index = Arc::clone(&corpus.index);
+        let query = query_vec.to_vec();
+        let params = options.search_params.clone();
+        let rescore_top_n = options.rescore.unwrap_or(config.rescore_top_n);
+        let _permit = lanes().acquire(Resource::Index).await;
+        run_blocking(move |cancel| {
+            let (mut dists, mut ids) = index.search(&query, config.candidate_pool_size, params.as_ref())?;
+            cancel.check()?;
+            index.rescore_exact(&query, &mut dists, &mut ids, rescore_top_n);
+            Ok((dists, ids))
+        })
+        .await?
+    };
+
+    // 3c. Lexical candidates (FTS5 BM25) for lexical / hybrid modes
+    if options.search_mode != SearchMode::Semantic {
+        let lexical = lexical_search(&corpus.db, query_clean, config.candidate_pool_size).await?;
+        (dists, ids) = blend_candidates(
+            &corpus.index,
+            query_vec,
+            (&dists, &ids),
+            &lexical,
+            options.search_mode,
+            config.hybrid_lexical_weight,
+        );
+    }
+    options.record(Stage::Faiss, started);
+
+    // 4. Fetch Metadata from SQLite
+    // Dynamic query construction for IN clause
+    if ids.is_empty() {
+        return Ok((vec![], Hydration::Full));
+    }
+
+    let custom_policy: CompiledFilterPolicy;
+    let policy = match &options.filters {
+        Some(overrides) => {
+            custom_policy = default_policy().with_overrides(overrides)?;
+            &custom_policy
+        }
+        None => default_policy(),
+    };
+    let obscurity = ObscurityPenalty::for_request(options.deep_dive, options.obscurity.as_ref())?;
+
+    // Map IDs to FAISS similarities (ranking order, debug)
+    let mut faiss_scores = HashMap::new();
+    for (i, id) in ids.iter().enumerate() {
+        faiss_scores.insert(*id, dists[i]);
+    }
+
+    // 5. Verification & Ranking, hydrating best FAISS score first
+    // Optional: Re-encode article titles to verify semantic match (The "Fix" in Python code)
+    // In Rust this is heavier because we don't batch-encode comfortably inside the loop.
+    // We will verify strictly based on the ranking formula for now to save latency.
+    let filter = CategoryFilter::new(&options.include_categories, &options.exclude_categories);
+    let ranker = ranker(options.ranking.unwrap_or(config.ranking))?;
+    let recency = recency_weight(corpus, options.recency);
+    let scorer = Scorer { corpus, options, policy, filter: &filter, query_clean, ranker, recency, obscurity };
+    let mut order: Vec<usize> = (0..ids.len()).collect();
+    order.sort_by(|&a, &b| dists[b].total_cmp(&dists[a]));
+    let candidates: Vec<i64> = order.into_iter().map(|i| ids[i]).collect();
+
+    // 4b. Affinity of every candidate to the client's graph, penalties for
+    // those about an excluded term
+    let context = GraphContext::load(corpus, &options.context).await?.map(Arc::new);
+    let exclusion = Exclusion::new(&options.exclude).map(Arc::new);
+    // Exclusion only ever lowers scores, so it leaves the bound intact
+    let cutoff = options
+        .limit
+        .and_then(|limit| ScoreCutoff::new(corpus, policy, ranker, limit, context.is_some(), recency));
+
+    // Category filters and policy overrides can't be applied without metadata,
+    // so they wait out the budget and never degrade to ID-only results
+    let can_degrade = filter.is_empty() && options.filters.is_none();
+    // The vector signals reconstruct every candidate, so they share the hydration budget
+    let budget = (config.hydration_budget_ms > 0 && can_degrade)
+        .then(|| Instant::now() + Duration::from_millis(config.hydration_budget_ms));
+    let rank = async {
+        let vector_signals = VectorSignals::load(corpus, context.as_ref(), exclusion.as_ref(), &candidates).await?;
+        rank_in_chunks(&scorer, &candidates, &faiss_scores, &vector_signals, cutoff.as_ref(), !filter.is_empty()).await
+    };
+    let ranked = match budget {
+        None => Some(rank.await),
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), rank).await.ok(),
+    };
+
+    let (mut results, stopped) = match ranked {
+        Some(Ok(ranked)) => ranked,
+        // Degraded mode: DB unavailable (e.g. mid artifact swap) or a statement hit
+        // DB_QUERY_TIMEOUT_MS, serve semantic-only results
+        Some(Err(e @ (AppError::Database(_) | AppError::Timeout(_)))) if can_degrade => {
+            warn!(
+                "⚠ Metadata DB unavailable for corpus '{}' ({}), returning partial results",
+                corpus.name, e
+            );
+            return Ok((id_only_results(corpus, &ids, &dists), Hydration::Partial));
+        }
+        Some(Err(e)) => return Err(e),
+        None => {
+            warn!(
+                "⚠ Metadata hydration exceeded {}ms for corpus '{}', returning partial results",
+                config.hydration_budget_ms, corpus.name
+            );
+            return Ok((id_only_results(corpus, &ids, &dists), Hydration::Partial));
+        }
+    };
+
+    // 5b. Niche queries: filtering can leave fewer usable results than a page,
+    // so top up from the keyword index and rank the additions alongside, in
+    // what is left of the hydration budget
+    let wanted = options.page_end.unwrap_or(config.backfill_min_results);
+    if config.backfill_min_results > 0
+        && options.search_mode == SearchMode::Semantic
+        && !stopped
+        && results.len() < wanted
+    {
+        let backfill = async {
+            let Some((hydrated, scores)) =
+                backfill_candidates(&scorer, query_vec, &faiss_scores, wanted, !filter.is_empty()).await?
+            else {
+                return Ok(Vec::new());
+            };
+            let ids: Vec<i64> = scores.keys().copied().collect();
+            let vector_signals = VectorSignals::load(corpus, context.as_ref(), exclusion.as_ref(), &ids).await?;
+            let started = Instant::now();
+            let added = scorer.score(hydrated, &scores, &vector_signals, true);
+            options.record(Stage::Rank, started);
+            Ok::<_, AppError>(added)
+        };
+        let added = match budget {
+            None => Some(backfill.await),
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), backfill).await.ok(),
+        };
+        match added {
+            Some(Ok(added)) => {
+                if !added.is_empty() {
+                    debug!("Backfilled {} keyword results for query {}", added.len(), query_digest(query_clean));
+                }
+                results.extend(added);
+            }
+            // Lexical search unavailable or slow: the semantic results stand
+            Some(Err(e)) => debug!("Keyword backfill skipped: {}", e),
+            None => debug!("Keyword backfill skipped: hydration budget spent"),
+        }
+    }
+
+    let started = Instant::now();
+    ranker.fuse(&mut results);
+    sort_by_score(&mut results);
+
+    // 5c. Spread the head of the pool over subtopics (maximal marginal relevance)
+    if let Some(diversity) = options.diversity.filter(|d| *d > 0.0) {
+        let index = Arc::clone(&corpus.index);
+        let window = config.mmr_window;
+        results = run_blocking(move |cancel| {
+            let mut results = results;
+            diversify(&index, &mut results, diversity, window, cancel)?;
+            Ok(results)
+        })
+        .await?;
+    }
+    options.record(Stage::Rank, started);
+
+    Ok((results, Hydration::Full))
+}
+
+/// Keyword candidates not among the FAISS ones, hydrated, with their cosine to
+/// the query as semantic score. None when there are none.
+async fn backfill_candidates(
+    scorer: &Scorer<'_>,
+    query_vec: &[f32],
+    seen: &HashMap<i64, f32>,
+    wanted: usize,
+    with_categories: bool,
+) -> Result<Option<(Hydrated, HashMap<i64, f32>)>, AppError> {
+    let corpus = scorer.corpus;
+    let lexical = lexical_search(&corpus.db, scorer.query_clean, wanted.saturating_mul(2)).await?;
+    let ids: Vec<i64> = lexical.into_iter().map(|(id, _)| id).filter(|id| !seen.contains_key(id)).collect();
+    if ids.is_empty() {
+        return Ok(None);
+    }
+
+    let index = Arc::clone(&corpus.index);
+    let query = query_vec.to_vec();
+    let candidates = ids.clone();
+    let scores: HashMap<i64, f32> = run_blocking(move |_| {
+        Ok(candidates.iter().map(|&id| (id, reconstructed_cosine(&index, id, &query))).collect())
+    })
+    .await?;
+
+    let started = Instant::now();
+    let hydrated = hydrate_candidates(corpus, &ids, with_categories, scorer.recency.is_some()).await?;
+    scorer.options.record(Stage::Sqlite, started);
+    Ok(Some((hydrated, scores)))
+}
+
+/// Hydrates and scores `candidates` (best semantic score first), a chunk at a
+/// time when there is a cutoff. True with the results when the cutoff stopped
+/// ranking before the end of the pool.
+async fn rank_in_chunks(
+    scorer: &Scorer<'_>,
+    candidates: &[i64],
+    semantic_scores: &HashMap<i64, f32>,
+    vector_signals: &VectorSignals,
+    cutoff: Option<&ScoreCutoff<'_>>,
+    with_categories: bool,
+) -> Result<(Vec<SearchResult>, bool), AppError> {
+    let chunk_size = match (cutoff, get_config().hydration_chunk_size) {
+        (Some(_), size) if size > 0 => size,
+        _ => candidates.len().max(1),
+    };
+
+    let mut results = Vec::new();
+    for (n, chunk) in candidates.chunks(chunk_size).enumerate() {
+        if let Some(cutoff) = cutoff {
+            let next = semantic_scores.get(&chunk[0]).copied().unwrap_or(0.0);
+            if cutoff.settled(&mut results, next) {
+                debug!("Ranking settled after {} of {} candidates", n * chunk_size, candidates.len());
+                return Ok((results, true));
+            }
+        }
+        let started = Instant::now();
+        let hydrated = hydrate_candidates(scorer.corpus, chunk, with_categories, scorer.recency.is_some()).await?;
+        scorer.options.record(Stage::Sqlite, started);
+        let started = Instant::now();
+        results.extend(scorer.score(hydrated, semantic_scores, vector_signals, false));
+        scorer.options.record(Stage::Rank, started);
+    }
+    Ok((results, false))
+}
+
+/// Per-candidate signals computed from index vectors rather than metadata.
+struct VectorSignals {
+    /// Affinity to the graph context, when the request had one
+    affinities: Option<HashMap<i64, f64>>,
+    /// Score factors of candidates about an excluded term
+    exclusions: HashMap<i64, f64>,
+}
+
+impl VectorSignals {
+    async fn load(
+        corpus: &Corpus,
+        context: Option<&Arc<GraphContext>>,
+        exclusion: Option<&Arc<Exclusion>>,
+        candidates: &[i64],
+    ) -> Result<Self, AppError> {
+        let affinities = match context {
+            Some(context) => Some(context.affinities(&corpus.index, candidates).await?),
+            None => None,
+        };
+        let exclusions = match exclusion {
+            Some(exclusion) => exclusion.factors(&corpus.index, candidates).await?,
+            None => HashMap::new(),
+        };
+        Ok(Self { affinities, exclusions })
+    }
+}
+
+/// When ranking can stop: once the `limit`-th best score is at least the
+/// highest score any candidate not yet scored could reach.
+struct ScoreCutoff<'a> {
+    limit: usize,
+    ranker: &'a dyn Ranker,
+    calibration: Calibration,
+    max_pagerank: f64,
+    max_pageviews: f64,
+    custom_weights: Vec<(&'a str, f64)>,
+    max_factor: f64,
+}
+
+impl<'a> ScoreCutoff<'a> {
+    /// None when there's no usable bound: signal maxima unknown, or a ranker
+    /// whose scores don't fall with the FAISS score (see `Ranker::upper_bound`).
+    fn new(
+        corpus: &'a Corpus,
+        policy: &CompiledFilterPolicy,
+        ranker: &'a dyn Ranker,
+        limit: usize,
+        with_context: bool,
+        recency: Option<f64>,
+    ) -> Option<Self> {
+        let (max_pagerank, max_pageviews) = corpus.signals.builtin_max?;
+        let mut custom_weights: Vec<(&str, f64)> =
+            corpus.signals.signals.iter().map(|s| (s.name.as_str(), s.weight)).collect();
+        // Affinity is at most 1, like a normalized registry signal
+        if with_context {
+            custom_weights.push((CONTEXT_SIGNAL, get_config().weight_context));
+        }
+        // And so is recency
+        if let Some(weight) = recency {
+            custom_weights.push((RECENCY_SIGNAL, weight));
+        }
+        if limit == 0 || ranker.upper_bound(1.0, max_pagerank, max_pageviews, &custom_weights).is_none() {
+            return None;
+        }
+        Some(Self {
+            limit,
+            ranker,
+            calibration: corpus.index.calibration,
+            max_pagerank,
+            max_pageviews,
+            custom_weights,
+            max_factor: policy.max_factor(),
+        })
+    }
+
+    /// True when no candidate with semantic score at most `semantic` can enter
+    /// the best `limit` of `results`, which are then cut to those.
+    fn settled(&self, results: &mut Vec<SearchResult>, semantic: f32) -> bool {
+        if results.len() < self.limit {
+            return false;
+        }
+        sort_by_score(results);
+        // Calibration never decreases, so the calibrated score bounds too
+        let semantic = self.calibration.apply(semantic) as f64;
+        let Some(bound) = self.ranker.upper_bound(semantic, self.max_pagerank, self.max_pageviews, &self.custom_weights) else {
+            return false;
+        };
+        let bound = bound * self.max_factor;
+        let kth = results[self.limit - 1].score_float;
+        if kth.is_nan() || kth < bound {
+            return false;
+        }
+        results.truncate(self.limit);
+        true
+    }
+}
+
+/// Filter policy, category filter and multi-signal scoring for hydrated candidates.
+struct Scorer<'a> {
+    corpus: &'a Corpus,
+    options: &'a RankOptions,
+    policy: &'a CompiledFilterPolicy,
+    filter: &'a CategoryFilter,
+    query_clean: &'a str,
+    ranker: &'a dyn Ranker,
+    /// Weight of edit recency; None leaves it out
+    recency: Option<f64>,
+    obscurity: ObscurityPenalty,
+}
+
+impl Scorer<'_> {
+    fn score(
+        &self,
+        hydrated: Hydrated,
+        semantic_scores: &HashMap<i64, f32>,
+        vector_signals: &VectorSignals,
+        backfilled: bool,
+    ) -> Vec<SearchResult> {
+        let (options, policy, filter, query_clean) = (self.options, self.policy, self.filter, self.query_clean);
+        let Hydrated { articles, custom_values, categories, last_modified } = hydrated;
+        let registry = &self.corpus.signals;
+        let config = get_config();
+        let now = chrono::Utc::now().timestamp();
+        let mut results = Vec::new();
+
+        for article in articles {
+            let policy_demotion = match policy.verdict(&article.title) {
+                FilterVerdict::Keep => 1.0,
+                FilterVerdict::Demote(factor) => factor,
+                FilterVerdict::Exclude => continue,
+            };
+            let exclusion = vector_signals.exclusions.get(&article.article_id).copied();
+            let demotion = policy_demotion * exclusion.unwrap_or(1.0);
+            if !filter.is_empty() {
+                let article_categories = categories.get(&article.article_id).map(Vec::as_slice).unwrap_or(&[]);
+                if !filter.allows(article_categories) { continue; }
+            }
+
+            let raw_score = *semantic_scores.get(&article.article_id).unwrap_or(&0.0);
+            let sem_norm = self.corpus.index.calibration.apply(raw_score);
+
+            let mut custom: Vec<(&str, f64, f64)> = match custom_values.get(&article.article_id) {
+                Some(values) => registry
+                    .signals
+                    .iter()
+                    .zip(values)
+                    .map(|(signal, value)| (signal.name.as_str(), signal.normalize(*value), signal.weight))
+                    .collect(),
+                None => vec![],
+            };
+            let custom_folded = custom_factor(&custom);
+            let registry_signals = custom.len();
+            let context = vector_signals
+                .affinities
+                .as_ref()
+                .map(|a| a.get(&article.article_id).copied().unwrap_or(0.0));
+            if let Some(affinity) = context {
+                custom.push((CONTEXT_SIGNAL, affinity, config.weight_context));
+            }
+            let recency = self.recency.map(|weight| {
+                let value = recency(last_modified.get(&article.article_id).copied(), now);
+                custom.push((RECENCY_SIGNAL, value, weight));
+                value
+            });
+            let (pagerank, pageviews) = (article.pagerank.unwrap_or(0.0), article.pageviews.unwrap_or(0) as f64);
+            let obscurity = Some(self.obscurity.factor(pagerank, pageviews)).filter(|factor| *factor < 1.0);
+            let signals = RankSignals {
+                semantic: sem_norm as f64,
+                pagerank,
+                pageviews,
+                backlinks: normalize_backlinks(article.backlinks),
+                title_match: calculate_title_match_score(&article.title, query_clean),
+                custom: custom_folded,
+                context,
+                recency,
+                obscurity,
+                demotion,
+            };
+            let final_score = self.ranker.score(&signals, &custom) * demotion;
+
+            let debug_info = if options.debug {
+                Some(DebugScores {
+                    sem_faiss: raw_score,
+                    sem_verify: raw_score, // Skipping double-verify for performance in V1
+                    sem_norm,
+                    context,
+                    recency,
+                    exclusion,
+                    obscurity,
+                    demotion: Some(policy_demotion).filter(|factor| *factor != 1.0),
+                    pagerank,
+                    pagerank_norm: normalize_pagerank(article.pagerank),
+                    pageviews: article.pageviews.unwrap_or(0),
+                    pageviews_norm: normalize_pageviews(article.pageviews),
+                    backlinks: article.backlinks.unwrap_or(0),
+                    backlinks_norm: signals.backlinks,
+                    title_match: signals.title_match,
+                    custom: custom[..registry_signals].iter().map(|&(name, value, _)| (name.to_string(), value)).collect(),
+                    geometric_mean: geometric_mean(&signals),
+                    final_score,
+                })
+            } else {
+                None
+            };
+
+            results.push(SearchResult {
+                id: article.article_id,
+                highlights: title_match_spans(&article.title, query_clean),
+                title: article.title.into(),
+                score: (final_score * 100.0) as i32,
+                score_float: final_score,
+                corpus: None,
+                thumbnail_url: None,
+                debug: debug_info,
+                backfilled,
+                signals,
+            });
+        }
+        results
+    }
+}
+
+/// Best first. A NaN score (e.g. from a malformed custom signal) sorts last
+/// instead of panicking the comparator.
+pub fn sort_by_score(results: &mut [SearchResult]) {
+    let key = |r: &SearchResult| if r.score_float.is_nan() { f64::NEG_INFINITY } else { r.score_float };
+    results.sort_by(|a, b| key(b).total_cmp(&key(a)));
+}
+
+/// Candidates scored by their calibrated FAISS similarity only, best first.
+/// Without titles the configured filter policy is applied from the verdicts
+/// precomputed at load; per-request overrides never get here.
+fn id_only_results(corpus: &Corpus, ids: &[i64], dists: &[f32]) -> Vec<SearchResult> {
+    let factors = &corpus.signals.policy_factors;
+    let mut results: Vec<SearchResult> = ids
+        .iter()
+        .zip(dists)
+        // FAISS pads lists shorter than k with -1
+        .filter(|(&id, _)| id >= 0)
+        .filter_map(|(&id, &raw)| {
+            let factor = factors.get(&id).copied().unwrap_or(1.0);
+            (factor > 0.0).then(|| (id, corpus.index.calibration.apply(raw) as f64 * factor))
+        })
+        .map(|(id, score)| SearchResult {
+            id,
+            title: Arc::default(),
+            score: (score * 100.0) as i32,
+            score_float: score,
+            corpus: None,
+            highlights: vec![],
+            thumbnail_url: None,
+            debug: None,
+            backfilled: false,
+            signals: RankSignals::default(),
+        })
+        .collect();
+    sort_by_score(&mut results);
+    results
+}
+
+/// Metadata of a batch of candidates, keyed by article ID.
+struct Hydrated {
+    articles: Vec<Article>,
+    /// Registry signal values, in registry order
+    custom_values: HashMap<i64, Vec<Option<f64>>>,
+    categories: HashMap<i64, Vec<String>>,
+    /// Last edit times (unix seconds)
+    last_modified: HashMap<i64, i64>,
+}
+
+/// Article rows, custom registry signal values and (if requested) categories
+/// and last edit times for the candidate IDs.
+async fn hydrate_candidates(
+    corpus: &Corpus,
+    ids: &[i64],
+    with_categories: bool,
+    with_last_modified: bool,
+) -> Result<Hydrated, AppError> {
+    let params = placeholders(ids.len());
+    let sql = format!(
+        "SELECT article_id, title, pagerank, pageviews, backlinks FROM articles WHERE article_id IN ({})", 
+        params
+    );
+
+    let mut query_builder = sqlx::query_as::<_, Article>(&sql);
+    for id in ids {
+        query_builder = query_builder.bind(id);
+    }
+    
+    let articles = with_deadline("hydrate articles", query_builder.fetch_all(&corpus.db)).await?;
+
+    // Custom registry signals (extra columns), fetched separately so Article stays fixed
+    let registry = &corpus.signals;
+    let mut custom_values: HashMap<i64, Vec<Option<f64>>> = HashMap::new();
+    if !registry.is_empty() {
+        let columns: Vec<&str> = registry.signals.iter().map(|s| s.name.as_str()).collect();
+        let sql = format!(
+            "SELECT article_id, {} FROM articles WHERE article_id IN ({})",
+            columns.join(", "),
+            params
+        );
+        let mut custom_query = sqlx::query(&sql);
+        for id in ids {
+            custom_query = custom_query.bind(id);
+        }
+        for row in with_deadline("hydrate custom signals", custom_query.fetch_all(&corpus.db)).await? {
+            let id: i64 = row.try_get(0)?;
+            let values = (1..=columns.len()).map(|i| row.try_get::<Option<f64>, _>(i).ok().flatten()).collect();
+            custom_values.insert(id, values);
+        }
+    }
+
+    let categories = if with_categories {
+        fetch_categories(&corpus.db, ids).await?
+    } else {
+        HashMap::new()
+    };
+    let last_modified = if with_last_modified {
+        fetch_last_modified(&corpus.db, ids).await?
+    } else {
+        HashMap::new()
+    };
+
+    Ok(Hydrated { articles, custom_values, categories, last_modified })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::engine::IndexHandle;
+    use crate::search::flat::FlatIndex;
+    use crate::signals::SignalRegistry;
+    use sqlx::SqlitePool;
+
+    #[tokio::test]
+    async fn id_only_results_skip_padded_labels() {
+        let corpus = Corpus {
+            name: "test".to_string(),
+            index: Arc::new(IndexHandle::from_replicas(vec![Box::new(FlatIndex::new(2))], "")),
+            db: SqlitePool::connect_lazy("sqlite::memory:").unwrap(),
+            signals: Arc::new(SignalRegistry::default()),
+            lang: None,
+            model: None,
+        };
+        let results = id_only_results(&corpus, &[3, -1, 1, -1], &[0.5, 0.0, 0.9, 0.0]);
+        let ids: Vec<i64> = results.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+}
@@ -0,0 +1,98 @@
+//! Queries made of several parts: `"query": ["jazz", "mathematics"]`, or a
+//! query plus `anchor_ids` (articles whose vectors join the query's). Each
+//! part is a unit vector, pooled into the one vector FAISS is searched with.
+//!
+//! Mean pooling lands between the parts, near articles related to all of them
+//! ("jazz AND mathematics"); max pooling keeps each dimension's strongest
+//! part, which leans towards articles strongly about either one.
+
+use serde::Deserialize;
+
+use crate::search::engine::IndexHandle;
+use crate::search::similarity::normalize;
+use crate::utils::errors::AppError;
+
+/// Terms per query; each one is a model call unless cached
+pub const MAX_QUERY_TERMS: usize = 8;
+/// Anchor articles per query
+pub const MAX_ANCHORS: usize = 32;
+
+/// `query` of a search request: one text or several terms.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum QueryInput {
+    Text(String),
+    Terms(Vec<String>),
+}
+
+impl QueryInput {
+    /// The parts to encode, underscores read as spaces; blank terms are dropped.
+    pub fn terms(&self) -> Vec<String> {
+        match self {
+            QueryInput::Text(text) => vec![text.replace('_', " ")],
+            QueryInput::Terms(terms) => terms
+                .iter()
+                .map(|t| t.replace('_', " ").trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The query as one line, for logs, title matching and lexical search.
+    pub fn text(&self) -> String {
+        self.terms().join(" ")
+    }
+
+    /// Case-insensitive identity of the query, for response cache keys.
+    pub fn cache_key(&self) -> String {
+        match self {
+            QueryInput::Text(text) => text.replace('_', " ").trim().to_lowercase(),
+            // Unit separator: terms are not the same query as their concatenation
+            QueryInput::Terms(_) => self.terms().join("\u{1f}").to_lowercase(),
+        }
+    }
+
+    pub fn is_terms(&self) -> bool {
+        matches!(self, QueryInput::Terms(_))
+    }
+}
+
+/// How the parts of a multi-vector query are combined (`pooling`, default mean).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum Pooling {
+    #[default]
+    Mean,
+    Max,
+}
+
+/// Unit-length combination of `vectors` (unit length, same dimension); None
+/// when there are none or they cancel out.
+pub fn pool(vectors: &[Vec<f32>], pooling: Pooling) -> Option<Vec<f32>> {
+    let (first, rest) = vectors.split_first()?;
+    let mut pooled = first.clone();
+    for v in rest {
+        match pooling {
+            Pooling::Mean => pooled.iter_mut().zip(v).for_each(|(p, x)| *p += x),
+            Pooling::Max => pooled.iter_mut().zip(v).for_each(|(p, x)| *p = p.max(*x)),
+        }
+    }
+    // The mean's scale doesn't matter once normalized
+    normalize(&mut pooled).then_some(pooled)
+}
+
+/// Unit vectors of the anchor articles (internal IDs) that have one.
+pub fn anchor_vectors(index: &IndexHandle, ids: &[i64]) -> Result<Vec<Vec<f32>>, AppError> {
+    if !index.can_reconstruct {
+        return Err(AppError::BadRequest(
+            "anchor_ids need an index that can reconstruct vectors".to_string(),
+        ));
+    }
+    Ok(ids
+        .iter()
+        .filter_map(|&id| index.reconstruct(id).ok())
+        .filter_map(|mut v| normalize(&mut v).then_some(v))
+        .collect())
+}
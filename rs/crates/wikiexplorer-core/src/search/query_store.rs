@@ -0,0 +1,252 @@
+use crate::search::embedder::MODEL_VERSION;
+use crate::utils::time::unix_now;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Query embeddings persisted across restarts in a sidecar SQLite file
+/// (QUERY_STORE_PATH), keyed by a hash of the normalized query text, model and
+/// MODEL_VERSION, so a build with another default model never reads them.
+///
+/// Repeated queries skip the model even after a restart, stored embeddings
+/// feed query analytics, and `wikiexplorer-cli benchmark --replay` ranks past
+/// queries without re-encoding them. Embeddings are little-endian f32 blobs;
+/// entries unused for QUERY_STORE_TTL_DAYS are purged. Query text is only
+/// kept with QUERY_STORE_KEEP_TEXT, and then only for PII_RETENTION_DAYS.
+#[derive(Clone)]
+pub struct QueryStore {
+    pool: SqlitePool,
+    ttl: Duration,
+    keep_text: bool,
+}
+
+/// A stored query, for analytics and replay
+#[derive(Debug, Clone)]
+pub struct StoredQuery {
+    /// Empty when QUERY_STORE_KEEP_TEXT is off
+    pub query: String,
+    /// Empty for the default model
+    pub model: String,
+    pub embedding: Vec<f32>,
+    pub uses: i64,
+    /// Uses within the window asked for in `recent`
+    pub recent_uses: i64,
+    pub last_seen: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct QueryStoreStats {
+    pub entries: i64,
+    pub ttl_days: u64,
+}
+
+impl QueryStore {
+    /// Opens (creating if needed) the store at `path` and drops expired entries.
+    pub async fn open(path: &str, ttl: Duration, keep_text: bool) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path))?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new().max_connections(2).connect_with(options).await?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS query_embeddings (
+                key TEXT PRIMARY KEY,
+                query TEXT NOT NULL,
+                model TEXT NOT NULL,
+                embedding BLOB NOT NULL,
+                uses INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_query_embeddings_last_seen ON query_embeddings (last_seen)")
+            .execute(&pool)
+            .await?;
+        // Uses per UTC day, for volumes over a recent window
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS query_uses (
+                key TEXT NOT NULL,
+                day INTEGER NOT NULL,
+                uses INTEGER NOT NULL DEFAULT 1,
+                PRIMARY KEY (key, day)
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        // Stores created before entries recorded their model version
+        let has_model_version: Option<(String,)> =
+            sqlx::query_as("SELECT name FROM pragma_table_info('query_embeddings') WHERE name = 'model_version'")
+                .fetch_optional(&pool)
+                .await?;
+        if has_model_version.is_none() {
+            sqlx::query("ALTER TABLE query_embeddings ADD COLUMN model_version TEXT NOT NULL DEFAULT ''")
+                .execute(&pool)
+                .await?;
+        }
+
+        let store = Self { pool, ttl, keep_text };
+        let purged = store.purge_expired().await?;
+        info!("✓ Query store opened at {} ({} expired entries purged)", path, purged);
+        // Text recorded while QUERY_STORE_KEEP_TEXT was on
+        if !keep_text {
+            let forgotten = store.forget_text(u64::MAX).await?;
+            if forgotten > 0 {
+                info!("✓ Forgot the text of {} stored queries (QUERY_STORE_KEEP_TEXT is off)", forgotten);
+            }
+        }
+        Ok(store)
+    }
+
+    /// Stored embedding for an embedding-cache key (see `SearchEngine::encode_query_with`).
+    pub async fn get(&self, key: &str) -> Option<Vec<f32>> {
+        let row: Option<(Vec<u8>,)> = sqlx::query_as("SELECT embedding FROM query_embeddings WHERE key = ? AND last_seen > ?")
+            .bind(hashed(key))
+            .bind(self.expiry() as i64)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| warn!("⚠ Query store lookup failed: {}", e))
+            .ok()
+            .flatten();
+        Some(from_blob(&row?.0))
+    }
+
+    /// Inserts the embedding of `query`, or marks an existing one as used again.
+    pub async fn record(&self, key: &str, model: Option<&str>, query: &str, embedding: &[f32]) {
+        let now = unix_now() as i64;
+        let result = sqlx::query(
+            "INSERT INTO query_embeddings (key, query, model, model_version, embedding, created_at, last_seen)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(key) DO UPDATE SET uses = uses + 1, last_seen = excluded.last_seen",
+        )
+        .bind(hashed(key))
+        .bind(if self.keep_text { query } else { "" })
+        .bind(model.unwrap_or(""))
+        .bind(MODEL_VERSION)
+        .bind(to_blob(embedding))
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = result {
+            warn!("⚠ Query store write failed: {}", e);
+        }
+        self.count_use(key, now).await;
+    }
+
+    /// Marks a stored entry as used, keeping it from expiring.
+    pub async fn touch(&self, key: &str) {
+        let now = unix_now() as i64;
+        let _ = sqlx::query("UPDATE query_embeddings SET uses = uses + 1, last_seen = ? WHERE key = ?")
+            .bind(now)
+            .bind(hashed(key))
+            .execute(&self.pool)
+            .await;
+        self.count_use(key, now).await;
+    }
+
+    async fn count_use(&self, key: &str, now: i64) {
+        let _ = sqlx::query(
+            "INSERT INTO query_uses (key, day, uses) VALUES (?, ?, 1)
+             ON CONFLICT(key, day) DO UPDATE SET uses = uses + 1",
+        )
+        .bind(hashed(key))
+        .bind(now / SECONDS_PER_DAY)
+        .execute(&self.pool)
+        .await;
+    }
+
+    /// Up to `limit` live entries of the default model (or `model`) recorded by
+    /// this MODEL_VERSION, most recently used first, with their uses over the
+    /// last `window` (whole UTC days, today included).
+    pub async fn recent(&self, model: Option<&str>, limit: usize, window: Duration) -> Result<Vec<StoredQuery>, sqlx::Error> {
+        let days = (window.as_secs() / SECONDS_PER_DAY as u64).max(1) as i64;
+        let first_day = unix_now() as i64 / SECONDS_PER_DAY - days + 1;
+        let rows: Vec<(String, String, Vec<u8>, i64, i64, i64)> = sqlx::query_as(
+            "SELECT q.query, q.model, q.embedding, q.uses,
+                    COALESCE((SELECT SUM(u.uses) FROM query_uses u WHERE u.key = q.key AND u.day >= ?), 0),
+                    q.last_seen
+             FROM query_embeddings q
+             WHERE q.model = ? AND q.model_version = ? AND q.last_seen > ? ORDER BY q.last_seen DESC LIMIT ?",
+        )
+        .bind(first_day)
+        .bind(model.unwrap_or(""))
+        .bind(MODEL_VERSION)
+        .bind(self.expiry() as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(query, model, blob, uses, recent_uses, last_seen)| StoredQuery {
+                query,
+                model,
+                embedding: from_blob(&blob),
+                uses,
+                recent_uses,
+                last_seen: last_seen.max(0) as u64,
+            })
+            .collect())
+    }
+
+    /// Deletes entries unused for longer than the TTL, and daily use counts
+    /// older than it; returns how many entries.
+    pub async fn purge_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM query_embeddings WHERE last_seen <= ?")
+            .bind(self.expiry() as i64)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM query_uses WHERE day < ?")
+            .bind(self.expiry() as i64 / SECONDS_PER_DAY)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Blanks the text of entries last searched before `cutoff` (unix seconds),
+    /// keeping their embeddings; returns how many (PII_RETENTION_DAYS).
+    pub async fn forget_text(&self, cutoff: u64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("UPDATE query_embeddings SET query = '' WHERE last_seen < ? AND query != ''")
+            .bind(cutoff as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn stats(&self) -> QueryStoreStats {
+        let entries = sqlx::query_scalar("SELECT COUNT(*) FROM query_embeddings")
+            .fetch_one(&self.pool)
+            .await
+            .unwrap_or(0);
+        QueryStoreStats { entries, ttl_days: self.ttl.as_secs() / 86_400 }
+    }
+
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    fn expiry(&self) -> u64 {
+        unix_now().saturating_sub(self.ttl.as_secs())
+    }
+}
+
+/// Keys are stored hashed, so the text itself is only kept with QUERY_STORE_KEEP_TEXT.
+fn hashed(key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(MODEL_VERSION.as_bytes());
+    hasher.update([0x1f]);
+    hasher.update(key.as_bytes());
+    hasher.finalize().iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+fn to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
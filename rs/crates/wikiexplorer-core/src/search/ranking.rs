@@ -0,0 +1,879 @@
+use crate::config::get_config;
+use crate::search::pipeline::SearchResult;
+use crate::utils::errors::AppError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+// Pre-compiled regex for performance
+static YEAR_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_year_regex() -> &'static Regex {
+    YEAR_REGEX.get_or_init(|| Regex::new(r"^\d{4}").unwrap())
+}
+
+/// Non-finite signal values (corrupt rows, overflowing imports) count as missing.
+fn finite_at_least(value: f64, floor: f64) -> f64 {
+    if value.is_finite() { value.max(floor) } else { floor }
+}
+
+pub fn normalize_pagerank(pagerank_score: Option<f64>) -> f64 {
+    match pagerank_score {
+        Some(score) if score.is_finite() && score > 0.0 => score / 100.0,
+        _ => 0.0,
+    }
+}
+
+pub fn normalize_pageviews(pageview_count: Option<i64>) -> f64 {
+    let count = match pageview_count {
+        Some(c) if c > 0 => c as f64,
+        _ => return 0.0,
+    };
+
+    if count < 100.0 {
+        return 0.1;
+    }
+
+    let min_log = 2.0; // 100 views
+    let max_log = 7.0; // 10M views
+
+    let log_val = count.log10();
+    let score = (log_val - min_log) / (max_log - min_log);
+    
+    // Clamp between 0.0 and 1.0
+    score.clamp(0.0, 1.0)
+}
+
+/// Log-scaled backlink count in [0, 1]: one link is 0, 100k or more is 1.
+/// Link counts are heavy-tailed, so a linear scale would leave nearly every
+/// article at 0.
+pub fn normalize_backlinks(backlink_count: Option<i64>) -> f64 {
+    let count = match backlink_count {
+        Some(c) if c > 0 => c as f64,
+        _ => return 0.0,
+    };
+    let max_log = 5.0; // 100k backlinks
+    (count.log10() / max_log).clamp(0.0, 1.0)
+}
+
+/// Title normalization shared by the title-match signal and highlighting.
+fn normalize_title_for_match(title: &str) -> String {
+    title.to_lowercase().replace('_', " ")
+}
+
+pub fn calculate_title_match_score(title: &str, query: &str) -> f64 {
+    let title_lower = normalize_title_for_match(title);
+    let query_lower = query.to_lowercase();
+
+    let title_words: HashSet<&str> = title_lower.split_whitespace().collect();
+    let query_words: HashSet<&str> = query_lower.split_whitespace().collect();
+
+    if title_words.is_empty() {
+        return 0.0;
+    }
+
+    let intersection_count = title_words.intersection(&query_words).count();
+    let union_count = title_words.union(&query_words).count();
+
+    if union_count == 0 {
+        return 0.0;
+    }
+
+    let mut base_score = intersection_count as f64 / union_count as f64;
+
+    // Exact or substring match boost
+    if title_lower == query_lower {
+        return 1.0;
+    } else if title_lower.starts_with(&query_lower) || title_lower.contains(&query_lower) {
+        base_score = (base_score * 1.5).min(1.0);
+    }
+
+    // Place indicator penalty logic
+    // Checks if " in " splits the title and the second part contains a location
+    if let Some((_, suffix)) = title_lower.split_once(" in ") {
+        let place_indicators = [
+            "africa", "asia", "europe", "america", "states", "kingdom",
+            "china", "india", "russia", "france", "germany", "japan",
+            "canada", "australia", "brazil", "mexico", "italy", "spain",
+            "california", "texas", "york", "london", "paris", "tokyo"
+        ];
+        if place_indicators.iter().any(|&place| suffix.contains(place)) {
+            base_score *= 0.5;
+        }
+    }
+
+    // Year penalty
+    if get_year_regex().is_match(&title_lower) {
+        base_score *= 0.4;
+    }
+
+    // Meta page penalty
+    let meta_prefixes = [
+        "list of", "index of", "glossary of", "timeline of", 
+        "outline of", "history of", "bibliography of"
+    ];
+    if meta_prefixes.iter().any(|&prefix| title_lower.starts_with(prefix)) {
+        base_score *= 0.1;
+    }
+
+    base_score.clamp(0.0, 1.0)
+}
+
+/// Spans of `title` matching the query, as `[start, end)` offsets in UTF-16 code
+/// units (what JS string indexing uses), sorted and non-overlapping.
+///
+/// Uses the title-match signal's rules: whole title words that are query words,
+/// plus the full query where it occurs as a substring of the title.
+pub fn title_match_spans(title: &str, query: &str) -> Vec<[usize; 2]> {
+    // Normalize char by char so each normalized char maps back to an original span
+    let mut normalized: Vec<(char, usize, usize)> = Vec::new();
+    let mut offset = 0;
+    for c in title.chars() {
+        let width = c.len_utf16();
+        for n in normalize_title_for_match(c.encode_utf8(&mut [0; 4])).chars() {
+            normalized.push((n, offset, offset + width));
+        }
+        offset += width;
+    }
+
+    let query_lower = query.to_lowercase();
+    let query_words: HashSet<&str> = query_lower.split_whitespace().collect();
+    let mut spans = Vec::new();
+
+    // Whole-word matches
+    let mut word_start = None;
+    for i in 0..=normalized.len() {
+        let is_space = normalized.get(i).is_none_or(|(c, _, _)| c.is_whitespace());
+        match (word_start, is_space) {
+            (None, false) => word_start = Some(i),
+            (Some(start), true) => {
+                let word: String = normalized[start..i].iter().map(|(c, _, _)| c).collect();
+                if query_words.contains(word.as_str()) {
+                    spans.push([normalized[start].1, normalized[i - 1].2]);
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    // Full-query substring matches
+    let needle: Vec<char> = query_lower.chars().collect();
+    if !needle.is_empty() && needle.iter().any(|c| !c.is_whitespace()) && needle.len() <= normalized.len() {
+        for start in 0..=(normalized.len() - needle.len()) {
+            let end = start + needle.len();
+            if normalized[start..end].iter().map(|(c, _, _)| *c).eq(needle.iter().cloned()) {
+                spans.push([normalized[start].1, normalized[end - 1].2]);
+            }
+        }
+    }
+
+    spans.sort_unstable();
+    let mut merged: Vec<[usize; 2]> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if span[0] <= last[1] => last[1] = last[1].max(span[1]),
+            _ => merged.push(span),
+        }
+    }
+    merged
+}
+
+/// Folds custom registry signals into a geometric-mean score.
+/// `factors` holds `(normalized value, weight)` pairs; factors with a
+/// non-finite weight are ignored. Never returns NaN or infinity.
+pub fn apply_custom_signals(score: f64, factors: &[(f64, f64)]) -> f64 {
+    fold_custom(score, factors.iter().copied())
+}
+
+fn fold_custom(score: f64, factors: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let config = get_config();
+    let score = factors
+        .filter(|(_, weight)| weight.is_finite())
+        .fold(score, |acc, (norm, weight)| acc * finite_at_least(norm, config.epsilon).powf(weight));
+    if score.is_finite() { score } else { 0.0 }
+}
+
+/// Weighted geometric mean of the ranking signals. Every factor is floored at
+/// `epsilon` (so zero-signal articles and empty titles still get a score) and
+/// the result is always finite. `semantic_similarity` is the calibrated score
+/// (`Calibration::apply`). Includes the configured obscurity penalty.
+pub fn calculate_multisignal_score(
+    semantic_similarity: f32,
+    pagerank_score: f64,
+    pageview_count: f64,
+    backlink_count: Option<i64>,
+    title: &str,
+    query: &str,
+) -> f64 {
+    let score = geometric_score(
+        semantic_similarity as f64,
+        pagerank_score,
+        pageview_count,
+        normalize_backlinks(backlink_count),
+        calculate_title_match_score(title, query),
+    );
+    score * ObscurityPenalty::configured().factor(pagerank_score, pageview_count)
+}
+
+/// `calculate_multisignal_score` with the title match and backlinks already
+/// normalized, before the obscurity penalty.
+fn geometric_score(semantic_similarity: f64, pagerank_score: f64, pageview_count: f64, backlinks: f64, title_match: f64) -> f64 {
+    let config = get_config();
+
+    let sem_norm = finite_at_least(semantic_similarity, config.epsilon);
+    let pr_norm = finite_at_least(pagerank_score, config.epsilon);
+    let pv_norm = finite_at_least(pageview_count, config.epsilon);
+    let bl_norm = finite_at_least(backlinks, config.epsilon);
+    let title_norm = title_match.max(config.epsilon);
+
+    // Geometric Mean
+    let score = sem_norm.powf(config.weight_semantic) *
+                pr_norm.powf(config.weight_pagerank) *
+                pv_norm.powf(config.weight_pageviews) *
+                bl_norm.powf(config.weight_backlinks) *
+                title_norm.powf(config.weight_title_match);
+
+    // Huge (but finite) signals can still overflow the product
+    if score.is_finite() { score } else { 0.0 }
+}
+
+/// The geometric mean of an article's built-in signals, without the obscurity
+/// penalty and custom signals, for debug breakdowns.
+pub fn geometric_mean(signals: &RankSignals) -> f64 {
+    geometric_score(signals.semantic, signals.pagerank, signals.pageviews, signals.backlinks, signals.title_match)
+}
+
+/// Semantically relevant articles nobody reads or links to: with pageviews
+/// and pagerank both below the thresholds (raw columns), the geometric score
+/// is multiplied by `factor`. Niche academic topics look the same, so requests
+/// can loosen it or turn it off (`deep_dive`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObscurityPenalty {
+    pub pageviews_below: f64,
+    pub pagerank_below: f64,
+    /// In [0, 1]; 1 is no penalty
+    pub factor: f64,
+}
+
+/// Per-request changes to the configured obscurity penalty
+/// (`SearchRequest.obscurity`). Unset fields keep the configured value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ObscurityOverrides {
+    pub pageviews_below: Option<f64>,
+    pub pagerank_below: Option<f64>,
+    pub penalty: Option<f64>,
+}
+
+impl ObscurityPenalty {
+    pub const OFF: Self = Self { pageviews_below: 0.0, pagerank_below: 0.0, factor: 1.0 };
+
+    /// OBSCURITY_PAGEVIEWS_BELOW, OBSCURITY_PAGERANK_BELOW and OBSCURITY_PENALTY.
+    pub fn configured() -> Self {
+        let config = get_config();
+        let factor = config.obscurity_penalty;
+        Self {
+            pageviews_below: config.obscurity_pageviews_below,
+            pagerank_below: config.obscurity_pagerank_below,
+            factor: if factor.is_finite() { factor.clamp(0.0, 1.0) } else { 1.0 },
+        }
+    }
+
+    /// The penalty of a request: off for a deep dive, else the configured one
+    /// with `overrides` applied. A penalty outside [0, 1] is rejected, since
+    /// early stopping relies on it only ever lowering scores.
+    pub fn for_request(deep_dive: bool, overrides: Option<&ObscurityOverrides>) -> Result<Self, AppError> {
+        if deep_dive {
+            return Ok(Self::OFF);
+        }
+        let mut penalty = Self::configured();
+        let Some(overrides) = overrides else {
+            return Ok(penalty);
+        };
+        if let Some(factor) = overrides.penalty {
+            if !(0.0..=1.0).contains(&factor) {
+                return Err(AppError::BadRequest("obscurity penalty must be between 0 and 1".to_string()));
+            }
+            penalty.factor = factor;
+        }
+        for (value, threshold) in [
+            (overrides.pageviews_below, &mut penalty.pageviews_below),
+            (overrides.pagerank_below, &mut penalty.pagerank_below),
+        ] {
+            if let Some(value) = value {
+                if !value.is_finite() {
+                    return Err(AppError::BadRequest("obscurity thresholds must be numbers".to_string()));
+                }
+                *threshold = value;
+            }
+        }
+        Ok(penalty)
+    }
+
+    /// Score factor of an article with these raw `pagerank` and `pageviews`.
+    pub fn factor(&self, pagerank: f64, pageviews: f64) -> f64 {
+        let epsilon = get_config().epsilon;
+        let obscure = finite_at_least(pageviews, epsilon) < self.pageviews_below
+            && finite_at_least(pagerank, epsilon) < self.pagerank_below;
+        if obscure { self.factor } else { 1.0 }
+    }
+}
+
+/// Highest score `calculate_multisignal_score` followed by `apply_custom_signals`
+/// can give an article with this semantic similarity, when pagerank and
+/// pageviews are at most the given maxima. Assumes an exact title match and
+/// backlinks and custom signals at their best; infinite when the bound overflows.
+pub fn multisignal_upper_bound(
+    semantic_similarity: f32,
+    max_pagerank: f64,
+    max_pageviews: f64,
+    custom_weights: &[f64],
+) -> f64 {
+    let config = get_config();
+    // A factor ranges over [epsilon, max]; with a negative weight the floor is the larger power
+    let ceiling = |max: f64, weight: f64| {
+        config.epsilon.powf(weight).max(finite_at_least(max, config.epsilon).powf(weight))
+    };
+
+    let custom: f64 = custom_weights
+        .iter()
+        .filter(|weight| weight.is_finite())
+        .map(|&weight| ceiling(1.0, weight))
+        .product();
+    let bound = finite_at_least(semantic_similarity as f64, config.epsilon).powf(config.weight_semantic)
+        * ceiling(max_pagerank, config.weight_pagerank)
+        * ceiling(max_pageviews, config.weight_pageviews)
+        * ceiling(1.0, config.weight_backlinks)
+        * ceiling(1.0, config.weight_title_match)
+        * custom;
+    if bound.is_nan() { f64::INFINITY } else { bound }
+}
+
+// --- Ranking strategies ---
+
+/// Constant of reciprocal rank fusion: a list's top article adds `1 / (k + 1)`
+const RRF_K: f64 = 60.0;
+
+/// How hydrated candidates are turned into a final score, per request
+/// (`ranking`, default RANKING).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum RankingStrategy {
+    /// Weighted geometric mean of the signals (`calculate_multisignal_score`)
+    #[default]
+    Geometric,
+    /// Weighted arithmetic mean of the normalized signals
+    Sum,
+    /// Reciprocal rank fusion of the per-signal rankings
+    Rrf,
+    /// Learned linear model from RANKING_MODEL_FILE
+    Linear,
+}
+
+impl FromStr for RankingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "geometric" => Ok(Self::Geometric),
+            "sum" => Ok(Self::Sum),
+            "rrf" => Ok(Self::Rrf),
+            "linear" => Ok(Self::Linear),
+            other => Err(format!("unknown ranking '{}'", other)),
+        }
+    }
+}
+
+/// An article's built-in ranking signals, kept on its result for rank fusion.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RankSignals {
+    /// Calibrated semantic similarity
+    pub semantic: f64,
+    /// Raw `pagerank` and `pageviews` columns, 0 when missing
+    pub pagerank: f64,
+    pub pageviews: f64,
+    /// Normalized backlink count (`normalize_backlinks`)
+    pub backlinks: f64,
+    pub title_match: f64,
+    /// Registry signals folded into one factor (`apply_custom_signals`); None without any
+    pub custom: Option<f64>,
+    /// Affinity to the request's graph context (`search::context`); None without one
+    pub context: Option<f64>,
+    /// Obscurity penalty factor of the request (`ObscurityPenalty`), applied
+    /// by the geometric ranker; None when the article isn't penalized
+    pub obscurity: Option<f64>,
+    /// Edit recency (`search::recency`); None unless the request ranks by it
+    pub recency: Option<f64>,
+    /// Filter policy factor (1 unless demoted)
+    pub demotion: f64,
+}
+
+/// A ranking strategy. Scores are compared within one ranked pool only.
+pub trait Ranker: Send + Sync {
+    /// Final score before demotion. `custom` holds the registry signals, the
+    /// graph context as `CONTEXT_SIGNAL` and edit recency as `RECENCY_SIGNAL`,
+    /// as (name, normalized value, weight).
+    fn score(&self, signals: &RankSignals, custom: &[(&str, f64, f64)]) -> f64;
+
+    /// Highest score before demotion of an article with calibrated similarity
+    /// `semantic` when pagerank and pageviews are at most the given (raw)
+    /// maxima; `custom` holds registry (name, weight). Must not increase as
+    /// `semantic` falls; None when scores can't be bounded, which ranks the
+    /// whole pool.
+    fn upper_bound(&self, _semantic: f64, _max_pagerank: f64, _max_pageviews: f64, _custom: &[(&str, f64)]) -> Option<f64> {
+        None
+    }
+
+    /// Rescores a whole ranked pool, for strategies that depend on the other
+    /// candidates. Runs after every candidate (backfill included) is scored.
+    fn fuse(&self, _results: &mut [SearchResult]) {}
+}
+
+/// The ranker of `strategy`; the linear one fails when no model is loaded
+/// (only for requests asking for it: the default is checked at startup).
+pub fn ranker(strategy: RankingStrategy) -> Result<&'static dyn Ranker, AppError> {
+    match strategy {
+        RankingStrategy::Geometric => Ok(&GeometricRanker),
+        RankingStrategy::Sum => Ok(&WeightedSumRanker),
+        RankingStrategy::Rrf => Ok(&RrfRanker),
+        RankingStrategy::Linear => match linear_model() {
+            Some(model) => Ok(model),
+            None => Err(AppError::BadRequest("ranking \"linear\" needs a model in RANKING_MODEL_FILE".to_string())),
+        },
+    }
+}
+
+/// Fails when RANKING is `linear` but RANKING_MODEL_FILE gives no model: every
+/// search without its own `ranking` would fail, so startup does instead.
+pub fn check_default_ranking() -> Result<(), AppError> {
+    let config = get_config();
+    if config.ranking == RankingStrategy::Linear && linear_model().is_none() {
+        return Err(AppError::Config(match &config.ranking_model_file {
+            Some(path) => format!("RANKING=linear, but the model in RANKING_MODEL_FILE ({}) could not be loaded", path),
+            None => "RANKING=linear needs RANKING_MODEL_FILE".to_string(),
+        }));
+    }
+    Ok(())
+}
+
+/// The built-in formula: `calculate_multisignal_score` times the custom factors.
+pub struct GeometricRanker;
+
+impl Ranker for GeometricRanker {
+    fn score(&self, signals: &RankSignals, custom: &[(&str, f64, f64)]) -> f64 {
+        let score = geometric_score(
+            signals.semantic,
+            signals.pagerank,
+            signals.pageviews,
+            signals.backlinks,
+            signals.title_match,
+        ) * signals.obscurity.unwrap_or(1.0);
+        fold_custom(score, custom.iter().map(|&(_, value, weight)| (value, weight)))
+    }
+
+    fn upper_bound(&self, semantic: f64, max_pagerank: f64, max_pageviews: f64, custom: &[(&str, f64)]) -> Option<f64> {
+        // A semantic weight <= 0 doesn't make the bound fall with the FAISS score
+        if get_config().weight_semantic <= 0.0 {
+            return None;
+        }
+        let weights: Vec<f64> = custom.iter().map(|(_, weight)| *weight).collect();
+        Some(multisignal_upper_bound(semantic as f32, max_pagerank, max_pageviews, &weights))
+    }
+}
+
+/// Ranking weights over normalized signals in [0, 1], divided by the sum of
+/// their magnitudes so scores stay in [0, 1].
+pub struct WeightedSumRanker;
+
+impl WeightedSumRanker {
+    fn weights() -> [f64; 5] {
+        let config = get_config();
+        [
+            config.weight_semantic,
+            config.weight_pagerank,
+            config.weight_pageviews,
+            config.weight_backlinks,
+            config.weight_title_match,
+        ]
+    }
+}
+
+impl Ranker for WeightedSumRanker {
+    fn score(&self, signals: &RankSignals, custom: &[(&str, f64, f64)]) -> f64 {
+        let values = normalized_builtins(signals);
+        let terms = Self::weights()
+            .into_iter()
+            .zip(values)
+            .chain(custom.iter().map(|&(_, value, weight)| (weight, value)))
+            .filter(|(weight, value)| weight.is_finite() && value.is_finite());
+        let (sum, total) = terms.fold((0.0, 0.0), |(sum, total), (weight, value)| (sum + weight * value, total + weight.abs()));
+        if total > 0.0 { (sum / total).max(0.0) } else { 0.0 }
+    }
+
+    fn upper_bound(&self, semantic: f64, max_pagerank: f64, max_pageviews: f64, custom: &[(&str, f64)]) -> Option<f64> {
+        let [w_sem, w_pr, w_pv, w_bl, w_title] = Self::weights();
+        if w_sem <= 0.0 {
+            return None;
+        }
+        let maxima = [
+            (w_pr, normalize_pagerank(Some(max_pagerank))),
+            (w_pv, normalize_pageviews(Some(max_pageviews as i64))),
+            (w_bl, 1.0),
+            (w_title, 1.0),
+        ];
+        let terms = maxima.into_iter().chain(custom.iter().map(|&(_, weight)| (weight, 1.0)));
+        let (rest, total) = terms
+            .filter(|(weight, _)| weight.is_finite())
+            .fold((0.0, w_sem), |(sum, total), (weight, max)| (sum + (weight * max).max(0.0), total + weight.abs()));
+        Some((w_sem * semantic.clamp(0.0, 1.0) + rest) / total)
+    }
+}
+
+/// Reads one signal of a result, None when it doesn't have that signal
+type SignalOf = fn(&RankSignals) -> Option<f64>;
+
+/// Semantic, pagerank, pageviews, backlinks, title match and graph context each rank the
+/// pool, and an article scores `Σ weight / (RRF_K + rank)` over those rankings
+/// (custom signals rank as one list, weight 1, and so does recency when
+/// requested). Only the ordering is meaningful.
+pub struct RrfRanker;
+
+impl Ranker for RrfRanker {
+    /// Placeholder until `fuse`: the geometric score
+    fn score(&self, signals: &RankSignals, custom: &[(&str, f64, f64)]) -> f64 {
+        GeometricRanker.score(signals, custom)
+    }
+
+    fn fuse(&self, results: &mut [SearchResult]) {
+        let config = get_config();
+        let lists: [(f64, SignalOf); 8] = [
+            (config.weight_semantic, |s| Some(s.semantic)),
+            (config.weight_pagerank, |s| Some(s.pagerank)),
+            (config.weight_pageviews, |s| Some(s.pageviews)),
+            (config.weight_backlinks, |s| Some(s.backlinks)),
+            (config.weight_title_match, |s| Some(s.title_match)),
+            (1.0, |s| s.custom),
+            (config.weight_context, |s| s.context),
+            (1.0, |s| s.recency),
+        ];
+
+        let mut fused = vec![0.0; results.len()];
+        let mut order: Vec<usize> = (0..results.len()).collect();
+        for (weight, signal) in lists {
+            if !(weight.is_finite() && weight > 0.0) {
+                continue;
+            }
+            let value = |i: usize| signal(&results[i].signals).filter(|v| v.is_finite()).unwrap_or(f64::NEG_INFINITY);
+            order.sort_by(|&a, &b| value(b).total_cmp(&value(a)));
+            for (rank, &i) in order.iter().enumerate() {
+                if signal(&results[i].signals).is_some() {
+                    fused[i] += weight / (RRF_K + rank as f64 + 1.0);
+                }
+            }
+        }
+
+        for (result, score) in results.iter_mut().zip(fused) {
+            let score = score * result.signals.demotion;
+            result.score_float = score;
+            result.score = (score * 100.0) as i32;
+            if let Some(debug) = &mut result.debug {
+                debug.final_score = score;
+            }
+        }
+    }
+}
+
+/// Logistic model of relevance, `σ(intercept + Σ coefficient · signal)`, over
+/// the normalized built-in signals (`semantic`, `pagerank`, `pageviews`,
+/// `backlinks`, `title_match`), the graph `context`, edit `recency` and custom signals by registry name. Loaded from the JSON
+/// file RANKING_MODEL_FILE: `{"intercept": -4.0, "coefficients": {"semantic": 6.0, ...}}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinearRanker {
+    #[serde(default)]
+    pub intercept: f64,
+    pub coefficients: HashMap<String, f64>,
+}
+
+impl LinearRanker {
+    fn coefficient(&self, name: &str) -> f64 {
+        self.coefficients.get(name).copied().filter(|c| c.is_finite()).unwrap_or(0.0)
+    }
+
+    fn builtin_coefficients(&self) -> [f64; 5] {
+        ["semantic", "pagerank", "pageviews", "backlinks", "title_match"].map(|name| self.coefficient(name))
+    }
+}
+
+impl Ranker for LinearRanker {
+    fn score(&self, signals: &RankSignals, custom: &[(&str, f64, f64)]) -> f64 {
+        let builtin: f64 = self
+            .builtin_coefficients()
+            .into_iter()
+            .zip(normalized_builtins(signals))
+            .map(|(c, value)| c * value)
+            .sum();
+        let custom: f64 = custom
+            .iter()
+            .filter(|(_, value, _)| value.is_finite())
+            .map(|&(name, value, _)| self.coefficient(name) * value)
+            .sum();
+        logistic(self.intercept + builtin + custom)
+    }
+
+    fn upper_bound(&self, semantic: f64, max_pagerank: f64, max_pageviews: f64, custom: &[(&str, f64)]) -> Option<f64> {
+        let [c_sem, c_pr, c_pv, c_bl, c_title] = self.builtin_coefficients();
+        if c_sem <= 0.0 {
+            return None;
+        }
+        // Each term is largest at its maximum (positive coefficient) or at 0
+        let rest = [
+            (c_pr, normalize_pagerank(Some(max_pagerank))),
+            (c_pv, normalize_pageviews(Some(max_pageviews as i64))),
+            (c_bl, 1.0),
+            (c_title, 1.0),
+        ]
+        .into_iter()
+        .chain(custom.iter().map(|&(name, _)| (self.coefficient(name), 1.0)))
+        .map(|(c, max)| (c * max).max(0.0))
+        .sum::<f64>();
+        Some(logistic(self.intercept + c_sem * semantic.clamp(0.0, 1.0) + rest))
+    }
+}
+
+/// RANKING_MODEL_FILE, read once; None when unset or unreadable.
+fn linear_model() -> Option<&'static LinearRanker> {
+    static MODEL: OnceLock<Option<LinearRanker>> = OnceLock::new();
+    MODEL
+        .get_or_init(|| {
+            let path = get_config().ranking_model_file.as_deref()?;
+            let parsed = std::fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|raw| serde_json::from_str::<LinearRanker>(&raw).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(model) => {
+                    info!("✓ Linear ranking model loaded from {} ({} coefficients)", path, model.coefficients.len());
+                    Some(model)
+                }
+                Err(e) => {
+                    warn!("⚠ Ignoring ranking model {}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Semantic, pagerank, pageviews, backlinks and title match, each in [0, 1].
+fn normalized_builtins(signals: &RankSignals) -> [f64; 5] {
+    [
+        signals.semantic.clamp(0.0, 1.0),
+        normalize_pagerank(Some(signals.pagerank)).min(1.0),
+        normalize_pageviews(Some(signals.pageviews as i64)),
+        signals.backlinks,
+        signals.title_match,
+    ]
+    .map(|value| if value.is_finite() { value } else { 0.0 })
+}
+
+fn logistic(x: f64) -> f64 {
+    let p = 1.0 / (1.0 + (-x).exp());
+    if p.is_nan() { 0.0 } else { p }
+}
+
+/// Registry signals folded into one factor, as `apply_custom_signals` would.
+pub fn custom_factor(custom: &[(&str, f64, f64)]) -> Option<f64> {
+    (!custom.is_empty()).then(|| fold_custom(1.0, custom.iter().map(|&(_, value, weight)| (value, weight))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::pipeline::sort_by_score;
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    /// Signal values as they come out of corrupt rows and overflowing imports
+    fn any_signal() -> impl Strategy<Value = f64> {
+        prop_oneof![
+            0.0..1.0e3f64,
+            Just(0.0),
+            Just(f64::NAN),
+            Just(f64::INFINITY),
+            Just(f64::NEG_INFINITY),
+            any::<f64>(),
+        ]
+    }
+
+    fn result(id: i64, score_float: f64) -> SearchResult {
+        SearchResult {
+            id,
+            title: Arc::default(),
+            score: 0,
+            score_float,
+            corpus: None,
+            highlights: vec![],
+            thumbnail_url: None,
+            debug: None,
+            backfilled: false,
+            signals: RankSignals::default(),
+        }
+    }
+
+    #[test]
+    fn pagerank_is_scaled_and_missing_values_are_zero() {
+        assert_eq!(normalize_pagerank(Some(50.0)), 0.5);
+        assert_eq!(normalize_pagerank(Some(0.0)), 0.0);
+        assert_eq!(normalize_pagerank(Some(-3.0)), 0.0);
+        assert_eq!(normalize_pagerank(Some(f64::NAN)), 0.0);
+        assert_eq!(normalize_pagerank(None), 0.0);
+    }
+
+    #[test]
+    fn pageviews_are_log_scaled_between_100_and_10m() {
+        assert_eq!(normalize_pageviews(None), 0.0);
+        assert_eq!(normalize_pageviews(Some(0)), 0.0);
+        assert_eq!(normalize_pageviews(Some(-5)), 0.0);
+        assert_eq!(normalize_pageviews(Some(99)), 0.1);
+        assert_eq!(normalize_pageviews(Some(100)), 0.0);
+        assert!((normalize_pageviews(Some(100_000)) - 0.6).abs() < 1e-12);
+        assert_eq!(normalize_pageviews(Some(10_000_000)), 1.0);
+        assert_eq!(normalize_pageviews(Some(i64::MAX)), 1.0);
+    }
+
+    #[test]
+    fn backlinks_are_log_scaled_up_to_100k() {
+        assert_eq!(normalize_backlinks(None), 0.0);
+        assert_eq!(normalize_backlinks(Some(0)), 0.0);
+        assert_eq!(normalize_backlinks(Some(1)), 0.0);
+        assert!((normalize_backlinks(Some(1_000)) - 0.6).abs() < 1e-12);
+        assert_eq!(normalize_backlinks(Some(100_000)), 1.0);
+        assert_eq!(normalize_backlinks(Some(10_000_000)), 1.0);
+    }
+
+    #[test]
+    fn geometric_score_weights_each_signal() {
+        let config = get_config();
+        assert!((geometric_score(1.0, 1.0, 1.0, 1.0, 1.0) - 1.0).abs() < 1e-12);
+        let expected = 0.5f64.powf(config.weight_semantic)
+            * 0.25f64.powf(config.weight_pagerank)
+            * 0.8f64.powf(config.weight_pageviews)
+            * 0.1f64.powf(config.weight_backlinks)
+            * 0.6f64.powf(config.weight_title_match);
+        assert!((geometric_score(0.5, 0.25, 0.8, 0.1, 0.6) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn geometric_score_floors_missing_signals_at_epsilon() {
+        let config = get_config();
+        let floor = geometric_score(0.0, 0.0, 0.0, 0.0, 0.0);
+        let weights = config.weight_semantic
+            + config.weight_pagerank
+            + config.weight_pageviews
+            + config.weight_backlinks
+            + config.weight_title_match;
+        assert!(floor > 0.0);
+        assert!((floor / config.epsilon.powf(weights) - 1.0).abs() < 1e-9);
+        assert_eq!(geometric_score(f64::NAN, f64::INFINITY, -1.0, f64::NAN, 0.0), floor);
+    }
+
+    #[test]
+    fn upper_bound_of_an_exact_match() {
+        let score = calculate_multisignal_score(0.8, 40.0, 0.5, Some(100_000), "Quantum mechanics", "quantum mechanics");
+        assert!(score <= multisignal_upper_bound(0.8, 40.0, 0.5, &[]));
+        let best = geometric_score(0.8f32 as f64, 40.0, 0.5, 1.0, 1.0);
+        assert!((multisignal_upper_bound(0.8, 40.0, 0.5, &[]) / best - 1.0).abs() < 1e-12);
+        assert_eq!(multisignal_upper_bound(0.8, 40.0, 0.5, &[f64::NAN]), multisignal_upper_bound(0.8, 40.0, 0.5, &[]));
+    }
+
+    proptest! {
+        #[test]
+        fn normalizers_stay_in_range(pagerank in any_signal(), pageviews in any::<Option<i64>>(), backlinks in any::<Option<i64>>()) {
+            prop_assert!(normalize_pagerank(Some(pagerank)).is_finite());
+            prop_assert!(normalize_pagerank(Some(pagerank)) >= 0.0);
+            prop_assert!((0.0..=1.0).contains(&normalize_pageviews(pageviews)));
+            prop_assert!((0.0..=1.0).contains(&normalize_backlinks(backlinks)));
+        }
+
+        #[test]
+        fn title_score_in_unit_range(title in "\\PC{0,40}", query in "\\PC{0,40}") {
+            let score = calculate_title_match_score(&title, &query);
+            prop_assert!((0.0..=1.0).contains(&score), "title score {}", score);
+            if title.trim().is_empty() {
+                prop_assert_eq!(score, 0.0);
+            }
+        }
+
+        #[test]
+        fn spans_are_sorted_disjoint_and_inside_the_title(title in "\\PC{0,40}", query in "\\PC{0,20}") {
+            let title_len = title.encode_utf16().count();
+            let spans = title_match_spans(&title, &query);
+            for span in &spans {
+                prop_assert!(span[0] < span[1] && span[1] <= title_len, "bad span {:?}", span);
+            }
+            for pair in spans.windows(2) {
+                prop_assert!(pair[0][1] < pair[1][0], "overlapping spans {:?}", spans);
+            }
+        }
+
+        #[test]
+        fn exact_title_is_highlighted_whole(title in "[a-zA-Z]{1,12}( [a-zA-Z]{1,12}){0,3}") {
+            let spans = title_match_spans(&title, &title);
+            prop_assert_eq!(spans, vec![[0, title.encode_utf16().count()]]);
+        }
+
+        #[test]
+        fn multisignal_score_is_finite_and_non_negative(
+            similarity in any::<f32>(),
+            pagerank in any_signal(),
+            pageviews in any_signal(),
+            backlinks in any::<Option<i64>>(),
+            title in "\\PC{0,20}",
+            query in "\\PC{0,20}",
+        ) {
+            let score = calculate_multisignal_score(similarity, pagerank, pageviews, backlinks, &title, &query);
+            prop_assert!(score.is_finite() && score >= 0.0, "score {}", score);
+        }
+
+        #[test]
+        fn custom_signals_never_give_nan(score in 0.0..10.0f64, factors in prop::collection::vec((any_signal(), any_signal()), 0..6)) {
+            prop_assert!(apply_custom_signals(score, &factors).is_finite());
+        }
+
+        #[test]
+        fn upper_bound_holds(
+            similarity in 0.0..1.0f32,
+            pagerank in 0.0..100.0f64,
+            pageviews in 0.0..1.0f64,
+            backlinks in any::<Option<i64>>(),
+            title in "[a-z]{1,8}( [a-z]{1,8}){0,3}",
+            query in "[a-z]{1,8}( [a-z]{1,8}){0,3}",
+            max_extra in 0.0..50.0f64,
+            custom in prop::collection::vec((0.0..=1.0f64, -2.0..2.0f64), 0..4),
+        ) {
+            let score = apply_custom_signals(
+                calculate_multisignal_score(similarity, pagerank, pageviews, backlinks, &title, &query),
+                &custom,
+            );
+            let weights: Vec<f64> = custom.iter().map(|&(_, weight)| weight).collect();
+            let bound = multisignal_upper_bound(similarity, pagerank + max_extra, pageviews + max_extra, &weights);
+            prop_assert!(score <= bound * (1.0 + 1e-9), "score {} above bound {}", score, bound);
+        }
+
+        #[test]
+        fn sort_puts_the_best_first_and_nan_last(scores in prop::collection::vec(any_signal(), 0..40)) {
+            let mut results: Vec<SearchResult> = scores.iter().enumerate().map(|(i, &s)| result(i as i64, s)).collect();
+            sort_by_score(&mut results);
+            let ranked: Vec<f64> = results.iter().map(|r| r.score_float).collect();
+            // NaN sorts as -inf, so the two may interleave at the bottom
+            let bottom = |s: f64| s.is_nan() || s == f64::NEG_INFINITY;
+            let first_nan = ranked.iter().position(|s| s.is_nan()).unwrap_or(ranked.len());
+            prop_assert!(ranked[first_nan..].iter().all(|&s| bottom(s)), "NaN ranked above a score: {:?}", ranked);
+            prop_assert!(ranked[..first_nan].windows(2).all(|w| w[0] >= w[1]), "not descending: {:?}", ranked);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Edit recency as a ranking signal: with a `recency` weight, a search for a
+//! current event prefers the articles edited since it happened over the ones
+//! nobody has touched in years.
+//!
+//! `articles.last_modified` holds each article's last edit (unix seconds,
+//! `wikiexplorer ingest last-modified`). An article's recency is 1 when just
+//! edited and halves every RECENCY_HALF_LIFE_DAYS; articles without a
+//! timestamp count as 0. Rankers see it as one more signal named
+//! [`RECENCY_SIGNAL`], weighted by the request's `recency` or WEIGHT_RECENCY.
+
+use crate::config::get_config;
+use crate::search::corpus::Corpus;
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Name of the signal, e.g. for its coefficient in RANKING_MODEL_FILE
+pub const RECENCY_SIGNAL: &str = "recency";
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Weight of the signal for a request overriding WEIGHT_RECENCY with
+/// `requested`; None when it is off or the corpus has no edit times.
+pub fn recency_weight(corpus: &Corpus, requested: Option<f64>) -> Option<f64> {
+    let weight = requested.unwrap_or(get_config().weight_recency);
+    (weight.is_finite() && weight > 0.0 && corpus.signals.has_last_modified).then_some(weight)
+}
+
+/// Recency in [0, 1] of an article last edited at `last_modified` (unix
+/// seconds), as of `now`.
+pub fn recency(last_modified: Option<i64>, now: i64) -> f64 {
+    let Some(edited) = last_modified else {
+        return 0.0;
+    };
+    let half_life = get_config().recency_half_life_days;
+    if !(half_life.is_finite() && half_life > 0.0) {
+        return 0.0;
+    }
+    // Clocks and dumps disagree: an edit "in the future" is just recent
+    let age_days = (now - edited).max(0) as f64 / SECONDS_PER_DAY;
+    0.5f64.powf(age_days / half_life)
+}
+
+/// `last_modified` of each of `ids` that has one.
+pub async fn fetch_last_modified(db: &SqlitePool, ids: &[i64]) -> Result<HashMap<i64, i64>, AppError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let sql = format!(
+        "SELECT article_id, last_modified FROM articles WHERE article_id IN ({}) AND last_modified IS NOT NULL",
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query_as::<_, (i64, i64)>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    Ok(with_deadline("hydrate last_modified", query.fetch_all(db)).await?.into_iter().collect())
+}
@@ -0,0 +1,169 @@
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::utils::time::unix_now;
+
+/// TTL cache for whole search responses, keyed by a hash of everything that
+/// determines the response. Optionally mirrored into SQLite so entries survive
+/// restarts and are shared across workers pointing at the same DB.
+pub struct ResponseCache<T: Clone + Serialize + DeserializeOwned> {
+    entries: Mutex<HashMap<String, (Instant, T)>>,
+    ttl: Duration,
+    capacity: usize,
+    persist: bool,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> ResponseCache<T> {
+    pub fn new(ttl: Duration, capacity: usize, persist: bool) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            capacity: capacity.max(1),
+            persist,
+        }
+    }
+
+    pub async fn get(&self, pool: &SqlitePool, key: &str) -> Option<T> {
+        {
+            let mut entries = self.entries.lock();
+            match entries.get(key) {
+                Some((expires, value)) if *expires > Instant::now() => return Some(value.clone()),
+                Some(_) => {
+                    entries.remove(key);
+                }
+                None => {}
+            }
+        }
+
+        if !self.persist {
+            return None;
+        }
+
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT body FROM response_cache WHERE key = ? AND expires_at > ?",
+        )
+        .bind(key)
+        .bind(unix_now() as i64)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten();
+
+        let value: T = serde_json::from_str(&row?.0).ok()?;
+        self.insert_memory(key.to_string(), value.clone());
+        Some(value)
+    }
+
+    pub async fn put(&self, pool: &SqlitePool, key: String, value: T) {
+        if self.persist {
+            if let Ok(body) = serde_json::to_string(&value) {
+                let result = sqlx::query(
+                    "INSERT OR REPLACE INTO response_cache (key, body, expires_at) VALUES (?, ?, ?)",
+                )
+                .bind(&key)
+                .bind(body)
+                .bind((unix_now() + self.ttl.as_secs()) as i64)
+                .execute(pool)
+                .await;
+
+                if let Err(e) = result {
+                    warn!("Response cache write failed: {:?}", e);
+                }
+            }
+        }
+        self.insert_memory(key, value);
+    }
+
+    fn insert_memory(&self, key: String, value: T) {
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.capacity {
+            let now = Instant::now();
+            entries.retain(|_, (expires, _)| *expires > now);
+            if entries.len() >= self.capacity {
+                // Still full of live entries: drop the one closest to expiry
+                if let Some(oldest) = entries.iter().min_by_key(|(_, (e, _))| *e).map(|(k, _)| k.clone()) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (Instant::now() + self.ttl, value));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+
+    /// Drops all entries, including the persisted ones.
+    pub async fn clear(&self, pool: &SqlitePool) {
+        self.entries.lock().clear();
+        if self.persist {
+            let _ = sqlx::query("DELETE FROM response_cache").execute(pool).await;
+        }
+    }
+}
+
+pub async fn ensure_cache_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS response_cache (
+            key TEXT PRIMARY KEY,
+            body TEXT NOT NULL,
+            expires_at INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Hex key from any hashable request description. Stable across builds and
+/// platforms, since keys are persisted.
+pub fn cache_key<H: Hash>(parts: &H) -> String {
+    let mut hasher = StableHasher(Sha256::new());
+    parts.hash(&mut hasher);
+    hasher.0.finalize().iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 with integers fed little-endian: std's `DefaultHasher` may change
+/// between releases, and the default `write_*` use native byte order.
+struct StableHasher(Sha256);
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().expect("SHA-256 has 32 bytes"))
+    }
+}
@@ -0,0 +1,67 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Full ranked result pools kept server-side for paging. A search returns a
+/// `page_token`; later pages are sliced from the stored pool instead of
+/// re-running FAISS and ranking.
+pub struct ResultPoolCache<T> {
+    pools: Mutex<HashMap<String, (Instant, Arc<T>)>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl<T> ResultPoolCache<T> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            pools: Mutex::new(HashMap::new()),
+            ttl,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Stores a pool and returns its token.
+    pub fn insert(&self, pool: Arc<T>) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        let now = Instant::now();
+
+        let mut pools = self.pools.lock();
+        if pools.len() >= self.capacity {
+            pools.retain(|_, (expires, _)| *expires > now);
+            if pools.len() >= self.capacity {
+                if let Some(oldest) = pools.iter().min_by_key(|(_, (e, _))| *e).map(|(k, _)| k.clone()) {
+                    pools.remove(&oldest);
+                }
+            }
+        }
+        pools.insert(token.clone(), (now + self.ttl, pool));
+        token
+    }
+
+    /// The pool for `token`, or `None` if unknown or expired.
+    pub fn get(&self, token: &str) -> Option<Arc<T>> {
+        let mut pools = self.pools.lock();
+        match pools.get(token) {
+            Some((expires, pool)) if *expires > Instant::now() => Some(Arc::clone(pool)),
+            Some(_) => {
+                pools.remove(token);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.lock().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.pools.lock().clear();
+    }
+}
@@ -0,0 +1,129 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Experimental cache that reuses results for queries whose embeddings are
+/// nearly identical to a previously seen query ("semantic cache").
+///
+/// Entries are matched by cosine distance to the cached query embedding and by
+/// a `variant` string holding every request option that changes ranking. The
+/// hit counters are exposed in `/api/health` so the distance threshold can be
+/// judged before the cache is enabled in production.
+pub struct SemanticCache<T: Clone> {
+    entries: Mutex<VecDeque<CacheEntry<T>>>,
+    capacity: usize,
+    max_distance: f32,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    exact_hits: AtomicU64,
+    // Sum of hit distances scaled by 1e6, for the average
+    hit_distance_micros: AtomicU64,
+}
+
+struct CacheEntry<T> {
+    embedding: Vec<f32>,
+    variant: String,
+    value: T,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SemanticCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub max_distance: f32,
+    pub hits: u64,
+    pub misses: u64,
+    /// Hits where the embedding matched exactly (same query text)
+    pub exact_hits: u64,
+    pub avg_hit_distance: f64,
+}
+
+impl<T: Clone> SemanticCache<T> {
+    pub fn new(capacity: usize, max_distance: f32) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity: capacity.max(1),
+            max_distance,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            exact_hits: AtomicU64::new(0),
+            hit_distance_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached value of the closest entry within `max_distance`.
+    pub fn lookup(&self, embedding: &[f32], variant: &str) -> Option<T> {
+        let entries = self.entries.lock();
+
+        let best = entries
+            .iter()
+            .filter(|e| e.variant == variant)
+            .map(|e| (cosine_distance(embedding, &e.embedding), e))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match best {
+            Some((distance, entry)) if distance <= self.max_distance => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                if distance <= f32::EPSILON {
+                    self.exact_hits.fetch_add(1, Ordering::Relaxed);
+                }
+                self.hit_distance_micros
+                    .fetch_add((distance.max(0.0) * 1e6) as u64, Ordering::Relaxed);
+                Some(entry.value.clone())
+            }
+            _ => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Inserts a value, evicting the oldest entry once at capacity.
+    pub fn insert(&self, embedding: Vec<f32>, variant: String, value: T) {
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(CacheEntry { embedding, variant, value });
+    }
+
+    /// Drops every entry (e.g. after an index reload); counters are kept.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    pub fn stats(&self) -> SemanticCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let avg_hit_distance = if hits == 0 {
+            0.0
+        } else {
+            self.hit_distance_micros.load(Ordering::Relaxed) as f64 / 1e6 / hits as f64
+        };
+
+        SemanticCacheStats {
+            entries: self.entries.lock().len(),
+            capacity: self.capacity,
+            max_distance: self.max_distance,
+            hits,
+            misses: self.misses.load(Ordering::Relaxed),
+            exact_hits: self.exact_hits.load(Ordering::Relaxed),
+            avg_hit_distance,
+        }
+    }
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    let denom = (norm_a.sqrt() * norm_b.sqrt()).max(f32::EPSILON);
+    1.0 - dot / denom
+}
@@ -0,0 +1,196 @@
+//! Typo tolerance for `did_you_mean`: "quantun mechanics" -> "quantum
+//! mechanics". A SymSpell index over the words of article titles: every word
+//! is stored with each of its one-character deletions, so a misspelled word
+//! finds its corrections by looking up its own deletions, then checking the
+//! edit distance. Words in fewer than SPELL_MIN_WORD_COUNT titles are left
+//! out, which keeps rare names from being suggested and the index small.
+//!
+//! Deletions are kept as a sorted list of 32-bit hashes rather than strings;
+//! a collision only costs one extra distance check.
+
+use futures::TryStreamExt;
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::utils::errors::AppError;
+
+/// Largest edit distance of a correction
+const MAX_DISTANCE: usize = 2;
+/// Shorter query words are left as typed: too many words are one edit apart
+const MIN_CORRECTED_LEN: usize = 4;
+
+pub struct SpellIndex {
+    words: Vec<Box<str>>,
+    /// Titles each word appears in
+    counts: Vec<u32>,
+    /// (hash of the word or one of its deletions, word index), sorted
+    deletes: Vec<(u32, u32)>,
+}
+
+impl SpellIndex {
+    /// Index of the words of every title in `pool` appearing in at least `min_count` titles.
+    pub async fn build(pool: &SqlitePool, min_count: u32) -> Result<Self, AppError> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let mut titles = sqlx::query_scalar::<_, String>("SELECT title FROM articles").fetch(pool);
+        while let Some(title) = titles.try_next().await? {
+            let mut words = tokens(&title);
+            words.sort_unstable();
+            words.dedup();
+            for word in words {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+        Ok(Self::from_counts(counts, min_count))
+    }
+
+    fn from_counts(counts: HashMap<String, u32>, min_count: u32) -> Self {
+        let mut words: Vec<(String, u32)> = counts.into_iter().filter(|(_, n)| *n >= min_count.max(1)).collect();
+        words.sort_unstable();
+
+        let mut deletes = Vec::new();
+        for (i, (word, _)) in words.iter().enumerate() {
+            deletes.push((hash(word), i as u32));
+            for deletion in deletions(word) {
+                deletes.push((hash(&deletion), i as u32));
+            }
+        }
+        deletes.sort_unstable();
+        deletes.dedup();
+
+        let (words, counts) = words.into_iter().map(|(w, n)| (w.into_boxed_str(), n)).unzip();
+        Self { words, counts, deletes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// `query` with its unknown words replaced by the closest, most common
+    /// known ones; None when no word changes. Everything else, including the
+    /// case of the corrected words and the punctuation between words, stays
+    /// as typed.
+    pub fn correct(&self, query: &str) -> Option<String> {
+        let mut corrected = String::with_capacity(query.len());
+        let mut copied = 0;
+        for (start, word) in word_spans(query) {
+            let Some(correction) = self.best_correction(&word.to_lowercase()) else {
+                continue;
+            };
+            corrected.push_str(&query[copied..start]);
+            corrected.push_str(&match_case(word, correction));
+            copied = start + word.len();
+        }
+        if copied == 0 {
+            return None;
+        }
+        corrected.push_str(&query[copied..]);
+        Some(corrected)
+    }
+
+    /// None for known words, words too short to correct and words with no
+    /// correction within MAX_DISTANCE.
+    fn best_correction(&self, word: &str) -> Option<&str> {
+        if word.chars().count() < MIN_CORRECTED_LEN || word.chars().any(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        // (distance, Reverse(titles), word index): closest first, then the most common
+        let mut best: Option<(usize, Reverse<u32>, u32)> = None;
+        for key in std::iter::once(word.to_string()).chain(deletions(word)) {
+            for i in self.candidates(hash(&key)) {
+                let candidate = &*self.words[i as usize];
+                let distance = match edit_distance(word, candidate, MAX_DISTANCE) {
+                    Some(0) => return None,
+                    Some(distance) => distance,
+                    None => continue,
+                };
+                let rank = (distance, Reverse(self.counts[i as usize]), i);
+                if best.is_none_or(|best| rank < best) {
+                    best = Some(rank);
+                }
+            }
+        }
+        best.map(|(_, _, i)| &*self.words[i as usize])
+    }
+
+    fn candidates(&self, hash: u32) -> impl Iterator<Item = u32> + '_ {
+        let start = self.deletes.partition_point(|(h, _)| *h < hash);
+        self.deletes[start..].iter().take_while(move |(h, _)| *h == hash).map(|(_, i)| *i)
+    }
+}
+
+/// Lowercase words of a title or query (`Alan_Turing` -> `alan`, `turing`).
+fn tokens(text: &str) -> Vec<String> {
+    word_spans(text).map(|(_, word)| word.to_lowercase()).collect()
+}
+
+/// Words of `text` as typed, with their byte offsets.
+fn word_spans(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(move |w| (w.as_ptr() as usize - text.as_ptr() as usize, w))
+}
+
+/// `correction` (lowercase) in the case `typed` was written in: all caps,
+/// capitalized or lowercase.
+fn match_case(typed: &str, correction: &str) -> String {
+    let mut letters = typed.chars().filter(|c| c.is_alphabetic());
+    let first_upper = letters.next().is_some_and(char::is_uppercase);
+    if first_upper && typed.chars().filter(|c| c.is_alphabetic()).count() > 1 && letters.all(char::is_uppercase) {
+        return correction.to_uppercase();
+    }
+    if first_upper {
+        let mut chars = correction.chars();
+        return chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+    }
+    correction.to_string()
+}
+
+/// `word` without one of its characters, for each character.
+fn deletions(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < 2 {
+        return vec![];
+    }
+    (0..chars.len())
+        .map(|skip| chars.iter().enumerate().filter(|(i, _)| *i != skip).map(|(_, c)| c).collect())
+        .collect()
+}
+
+fn hash(key: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+/// Damerau-Levenshtein distance (adjacent transpositions count once), None
+/// above `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let width = b.len() + 1;
+    let mut rows = vec![vec![0usize; width]; a.len() + 1];
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        rows[i][0] = i;
+        for j in 1..width {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = d;
+        }
+    }
+    let distance = rows[a.len()][b.len()];
+    (distance <= max).then_some(distance)
+}
@@ -0,0 +1,64 @@
+//! Where the time of one search went, for `debug: true` requests. Stages run
+//! in the server and in the ranking pipeline (once per corpus in federated
+//! search), so they are recorded into a shared [`StageTimings`] and summed.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Query (and `exclude`) embeddings
+    Encode,
+    /// Candidate search: FAISS, plus FTS5 in lexical and hybrid modes
+    Faiss,
+    /// Metadata hydration of the candidates
+    Sqlite,
+    /// Scoring, rank fusion and diversification
+    Rank,
+    /// Edges between the returned results
+    CrossEdges,
+}
+
+/// Microseconds spent in each stage so far.
+#[derive(Debug, Default)]
+pub struct StageTimings {
+    micros: [AtomicU64; 5],
+}
+
+impl StageTimings {
+    pub fn record(&self, stage: Stage, elapsed: Duration) {
+        self.micros[stage as usize].fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Records the time since `started`.
+    pub fn since(&self, stage: Stage, started: Instant) {
+        self.record(stage, started.elapsed());
+    }
+
+    /// Milliseconds per stage, with `total` the whole request.
+    pub fn snapshot(&self, total: Duration) -> Timings {
+        let ms = |stage: Stage| self.micros[stage as usize].load(Ordering::Relaxed) as f64 / 1000.0;
+        Timings {
+            encode_ms: ms(Stage::Encode),
+            faiss_ms: ms(Stage::Faiss),
+            sqlite_ms: ms(Stage::Sqlite),
+            rank_ms: ms(Stage::Rank),
+            cross_edges_ms: ms(Stage::CrossEdges),
+            total_ms: total.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// `timings` of a debug search response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Timings {
+    pub encode_ms: f64,
+    pub faiss_ms: f64,
+    pub sqlite_ms: f64,
+    pub rank_ms: f64,
+    pub cross_edges_ms: f64,
+    /// The whole request; stages of federated corpora overlap, so theirs can add up to more
+    pub total_ms: f64,
+}
@@ -0,0 +1,29 @@
+//! Title lookups shared by endpoints that take titles instead of IDs.
+
+use sqlx::SqlitePool;
+
+use crate::utils::db_deadline::with_deadline;
+use crate::utils::errors::AppError;
+use crate::utils::sql::placeholders;
+
+/// Articles whose title matches any of `titles` case-insensitively (titles
+/// with underscores, as stored), lowest article ID first, as (article ID,
+/// title). Callers prefer an exact match and fall back to the first row.
+pub async fn articles_by_title(
+    db: &SqlitePool,
+    titles: &[&str],
+    statement: &'static str,
+) -> Result<Vec<(i64, String)>, AppError> {
+    if titles.is_empty() {
+        return Ok(vec![]);
+    }
+    let sql = format!(
+        "SELECT article_id, title FROM articles WHERE title COLLATE NOCASE IN ({}) ORDER BY article_id",
+        placeholders(titles.len())
+    );
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for title in titles {
+        query = query.bind(*title);
+    }
+    with_deadline(statement, query.fetch_all(db)).await
+}
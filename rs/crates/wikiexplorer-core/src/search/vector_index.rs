@@ -0,0 +1,201 @@
+//! The ANN index behind [`IndexHandle`](crate::search::engine::IndexHandle),
+//! selected at startup by INDEX_BACKEND:
+//! - `auto` (default): by file extension, `*.hnsw` is HNSW, `*.flat` a saved
+//!   flat matrix and anything else FAISS
+//! - `faiss`: a FAISS index (`faiss` feature)
+//! - `hnsw`: the pure-Rust HNSW backend (`hnsw` feature, see
+//!   [`crate::search::hnsw`]); a FAISS file is converted in memory at load
+//! - `flat`: exhaustive search over an in-memory matrix ([`crate::search::flat`]),
+//!   copied from whatever index INDEX_PATH holds
+//!
+//! Positions are article IDs (modulo the label map). Scores are in the
+//! index's [`Metric`]: inner product for indexes built now, squared L2 for
+//! older ones and HNSW; `IndexHandle` turns them into similarities.
+
+use std::str::FromStr;
+use tracing::info;
+
+use crate::search::flat::FlatIndex;
+use crate::search::similarity::Metric;
+use crate::utils::errors::AppError;
+
+/// Dimension of the placeholder index served when none can be loaded (MiniLM)
+pub const FALLBACK_DIM: u32 = 384;
+
+/// Scores and positions of one query's nearest vectors, nearest first.
+pub type Hits = (Vec<f32>, Vec<i64>);
+
+pub trait VectorIndex: Send {
+    fn dim(&self) -> u32;
+
+    fn ntotal(&self) -> u64;
+
+    /// What `search` scores are
+    fn metric(&self) -> Metric {
+        Metric::L2
+    }
+
+    /// Up to `k` (score, position) pairs, nearest first. FAISS
+    /// pads with -1 positions when the index holds fewer than `k` vectors.
+    fn search(&mut self, query: &[f32], k: usize) -> Result<Hits, AppError>;
+
+    /// `search` for several row-major queries; one result per query.
+    fn search_batch(&mut self, queries: &[f32], k: usize) -> Result<Vec<Hits>, AppError> {
+        queries.chunks(self.dim().max(1) as usize).map(|query| self.search(query, k)).collect()
+    }
+
+    /// The stored vector at `position`; fails for index types that can't return it.
+    fn reconstruct(&self, position: u64) -> Result<Vec<f32>, AppError>;
+
+    /// Appends row-major vectors at the next positions.
+    fn add(&mut self, vectors: &[f32]) -> Result<(), AppError>;
+
+    /// Another replica over this one's memory, for index types whose searches
+    /// only read shared data; None when a replica needs its own copy.
+    fn replica(&self) -> Option<Box<dyn VectorIndex>> {
+        None
+    }
+
+    /// Sets a runtime search parameter (`nprobe`, `efSearch`, ...). Fails if the
+    /// index type has no such parameter.
+    fn set_search_parameter(&mut self, name: &str, value: f64) -> Result<(), AppError> {
+        Err(AppError::Faiss(format!("Index has no parameter {}={}", name, value)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexBackend {
+    Auto,
+    Faiss,
+    Hnsw,
+    Flat,
+}
+
+impl FromStr for IndexBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "faiss" => Ok(Self::Faiss),
+            "hnsw" => Ok(Self::Hnsw),
+            "flat" | "brute-force" | "bruteforce" => Ok(Self::Flat),
+            other => Err(format!("unknown index backend '{}'", other)),
+        }
+    }
+}
+
+/// Reads the index at `path` into `backend`.
+pub fn load_index(path: &str, backend: IndexBackend) -> Result<Box<dyn VectorIndex>, AppError> {
+    match backend {
+        IndexBackend::Auto => read_index_file(path),
+        IndexBackend::Faiss => read_faiss(path),
+        IndexBackend::Flat => {
+            let source = read_index_file(path)?;
+            let index = FlatIndex::copy_of(source.as_ref())
+                .map_err(|e| AppError::Config(format!("INDEX_BACKEND=flat needs a reconstructable index: {}", e)))?;
+            info!("✓ Copied {} vectors into a flat index", index.ntotal());
+            Ok(Box::new(index))
+        }
+        #[cfg(feature = "hnsw")]
+        IndexBackend::Hnsw => {
+            use crate::search::hnsw::{is_hnsw_path, HnswIndex};
+
+            if is_hnsw_path(path) {
+                return Ok(Box::new(HnswIndex::load(path)?));
+            }
+            let source = FlatIndex::copy_of(read_index_file(path)?.as_ref())?;
+            info!("Building an HNSW graph over {} vectors (save one with `index convert` to skip this)...", source.ntotal());
+            let dim = source.dim();
+            Ok(Box::new(HnswIndex::build(source.into_vectors(), dim, &Default::default())))
+        }
+        #[cfg(not(feature = "hnsw"))]
+        IndexBackend::Hnsw => Err(AppError::Config("Built without the `hnsw` feature".to_string())),
+    }
+}
+
+/// The backend the file's extension names
+fn read_index_file(path: &str) -> Result<Box<dyn VectorIndex>, AppError> {
+    if crate::search::flat::is_flat_path(path) {
+        return Ok(Box::new(FlatIndex::load(path)?));
+    }
+    #[cfg(feature = "hnsw")]
+    if crate::search::hnsw::is_hnsw_path(path) {
+        return Ok(Box::new(crate::search::hnsw::HnswIndex::load(path)?));
+    }
+    read_faiss(path)
+}
+
+fn read_faiss(path: &str) -> Result<Box<dyn VectorIndex>, AppError> {
+    #[cfg(feature = "faiss")]
+    {
+        let index = faiss::read_index(path)
+            .map_err(|e| AppError::Faiss(format!("Could not load index at {}: {:?}", path, e)))?;
+        Ok(Box::new(FaissIndex(index)))
+    }
+    #[cfg(not(feature = "faiss"))]
+    Err(AppError::Config(format!("Built without FAISS, {} must be a .hnsw index", path)))
+}
+
+/// Empty index served when the configured one is missing at startup
+pub fn empty_index(dim: u32) -> Box<dyn VectorIndex> {
+    Box::new(FlatIndex::new(dim))
+}
+
+#[cfg(feature = "faiss")]
+pub struct FaissIndex(pub Box<dyn faiss::Index>);
+
+#[cfg(feature = "faiss")]
+impl From<faiss::MetricType> for Metric {
+    fn from(metric: faiss::MetricType) -> Self {
+        match metric {
+            faiss::MetricType::InnerProduct => Metric::InnerProduct,
+            _ => Metric::L2,
+        }
+    }
+}
+
+#[cfg(feature = "faiss")]
+impl VectorIndex for FaissIndex {
+    fn dim(&self) -> u32 {
+        self.0.d()
+    }
+
+    fn ntotal(&self) -> u64 {
+        self.0.ntotal()
+    }
+
+    fn metric(&self) -> Metric {
+        self.0.metric_type().into()
+    }
+
+    fn search(&mut self, query: &[f32], k: usize) -> Result<Hits, AppError> {
+        // faiss::Index::search returns (distances, labels)
+        // labels are i64 (indices), distances are f32
+        let result = self.0.search(query, k).map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+        Ok((result.distances, result.labels.into_iter().map(|l| l.get_u64() as i64).collect()))
+    }
+
+    /// One FAISS call for the whole batch (BLAS / OpenMP across queries)
+    fn search_batch(&mut self, queries: &[f32], k: usize) -> Result<Vec<Hits>, AppError> {
+        let result = self.0.search(queries, k).map_err(|e| AppError::Faiss(format!("{:?}", e)))?;
+        Ok(result
+            .distances
+            .chunks(k.max(1))
+            .zip(result.labels.chunks(k.max(1)))
+            .map(|(d, l)| (d.to_vec(), l.iter().map(|l| l.get_u64() as i64).collect()))
+            .collect())
+    }
+
+    fn reconstruct(&self, position: u64) -> Result<Vec<f32>, AppError> {
+        self.0.reconstruct(position).map_err(|e| AppError::Faiss(format!("{:?}", e)))
+    }
+
+    fn add(&mut self, vectors: &[f32]) -> Result<(), AppError> {
+        self.0.add(vectors).map_err(|e| AppError::Faiss(format!("Adding vectors failed: {:?}", e)))
+    }
+
+    fn set_search_parameter(&mut self, name: &str, value: f64) -> Result<(), AppError> {
+        crate::index::params::set_search_parameter(self.0.as_mut(), name, value)
+    }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+use crate::export::{ExportEdge, ExportGraph, ExportNode};
+use crate::utils::errors::AppError;
+
+pub mod snapshots;
+pub mod thumbnail;
+
+/// A saved exploration graph, shared by its UUID.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Session {
+    pub id: String,
+    pub name: String,
+    pub corpus: String,
+    pub graph: SessionGraph,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SessionGraph {
+    pub nodes: Vec<SessionNode>,
+    #[serde(default)]
+    pub edges: Vec<SessionEdge>,
+    /// Free-form layout hints for the frontend (zoom, camera, algorithm, ...)
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub layout: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SessionNode {
+    pub id: i64,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SessionEdge {
+    pub source: i64,
+    pub target: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// "expansion" or "semantic", as in research graphs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+impl SessionGraph {
+    pub fn node_ids(&self) -> Vec<i64> {
+        self.nodes.iter().map(|n| n.id).collect()
+    }
+}
+
+impl Session {
+    pub fn to_export_graph(&self) -> ExportGraph {
+        ExportGraph {
+            nodes: self
+                .graph
+                .nodes
+                .iter()
+                .map(|n| ExportNode { id: n.id, title: n.title.clone(), depth: n.depth.map(|d| d as usize), score: None })
+                .collect(),
+            edges: self
+                .graph
+                .edges
+                .iter()
+                .map(|e| ExportEdge { source: e.source, target: e.target, score: e.score.unwrap_or(0.0), kind: e.kind.clone() })
+                .collect(),
+        }
+    }
+}
+
+pub async fn ensure_sessions_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            corpus TEXT NOT NULL,
+            graph TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            thumbnail TEXT,
+            id_space TEXT NOT NULL DEFAULT 'legacy',
+            edit_token_hash TEXT
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    // Tables created before thumbnails were stored
+    let has_thumbnail: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('sessions') WHERE name = 'thumbnail'")
+            .fetch_optional(pool)
+            .await?;
+    if has_thumbnail.is_none() {
+        sqlx::query("ALTER TABLE sessions ADD COLUMN thumbnail TEXT").execute(pool).await?;
+    }
+    // Tables created before node IDs were versioned (see `StableIds::id_space`)
+    let has_id_space: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('sessions') WHERE name = 'id_space'")
+            .fetch_optional(pool)
+            .await?;
+    if has_id_space.is_none() {
+        sqlx::query("ALTER TABLE sessions ADD COLUMN id_space TEXT NOT NULL DEFAULT 'legacy'")
+            .execute(pool)
+            .await?;
+    }
+    // Tables created before edit tokens; their sessions are editable by the admin only
+    let has_edit_token: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('sessions') WHERE name = 'edit_token_hash'")
+            .fetch_optional(pool)
+            .await?;
+    if has_edit_token.is_none() {
+        sqlx::query("ALTER TABLE sessions ADD COLUMN edit_token_hash TEXT").execute(pool).await?;
+    }
+    Ok(())
+}
+
+/// Node IDs of `graph` are public IDs in `id_space`. Only the digest of
+/// `edit_token` is stored.
+pub async fn insert_session(
+    pool: &SqlitePool,
+    id: &str,
+    name: &str,
+    corpus: &str,
+    graph: &SessionGraph,
+    id_space: &str,
+    edit_token: &str,
+) -> Result<(), AppError> {
+    sqlx::query("INSERT INTO sessions (id, name, corpus, graph, id_space, edit_token_hash) VALUES (?, ?, ?, ?, ?, ?)")
+        .bind(id)
+        .bind(name)
+        .bind(corpus)
+        .bind(encode_graph(graph)?)
+        .bind(id_space)
+        .bind(hash_edit_token(edit_token))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Digest of the token that allows changing or deleting a session, as stored
+pub fn hash_edit_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The stored edit token digest of session `id`; None for sessions saved
+/// before edit tokens.
+pub async fn edit_token_hash(pool: &SqlitePool, id: &str) -> Result<Option<String>, AppError> {
+    let row: Option<(Option<String>,)> = sqlx::query_as("SELECT edit_token_hash FROM sessions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    let (hash,) = row.ok_or_else(|| AppError::NotFound(format!("Unknown session '{}'", id)))?;
+    Ok(hash)
+}
+
+/// Replaces the name and/or graph, a graph together with the `id_space` of its
+/// node IDs. Returns false if the session doesn't exist.
+pub async fn update_session(
+    pool: &SqlitePool,
+    id: &str,
+    name: Option<&str>,
+    graph: Option<(&SessionGraph, &str)>,
+) -> Result<bool, AppError> {
+    let (graph, id_space) = match graph {
+        Some((graph, id_space)) => (Some(encode_graph(graph)?), Some(id_space)),
+        None => (None, None),
+    };
+    // A new graph invalidates the thumbnail
+    let result = sqlx::query(
+        "UPDATE sessions SET name = COALESCE(?1, name), graph = COALESCE(?2, graph),
+         id_space = COALESCE(?3, id_space),
+         thumbnail = CASE WHEN ?2 IS NULL THEN thumbnail ELSE NULL END,
+         updated_at = strftime('%s', 'now') WHERE id = ?4",
+    )
+    .bind(name)
+    .bind(graph)
+    .bind(id_space)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_session(pool: &SqlitePool, id: &str) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM sessions WHERE id = ?").bind(id).execute(pool).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn fetch_session(pool: &SqlitePool, id: &str) -> Result<Session, AppError> {
+    let row: Option<(String, String, String, String, i64, i64)> = sqlx::query_as(
+        "SELECT id, name, corpus, graph, created_at, updated_at FROM sessions WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    let (id, name, corpus, graph, created_at, updated_at) =
+        row.ok_or_else(|| AppError::NotFound(format!("Unknown session '{}'", id)))?;
+    let graph = serde_json::from_str(&graph)
+        .map_err(|e| anyhow::anyhow!("Session {} has a corrupt graph: {}", id, e))?;
+
+    Ok(Session { id, name, corpus, graph, created_at, updated_at })
+}
+
+fn encode_graph(graph: &SessionGraph) -> Result<String, AppError> {
+    Ok(serde_json::to_string(graph).map_err(|e| anyhow::anyhow!("Could not encode session graph: {}", e))?)
+}
@@ -0,0 +1,218 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::time::UNIX_EPOCH;
+
+use crate::index::manifest::IndexManifest;
+use crate::search::engine::IndexHandle;
+use crate::search::embedder::MODEL_VERSION;
+use crate::utils::cancel::CancelToken;
+use crate::utils::errors::AppError;
+
+use super::SessionGraph;
+
+/// Pairs scoring below this are left out of snapshots (and count as "absent")
+pub const SNAPSHOT_MIN_SCORE: f32 = 0.3;
+/// All-pairs scoring is quadratic; larger graphs only snapshot their saved edges
+const MAX_ALL_PAIRS_NODES: usize = 500;
+
+/// Identifies the index + model a snapshot was scored with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusVersion {
+    pub version: String,
+    pub model_version: String,
+}
+
+impl CorpusVersion {
+    /// From the index manifest when there is one, else the index file's mtime.
+    pub fn of(index: &IndexHandle) -> Self {
+        let ntotal = index.pool.acquire().ntotal();
+        match IndexManifest::load(&index.path) {
+            Ok(manifest) => Self {
+                version: format!("{}/{}/{}", manifest.model, manifest.created_at, ntotal),
+                model_version: manifest.model,
+            },
+            Err(_) => {
+                let modified = std::fs::metadata(&index.path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                Self {
+                    version: format!("{}/{}/{}", MODEL_VERSION, modified, ntotal),
+                    model_version: MODEL_VERSION.to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SnapshotInfo {
+    pub id: i64,
+    pub corpus_version: String,
+    pub model_version: String,
+    pub edge_count: i64,
+    pub created_at: i64,
+}
+
+pub async fn ensure_snapshots_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS session_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            corpus_version TEXT NOT NULL,
+            model_version TEXT NOT NULL,
+            edges TEXT NOT NULL,
+            edge_count INTEGER NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            UNIQUE (session_id, corpus_version)
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn has_snapshot(pool: &SqlitePool, session_id: &str, version: &CorpusVersion) -> Result<bool, sqlx::Error> {
+    let row: Option<(i64,)> =
+        sqlx::query_as("SELECT id FROM session_snapshots WHERE session_id = ? AND corpus_version = ?")
+            .bind(session_id)
+            .bind(&version.version)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.is_some())
+}
+
+/// Scores the graph against `index`: every node pair above `SNAPSHOT_MIN_SCORE`
+/// for graphs up to `MAX_ALL_PAIRS_NODES` nodes, plus every saved edge.
+/// Blocking (vector reconstruction).
+pub fn score_graph(index: &IndexHandle, graph: &SessionGraph, cancel: &CancelToken) -> Result<Vec<(i64, i64, f32)>, AppError> {
+    let mut vectors: HashMap<i64, Vec<f32>> = HashMap::new();
+    for (n, node) in graph.nodes.iter().enumerate() {
+        if n % 256 == 0 {
+            cancel.check()?;
+        }
+        // Node IDs are public ones
+        let Some(article_id) = index.ids.article(node.id) else {
+            continue;
+        };
+        if let Ok(v) = index.reconstruct(article_id) {
+            let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+            vectors.insert(node.id, v.into_iter().map(|x| x / norm).collect());
+        }
+    }
+    let score = |a: i64, b: i64| -> Option<f32> {
+        let (a, b) = (vectors.get(&a)?, vectors.get(&b)?);
+        Some(a.iter().zip(b).map(|(x, y)| x * y).sum())
+    };
+
+    let mut pairs: HashSet<(i64, i64)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source != e.target)
+        .map(|e| if e.source < e.target { (e.source, e.target) } else { (e.target, e.source) })
+        .collect();
+    if vectors.len() <= MAX_ALL_PAIRS_NODES {
+        let mut ids: Vec<i64> = vectors.keys().cloned().collect();
+        ids.sort_unstable();
+        for (i, &a) in ids.iter().enumerate() {
+            cancel.check()?;
+            for &b in &ids[i + 1..] {
+                if score(a, b).is_some_and(|s| s >= SNAPSHOT_MIN_SCORE) {
+                    pairs.insert((a, b));
+                }
+            }
+        }
+    }
+
+    let mut edges: Vec<(i64, i64, f32)> =
+        pairs.into_iter().filter_map(|(a, b)| Some((a, b, score(a, b)?))).collect();
+    edges.sort_by_key(|&(a, b, _)| (a, b));
+    Ok(edges)
+}
+
+pub async fn insert_snapshot(
+    pool: &SqlitePool,
+    session_id: &str,
+    version: &CorpusVersion,
+    edges: &[(i64, i64, f32)],
+) -> Result<(), AppError> {
+    let encoded = serde_json::to_string(edges).map_err(|e| anyhow::anyhow!("Could not encode snapshot: {}", e))?;
+    // A concurrent touch may have recorded this version already
+    sqlx::query(
+        "INSERT OR IGNORE INTO session_snapshots (session_id, corpus_version, model_version, edges, edge_count)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(&version.version)
+    .bind(&version.model_version)
+    .bind(encoded)
+    .bind(edges.len() as i64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Drops every snapshot of a deleted session.
+pub async fn delete_snapshots(pool: &SqlitePool, session_id: &str) -> Result<(), AppError> {
+    ensure_snapshots_table(pool).await?;
+    sqlx::query("DELETE FROM session_snapshots WHERE session_id = ?").bind(session_id).execute(pool).await?;
+    Ok(())
+}
+
+pub async fn list_snapshots(pool: &SqlitePool, session_id: &str) -> Result<Vec<SnapshotInfo>, AppError> {
+    Ok(sqlx::query_as::<_, SnapshotInfo>(
+        "SELECT id, corpus_version, model_version, edge_count, created_at
+         FROM session_snapshots WHERE session_id = ? ORDER BY id ASC",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn load_snapshot_edges(pool: &SqlitePool, session_id: &str, snapshot_id: i64) -> Result<Vec<(i64, i64, f32)>, AppError> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT edges FROM session_snapshots WHERE id = ? AND session_id = ?")
+        .bind(snapshot_id)
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await?;
+    let (edges,) = row.ok_or_else(|| AppError::NotFound(format!("Unknown snapshot {}", snapshot_id)))?;
+    Ok(serde_json::from_str(&edges).map_err(|e| anyhow::anyhow!("Snapshot {} is corrupt: {}", snapshot_id, e))?)
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct EdgeShift {
+    pub source: i64,
+    pub target: i64,
+    /// None when the pair scored below `SNAPSHOT_MIN_SCORE` (or wasn't scored) in that snapshot
+    pub before: Option<f32>,
+    pub after: Option<f32>,
+    pub delta: f32,
+}
+
+/// Per-pair score changes between two snapshots, largest shifts first.
+pub fn compare(before: &[(i64, i64, f32)], after: &[(i64, i64, f32)]) -> Vec<EdgeShift> {
+    let before: HashMap<(i64, i64), f32> = before.iter().map(|&(a, b, s)| ((a, b), s)).collect();
+    let after: HashMap<(i64, i64), f32> = after.iter().map(|&(a, b, s)| ((a, b), s)).collect();
+    let keys: HashSet<&(i64, i64)> = before.keys().chain(after.keys()).collect();
+
+    let mut shifts: Vec<EdgeShift> = keys
+        .into_iter()
+        .map(|&(source, target)| {
+            let (b, a) = (before.get(&(source, target)).copied(), after.get(&(source, target)).copied());
+            EdgeShift {
+                source,
+                target,
+                before: b,
+                after: a,
+                delta: a.unwrap_or(SNAPSHOT_MIN_SCORE) - b.unwrap_or(SNAPSHOT_MIN_SCORE),
+            }
+        })
+        .collect();
+    shifts.sort_by(|x, y| y.delta.abs().total_cmp(&x.delta.abs()).then((x.source, x.target).cmp(&(y.source, y.target))));
+    shifts
+}
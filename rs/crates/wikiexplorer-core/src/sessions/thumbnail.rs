@@ -0,0 +1,145 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::Graph;
+use crate::utils::errors::AppError;
+
+use super::SessionGraph;
+
+const TOP_NODES: usize = 5;
+const TOP_CLUSTERS: usize = 3;
+
+/// Compact preview of a saved graph for gallery/list views, stored alongside
+/// the session so listings don't load full graphs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Thumbnail {
+    /// Most central titles, centrality weighted by pageviews
+    pub top_nodes: Vec<String>,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Representative titles of the largest connected clusters
+    pub clusters: Vec<String>,
+}
+
+/// Builds the descriptor. `pageviews` may be missing entries (counted as 0).
+pub fn describe(graph: &SessionGraph, pageviews: &HashMap<i64, i64>) -> Thumbnail {
+    let ids = graph.node_ids();
+    let g = Graph::new(&ids, graph.edges.iter().map(|e| (e.source, e.target, e.score.unwrap_or(1.0))));
+    let titles: HashMap<i64, &str> = graph.nodes.iter().map(|n| (n.id, n.title.as_str())).collect();
+
+    // Popular articles break ties between equally connected nodes; isolated nodes rank by pageviews alone
+    let centrality = |id: i64| {
+        let views = pageviews.get(&id).copied().unwrap_or(0).max(0) as f32;
+        (1.0 + g.weighted_degree(id)) * (1.0 + views.ln_1p())
+    };
+    let mut ranked: Vec<i64> = g.nodes().to_vec();
+    ranked.sort_by(|a, b| centrality(*b).total_cmp(&centrality(*a)).then(a.cmp(b)));
+    let title_of = |id: &i64| titles.get(id).filter(|t| !t.is_empty()).map(|t| t.to_string());
+
+    // Only multi-node components count as clusters
+    let mut members: HashMap<usize, Vec<i64>> = HashMap::new();
+    for (id, label) in g.connected_components() {
+        members.entry(label).or_default().push(id);
+    }
+    let mut clusters: Vec<Vec<i64>> = members.into_values().filter(|m| m.len() > 1).collect();
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a.iter().min().cmp(&b.iter().min())));
+
+    Thumbnail {
+        top_nodes: ranked.iter().filter_map(title_of).take(TOP_NODES).collect(),
+        node_count: g.nodes().len(),
+        edge_count: g.edge_count(),
+        clusters: clusters
+            .iter()
+            .take(TOP_CLUSTERS)
+            .filter_map(|m| m.iter().max_by(|a, b| centrality(**a).total_cmp(&centrality(**b)).then(b.cmp(a))))
+            .filter_map(title_of)
+            .collect(),
+    }
+}
+
+pub async fn store_thumbnail(pool: &SqlitePool, session_id: &str, thumbnail: &Thumbnail) -> Result<(), AppError> {
+    let encoded = serde_json::to_string(thumbnail).map_err(|e| anyhow::anyhow!("Could not encode thumbnail: {}", e))?;
+    sqlx::query("UPDATE sessions SET thumbnail = ? WHERE id = ?")
+        .bind(encoded)
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// A session as shown in listings: metadata plus its thumbnail (None until the
+/// background writer has produced one).
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SessionListing {
+    pub id: String,
+    pub name: String,
+    pub corpus: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub thumbnail: Option<Thumbnail>,
+}
+
+/// Most recently updated sessions first, ties by ID: up to `limit` of those
+/// after `after` (the `(updated_at, id)` of the last one already listed),
+/// skipping `offset` more.
+pub async fn list_sessions(
+    pool: &SqlitePool,
+    limit: i64,
+    after: Option<(i64, String)>,
+    offset: i64,
+) -> Result<Vec<SessionListing>, AppError> {
+    let (updated_at, id) = after.map_or((None, None), |(u, id)| (Some(u), Some(id)));
+    let rows: Vec<(String, String, String, i64, i64, Option<String>)> = sqlx::query_as(
+        "SELECT id, name, corpus, created_at, updated_at, thumbnail FROM sessions
+         WHERE ?1 IS NULL OR updated_at < ?1 OR (updated_at = ?1 AND id > ?2)
+         ORDER BY updated_at DESC, id LIMIT ?3 OFFSET ?4",
+    )
+    .bind(updated_at)
+    .bind(id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, corpus, created_at, updated_at, thumbnail)| SessionListing {
+            id,
+            name,
+            corpus,
+            created_at,
+            updated_at,
+            // A corrupt descriptor is simply regenerated
+            thumbnail: thumbnail.and_then(|t| serde_json::from_str(&t).ok()),
+        })
+        .collect())
+}
+
+/// Session IDs whose thumbnail needs (re)computing, drained by the background writer.
+#[derive(Default)]
+pub struct ThumbnailQueue {
+    pending: Mutex<HashSet<String>>,
+}
+
+impl ThumbnailQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enqueue(&self, session_id: &str) {
+        self.pending.lock().insert(session_id.to_string());
+    }
+
+    pub fn drain(&self) -> Vec<String> {
+        self.pending.lock().drain().collect()
+    }
+
+    /// Puts IDs back after a failed write so they're retried.
+    pub fn restore(&self, ids: Vec<String>) {
+        self.pending.lock().extend(ids);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cli::SignalImportArgs;
+use crate::config::get_config;
+use crate::signals::{ensure_registry_table, validate_signal_name};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+const BATCH_SIZE: usize = 5_000;
+
+enum ArticleKey {
+    Id(i64),
+    Title(String),
+}
+
+/// Imports `<key>,<value>` rows from a CSV into a new/existing numeric column of
+/// `articles` and registers it in `signal_registry` with the given weight.
+/// The key column is `article_id` or `title`, detected from the CSV header.
+pub async fn run(args: SignalImportArgs) -> anyhow::Result<()> {
+    let config = get_config();
+    validate_signal_name(&args.name).map_err(anyhow::Error::msg)?;
+
+    // 1. Parse the CSV up front so a malformed file changes nothing
+    let mut reader = csv::Reader::from_path(&args.csv)?;
+    let headers = reader.headers()?.clone();
+    let key_by_title = match headers.get(0).map(|h| h.trim().to_lowercase()) {
+        Some(h) if h == "article_id" || h == "id" => false,
+        Some(h) if h == "title" => true,
+        other => anyhow::bail!("First CSV column must be 'article_id' or 'title', got {:?}", other),
+    };
+
+    let mut rows = Vec::new();
+    let mut skipped = 0usize;
+    for record in reader.records() {
+        let record = record?;
+        let (Some(key), Some(raw_value)) = (record.get(0), record.get(1)) else {
+            skipped += 1;
+            continue;
+        };
+        let Ok(value) = raw_value.trim().parse::<f64>() else {
+            skipped += 1;
+            continue;
+        };
+        if !value.is_finite() {
+            skipped += 1;
+            continue;
+        }
+
+        let key = if key_by_title {
+            ArticleKey::Title(key.trim().replace(' ', "_"))
+        } else {
+            match key.trim().parse::<i64>() {
+                Ok(id) => ArticleKey::Id(id),
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            }
+        };
+        rows.push((key, value));
+    }
+
+    if rows.is_empty() {
+        anyhow::bail!("No usable rows in {}", args.csv);
+    }
+    info!("Parsed {} rows from {} ({} skipped)", rows.len(), args.csv, skipped);
+
+    // 2. Schema: add the column if needed, make sure the registry exists
+    let pool = SqlitePool::connect(&format!("sqlite:{}", config.metadata_path)).await?;
+    ensure_registry_table(&pool).await?;
+
+    let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('articles')")
+        .fetch_all(&pool)
+        .await?;
+    if !columns.iter().any(|(c,)| c == &args.name) {
+        // Name is validated above, so interpolating it is safe
+        sqlx::query(&format!("ALTER TABLE articles ADD COLUMN {} REAL", args.name))
+            .execute(&pool)
+            .await?;
+        info!("✓ Added column articles.{}", args.name);
+    }
+
+    // 3. Batched upserts
+    let by_id_sql = format!("UPDATE articles SET {} = ? WHERE article_id = ?", args.name);
+    let by_title_sql = format!("UPDATE articles SET {} = ? WHERE title = ?", args.name);
+    let mut matched = 0u64;
+
+    for chunk in rows.chunks(BATCH_SIZE) {
+        let mut tx = pool.begin().await?;
+        for (key, value) in chunk {
+            let result = match key {
+                ArticleKey::Id(id) => sqlx::query(&by_id_sql).bind(value).bind(id).execute(&mut *tx).await?,
+                ArticleKey::Title(title) => sqlx::query(&by_title_sql).bind(value).bind(title).execute(&mut *tx).await?,
+            };
+            matched += result.rows_affected();
+        }
+        tx.commit().await?;
+    }
+
+    if matched < rows.len() as u64 {
+        warn!("⚠ {} rows did not match any article", rows.len() as u64 - matched);
+    }
+
+    // 4. Register with bounds for normalization
+    let (min_value, max_value): (Option<f64>, Option<f64>) = sqlx::query_as(&format!(
+        "SELECT MIN({0}), MAX({0}) FROM articles WHERE {0} IS NOT NULL",
+        args.name
+    ))
+    .fetch_one(&pool)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO signal_registry (name, weight, min_value, max_value) VALUES (?, ?, ?, ?)
+         ON CONFLICT(name) DO UPDATE SET weight = excluded.weight,
+             min_value = excluded.min_value, max_value = excluded.max_value",
+    )
+    .bind(&args.name)
+    .bind(args.weight)
+    .bind(min_value.unwrap_or(0.0))
+    .bind(max_value.unwrap_or(0.0))
+    .execute(&pool)
+    .await?;
+
+    info!(
+        "✓ Signal '{}' registered: {} articles updated, weight {:.2}, range [{}, {}]",
+        args.name,
+        matched,
+        args.weight,
+        min_value.unwrap_or(0.0),
+        max_value.unwrap_or(0.0)
+    );
+    info!("Restart the server or POST /api/admin/reload-index to start ranking with it");
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use futures::TryStreamExt;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::search::filter_policy::{default_policy, FilterVerdict};
+
+pub mod import;
+
+/// Columns the ranking code already knows about; custom signals can't shadow them.
+const BUILTIN_COLUMNS: &[&str] = &["article_id", "title", "pagerank", "pageviews", "backlinks", "wikidata_qid", "last_modified"];
+
+/// A numeric `articles` column registered for ranking, stored in `signal_registry`.
+/// Values are min-max normalized with the bounds recorded at import time and enter
+/// the geometric mean as `norm ^ weight`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CustomSignal {
+    pub name: String,
+    pub weight: f64,
+    pub min_value: f64,
+    pub max_value: f64,
+}
+
+impl CustomSignal {
+    pub fn normalize(&self, value: Option<f64>) -> f64 {
+        let Some(value) = value.filter(|v| v.is_finite()) else {
+            return 0.0;
+        };
+        let range = self.max_value - self.min_value;
+        if range <= 0.0 {
+            return 1.0;
+        }
+        // NaN from infinite bounds counts as the bottom of the range
+        let normalized = (value - self.min_value) / range;
+        if normalized.is_nan() { 0.0 } else { normalized.clamp(0.0, 1.0) }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SignalRegistry {
+    pub signals: Vec<CustomSignal>,
+    /// Largest pagerank and pageviews in the DB: ceilings of the built-in
+    /// popularity signals, for stopping ranking early. None if unknown.
+    #[serde(skip)]
+    pub builtin_max: Option<(f64, f64)>,
+    /// Whether `articles` has the `last_modified` column, which the recency
+    /// signal reads
+    #[serde(skip)]
+    pub has_last_modified: bool,
+    /// Whether the DB has the `links` table (`ingest links`), checked once
+    /// here instead of on every query that reads it
+    #[serde(skip)]
+    pub has_links: bool,
+    /// Score factors of the articles the configured filter policy doesn't
+    /// keep as they are (0 when excluded), for results served without titles
+    #[serde(skip)]
+    pub policy_factors: HashMap<i64, f64>,
+}
+
+impl SignalRegistry {
+    /// Loads registered signals; a DB without the registry table simply has none.
+    pub async fn load(pool: &SqlitePool) -> Self {
+        let rows = sqlx::query_as::<_, CustomSignal>(
+            "SELECT name, weight, min_value, max_value FROM signal_registry ORDER BY name",
+        )
+        .fetch_all(pool)
+        .await;
+
+        let signals = match rows {
+            Ok(signals) => {
+                for s in &signals {
+                    info!("✓ Custom signal '{}' (weight {:.2})", s.name, s.weight);
+                }
+                signals
+            }
+            Err(e) => {
+                if !e.to_string().contains("no such table") {
+                    warn!("Could not load signal registry: {:?}", e);
+                }
+                vec![]
+            }
+        };
+        Self {
+            signals,
+            builtin_max: load_builtin_max(pool).await,
+            has_last_modified: has_column(pool, "last_modified").await,
+            has_links: has_table(pool, "links").await,
+            policy_factors: load_policy_factors(pool).await,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+}
+
+/// One scan of `articles` per (re)load; queries only ever read the result.
+async fn load_builtin_max(pool: &SqlitePool) -> Option<(f64, f64)> {
+    let row = sqlx::query_as::<_, (Option<f64>, Option<i64>)>("SELECT MAX(pagerank), MAX(pageviews) FROM articles")
+        .fetch_one(pool)
+        .await;
+    match row {
+        Ok((pagerank, pageviews)) => Some((pagerank.unwrap_or(0.0), pageviews.unwrap_or(0) as f64)),
+        Err(e) => {
+            warn!("Could not read signal maxima: {}", e);
+            None
+        }
+    }
+}
+
+/// Like `load_builtin_max`, one scan of the titles per (re)load.
+async fn load_policy_factors(pool: &SqlitePool) -> HashMap<i64, f64> {
+    let policy = default_policy();
+    let mut factors = HashMap::new();
+    let mut rows = sqlx::query_as::<_, (i64, String)>("SELECT article_id, title FROM articles").fetch(pool);
+    loop {
+        match rows.try_next().await {
+            Ok(Some((id, title))) => match policy.verdict(&title) {
+                FilterVerdict::Keep => {}
+                FilterVerdict::Demote(factor) => {
+                    factors.insert(id, factor);
+                }
+                FilterVerdict::Exclude => {
+                    factors.insert(id, 0.0);
+                }
+            },
+            Ok(None) => break,
+            Err(e) => {
+                warn!("Could not read titles for the filter policy: {}", e);
+                break;
+            }
+        }
+    }
+    factors
+}
+
+/// False as well when the table can't be read.
+async fn has_column(pool: &SqlitePool, column: &str) -> bool {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name = ?")
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .is_ok_and(|n| n > 0)
+}
+
+async fn has_table(pool: &SqlitePool, table: &str) -> bool {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(pool)
+        .await
+        .is_ok_and(|n| n > 0)
+}
+
+pub async fn ensure_registry_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS signal_registry (
+            name TEXT PRIMARY KEY,
+            weight REAL NOT NULL,
+            min_value REAL NOT NULL,
+            max_value REAL NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Signal names become column names, so only plain lowercase identifiers are allowed.
+pub fn validate_signal_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars.next().is_some_and(|c| c.is_ascii_lowercase() || c == '_');
+    let valid_rest = chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if !valid_start || !valid_rest || name.len() > 64 {
+        return Err(format!("Invalid signal name '{}': use [a-z_][a-z0-9_]*", name));
+    }
+    if BUILTIN_COLUMNS.contains(&name) {
+        return Err(format!("'{}' is a built-in column", name));
+    }
+    Ok(())
+}
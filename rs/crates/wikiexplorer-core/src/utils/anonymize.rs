@@ -0,0 +1,62 @@
+//! What of a client's IP address may reach logs and storage (IP_ANONYMIZATION),
+//! and how queries are identified in logs without their text.
+
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::config::get_config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpAnonymization {
+    /// The full address
+    Keep,
+    /// IPv4 to its /24, IPv6 to its /48
+    Truncate,
+    /// Keyed SHA-256 (IP_HASH_SALT): stable per client, not reversible without the salt
+    Hash,
+}
+
+impl FromStr for IpAnonymization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" | "keep" => Ok(Self::Keep),
+            "truncate" => Ok(Self::Truncate),
+            "hash" => Ok(Self::Hash),
+            other => Err(format!("unknown IP anonymization '{}'", other)),
+        }
+    }
+}
+
+/// `ip` as it may be logged or stored under the configured policy.
+pub fn anonymize_ip(ip: IpAddr) -> String {
+    let config = get_config();
+    match config.ip_anonymization {
+        IpAnonymization::Keep => ip.to_string(),
+        IpAnonymization::Truncate => truncate_ip(ip).to_string(),
+        IpAnonymization::Hash => {
+            let digest = Sha256::digest(format!("{}|{}", config.ip_hash_salt, ip).as_bytes());
+            digest.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+        }
+    }
+}
+
+/// Short hash identifying a query in logs without its text
+pub fn query_digest(query: &str) -> String {
+    Sha256::digest(query.as_bytes()).iter().take(6).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn truncate_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, c, 0))
+        }
+        IpAddr::V6(v6) => {
+            let s = v6.segments();
+            IpAddr::V6(Ipv6Addr::new(s[0], s[1], s[2], 0, 0, 0, 0, 0))
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::utils::errors::AppError;
+
+/// Cooperative cancellation flag for CPU-bound work running off the async runtime.
+///
+/// Axum drops a handler's future when the client disconnects, which stops async
+/// stages at their next `.await` but not a `spawn_blocking` closure already
+/// running. Blocking work gets a token and calls `check()` between steps.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Cancels its token when dropped, i.e. when the future holding it is abandoned.
+struct CancelOnDrop(CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Runs `f` on the blocking pool. If the calling future is dropped before `f`
+/// finishes, `f`'s token is cancelled so it can stop early and release locks.
+pub async fn run_blocking<T, F>(f: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&CancelToken) -> Result<T, AppError> + Send + 'static,
+{
+    let token = CancelToken::default();
+    let _guard = CancelOnDrop(token.clone());
+    tokio::task::spawn_blocking(move || f(&token))
+        .await
+        .map_err(|e| AppError::Anyhow(e.into()))?
+}
@@ -0,0 +1,49 @@
+/// Origins allowed to call the API from a browser, parsed from a comma-separated
+/// list: exact origins (`https://wikiexplorer.org`), wildcard subdomains
+/// (`https://*.wikiexplorer.org`) or `*` for any origin.
+#[derive(Debug, Clone, Default)]
+pub struct OriginPolicy {
+    any: bool,
+    exact: Vec<String>,
+    /// (scheme + "://", ".domain") pairs from wildcard entries
+    wildcard: Vec<(String, String)>,
+}
+
+impl OriginPolicy {
+    pub fn parse(raw: &str) -> Self {
+        let mut policy = Self::default();
+        for entry in raw.split(',').map(|e| e.trim().trim_end_matches('/')).filter(|e| !e.is_empty()) {
+            if entry == "*" {
+                policy.any = true;
+            } else if let Some((scheme, domain)) = entry.split_once("://*.") {
+                policy.wildcard.push((format!("{}://", scheme), format!(".{}", domain).to_lowercase()));
+            } else {
+                policy.exact.push(entry.to_lowercase());
+            }
+        }
+        policy
+    }
+
+    /// Whether `*` was listed
+    pub fn allows_any(&self) -> bool {
+        self.any
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.any && self.exact.is_empty() && self.wildcard.is_empty()
+    }
+
+    pub fn allows(&self, origin: &str) -> bool {
+        if self.any {
+            return true;
+        }
+        let origin = origin.to_lowercase();
+        self.exact.contains(&origin)
+            || self.wildcard.iter().any(|(scheme, suffix)| {
+                origin
+                    .strip_prefix(scheme.as_str())
+                    // "https://a.example.org" matches, "https://evilexample.org" doesn't
+                    .is_some_and(|host| host.ends_with(suffix.as_str()) && host.len() > suffix.len())
+            })
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config::get_config;
+use crate::utils::errors::AppError;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Awaits a hot-path SQLite statement for at most DB_QUERY_TIMEOUT_MS and logs
+/// it when it takes longer than SLOW_QUERY_MS. `statement` names it in logs
+/// and in the `AppError::Timeout`.
+///
+/// sqlx can't interrupt SQLite, so a timed-out statement still finishes on its
+/// connection; the deadline only frees the request waiting on it.
+pub async fn with_deadline<T, F>(statement: &'static str, query: F) -> Result<T, AppError>
+where
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    let config = get_config();
+    let started = Instant::now();
+    let result = if config.db_query_timeout_ms == 0 {
+        Some(query.await)
+    } else {
+        tokio::time::timeout(Duration::from_millis(config.db_query_timeout_ms), query).await.ok()
+    };
+
+    let elapsed = started.elapsed();
+    if elapsed >= Duration::from_millis(config.slow_query_ms) {
+        warn!(
+            statement,
+            elapsed_ms = elapsed.as_millis() as u64,
+            timed_out = result.is_none(),
+            "⚠ Slow query '{}' took {:?}",
+            statement,
+            elapsed
+        );
+    }
+
+    match result {
+        Some(result) => Ok(result?),
+        None => Err(AppError::Timeout(format!("query '{}' after {:?}", statement, elapsed))),
+    }
+}
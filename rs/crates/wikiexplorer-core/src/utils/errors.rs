@@ -0,0 +1,167 @@
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Vector Search error: {0}")]
+    Faiss(String), // faiss crate errors are sometimes strings or custom types
+
+    #[cfg(feature = "libtorch")]
+    #[error("Model error: {0}")]
+    Model(#[from] rust_bert::RustBertError),
+
+    #[error("Inference error: {0}")]
+    Inference(String),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Cancelled")]
+    Cancelled,
+
+    #[error("Timed out: {0}")]
+    Timeout(String), // What exceeded its deadline
+
+    #[error("Upstream error: {0}")]
+    Upstream(String), // An external API (Wikimedia) failed
+
+    #[error("Rate limited, retry in {0}s")]
+    RateLimited(u64), // Seconds until a token is available
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Anyhow error: {0}")]
+    Anyhow(#[from] anyhow::Error),
+}
+
+/// Media type of error bodies (RFC 7807)
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Problem `type` URIs are relative to this path, one per error kind; the
+/// server describes each kind there
+pub const PROBLEM_TYPE_BASE: &str = "/problems/";
+
+/// `detail` of server errors, whose own text names internals (paths, SQL,
+/// model state); the server logs that text under the request ID instead
+const REDACTED_DETAIL: &str = "The server could not handle this request; quote the request_id when reporting it";
+
+impl AppError {
+    /// Stable, machine-readable error kind; the last segment of the problem `type`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::Io(_) => "internal",
+            AppError::Faiss(_) => "vector-index",
+            #[cfg(feature = "libtorch")]
+            AppError::Model(_) => "model",
+            AppError::Inference(_) => "model",
+            AppError::Unauthorized => "unauthorized",
+            AppError::BadRequest(_) => "bad-request",
+            AppError::NotFound(_) => "not-found",
+            AppError::Cancelled => "cancelled",
+            AppError::Timeout(_) => "timeout",
+            AppError::Upstream(_) => "upstream",
+            AppError::RateLimited(_) => "rate-limited",
+            AppError::Config(_) => "internal",
+            AppError::Anyhow(_) => "internal",
+        }
+    }
+
+    /// Whether repeating the same request later may succeed.
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            AppError::Database(_)
+                | AppError::Inference(_)
+                | AppError::Cancelled
+                | AppError::Timeout(_)
+                | AppError::Upstream(_)
+                | AppError::RateLimited(_)
+        )
+    }
+}
+
+impl AppError {
+    /// HTTP status of the error
+    pub fn status(&self) -> u16 {
+        match self {
+            AppError::Unauthorized => 401,
+            AppError::BadRequest(_) => 400,
+            AppError::NotFound(_) => 404,
+            AppError::Cancelled => 409,
+            AppError::Timeout(_) => 504,
+            AppError::Upstream(_) => 502,
+            AppError::RateLimited(_) => 429,
+            _ => 500,
+        }
+    }
+
+    /// Short, human-readable summary of the error kind (the problem `title`)
+    pub fn title(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "Database Error",
+            AppError::Faiss(_) => "Vector Index Error",
+            #[cfg(feature = "libtorch")]
+            AppError::Model(_) => "ML Model Error",
+            AppError::Inference(_) => "ML Model Error",
+            AppError::Unauthorized => "Unauthorized",
+            AppError::BadRequest(_) => "Bad Request",
+            AppError::NotFound(_) => "Not Found",
+            AppError::Cancelled => "Cancelled",
+            AppError::Timeout(_) => "Timed Out",
+            AppError::Upstream(_) => "Upstream Unavailable",
+            AppError::RateLimited(_) => "Too Many Requests",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// Problem details (RFC 7807): `type`, `title`, `status`, `detail`, plus
+    /// `retryable`, `details` and, when rate limited, `retry_after_secs`. The
+    /// server sends them as `application/problem+json` and adds the request's
+    /// `instance` and ID. `error` repeats the title for clients that predate
+    /// problem details; `details` keeps the machine-readable part of the old
+    /// field of that name. 5xx `detail` is redacted.
+    pub fn problem(&self) -> Value {
+        let status = self.status();
+        let detail = match self {
+            AppError::BadRequest(msg) | AppError::NotFound(msg) => msg.clone(),
+            _ if status < 500 => self.to_string(),
+            _ => REDACTED_DETAIL.to_string(),
+        };
+        let mut details = json!({ "kind": self.kind() });
+        match self {
+            AppError::BadRequest(msg) | AppError::NotFound(msg) => details["message"] = msg.as_str().into(),
+            AppError::RateLimited(retry_after) => details["retry_after_secs"] = (*retry_after).into(),
+            _ => {}
+        }
+        let mut body = json!({
+            "type": format!("{}{}", PROBLEM_TYPE_BASE, self.kind()),
+            "title": self.title(),
+            "status": status,
+            "detail": detail,
+            "retryable": self.retryable(),
+            "details": details,
+            "error": match self {
+                AppError::BadRequest(msg) | AppError::NotFound(msg) => msg.as_str(),
+                _ => self.title(),
+            },
+        });
+        if let AppError::RateLimited(retry_after) = self {
+            body["retry_after_secs"] = (*retry_after).into();
+        }
+        body
+    }
+}
@@ -0,0 +1,144 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::utils::time::unix_now;
+
+const MINUTES_KEPT: usize = 60;
+const RECENT_ERRORS_KEPT: usize = 50;
+const MAX_TRACKED_QUERIES: usize = 10_000;
+
+/// Process-wide request metrics, kept in memory for the admin dashboard.
+pub struct Metrics {
+    started_at: Instant,
+    requests: AtomicU64,
+    errors: AtomicU64,
+    minutes: Mutex<VecDeque<MinuteBucket>>,
+    recent_errors: Mutex<VecDeque<ErrorEvent>>,
+    queries: Mutex<HashMap<String, u64>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MinuteBucket {
+    pub minute: u64, // minutes since server start
+    pub requests: u64,
+    pub errors: u64,
+    pub total_latency_ms: u64,
+}
+
+impl MinuteBucket {
+    pub fn avg_latency_ms(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.total_latency_ms as f64 / self.requests as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEvent {
+    pub timestamp: u64,
+    pub message: String,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            requests: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            minutes: Mutex::new(VecDeque::with_capacity(MINUTES_KEPT)),
+            recent_errors: Mutex::new(VecDeque::with_capacity(RECENT_ERRORS_KEPT)),
+            queries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn uptime_secs(&self) -> u64 {
+        self.started_at.elapsed().as_secs()
+    }
+
+    pub fn total_requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    pub fn total_errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    pub fn record_request(&self, latency_ms: u64, is_error: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if is_error {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let minute = self.started_at.elapsed().as_secs() / 60;
+        let mut minutes = self.minutes.lock();
+        if minutes.back().map(|b| b.minute) != Some(minute) {
+            if minutes.len() >= MINUTES_KEPT {
+                minutes.pop_front();
+            }
+            minutes.push_back(MinuteBucket { minute, requests: 0, errors: 0, total_latency_ms: 0 });
+        }
+        if let Some(bucket) = minutes.back_mut() {
+            bucket.requests += 1;
+            bucket.total_latency_ms += latency_ms;
+            if is_error {
+                bucket.errors += 1;
+            }
+        }
+    }
+
+    pub fn record_error(&self, message: String) {
+        let timestamp = unix_now();
+
+        let mut errors = self.recent_errors.lock();
+        if errors.len() >= RECENT_ERRORS_KEPT {
+            errors.pop_front();
+        }
+        errors.push_back(ErrorEvent { timestamp, message });
+    }
+
+    pub fn record_query(&self, query: &str) {
+        let key = query.trim().to_lowercase();
+        if key.is_empty() {
+            return;
+        }
+
+        let mut queries = self.queries.lock();
+        if queries.len() >= MAX_TRACKED_QUERIES && !queries.contains_key(&key) {
+            // Forget the long tail of one-off queries
+            queries.retain(|_, count| *count > 1);
+        }
+        *queries.entry(key).or_insert(0) += 1;
+    }
+
+    pub fn minutes(&self) -> Vec<MinuteBucket> {
+        self.minutes.lock().iter().cloned().collect()
+    }
+
+    pub fn recent_errors(&self) -> Vec<ErrorEvent> {
+        self.recent_errors.lock().iter().rev().cloned().collect()
+    }
+
+    pub fn top_queries(&self, n: usize) -> Vec<(String, u64)> {
+        let mut top: Vec<(String, u64)> = self
+            .queries
+            .lock()
+            .iter()
+            .map(|(q, c)| (q.clone(), *c))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(n);
+        top
+    }
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
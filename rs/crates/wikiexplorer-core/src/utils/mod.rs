@@ -0,0 +1,10 @@
+pub mod anonymize;
+pub mod cancel;
+pub mod cors;
+pub mod db_deadline;
+pub mod errors;
+pub mod metrics;
+pub mod privacy;
+pub mod slo;
+pub mod sql;
+pub mod time;
@@ -0,0 +1,114 @@
+//! Protection for published usage aggregates (top queries, learned
+//! suggestions): rare queries are suppressed and counts get Laplace noise, so
+//! the output doesn't reveal whether any one user searched for something.
+//! On by default (ANALYTICS_MIN_COUNT 5, ANALYTICS_EPSILON 1).
+//!
+//! The noise of an item is fixed for a period (a UTC day), drawn from a seed
+//! of a secret kept in the user DB, the item and the period: asking again,
+//! even after a restart, returns the same noisy count, so repeated requests
+//! can't be averaged to recover the exact one.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::config::get_config;
+use crate::utils::time::unix_now;
+
+/// How long an item keeps the same noise
+const NOISE_PERIOD_SECS: u64 = 86_400;
+
+/// Set by `load_noise_secret`; random for the process when it wasn't called
+static NOISE_SECRET: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+pub struct PrivacyPolicy {
+    /// Queries seen fewer times are never published
+    pub min_count: u64,
+    /// Privacy budget per published count; None publishes exact counts
+    pub epsilon: Option<f64>,
+}
+
+impl PrivacyPolicy {
+    /// ANALYTICS_MIN_COUNT / ANALYTICS_EPSILON
+    pub fn from_config() -> Self {
+        let config = get_config();
+        Self {
+            min_count: config.analytics_min_count,
+            epsilon: config.analytics_epsilon.filter(|e| e.is_finite() && *e > 0.0),
+        }
+    }
+
+    pub fn allows(&self, count: u64) -> bool {
+        count >= self.min_count
+    }
+
+    /// The count to publish for `key`, or None if it must be suppressed. With
+    /// noise, the threshold applies to the noisy count only, so whether an
+    /// item is published doesn't reveal its exact count either.
+    pub fn publish(&self, key: &str, count: u64) -> Option<u64> {
+        let Some(epsilon) = self.epsilon else {
+            return self.allows(count).then_some(count);
+        };
+        // One user changes a count by at most 1
+        let noise = laplace(&mut noise_rng(key), 1.0 / epsilon);
+        let noisy = (count as f64 + noise).round().max(0.0) as u64;
+        self.allows(noisy).then_some(noisy)
+    }
+
+    /// Applies `publish` to each count and re-sorts by the published counts.
+    pub fn publish_top(&self, counts: Vec<(String, u64)>) -> Vec<(String, u64)> {
+        let mut published: Vec<(String, u64)> = counts
+            .into_iter()
+            .filter_map(|(key, count)| self.publish(&key, count).map(|c| (key, c)))
+            .collect();
+        published.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        published
+    }
+}
+
+/// Loads the noise secret from the user DB, storing a new one on first start.
+/// Call before anything is published.
+pub async fn load_noise_secret(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS analytics_secret (
+            id INTEGER PRIMARY KEY CHECK (id = 0),
+            secret TEXT NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("INSERT OR IGNORE INTO analytics_secret (id, secret) VALUES (0, ?)")
+        .bind(random_secret())
+        .execute(pool)
+        .await?;
+    let (secret,): (String,) = sqlx::query_as("SELECT secret FROM analytics_secret WHERE id = 0").fetch_one(pool).await?;
+    if NOISE_SECRET.set(secret).is_err() {
+        warn!("⚠ Analytics noise secret loaded after first use; keeping this process's random one");
+    }
+    Ok(())
+}
+
+fn random_secret() -> String {
+    rand::thread_rng().gen::<[u8; 32]>().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The noise source of `key` in the current period, keyed by the noise secret
+/// so the noise can't be recomputed outside the server.
+fn noise_rng(key: &str) -> StdRng {
+    let period = unix_now() / NOISE_PERIOD_SECS;
+    let secret = NOISE_SECRET.get_or_init(random_secret);
+    let digest = Sha256::digest(format!("{}|{}|{}", secret, period, key).as_bytes());
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&digest);
+    StdRng::from_seed(seed)
+}
+
+/// Sample of a zero-centered Laplace distribution with scale `b` (inverse CDF).
+fn laplace(rng: &mut impl Rng, b: f64) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -b * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln()
+}
@@ -0,0 +1,218 @@
+//! Per-route service level objectives (SLOS) and their error-budget burn
+//! rates. A request is good when it didn't fail with a 5xx and, if the SLO has
+//! a latency threshold, answered within it. Burn rate is the share of bad
+//! requests over a window divided by the budget (`1 - target`): 1.0 spends the
+//! budget exactly over the SLO period, 14.4 spends a 30-day budget in two days.
+//!
+//! Alerts follow the multi-window policy of the SRE workbook: `fast` when both
+//! the 1h and 5m burn exceed 14.4, `slow` when both the 6h and 30m burn exceed 6.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::config::get_config;
+
+/// Longest window, in minutes
+const MINUTES_KEPT: usize = 360;
+
+/// Windows reported, as (label, minutes)
+const WINDOWS: [(&str, u64); 4] = [("5m", 5), ("30m", 30), ("1h", 60), ("6h", 360)];
+
+const FAST_BURN: f64 = 14.4;
+const SLOW_BURN: f64 = 6.0;
+
+/// Windows with fewer requests never alert, a handful of slow requests after a
+/// quiet night shouldn't page anyone
+const MIN_WINDOW_REQUESTS: u64 = 20;
+
+/// One objective: `route=800ms@99.5%` (latency and errors) or `route=99.9%`
+/// (errors only). `route` is the router's path pattern, e.g. `/api/session/:id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloSpec {
+    pub route: String,
+    pub latency_ms: Option<u64>,
+    /// Share of good requests, 0..1
+    pub target: f64,
+}
+
+impl FromStr for SloSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (route, objective) = s.trim().split_once('=').ok_or_else(|| format!("'{}' has no '='", s))?;
+        let (latency, target) = match objective.split_once('@') {
+            Some((latency, target)) => (Some(latency), target),
+            None => (None, objective),
+        };
+        let latency_ms = latency
+            .map(|l| l.trim().trim_end_matches("ms").parse::<u64>())
+            .transpose()
+            .map_err(|e| format!("bad latency in '{}': {}", s, e))?;
+        let percent: f64 = target
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|e| format!("bad target in '{}': {}", s, e))?;
+        if !(0.0..100.0).contains(&percent) {
+            return Err(format!("target of '{}' must be below 100%", s));
+        }
+        Ok(Self { route: route.trim().to_string(), latency_ms, target: percent / 100.0 })
+    }
+}
+
+impl SloSpec {
+    /// Parses `SLOS="/api/related=800ms@99.5%,/api/health=99.9%"`. Malformed
+    /// entries are skipped with a warning.
+    pub fn parse_list(raw: &str) -> Vec<Self> {
+        raw.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| {
+                entry
+                    .parse()
+                    .map_err(|e| tracing::warn!("⚠ Ignoring SLO {}", e))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum BurnAlert {
+    Fast,
+    Slow,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct WindowBurn {
+    pub window: &'static str,
+    pub total: u64,
+    pub bad: u64,
+    pub burn_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SloStatus {
+    pub route: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    pub target_percent: f64,
+    pub windows: Vec<WindowBurn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<BurnAlert>,
+}
+
+#[derive(Clone, Copy)]
+struct SloBucket {
+    minute: u64,
+    total: u64,
+    bad: u64,
+}
+
+struct RouteSlo {
+    spec: SloSpec,
+    minutes: Mutex<VecDeque<SloBucket>>,
+}
+
+/// Per-minute good/bad counts of every route with an SLO.
+pub struct SloTracker {
+    started_at: Instant,
+    routes: Vec<RouteSlo>,
+}
+
+impl SloTracker {
+    fn new(specs: &[SloSpec]) -> Self {
+        Self {
+            started_at: Instant::now(),
+            routes: specs
+                .iter()
+                .map(|spec| RouteSlo { spec: spec.clone(), minutes: Mutex::new(VecDeque::with_capacity(MINUTES_KEPT)) })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn record(&self, route: &str, latency_ms: u64, is_error: bool) {
+        let Some(slo) = self.routes.iter().find(|slo| slo.spec.route == route) else {
+            return;
+        };
+        let bad = is_error || slo.spec.latency_ms.is_some_and(|limit| latency_ms > limit);
+
+        let minute = self.current_minute();
+        let mut minutes = slo.minutes.lock();
+        if minutes.back().map(|b| b.minute) != Some(minute) {
+            if minutes.len() >= MINUTES_KEPT {
+                minutes.pop_front();
+            }
+            minutes.push_back(SloBucket { minute, total: 0, bad: 0 });
+        }
+        if let Some(bucket) = minutes.back_mut() {
+            bucket.total += 1;
+            bucket.bad += bad as u64;
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<SloStatus> {
+        let now = self.current_minute();
+        self.routes
+            .iter()
+            .map(|slo| {
+                let budget = 1.0 - slo.spec.target;
+                let minutes = slo.minutes.lock();
+                let windows: Vec<WindowBurn> = WINDOWS
+                    .iter()
+                    .map(|&(window, span)| {
+                        let (total, bad) = minutes
+                            .iter()
+                            .filter(|b| now - b.minute < span)
+                            .fold((0, 0), |(t, b), bucket| (t + bucket.total, b + bucket.bad));
+                        let burn_rate = if total == 0 { 0.0 } else { bad as f64 / total as f64 / budget };
+                        WindowBurn { window, total, bad, burn_rate }
+                    })
+                    .collect();
+                SloStatus {
+                    route: slo.spec.route.clone(),
+                    latency_ms: slo.spec.latency_ms,
+                    target_percent: slo.spec.target * 100.0,
+                    alert: alert(&windows),
+                    windows,
+                }
+            })
+            .collect()
+    }
+
+    fn current_minute(&self) -> u64 {
+        self.started_at.elapsed().as_secs() / 60
+    }
+}
+
+/// `windows` in WINDOWS order
+fn alert(windows: &[WindowBurn]) -> Option<BurnAlert> {
+    let burning = |i: usize, threshold: f64| {
+        let window: &WindowBurn = &windows[i];
+        window.total >= MIN_WINDOW_REQUESTS && window.burn_rate > threshold
+    };
+    if burning(0, FAST_BURN) && burning(2, FAST_BURN) {
+        Some(BurnAlert::Fast)
+    } else if burning(1, SLOW_BURN) && burning(3, SLOW_BURN) {
+        Some(BurnAlert::Slow)
+    } else {
+        None
+    }
+}
+
+static SLO_TRACKER: OnceLock<SloTracker> = OnceLock::new();
+
+pub fn slo_tracker() -> &'static SloTracker {
+    SLO_TRACKER.get_or_init(|| SloTracker::new(&get_config().slos))
+}
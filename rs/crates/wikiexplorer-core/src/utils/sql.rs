@@ -0,0 +1,23 @@
+//! SQL text shared by the hot-path queries.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Longest placeholder list served from the shared string
+const SHARED_PLACEHOLDERS: usize = 4096;
+
+/// `?,?,...,?` with `n` placeholders, for an `IN (...)` clause. Up to 4096 of
+/// them are a slice of one string built on first use, so per-request queries
+/// don't allocate the list.
+pub fn placeholders(n: usize) -> Cow<'static, str> {
+    static SHARED: OnceLock<String> = OnceLock::new();
+    let len = (2 * n).saturating_sub(1);
+    if n <= SHARED_PLACEHOLDERS {
+        let shared = SHARED.get_or_init(|| "?,".repeat(SHARED_PLACEHOLDERS));
+        Cow::Borrowed(&shared[..len])
+    } else {
+        let mut list = "?,".repeat(n);
+        list.truncate(len);
+        Cow::Owned(list)
+    }
+}
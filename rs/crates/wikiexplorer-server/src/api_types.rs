@@ -0,0 +1,91 @@
+//! `wikiexplorer api-types`: TypeScript bindings of every type the HTTP API
+//! reads or writes, generated with ts-rs from the same structs the handlers
+//! use. The output is committed under `frontend/src/api/bindings`, where the
+//! typed client (`frontend/src/api/client.ts`) imports it; regenerate it with
+//! `npm run gen:api` whenever a request or response struct changes (CI fails on
+//! drift).
+
+use std::fs;
+use std::path::Path;
+use tracing::info;
+use ts_rs::TS;
+
+use crate::cli::ApiTypesArgs;
+use crate::routes::util::pagination::Page;
+use crate::routes::{
+    admin, clusters, export, graph_metrics, health, interwiki, metadata, research, resolve, search, sessions, suggest,
+    summary, tasks, watches,
+};
+use crate::tasks::TaskInfo;
+use crate::watches::Watch;
+use wikiexplorer_core::sessions::snapshots::SnapshotInfo;
+use wikiexplorer_core::sessions::Session;
+use wikiexplorer_core::sessions::thumbnail::SessionListing;
+
+pub fn run(args: ApiTypesArgs) -> anyhow::Result<()> {
+    let out = &args.out;
+    fs::create_dir_all(out)?;
+    // So types that were renamed or removed disappear
+    for_each_binding(out, &mut |path| Ok(fs::remove_file(path)?))?;
+
+    // One root per request and response body; their field types follow
+    search::SearchRequest::export_all_to(out)?;
+    search::SearchResponse::export_all_to(out)?;
+    metadata::MetadataRequest::export_all_to(out)?;
+    metadata::MetadataResponse::export_all_to(out)?;
+    suggest::SuggestResponse::export_all_to(out)?;
+    resolve::ResolveRequest::export_all_to(out)?;
+    resolve::ResolveResponse::export_all_to(out)?;
+    clusters::ClusterRequest::export_all_to(out)?;
+    clusters::ClusterResponse::export_all_to(out)?;
+    export::ExportRequest::export_all_to(out)?;
+    research::ResearchGraphRequest::export_all_to(out)?;
+    research::ResearchGraphResponse::export_all_to(out)?;
+    research::JobSubmitted::export_all_to(out)?;
+    watches::CreateWatchRequest::export_all_to(out)?;
+    watches::WatchUpdatesResponse::export_all_to(out)?;
+    Watch::export_all_to(out)?;
+    sessions::CreateSessionRequest::export_all_to(out)?;
+    sessions::CreatedSession::export_all_to(out)?;
+    sessions::UpdateSessionRequest::export_all_to(out)?;
+    sessions::SessionResponse::export_all_to(out)?;
+    Session::export_all_to(out)?;
+    sessions::SnapshotComparison::export_all_to(out)?;
+    SnapshotInfo::export_all_to(out)?;
+    Page::<SessionListing>::export_all_to(out)?;
+    summary::GraphSummary::export_all_to(out)?;
+    graph_metrics::GraphMetrics::export_all_to(out)?;
+    interwiki::InterwikiResponse::export_all_to(out)?;
+    TaskInfo::export_all_to(out)?;
+    tasks::CancelResponse::export_all_to(out)?;
+    health::HealthResponse::export_all_to(out)?;
+    admin::ReloadRequest::export_all_to(out)?;
+    admin::ReloadResponse::export_all_to(out)?;
+    admin::PurgeResponse::export_all_to(out)?;
+    admin::SloResponse::export_all_to(out)?;
+
+    // ts-rs types 64-bit integers as `bigint`, but `JSON.parse` yields numbers
+    // (IDs and counts stay far below 2^53)
+    let mut written = 0;
+    for_each_binding(out, &mut |path| {
+        let source = fs::read_to_string(path)?;
+        fs::write(path, source.replace("bigint", "number"))?;
+        written += 1;
+        Ok(())
+    })?;
+    info!("✓ Wrote {} TypeScript bindings to {}", written, out.display());
+    Ok(())
+}
+
+/// Calls `f` on every `.ts` file under `dir`.
+fn for_each_binding(dir: &Path, f: &mut dyn FnMut(&Path) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            for_each_binding(&path, f)?;
+        } else if path.extension().is_some_and(|e| e == "ts") {
+            f(&path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use clap::{Args, Parser, Subcommand};
+use wikiexplorer_core::cli::{
+    BenchArgs, BuildArgs, CalibrateArgs, CategoryImportArgs, ConvertArgs, ImageIngestArgs, KnnPrecomputeArgs,
+    LastModifiedIngestArgs, LinkIngestArgs, MigrateIdsArgs, PageviewIngestArgs, QidIngestArgs, SignalImportArgs,
+    StableIdsArgs, TuneArgs, UpdateArgs,
+};
+
+#[derive(Parser)]
+#[command(name = "wikiexplorer", about = "WikiExplorer semantic search backend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Serve a small bundled corpus (generated into --demo-dir on first run)
+    #[arg(long)]
+    pub demo: bool,
+
+    /// Where the demo corpus is generated
+    #[arg(long, default_value = "demo-data")]
+    pub demo_dir: std::path::PathBuf,
+
+    /// Desktop builds: don't open the UI in the browser on launch
+    #[arg(long)]
+    pub no_browser: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the HTTP API (default when no subcommand is given)
+    Serve,
+    /// Index construction and maintenance
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+    /// Calibrate nprobe against exact search and record it in the manifest
+    Tune(TuneArgs),
+    /// Learn the semantic score calibration from a validation set and record it in the manifest
+    Calibrate(CalibrateArgs),
+    /// Measure FAISS search throughput under concurrent load
+    Bench(BenchArgs),
+    /// Custom ranking signals
+    Signals {
+        #[command(subcommand)]
+        command: SignalsCommand,
+    },
+    /// Article categories used by search filters
+    Categories {
+        #[command(subcommand)]
+        command: CategoriesCommand,
+    },
+    /// Compute pageviews, pagerank and backlinks from Wikipedia dumps, load thumbnails
+    Ingest {
+        #[command(subcommand)]
+        command: IngestCommand,
+    },
+    /// Precomputed nearest-neighbour edges used by cross-edges
+    Knn {
+        #[command(subcommand)]
+        command: KnnCommand,
+    },
+    /// Write TypeScript bindings of the HTTP API's request and response types
+    /// (needs the `ts` feature)
+    ApiTypes(ApiTypesArgs),
+}
+
+#[derive(Subcommand)]
+pub enum KnnCommand {
+    /// Store every article's nearest neighbours in `knn_edges`
+    Precompute(KnnPrecomputeArgs),
+}
+
+#[derive(Subcommand)]
+pub enum IngestCommand {
+    /// Sum article views from pageview dump files into `pageviews`
+    Pageviews(PageviewIngestArgs),
+    /// Compute `pagerank` and `backlinks` from the link graph
+    Links(LinkIngestArgs),
+    /// Load article thumbnails into `article_images`
+    Images(ImageIngestArgs),
+    /// Load Wikidata QIDs into `wikidata_qid`, linking articles across languages
+    Qids(QidIngestArgs),
+    /// Load last edit times into `last_modified`, for the recency signal
+    LastModified(LastModifiedIngestArgs),
+}
+
+#[derive(Subcommand)]
+pub enum CategoriesCommand {
+    /// Load `article_id,category` rows from CSV into the metadata DB
+    Import(CategoryImportArgs),
+}
+
+#[derive(Subcommand)]
+pub enum SignalsCommand {
+    /// Import a numeric column from CSV and register it for ranking
+    Import(SignalImportArgs),
+}
+
+#[derive(Subcommand)]
+pub enum IndexCommand {
+    /// Rebuild the vectors of an existing index into a new FAISS layout, or
+    /// embed the metadata DB's articles into a new index (--from-metadata)
+    Build(BuildArgs),
+    /// Embed new and re-titled articles into an existing index and tombstone
+    /// removed ones, without a full rebuild
+    Update(UpdateArgs),
+    /// Copy the vectors of a FAISS index into a pure-Rust HNSW index (`.hnsw`),
+    /// servable by builds without FAISS
+    Convert(ConvertArgs),
+    /// Write the stable-ID sidecar of an existing index from the metadata DB's
+    /// `page_id` column
+    StableIds(StableIdsArgs),
+    /// Rewrite stored IDs (edge caches, sessions, snapshots, watches) from old
+    /// article IDs to stable IDs with a mapping file
+    MigrateIds(MigrateIdsArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ApiTypesArgs {
+    /// Directory the `.ts` files are written to (and stale ones removed from)
+    #[arg(long, default_value = "../frontend/src/api/bindings")]
+    pub out: std::path::PathBuf,
+}
@@ -0,0 +1,90 @@
+//! Desktop builds (`--no-default-features --features desktop`): the frontend
+//! and the ONNX export of the embedding model are compiled into the binary,
+//! the bundled demo corpus is used unless INDEX_PATH points elsewhere, and the
+//! browser opens on launch. Search runs on HNSW and ONNX Runtime, so nothing
+//! has to ship alongside the binary and nothing is downloaded.
+
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use rust_embed::RustEmbed;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Output of `npm run build:desktop`
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/../../../frontend/dist"]
+struct Assets;
+
+/// `models/all-MiniLM-L6-v2-onnx`: `tokenizer.json` and `model_quantized.onnx`
+#[derive(RustEmbed)]
+#[folder = "$CARGO_MANIFEST_DIR/../../models/all-MiniLM-L6-v2-onnx"]
+struct Model;
+
+/// Where the bundled model is unpacked, inside the demo directory
+const MODEL_DIR: &str = "model";
+
+/// Address the desktop build listens on: nothing outside this machine needs it
+pub const LISTEN_ADDR: &str = "127.0.0.1:5002";
+
+/// Serves embedded assets; unknown paths get `index.html` so client-side
+/// routes survive a reload.
+pub async fn static_handler(uri: Uri) -> Response {
+    let path = uri.path().trim_start_matches('/');
+    let path = if path.is_empty() { "index.html" } else { path };
+
+    // Unknown API routes stay 404s instead of becoming the app
+    if path.starts_with("api/") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match Assets::get(path).or_else(|| Assets::get("index.html")) {
+        Some(file) => {
+            let mime = file.metadata.mimetype().to_string();
+            ([(header::CONTENT_TYPE, mime)], file.data.into_owned()).into_response()
+        }
+        None => (StatusCode::NOT_FOUND, "Frontend not embedded, run `npm run build:desktop` first").into_response(),
+    }
+}
+
+/// Opens `url` in the default browser; failures only cost the user a click.
+pub fn open_browser(url: &str) {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    match command.arg(url).spawn() {
+        Ok(_) => info!("✓ Opened {} in the browser", url),
+        Err(e) => warn!("⚠ Could not open a browser ({}), visit {}", e, url),
+    }
+}
+
+/// Unpacks the bundled model into `dir` and makes it the default embedder;
+/// explicitly set variables still win. Sets environment variables, so it must
+/// run before the first `get_config()` and before the tokio runtime starts.
+pub fn configure_env(dir: &Path) -> anyhow::Result<()> {
+    let model_dir = dir.join(MODEL_DIR);
+    std::fs::create_dir_all(&model_dir)?;
+    for name in Model::iter() {
+        let path = model_dir.join(name.as_ref());
+        if path.exists() {
+            continue;
+        }
+        let file = Model::get(&name).ok_or_else(|| anyhow::anyhow!("Bundled model file {} missing", name))?;
+        // Via a temporary file, so an interrupted unpack is redone next launch
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, &file.data)?;
+        std::fs::rename(tmp, path)?;
+    }
+
+    let defaults = [("EMBEDDER_BACKEND", "onnx".to_string()), ("ONNX_MODEL_DIR", model_dir.display().to_string())];
+    for (key, value) in defaults {
+        if std::env::var_os(key).is_none() {
+            std::env::set_var(key, value);
+        }
+    }
+    Ok(())
+}
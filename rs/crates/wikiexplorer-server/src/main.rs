@@ -0,0 +1,298 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+use std::future::IntoFuture;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use sqlx::SqlitePool;
+use clap::Parser;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+mod cli;
+mod state;
+mod utils;
+mod routes;
+mod suggestions;
+mod tasks;
+mod watches;
+mod thumbnails;
+mod pageimages;
+mod pageviews;
+mod query_topics;
+mod wikisummary;
+#[cfg(feature = "desktop")]
+mod desktop;
+#[cfg(feature = "ts")]
+mod api_types;
+
+use crate::cli::{CategoriesCommand, Cli, Command, IndexCommand, IngestCommand, KnnCommand, SignalsCommand};
+use crate::state::AppState;
+use wikiexplorer_core::config::get_config;
+use wikiexplorer_core::{categories, demo, images, index, ingest, search, sessions, signals};
+use wikiexplorer_core::utils::privacy;
+
+fn main() -> anyhow::Result<()> {
+    let mut cli = Cli::parse();
+    // The desktop build ships the demo corpus as its default data
+    if cfg!(feature = "desktop") && std::env::var_os("INDEX_PATH").is_none() {
+        cli.demo = true;
+    }
+    // These set env vars: before anything reads the config, and while this is
+    // still the only thread
+    #[cfg(feature = "desktop")]
+    desktop::configure_env(&cli.demo_dir)?;
+    if cli.demo {
+        demo::configure_env(&cli.demo_dir);
+    }
+
+    tokio::runtime::Builder::new_multi_thread().enable_all().build()?.block_on(run(cli))
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    // The console layer wants tokio's trace-level spans, so logs filter on their own
+    #[cfg(feature = "tokio-console")]
+    let console = Some(console_subscriber::spawn());
+    #[cfg(not(feature = "tokio-console"))]
+    let console: Option<tracing_subscriber::layer::Identity> = None;
+    let registry = tracing_subscriber::registry().with(console);
+    if get_config().log_json {
+        let logs = fmt::layer().json().with_current_span(true).with_span_list(false);
+        registry.with(logs.with_filter(LevelFilter::INFO)).init();
+    } else {
+        let logs = fmt::layer().with_target(false).compact();
+        registry.with(logs.with_filter(LevelFilter::INFO)).init();
+    }
+
+    if cli.demo {
+        demo::prepare(&cli.demo_dir).await?;
+    }
+
+    match cli.command.unwrap_or(Command::Serve) {
+        #[cfg(feature = "desktop")]
+        Command::Serve => serve(cli.no_browser).await,
+        #[cfg(not(feature = "desktop"))]
+        Command::Serve => serve().await,
+        #[cfg(feature = "faiss")]
+        Command::Index { command: IndexCommand::Build(args) } if args.from_metadata => index::embed::run(args).await,
+        #[cfg(feature = "faiss")]
+        Command::Index { command: IndexCommand::Build(args) } => index::builder::run(args),
+        #[cfg(feature = "faiss")]
+        Command::Index { command: IndexCommand::Update(args) } => index::update::run(args).await,
+        #[cfg(feature = "faiss")]
+        Command::Tune(args) => index::tune::run(args),
+        #[cfg(not(feature = "faiss"))]
+        Command::Index { command: IndexCommand::Build(_) | IndexCommand::Update(_) } | Command::Tune(_) => {
+            anyhow::bail!("Built without the `faiss` feature; index build, update and tune need FAISS")
+        }
+        #[cfg(all(feature = "faiss", feature = "hnsw"))]
+        Command::Index { command: IndexCommand::Convert(args) } => index::convert::run(args),
+        #[cfg(not(all(feature = "faiss", feature = "hnsw")))]
+        Command::Index { command: IndexCommand::Convert(_) } => {
+            anyhow::bail!("index convert needs both the `faiss` and `hnsw` features")
+        }
+        Command::Index { command: IndexCommand::StableIds(args) } => index::stable_ids::run(args).await,
+        Command::Index { command: IndexCommand::MigrateIds(args) } => index::migrate_ids::run(args).await,
+        Command::Knn { command: KnnCommand::Precompute(args) } => index::knn::run(args).await,
+        Command::Calibrate(args) => index::calibrate::run(args).await,
+        Command::Bench(args) => index::bench::run(args),
+        Command::Signals { command: SignalsCommand::Import(args) } => signals::import::run(args).await,
+        Command::Categories { command: CategoriesCommand::Import(args) } => categories::import::run(args).await,
+        Command::Ingest { command: IngestCommand::Pageviews(args) } => ingest::pageviews::run(args).await,
+        Command::Ingest { command: IngestCommand::Links(args) } => ingest::links::run(args).await,
+        Command::Ingest { command: IngestCommand::Images(args) } => ingest::images::run(args).await,
+        Command::Ingest { command: IngestCommand::Qids(args) } => ingest::qids::run(args).await,
+        Command::Ingest { command: IngestCommand::LastModified(args) } => ingest::last_modified::run(args).await,
+        #[cfg(feature = "ts")]
+        Command::ApiTypes(args) => api_types::run(args),
+        #[cfg(not(feature = "ts"))]
+        Command::ApiTypes(_) => anyhow::bail!("Built without the `ts` feature; api-types needs ts-rs"),
+    }
+}
+
+async fn serve(#[cfg(feature = "desktop")] no_browser: bool) -> anyhow::Result<()> {
+    let config = get_config(); // Initialize config
+    info!("Starting WikiExplorer Backend...");
+
+    // Database
+    info!("Connecting to database at: {}", config.metadata_path);
+    let db_pool = SqlitePool::connect(&format!("sqlite:{}", config.metadata_path)).await?;
+    let user_db = utils::user_db::open(&config.user_db_path).await?;
+    sessions::ensure_sessions_table(&user_db).await?;
+    sessions::snapshots::ensure_snapshots_table(&user_db).await?;
+    watches::ensure_watch_tables(&user_db).await?;
+    suggestions::ensure_stats_table(&user_db).await?;
+    wikisummary::ensure_summary_table(&user_db).await?;
+    pageviews::ensure_refreshed_table(&user_db).await?;
+    privacy::load_noise_secret(&user_db).await?;
+
+    // State (loads Model + Index)
+    let state = AppState::new(db_pool, user_db).await?;
+    let state_arc = Arc::new(state);
+    utils::db_health::spawn_monitor(state_arc.clone());
+    utils::maintenance::spawn_maintenance(state_arc.clone());
+    utils::retention::spawn_retention(state_arc.clone());
+    suggestions::spawn_rollup(state_arc.clone());
+    watches::spawn_watch_runner(state_arc.clone());
+    thumbnails::spawn_thumbnail_writer(state_arc.clone());
+    pageviews::spawn_pageview_refresh(state_arc.clone());
+    pageimages::spawn_image_fetcher(state_arc.clone());
+    query_topics::spawn_topic_clustering(state_arc.clone());
+    state_arc.spawn_spelling_build();
+    utils::rate_limit::spawn_cleanup();
+    utils::runtime_metrics::spawn_runtime_probes(config.prometheus_metrics);
+    utils::slo_alerts::spawn_slo_alerts(state_arc.clone());
+
+    for corpus in state_arc.all_corpora() {
+        if let Err(e) = images::ensure_images_table(&corpus.db).await {
+            warn!("⚠ Could not create article images table for corpus '{}': {}", corpus.name, e);
+        }
+    }
+
+    // Building the title index can take a while on a fresh DB; don't block startup
+    let db = state_arc.db();
+    tokio::spawn(async move {
+        routes::suggest::ensure_title_index(&db).await;
+        search::lexical::ensure_fts_table(&db).await;
+        if let Err(e) = search::cross_edges::ensure_edge_cache_table(&db).await {
+            warn!("⚠ Edge cache unavailable, cross edges will always be computed: {}", e);
+        }
+    });
+
+    // Router: admin endpoints get their own, stricter CORS policy
+    if config.allowed_origins.is_empty() {
+        info!("ALLOWED_ORIGINS not set, browsers can only call the API same-origin");
+    } else if config.allowed_origins.allows_any() {
+        warn!("⚠ ALLOWED_ORIGINS=*, any website can call the API from its visitors' browsers");
+    }
+    let admin = Router::new()
+        .route("/api/admin/reload-index", post(routes::admin::reload_index_handler))
+        .route("/api/admin/cache/purge", post(routes::admin::purge_cache_handler))
+        .route("/api/admin/slo", get(routes::admin::slo_handler))
+        .route("/admin", get(routes::admin::dashboard_handler))
+        .layer(utils::cors::admin_layer(&config.admin_allowed_origins));
+
+    let app = Router::new()
+        .route("/api/health", get(routes::health::health_handler))
+        .route("/problems/:kind", get(utils::problem::problem_type_handler))
+        .route("/metrics", get(routes::metrics::metrics_handler))
+        .route("/api/related", post(routes::search::search_handler))
+        .route("/api/metadata", post(routes::metadata::metadata_handler))
+        .route("/api/suggest", get(routes::suggest::suggest_handler))
+        .route("/api/resolve", post(routes::resolve::resolve_handler))
+        .route("/api/clusters", post(routes::clusters::clusters_handler))
+        .route(
+            "/api/export",
+            get(routes::export::export_get_handler).post(routes::export::export_post_handler),
+        )
+        .route("/api/research/graph", post(routes::research::research_graph_handler))
+        .route("/api/research/graph/jobs", post(routes::research::research_graph_job_handler))
+        .route(
+            "/api/users/:id/watches",
+            get(routes::watches::list_watches_handler).post(routes::watches::create_watch_handler),
+        )
+        .route("/api/users/:id/watches/:watch_id", delete(routes::watches::delete_watch_handler))
+        .route("/api/users/:id/watch-updates", get(routes::watches::watch_updates_handler))
+        .route(
+            "/api/session",
+            get(routes::sessions::list_sessions_handler).post(routes::sessions::create_session_handler),
+        )
+        .route(
+            "/api/session/:id",
+            get(routes::sessions::get_session_handler)
+                .put(routes::sessions::update_session_handler)
+                .delete(routes::sessions::delete_session_handler),
+        )
+        .route("/api/session/:id/snapshots", get(routes::sessions::list_snapshots_handler))
+        .route("/api/session/:id/snapshots/compare", get(routes::sessions::compare_snapshots_handler))
+        .route("/api/graphs/:id/summary", post(routes::summary::graph_summary_handler))
+        .route("/api/graph/metrics", post(routes::graph_metrics::graph_metrics_handler))
+        .route("/api/summary/:title", get(routes::article_summary::article_summary_handler))
+        .route("/api/interwiki/:id", get(routes::interwiki::interwiki_handler))
+        .route("/api/tasks", get(routes::tasks::list_tasks_handler))
+        .route(
+            "/api/tasks/:id",
+            get(routes::tasks::task_status_handler).delete(routes::tasks::cancel_task_handler),
+        )
+        .route("/api/tasks/:id/artifact", get(routes::tasks::task_artifact_handler))
+        .layer(utils::cors::public_layer(&config.allowed_origins))
+        .merge(admin)
+        .layer(axum::middleware::from_fn(utils::lanes::assign_lane))
+        .layer(axum::middleware::from_fn(utils::rate_limit::rate_limit))
+        .layer(axum::middleware::from_fn(utils::metrics::track_metrics))
+        .layer(axum::middleware::from_fn(utils::problem::problem_details))
+        .layer(axum::middleware::from_fn(utils::request_log::log_requests))
+        .with_state(state_arc.clone());
+    #[cfg(feature = "desktop")]
+    let app = app.fallback(desktop::static_handler);
+
+    #[cfg(feature = "desktop")]
+    let addr = desktop::LISTEN_ADDR;
+    #[cfg(not(feature = "desktop"))]
+    let addr = "0.0.0.0:5002";
+    info!("🚀 Server listening on {}", addr);
+    
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    #[cfg(feature = "desktop")]
+    if !no_browser {
+        desktop::open_browser(&format!("http://{}/", addr));
+    }
+
+    // SIGTERM/SIGINT stop accepting connections; in-flight requests get SHUTDOWN_DRAIN_SECS
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+    let mut signalled = shutdown_rx.clone();
+    // Connect info gives the rate limiter each client's address
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(async move {
+            let _ = signalled.wait_for(|stop| *stop).await;
+        });
+
+    let mut signalled = shutdown_rx;
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_secs);
+    tokio::select! {
+        result = server.into_future() => result?,
+        _ = async move {
+            let _ = signalled.wait_for(|stop| *stop).await;
+            tokio::time::sleep(drain_timeout).await;
+        } => warn!("⚠ Drain timeout ({:?}) reached, dropping remaining connections", drain_timeout),
+    }
+
+    state_arc.shutdown().await;
+    info!("✓ Shutdown complete");
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("⚠ Could not listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("⚠ Could not listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT, shutting down..."),
+        _ = terminate => info!("Received SIGTERM, shutting down..."),
+    }
+}
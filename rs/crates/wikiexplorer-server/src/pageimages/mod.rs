@@ -0,0 +1,206 @@
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::state::AppState;
+use crate::utils::wikimedia;
+use wikiexplorer_core::images::{ensure_images_table, fetch_thumbnails, store_thumbnails};
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::search::pipeline::SearchResult;
+use wikiexplorer_core::utils::errors::AppError;
+
+const FETCH_INTERVAL: Duration = Duration::from_secs(2);
+/// Titles per PageImages request (the API limit for anonymous clients)
+const TITLES_PER_REQUEST: usize = 50;
+/// Lookups waiting beyond this are dropped; they are queued again on the next search
+const MAX_PENDING: usize = 10_000;
+
+/// Articles of returned search pages with no `article_images` row yet, per corpus.
+#[derive(Default)]
+pub struct ImageQueue {
+    pending: Mutex<HashMap<String, HashMap<i64, String>>>,
+}
+
+impl ImageQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn enqueue(&self, corpus: &str, articles: impl Iterator<Item = (i64, String)>) {
+        let mut pending = self.pending.lock();
+        let queued: usize = pending.values().map(HashMap::len).sum();
+        let entry = pending.entry(corpus.to_string()).or_default();
+        entry.extend(articles.take(MAX_PENDING.saturating_sub(queued)));
+    }
+
+    fn drain(&self) -> HashMap<String, HashMap<i64, String>> {
+        std::mem::take(&mut *self.pending.lock())
+    }
+}
+
+/// Sets `thumbnail_url` on results with a stored thumbnail. Results whose
+/// article was never looked up are queued for the fetcher (THUMBNAIL_FETCH).
+/// Thumbnails are decoration: lookup failures leave results without them.
+pub async fn attach_thumbnails(state: &AppState, default_corpus: &Corpus, results: &mut [SearchResult]) {
+    let mut by_corpus: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, result) in results.iter().enumerate() {
+        let corpus = result.corpus.clone().unwrap_or_else(|| default_corpus.name.clone());
+        by_corpus.entry(corpus).or_default().push(i);
+    }
+
+    for (name, positions) in by_corpus {
+        let Some(corpus) = state.corpus(&name) else {
+            continue;
+        };
+        let ids: Vec<i64> = positions.iter().map(|&i| results[i].id).collect();
+        let known = match fetch_thumbnails(&corpus.db, &ids).await {
+            Ok(known) => known,
+            Err(e) => {
+                debug!("Thumbnails unavailable for corpus '{}': {}", name, e);
+                continue;
+            }
+        };
+
+        let mut missing = Vec::new();
+        for &i in &positions {
+            let result = &mut results[i];
+            match known.get(&result.id) {
+                Some(url) => result.thumbnail_url = url.clone(),
+                None if !result.title.is_empty() => missing.push((result.id, result.title.to_string())),
+                None => {}
+            }
+        }
+        if state.config.thumbnail_fetch && !missing.is_empty() {
+            state.images.enqueue(&name, missing.into_iter());
+        }
+    }
+}
+
+/// Background task (THUMBNAIL_FETCH): looks up queued articles through the
+/// PageImages API of WIKI_ACTION_API and stores the result, including the
+/// absence of an image, in the corpus's `article_images`.
+pub fn spawn_image_fetcher(state: Arc<AppState>) {
+    if !state.config.thumbnail_fetch {
+        return;
+    }
+    let client = match wikimedia::client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("⚠ Thumbnail fetching disabled, HTTP client failed: {}", e);
+            return;
+        }
+    };
+
+    info!("✓ Thumbnails fetched from {}", state.config.wiki_action_api);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(FETCH_INTERVAL).await;
+            for (name, articles) in state.images.drain() {
+                let Some(corpus) = state.corpus(&name) else {
+                    continue;
+                };
+                // Every corpus has its own table, and a swapped-in DB may not have it yet
+                if let Err(e) = ensure_images_table(&corpus.db).await {
+                    warn!("⚠ No article images table for corpus '{}': {}", name, e);
+                    continue;
+                }
+                let articles: Vec<(i64, String)> = articles.into_iter().collect();
+                for chunk in articles.chunks(TITLES_PER_REQUEST) {
+                    match fetch_page_images(&state, &client, chunk).await {
+                        Ok(rows) => {
+                            if let Err(e) = store_thumbnails(&corpus.db, &rows).await {
+                                warn!("⚠ Storing thumbnails for corpus '{}' failed: {}", name, e);
+                            }
+                        }
+                        // Not stored: the articles are queued again when next returned
+                        Err(e) => warn!("⚠ Thumbnail lookup failed: {}", e),
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[derive(Deserialize)]
+struct PageImagesResponse {
+    query: Option<PageImagesQuery>,
+}
+
+#[derive(Deserialize)]
+struct PageImagesQuery {
+    #[serde(default)]
+    normalized: Vec<TitleMapping>,
+    #[serde(default)]
+    redirects: Vec<TitleMapping>,
+    #[serde(default)]
+    pages: Vec<Page>,
+}
+
+#[derive(Deserialize)]
+struct TitleMapping {
+    from: String,
+    to: String,
+}
+
+#[derive(Deserialize)]
+struct Page {
+    title: String,
+    thumbnail: Option<PageThumbnail>,
+}
+
+#[derive(Deserialize)]
+struct PageThumbnail {
+    source: String,
+}
+
+/// Thumbnail URL (or None) of each article, following title normalization and redirects.
+async fn fetch_page_images(
+    state: &AppState,
+    client: &reqwest::Client,
+    articles: &[(i64, String)],
+) -> Result<Vec<(i64, Option<String>)>, AppError> {
+    let titles: Vec<String> = articles.iter().map(|(_, title)| title.replace('_', " ")).collect();
+    let width = state.config.thumbnail_width.to_string();
+    let response: PageImagesResponse = client
+        .get(&state.config.wiki_action_api)
+        .query(&[
+            ("action", "query"),
+            ("format", "json"),
+            ("formatversion", "2"),
+            ("redirects", "1"),
+            ("prop", "pageimages"),
+            ("piprop", "thumbnail"),
+            ("pithumbsize", width.as_str()),
+            ("pilimit", "max"),
+            ("titles", titles.join("|").as_str()),
+        ])
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| AppError::Upstream(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| AppError::Upstream(e.to_string()))?;
+
+    let query = response.query.ok_or_else(|| AppError::Upstream("PageImages response without query".to_string()))?;
+    let normalized: HashMap<&str, &str> = query.normalized.iter().map(|m| (m.from.as_str(), m.to.as_str())).collect();
+    let redirects: HashMap<&str, &str> = query.redirects.iter().map(|m| (m.from.as_str(), m.to.as_str())).collect();
+    let thumbnails: HashMap<&str, Option<&str>> = query
+        .pages
+        .iter()
+        .map(|page| (page.title.as_str(), page.thumbnail.as_ref().map(|t| t.source.as_str())))
+        .collect();
+
+    Ok(articles
+        .iter()
+        .zip(&titles)
+        .map(|((id, _), title)| {
+            let title = normalized.get(title.as_str()).copied().unwrap_or(title.as_str());
+            let title = redirects.get(title).copied().unwrap_or(title);
+            (*id, thumbnails.get(title).copied().flatten().map(str::to_string))
+        })
+        .collect())
+}
@@ -0,0 +1,237 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use futures::stream::{self, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use crate::utils::wikimedia::{self, encode_title};
+use wikiexplorer_core::utils::errors::AppError;
+
+const API_BASE: &str = "https://wikimedia.org/api/rest_v1/metrics/pageviews/per-article";
+const WRITE_BATCH: usize = 500;
+
+/// Outcome of the scheduled pageview refresh, for `/api/health`.
+pub struct PageviewRefresh {
+    last: Mutex<Option<PageviewRefreshRun>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct PageviewRefreshRun {
+    /// RFC 3339
+    pub finished_at: String,
+    pub duration_ms: u64,
+    /// First and last day (`YYYYMMDD`) of the views of every updated article
+    pub window_start: String,
+    pub window_end: String,
+    pub updated: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct PageviewRefreshStats {
+    pub every_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_refresh: Option<PageviewRefreshRun>,
+}
+
+impl PageviewRefresh {
+    pub fn new() -> Self {
+        Self { last: Mutex::new(None) }
+    }
+
+    /// None when the job is disabled
+    pub fn stats(&self, every: Option<Duration>) -> Option<PageviewRefreshStats> {
+        every.map(|every| PageviewRefreshStats {
+            every_secs: every.as_secs(),
+            last_refresh: self.last.lock().clone(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct PerArticle {
+    items: Vec<PerArticleItem>,
+}
+
+#[derive(Deserialize)]
+struct PerArticleItem {
+    views: i64,
+}
+
+/// Refreshed views, kept in the user DB so a reload swapping the metadata DB
+/// (or rebuilding it) gets them back instead of losing them. Holds only the
+/// latest run, so every row counts the same window.
+pub async fn ensure_refreshed_table(user_db: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS refreshed_pageviews (
+            title TEXT PRIMARY KEY,
+            views INTEGER NOT NULL,
+            window_start TEXT NOT NULL,
+            window_end TEXT NOT NULL
+        )",
+    )
+    .execute(user_db)
+    .await?;
+    Ok(())
+}
+
+/// Writes the refreshed views into `articles.pageviews` of `db`, matching by
+/// title; returns how many articles were updated.
+pub async fn apply_refreshed(user_db: &SqlitePool, db: &SqlitePool) -> Result<usize, sqlx::Error> {
+    let refreshed: Vec<(String, i64)> = sqlx::query_as("SELECT title, views FROM refreshed_pageviews")
+        .fetch_all(user_db)
+        .await?;
+    if refreshed.is_empty() {
+        return Ok(0);
+    }
+    let ids: HashMap<String, i64> = sqlx::query_as("SELECT title, article_id FROM articles")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+    let updates: Vec<(i64, i64)> = refreshed
+        .into_iter()
+        .filter_map(|(title, views)| Some((*ids.get(&title)?, views)))
+        .collect();
+
+    for chunk in updates.chunks(WRITE_BATCH) {
+        let mut tx = db.begin().await?;
+        for (id, views) in chunk {
+            sqlx::query("UPDATE articles SET pageviews = ? WHERE article_id = ?")
+                .bind(views)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+    }
+    Ok(updates.len())
+}
+
+/// Background task (PAGEVIEW_REFRESH, e.g. `@daily`): replaces `pageviews` of the
+/// PAGEVIEW_REFRESH_TOP_N most viewed articles with their views over the last
+/// PAGEVIEW_REFRESH_DAYS days from the Wikimedia pageviews API. The long tail
+/// keeps its imported value (see `wikiexplorer ingest pageviews`), so
+/// PAGEVIEW_REFRESH_DAYS should match the window of the imported dumps.
+///
+/// Each run fetches one window for all articles and replaces the stored
+/// refresh (see [`ensure_refreshed_table`]); it is applied to the metadata DB
+/// at startup, after each run and whenever the metadata DB is swapped.
+pub fn spawn_pageview_refresh(state: Arc<AppState>) {
+    let Some(every) = state.config.pageview_refresh_every else {
+        return;
+    };
+    let client = match wikimedia::client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("⚠ Pageview refresh disabled, HTTP client failed: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        // The metadata DB may have been rebuilt since the last run
+        match apply_refreshed(&state.user_db(), &state.db()).await {
+            Ok(0) => {}
+            Ok(applied) => info!("✓ Restored refreshed pageviews of {} articles", applied),
+            Err(e) => warn!("⚠ Restoring refreshed pageviews failed: {}", e),
+        }
+        loop {
+            tokio::time::sleep(every).await;
+            if !state.db_health.is_healthy() {
+                continue;
+            }
+
+            let started = Instant::now();
+            match refresh(&state, &client).await {
+                Ok(mut run) => {
+                    run.duration_ms = started.elapsed().as_millis() as u64;
+                    let (updated, failed) = (run.updated, run.failed);
+                    info!("✓ Pageviews refreshed for {} articles ({} failed) in {}ms", updated, failed, run.duration_ms);
+                    *state.pageview_refresh.last.lock() = Some(run);
+                }
+                Err(e) => warn!("⚠ Pageview refresh failed: {}", e),
+            }
+        }
+    });
+    info!("✓ Pageview refresh every {:?}", every);
+}
+
+/// Fetches the views of the top articles over one window, stores them as the
+/// new refresh and applies it to the active metadata DB.
+async fn refresh(state: &AppState, client: &reqwest::Client) -> Result<PageviewRefreshRun, AppError> {
+    let config = state.config;
+    let articles: Vec<String> = sqlx::query_scalar("SELECT title FROM articles ORDER BY pageviews DESC LIMIT ?")
+        .bind(config.pageview_refresh_top_n as i64)
+        .fetch_all(&state.db())
+        .await?;
+
+    // The API's data for today is incomplete, so the window ends yesterday
+    let end = Utc::now().date_naive() - ChronoDuration::days(1);
+    let start = end - ChronoDuration::days(config.pageview_refresh_days.max(1) as i64 - 1);
+    let (window_start, window_end) = (start.format("%Y%m%d").to_string(), end.format("%Y%m%d").to_string());
+    let range = format!("{}/{}", window_start, window_end);
+
+    let results: Vec<(String, Option<i64>)> = stream::iter(articles)
+        .map(|title| {
+            let url = format!(
+                "{}/{}/all-access/user/{}/daily/{}",
+                API_BASE,
+                config.pageview_project,
+                encode_title(&title),
+                range
+            );
+            async move {
+                let views = fetch_views(client, &url).await;
+                (title, views)
+            }
+        })
+        .buffer_unordered(config.pageview_refresh_concurrency.max(1))
+        .collect()
+        .await;
+
+    let updates: Vec<(&str, i64)> = results.iter().filter_map(|(title, views)| Some((title.as_str(), (*views)?))).collect();
+    let failed = results.len() - updates.len();
+
+    // Replaced as a whole so no row outlives the window it was fetched for
+    let user_db = state.user_db();
+    let mut tx = user_db.begin().await?;
+    sqlx::query("DELETE FROM refreshed_pageviews").execute(&mut *tx).await?;
+    for (title, views) in &updates {
+        sqlx::query("INSERT INTO refreshed_pageviews (title, views, window_start, window_end) VALUES (?, ?, ?, ?)")
+            .bind(title)
+            .bind(views)
+            .bind(&window_start)
+            .bind(&window_end)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    let updated = apply_refreshed(&user_db, &state.db()).await?;
+
+    Ok(PageviewRefreshRun {
+        finished_at: Utc::now().to_rfc3339(),
+        duration_ms: 0,
+        window_start,
+        window_end,
+        updated,
+        failed,
+    })
+}
+
+/// Summed daily views; None when the request fails. A 404 means no views in the window.
+async fn fetch_views(client: &reqwest::Client, url: &str) -> Option<i64> {
+    let response = client.get(url).send().await.ok()?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Some(0);
+    }
+    let body: PerArticle = response.error_for_status().ok()?.json().await.ok()?;
+    Some(body.items.iter().map(|item| item.views).sum())
+}
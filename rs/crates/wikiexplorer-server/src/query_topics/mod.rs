@@ -0,0 +1,181 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use wikiexplorer_core::search::clustering::kmeans_cosine;
+use wikiexplorer_core::search::engine::IndexHandle;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::privacy::PrivacyPolicy;
+use wikiexplorer_core::utils::sql::placeholders;
+
+/// Example queries listed per topic
+const EXAMPLES_PER_TOPIC: usize = 3;
+
+/// Latest clustering of stored query embeddings, published next to the top
+/// queries on the admin dashboard (`/admin`).
+pub struct QueryTopics {
+    last: Mutex<Option<Arc<TopicReport>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicReport {
+    /// RFC 3339
+    pub generated_at: String,
+    pub duration_ms: u64,
+    /// Distinct stored queries clustered
+    pub queries: usize,
+    /// Largest first; topics under ANALYTICS_MIN_COUNT are left out
+    pub topics: Vec<QueryTopic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryTopic {
+    /// Article nearest to the topic's most central query
+    pub article: Option<String>,
+    /// Searches in the topic over the last QUERY_TOPICS_WINDOW_DAYS, ANALYTICS_EPSILON noise applied
+    pub volume: u64,
+    /// Distinct queries in the topic
+    pub queries: usize,
+    /// Most used queries of the topic (in the window) that are themselves publishable; empty
+    /// when QUERY_STORE_KEEP_TEXT is off
+    pub examples: Vec<String>,
+}
+
+impl QueryTopics {
+    pub fn new() -> Self {
+        Self { last: Mutex::new(None) }
+    }
+
+    pub fn latest(&self) -> Option<Arc<TopicReport>> {
+        self.last.lock().clone()
+    }
+}
+
+/// Background task: every QUERY_TOPICS_INTERVAL_SECS (0 disables) clusters the
+/// QUERY_TOPICS_SAMPLE most recently used queries of the query store into up
+/// to QUERY_TOPICS_K topics (spherical k-means on their embeddings), ranked by
+/// their searches over the last QUERY_TOPICS_WINDOW_DAYS. Needs QUERY_STORE_PATH.
+pub fn spawn_topic_clustering(state: Arc<AppState>) {
+    let interval = state.config.query_topics_interval_secs;
+    if interval == 0 || state.search_engine.query_store().is_none() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            match cluster_topics(&state).await {
+                Ok(Some(report)) => {
+                    info!(
+                        "✓ Query topics: {} queries in {} topics ({}ms)",
+                        report.queries,
+                        report.topics.len(),
+                        report.duration_ms
+                    );
+                    *state.query_topics.last.lock() = Some(Arc::new(report));
+                }
+                Ok(None) => {}
+                Err(e) => warn!("⚠ Query topic clustering failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
+
+/// None when the store holds nothing to cluster.
+async fn cluster_topics(state: &AppState) -> Result<Option<TopicReport>, AppError> {
+    let started = Instant::now();
+    let Some(store) = state.search_engine.query_store() else {
+        return Ok(None);
+    };
+    // Per-language models embed differently; topics are over the default model's queries
+    let corpus = state.primary_corpus();
+    let dim = corpus.index.dim as usize;
+    let window = Duration::from_secs(state.config.query_topics_window_days.max(1) * 86_400);
+    let mut stored = store.recent(None, state.config.query_topics_sample, window).await?;
+    // Topics of what the audience explores now, not of every query the store remembers
+    stored.retain(|q| q.embedding.len() == dim && q.recent_uses > 0);
+    if stored.is_empty() {
+        return Ok(None);
+    }
+
+    let vectors: Vec<Vec<f32>> = stored.iter().map(|q| q.embedding.clone()).collect();
+    let k = state.config.query_topics_k;
+    let clustering = tokio::task::spawn_blocking(move || kmeans_cosine(&vectors, k))
+        .await
+        .map_err(|e| AppError::Anyhow(e.into()))?;
+
+    // Name each topic after the article nearest to its most central query
+    let representatives: Vec<f32> = clustering
+        .representatives
+        .iter()
+        .flat_map(|&i| stored[i].embedding.iter().copied())
+        .collect();
+    let index = Arc::clone(&corpus.index);
+    let nearest = tokio::task::spawn_blocking(move || nearest_articles(&index, &representatives))
+        .await
+        .map_err(|e| AppError::Anyhow(e.into()))??;
+    let titles = fetch_titles(&corpus.db, &nearest.iter().flatten().copied().collect::<Vec<_>>()).await?;
+
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); clustering.sizes.len()];
+    for (i, &label) in clustering.labels.iter().enumerate() {
+        members[label].push(i);
+    }
+
+    let policy = PrivacyPolicy::from_config();
+    let mut topics: Vec<QueryTopic> = members
+        .into_iter()
+        .zip(nearest)
+        .filter_map(|(mut members, article)| {
+            let volume = members.iter().map(|&i| stored[i].recent_uses as u64).sum();
+            let key = format!("topic:{}", article.map_or(String::new(), |id| id.to_string()));
+            let volume = policy.publish(&key, volume)?;
+            members.sort_by(|&a, &b| stored[b].recent_uses.cmp(&stored[a].recent_uses));
+            let examples = members
+                .iter()
+                .map(|&i| &stored[i])
+                .filter(|q| !q.query.is_empty() && policy.publish(&q.query, q.recent_uses as u64).is_some())
+                .take(EXAMPLES_PER_TOPIC)
+                .map(|q| q.query.clone())
+                .collect();
+            Some(QueryTopic {
+                article: article.and_then(|id| titles.get(&id).cloned()),
+                volume,
+                queries: members.len(),
+                examples,
+            })
+        })
+        .collect();
+    topics.sort_by_key(|t| std::cmp::Reverse(t.volume));
+
+    Ok(Some(TopicReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        queries: stored.len(),
+        topics,
+    }))
+}
+
+/// Nearest article to each of `vectors` (row-major)
+fn nearest_articles(index: &IndexHandle, vectors: &[f32]) -> Result<Vec<Option<i64>>, AppError> {
+    Ok(index
+        .search_many(vectors, 1)?
+        .into_iter()
+        .map(|(_, ids)| ids.into_iter().find(|id| *id >= 0))
+        .collect())
+}
+
+async fn fetch_titles(db: &sqlx::SqlitePool, ids: &[i64]) -> Result<HashMap<i64, String>, AppError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", placeholders(ids.len()));
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    Ok(query.fetch_all(db).await?.into_iter().collect())
+}
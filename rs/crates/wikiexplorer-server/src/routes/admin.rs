@@ -0,0 +1,308 @@
+use askama::Template;
+use axum::{
+    extract::{Json, State},
+    http::HeaderMap,
+    response::Html,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Instant;
+use subtle::ConstantTimeEq;
+use tracing::info;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::config::Config;
+use wikiexplorer_core::search::engine::IndexHandle;
+use wikiexplorer_core::search::semantic_cache::SemanticCacheStats;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::metrics::metrics;
+use wikiexplorer_core::utils::privacy::PrivacyPolicy;
+use wikiexplorer_core::utils::slo::{slo_tracker, SloStatus};
+use wikiexplorer_core::utils::time::unix_now;
+
+/// Rows in the dashboard's top queries table
+const TOP_QUERIES_SHOWN: usize = 20;
+
+/// Checks `Authorization: Bearer <ADMIN_TOKEN>`, the only place the token is
+/// accepted (query strings end up in logs and history). Admin endpoints are
+/// disabled entirely when no token is configured.
+pub fn require_admin(headers: &HeaderMap, config: &Config) -> Result<(), AppError> {
+    let provided = headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+    let Some(expected) = config.admin_token.as_deref() else {
+        return Err(AppError::Unauthorized);
+    };
+
+    // Digests compared in constant time, so neither the token's content nor its length leaks
+    let matches = provided.is_some_and(|token| {
+        bool::from(Sha256::digest(token.as_bytes()).ct_eq(&Sha256::digest(expected.as_bytes())))
+    });
+    if matches {
+        Ok(())
+    } else {
+        Err(AppError::Unauthorized)
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ReloadRequest {
+    #[serde(default)]
+    index_path: Option<String>,
+    #[serde(default)]
+    metadata_path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ReloadResponse {
+    status: String,
+    index_path: String,
+    metadata_path: String,
+    total_vectors: u64,
+    can_reconstruct: bool,
+    elapsed_ms: u128,
+}
+
+/// Loads a new index + metadata DB (defaults: the configured paths) and swaps them in.
+/// In-flight searches finish on the old index; nothing is swapped if either load fails.
+pub async fn reload_index_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Option<Json<ReloadRequest>>,
+) -> Result<Json<ReloadResponse>, ApiError> {
+    require_admin(&headers, state.config)?;
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+
+    let _guard = state.reload_lock.lock().await;
+    let started = Instant::now();
+
+    let index_path = request.index_path.unwrap_or_else(|| state.config.index_path.clone());
+    let metadata_path = request.metadata_path.unwrap_or_else(|| state.config.metadata_path.clone());
+    info!("ADMIN: reloading index={} metadata={}", index_path, metadata_path);
+
+    // 1. Metadata DB (verify it's usable before touching the index)
+    let pool = SqlitePool::connect(&format!("sqlite:{}", metadata_path)).await?;
+    sqlx::query("SELECT 1 FROM articles LIMIT 1").execute(&pool).await?;
+
+    // 2. Index (blocking file IO, keep it off the runtime)
+    let path = index_path.clone();
+    let handle = tokio::task::spawn_blocking(move || IndexHandle::load(&path))
+        .await
+        .map_err(|e| AppError::Anyhow(e.into()))??;
+
+    let total_vectors = handle.ntotal;
+    let can_reconstruct = handle.can_reconstruct;
+
+    // 3. Swap both; cached results refer to the old corpus
+    if state.config.response_cache_persist {
+        wikiexplorer_core::search::response_cache::ensure_cache_table(&pool).await?;
+    }
+    crate::routes::suggest::ensure_title_index(&pool).await;
+    wikiexplorer_core::search::lexical::ensure_fts_table(&pool).await;
+    wikiexplorer_core::search::cross_edges::ensure_edge_cache_table(&pool).await?;
+    state.swap_db(pool, metadata_path.clone()).await;
+    state.search_engine.swap_index(handle);
+    if let Some(cache) = &state.semantic_cache {
+        cache.clear();
+    }
+    if let Some(cache) = &state.response_cache {
+        cache.clear(&state.db()).await;
+    }
+    state.result_pools.clear();
+    state.spawn_spelling_build();
+
+    info!("✓ Reload complete in {:?}", started.elapsed());
+    Ok(Json(ReloadResponse {
+        status: "reloaded".to_string(),
+        index_path,
+        metadata_path,
+        total_vectors,
+        can_reconstruct,
+        elapsed_ms: started.elapsed().as_millis(),
+    }))
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct PurgeResponse {
+    status: String,
+    purged_entries: usize,
+}
+
+pub async fn purge_cache_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeResponse>, ApiError> {
+    require_admin(&headers, state.config)?;
+
+    let mut purged_entries = 0;
+    if let Some(cache) = &state.semantic_cache {
+        purged_entries += cache.stats().entries;
+        cache.clear();
+    }
+    if let Some(cache) = &state.response_cache {
+        purged_entries += cache.len();
+        cache.clear(&state.db()).await;
+    }
+    purged_entries += state.result_pools.len();
+    state.result_pools.clear();
+
+    info!("ADMIN: purged {} cache entries", purged_entries);
+    Ok(Json(PurgeResponse { status: "purged".to_string(), purged_entries }))
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SloResponse {
+    slos: Vec<SloStatus>,
+}
+
+/// `GET /api/admin/slo`: burn rates of every SLO over 5m, 30m, 1h and 6h.
+pub async fn slo_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<SloResponse>, ApiError> {
+    require_admin(&headers, state.config)?;
+    Ok(Json(SloResponse { slos: slo_tracker().statuses() }))
+}
+
+// ============================================================================
+// DASHBOARD
+// ============================================================================
+
+struct TopicRow {
+    article: String,
+    volume: u64,
+    examples: String,
+}
+
+struct ErrorRow {
+    age: String,
+    message: String,
+}
+
+/// Sign-in page: browsers can't attach a bearer header to a plain navigation,
+/// so it asks for the token and fetches the dashboard with the header.
+#[derive(Template)]
+#[template(path = "admin_login.html")]
+struct LoginTemplate;
+
+#[derive(Template)]
+#[template(path = "admin.html")]
+struct DashboardTemplate {
+    status: String,
+    uptime: String,
+    total_requests: u64,
+    total_errors: u64,
+    total_vectors: u64,
+    can_reconstruct: bool,
+    index_path: String,
+    requests_sparkline: String,
+    latency_sparkline: String,
+    cache: Option<SemanticCacheStats>,
+    top_queries: Vec<(String, u64)>,
+    query_topics: Vec<TopicRow>,
+    recent_errors: Vec<ErrorRow>,
+}
+
+/// Self-contained operator page: health, last-hour sparklines, top queries,
+/// recent errors and reload/purge buttons. `GET /admin` with
+/// `Authorization: Bearer <ADMIN_TOKEN>`; without the header, a sign-in page
+/// that sends it.
+pub async fn dashboard_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Html<String>, ApiError> {
+    if !headers.contains_key("authorization") {
+        let html = LoginTemplate.render().map_err(|e| AppError::Anyhow(e.into()))?;
+        return Ok(Html(html));
+    }
+    require_admin(&headers, state.config)?;
+
+    let m = metrics();
+    let handle = state.search_engine.index();
+    let db_ok = sqlx::query("SELECT 1").execute(&state.db()).await.is_ok();
+
+    let minutes = m.minutes();
+    let requests: Vec<f64> = minutes.iter().map(|b| b.requests as f64).collect();
+    let latencies: Vec<f64> = minutes.iter().map(|b| b.avg_latency_ms()).collect();
+
+    let now = unix_now();
+
+    let template = DashboardTemplate {
+        status: if db_ok { "ok".to_string() } else { "database unavailable".to_string() },
+        uptime: format_duration(m.uptime_secs()),
+        total_requests: m.total_requests(),
+        total_errors: m.total_errors(),
+        total_vectors: handle.ntotal,
+        can_reconstruct: handle.can_reconstruct,
+        index_path: handle.path.clone(),
+        requests_sparkline: sparkline(&requests, 240.0, 40.0),
+        latency_sparkline: sparkline(&latencies, 240.0, 40.0),
+        cache: state.semantic_cache.as_ref().map(|c| c.stats()),
+        // Over-fetch: suppressed and re-ranked queries would leave the table short
+        top_queries: {
+            let mut top = PrivacyPolicy::from_config().publish_top(m.top_queries(TOP_QUERIES_SHOWN * 5));
+            top.truncate(TOP_QUERIES_SHOWN);
+            top
+        },
+        query_topics: state
+            .query_topics
+            .latest()
+            .map(|report| {
+                report
+                    .topics
+                    .iter()
+                    .take(TOP_QUERIES_SHOWN)
+                    .map(|t| TopicRow {
+                        article: t.article.clone().unwrap_or_else(|| "?".to_string()),
+                        volume: t.volume,
+                        examples: t.examples.join(", "),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        recent_errors: m
+            .recent_errors()
+            .into_iter()
+            .map(|e| ErrorRow {
+                age: format!("{} ago", format_duration(now.saturating_sub(e.timestamp))),
+                message: e.message,
+            })
+            .collect(),
+    };
+
+    let html = template.render().map_err(|e| AppError::Anyhow(e.into()))?;
+    Ok(Html(html))
+}
+
+/// SVG polyline points for a series scaled into a width x height box.
+fn sparkline(values: &[f64], width: f64, height: f64) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let max = values.iter().cloned().fold(0.0, f64::max).max(1e-9);
+    let step = if values.len() > 1 { width / (values.len() - 1) as f64 } else { 0.0 };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", i as f64 * step, height - (v / max) * height))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+        s => format!("{}d {}h", s / 86400, (s % 86400) / 3600),
+    }
+}
@@ -0,0 +1,35 @@
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderName};
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::search::titles::articles_by_title;
+use wikiexplorer_core::utils::errors::AppError;
+
+const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+/// Wikipedia caps titles at 255 bytes
+const MAX_TITLE_BYTES: usize = 255;
+
+/// `GET /api/summary/:title`: Wikipedia's page summary (extract, thumbnail,
+/// URLs) for node previews, passed through unchanged from the local cache or
+/// the Wikimedia REST API. `X-Cache` tells which. Only articles of the
+/// primary corpus are looked up, so the endpoint can't proxy arbitrary titles.
+pub async fn article_summary_handler(
+    State(state): State<Arc<AppState>>,
+    Path(title): Path<String>,
+) -> Result<Response, ApiError> {
+    let title = title.trim().replace(' ', "_");
+    if title.is_empty() || title.len() > MAX_TITLE_BYTES {
+        return Err(AppError::BadRequest("Invalid article title".to_string()).into());
+    }
+
+    let matches = articles_by_title(&state.db(), &[&title], "summary title").await?;
+    let Some(title) = matches.iter().find(|(_, t)| *t == title).or(matches.first()).map(|(_, t)| t) else {
+        return Err(AppError::NotFound(format!("Unknown article '{}'", title)).into());
+    };
+
+    let (body, cache) = state.summaries.summary(&state.user_db(), title).await?;
+    Ok(([(header::CONTENT_TYPE, "application/json"), (X_CACHE, cache.as_str())], body).into_response())
+}
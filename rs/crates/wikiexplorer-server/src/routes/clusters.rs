@@ -0,0 +1,132 @@
+use axum::extract::{Json, State};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::search::clustering::{default_k, kmeans_cosine};
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const MAX_CONTEXT: usize = 5000;
+const MAX_CLUSTERS: usize = 50;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ClusterRequest {
+    context: Vec<i64>, // Node IDs currently on the graph
+    #[serde(default)]
+    k: Option<usize>, // Number of clusters (default: sqrt(n / 2), at most 12)
+    #[serde(default)]
+    corpus: Option<String>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Cluster {
+    label: usize,
+    size: usize,
+    representative_id: i64,
+    representative_title: String,
+    members: Vec<i64>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ClusterResponse {
+    clusters: Vec<Cluster>,
+    /// Node ID -> cluster label
+    assignments: HashMap<i64, usize>,
+    /// IDs whose vectors could not be reconstructed
+    unassigned: Vec<i64>,
+}
+
+/// Groups the context nodes by embedding similarity (spherical k-means) so the
+/// frontend can color communities. Labels are ordered by cluster size.
+pub async fn clusters_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ClusterRequest>,
+) -> Result<Json<ClusterResponse>, ApiError> {
+    if request.context.len() > MAX_CONTEXT {
+        return Err(AppError::BadRequest(format!("At most {} context nodes", MAX_CONTEXT)).into());
+    }
+    let corpus = match request.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+    if !corpus.index.can_reconstruct {
+        return Err(AppError::BadRequest("Index does not support vector reconstruction".to_string()).into());
+    }
+
+    // Context IDs are public (stable) ones; the index works on article IDs
+    let mut ids = corpus.index.ids.articles_of(&request.context);
+    ids.sort_unstable();
+    ids.dedup();
+
+    // Reconstruction + k-means are CPU bound
+    let index = Arc::clone(&corpus.index);
+    let k = request.k;
+    let (valid_ids, mut unassigned, clustering) = tokio::task::spawn_blocking(move || {
+        let mut vectors = Vec::new();
+        let mut valid_ids = Vec::new();
+        let mut unassigned = Vec::new();
+        for id in ids {
+            match index.reconstruct(id) {
+                Ok(v) => {
+                    vectors.push(v);
+                    valid_ids.push(id);
+                }
+                Err(_) => unassigned.push(id),
+            }
+        }
+
+        let k = k.unwrap_or_else(|| default_k(vectors.len())).clamp(1, MAX_CLUSTERS);
+        let clustering = kmeans_cosine(&vectors, k);
+        (valid_ids, unassigned, clustering)
+    })
+    .await
+    .map_err(|e| AppError::Anyhow(e.into()))?;
+
+    // Representative titles
+    let representative_ids: Vec<i64> = clustering.representatives.iter().map(|&i| valid_ids[i]).collect();
+    let mut titles: HashMap<i64, String> = HashMap::new();
+    if !representative_ids.is_empty() {
+        let params = placeholders(representative_ids.len());
+        let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", params);
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+        for id in &representative_ids {
+            query = query.bind(id);
+        }
+        titles.extend(with_deadline("cluster titles", query.fetch_all(&corpus.db)).await?);
+    }
+
+    let public = |id: i64| corpus.index.ids.public(id);
+    let mut clusters: Vec<Cluster> = representative_ids
+        .iter()
+        .enumerate()
+        .map(|(label, &id)| Cluster {
+            label,
+            size: clustering.sizes[label],
+            representative_id: public(id),
+            representative_title: titles.get(&id).cloned().unwrap_or_default(),
+            members: vec![],
+        })
+        .collect();
+
+    let mut assignments = HashMap::new();
+    for (&id, &label) in valid_ids.iter().zip(&clustering.labels) {
+        clusters[label].members.push(public(id));
+        assignments.insert(public(id), label);
+    }
+    unassigned.iter_mut().for_each(|id| *id = public(*id));
+    // IDs this index doesn't know at all
+    unassigned.extend(request.context.iter().filter(|id| corpus.index.ids.article(**id).is_none()));
+
+    info!("Clusters: {} nodes into {} clusters", valid_ids.len(), clusters.len());
+    Ok(Json(ClusterResponse { clusters, assignments, unassigned }))
+}
@@ -0,0 +1,173 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::export::{ExportEdge, ExportFormat, ExportGraph, ExportNode};
+use wikiexplorer_core::search::cross_edges::{calculate_cross_edges, EdgeBudget};
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const MAX_EXPORT_NODES: usize = 5000;
+/// Strongest cross-edges kept in one export
+const MAX_EXPORT_EDGES: usize = 50_000;
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    ids: String, // Comma-separated node IDs
+    #[serde(default)]
+    session: Option<String>,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    threshold: Option<f32>,
+    #[serde(default)]
+    corpus: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ExportRequest {
+    #[serde(default)]
+    ids: Vec<i64>,
+    #[serde(default)]
+    session: Option<String>, // Export a saved session as-is instead of `ids`
+    #[serde(default)]
+    format: Option<String>, // graphml | gexf | dot (else from Accept, default graphml)
+    #[serde(default)]
+    threshold: Option<f32>, // Cross edge threshold in [0, 1] (default CROSS_EDGE_THRESHOLD)
+    #[serde(default)]
+    corpus: Option<String>,
+}
+
+/// `GET /api/export?ids=1,2,3&format=gexf` or `GET /api/export?session=<uuid>`
+pub async fn export_get_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let ids = query
+        .ids
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AppError::BadRequest("ids must be comma-separated integers".to_string()))?;
+
+    let request = ExportRequest {
+        ids,
+        session: query.session,
+        format: query.format,
+        threshold: query.threshold,
+        corpus: query.corpus,
+    };
+    Ok(export(&state, &headers, request).await?)
+}
+
+/// `POST /api/export` with a JSON body (for node lists too long for a URL)
+pub async fn export_post_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ExportRequest>,
+) -> Result<Response, ApiError> {
+    Ok(export(&state, &headers, request).await?)
+}
+
+/// Serializes the given nodes plus their semantic cross edges for Gephi & co.
+async fn export(state: &AppState, headers: &HeaderMap, request: ExportRequest) -> Result<Response, AppError> {
+    let format = match request.format.as_deref() {
+        Some(name) => ExportFormat::parse(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown export format '{}'", name)))?,
+        None => headers
+            .get(header::ACCEPT)
+            .and_then(|h| h.to_str().ok())
+            .and_then(ExportFormat::from_accept)
+            .unwrap_or(ExportFormat::GraphMl),
+    };
+
+    if let Some(session_id) = request.session.as_deref() {
+        let session = crate::routes::sessions::load(state, session_id).await?;
+        info!("EXPORT: session {} ({} nodes) as {:?}", session.id, session.graph.nodes.len(), format);
+        return Ok(render(session.to_export_graph(), format));
+    }
+
+    let mut ids = request.ids;
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() || ids.len() > MAX_EXPORT_NODES {
+        return Err(AppError::BadRequest(format!("Export 1 to {} nodes", MAX_EXPORT_NODES)));
+    }
+
+    let corpus = match request.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+    let threshold = request.threshold.unwrap_or(state.config.cross_edge_threshold as f32);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::BadRequest("threshold must be between 0 and 1".to_string()));
+    }
+    // Requested and exported IDs are public ones
+    let ids = corpus.index.ids.articles_of(&ids);
+    if ids.is_empty() {
+        return Err(AppError::NotFound("None of the IDs are in this index".to_string()));
+    }
+
+    // Titles for the nodes
+    let params = placeholders(ids.len());
+    let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", params);
+    let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let titles: HashMap<i64, String> = with_deadline("export titles", query.fetch_all(&corpus.db)).await?.into_iter().collect();
+
+    // Edges carry public IDs, like the exported nodes. Up to MAX_EXPORT_NODES
+    // nodes is millions of pairs, so the interactive per-node and time limits apply
+    let budget = EdgeBudget { max_edges: MAX_EXPORT_EDGES, ..EdgeBudget::from_config() };
+    let (edges, _) = calculate_cross_edges(&corpus.index, &corpus.db, &ids, &[], threshold, &budget).await?;
+    let edges = edges
+        .into_iter()
+        .map(|e| ExportEdge { source: e.source_id, target: e.target_id, score: e.score, kind: None })
+        .collect();
+
+    let graph = ExportGraph {
+        nodes: ids
+            .iter()
+            .map(|id| ExportNode {
+                id: corpus.index.ids.public(*id),
+                title: titles.get(id).cloned().unwrap_or_default(),
+                depth: None,
+                score: None,
+            })
+            .collect(),
+        edges,
+    };
+
+    info!("EXPORT: {} nodes, {} edges as {:?}", graph.nodes.len(), graph.edges.len(), format);
+    Ok(render(graph, format))
+}
+
+fn render(graph: ExportGraph, format: ExportFormat) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"wikiexplorer-graph.{}\"", format.extension()),
+            ),
+        ],
+        graph.render(format),
+    )
+        .into_response()
+}
@@ -0,0 +1,138 @@
+use axum::extract::Json;
+use serde::Serialize;
+
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::graph::Graph;
+use wikiexplorer_core::sessions::SessionGraph;
+use wikiexplorer_core::utils::cancel::{run_blocking, CancelToken};
+use wikiexplorer_core::utils::errors::AppError;
+
+const MAX_NODES: usize = 5000;
+const MAX_EDGES: usize = 100_000;
+/// Larger components get a double-sweep lower bound instead of one BFS per node
+const EXACT_DIAMETER_NODES: usize = 2000;
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct GraphMetrics {
+    node_count: usize,
+    edge_count: usize,
+    /// Edges present over edges possible
+    density: f64,
+    /// Mean local clustering coefficient; nodes below degree 2 count as 0
+    average_clustering: f64,
+    components: ComponentStats,
+    /// Longest shortest path (hops) in the largest component, None for an empty graph
+    diameter: Option<usize>,
+    /// False when `diameter` is a lower bound (largest component above 2000 nodes)
+    diameter_exact: bool,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct ComponentStats {
+    count: usize,
+    largest: usize,
+    /// Nodes without any edge
+    isolated: usize,
+}
+
+/// `POST /api/graph/metrics`: structure of a submitted graph, in the same
+/// `{nodes: [{id}], edges: [{source, target}]}` shape sessions are saved in.
+/// Edge direction and scores are ignored; edges to unknown nodes are dropped.
+pub async fn graph_metrics_handler(Json(graph): Json<SessionGraph>) -> Result<Json<GraphMetrics>, ApiError> {
+    if graph.nodes.len() > MAX_NODES {
+        return Err(AppError::BadRequest(format!("At most {} nodes", MAX_NODES)).into());
+    }
+    if graph.edges.len() > MAX_EDGES {
+        return Err(AppError::BadRequest(format!("At most {} edges", MAX_EDGES)).into());
+    }
+
+    let metrics = run_blocking(move |cancel| measure(&graph, cancel)).await?;
+
+    Ok(Json(metrics))
+}
+
+/// Metrics of `graph`, checking `cancel` between the diameter's BFS passes
+fn measure(graph: &SessionGraph, cancel: &CancelToken) -> Result<GraphMetrics, AppError> {
+    let g = Graph::new(&graph.node_ids(), graph.edges.iter().map(|e| (e.source, e.target, 1.0)));
+
+    let labels = g.connected_components();
+    let mut sizes = vec![0usize; labels.values().max().map_or(0, |max| max + 1)];
+    for &label in labels.values() {
+        sizes[label] += 1;
+    }
+    // Label 0 is the largest component
+    let largest: Vec<i64> = g.nodes().iter().filter(|id| labels[*id] == 0).cloned().collect();
+
+    let (diameter, diameter_exact) = match largest.first() {
+        None => (None, true),
+        Some(_) if largest.len() <= EXACT_DIAMETER_NODES => {
+            let mut diameter = 0;
+            for &id in &largest {
+                cancel.check()?;
+                diameter = diameter.max(g.eccentricity(id).0);
+            }
+            (Some(diameter), true)
+        }
+        Some(&start) => {
+            let (_, far) = g.eccentricity(start);
+            (Some(g.eccentricity(far).0), false)
+        }
+    };
+
+    Ok(GraphMetrics {
+        node_count: g.nodes().len(),
+        edge_count: g.edge_count(),
+        density: g.density(),
+        average_clustering: g.average_clustering(),
+        components: ComponentStats {
+            count: sizes.len(),
+            largest: largest.len(),
+            isolated: g.nodes().iter().filter(|&&id| g.degree(id) == 0).count(),
+        },
+        diameter,
+        diameter_exact,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wikiexplorer_core::sessions::{SessionEdge, SessionNode};
+
+    fn graph(ids: &[i64], edges: &[(i64, i64)]) -> SessionGraph {
+        SessionGraph {
+            nodes: ids
+                .iter()
+                .map(|&id| SessionNode { id, title: String::new(), x: None, y: None, depth: None })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|&(source, target)| SessionEdge { source, target, score: None, kind: None })
+                .collect(),
+            layout: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn empty_graph_has_no_components_or_diameter() {
+        let metrics = measure(&graph(&[], &[]), &CancelToken::default()).unwrap();
+        assert_eq!(metrics.components.count, 0);
+        assert_eq!(metrics.components.largest, 0);
+        assert_eq!(metrics.diameter, None);
+        assert!(metrics.diameter_exact);
+    }
+
+    #[test]
+    fn diameter_is_of_the_largest_component() {
+        // Path 1-2-3-4, pair 5-6 and isolated 7; the 6-6 loop and the repeated edge are dropped
+        let g = graph(&[1, 2, 3, 4, 5, 6, 7], &[(1, 2), (2, 3), (3, 4), (5, 6), (6, 5), (6, 6)]);
+        let metrics = measure(&g, &CancelToken::default()).unwrap();
+        assert_eq!(metrics.edge_count, 4);
+        assert_eq!(metrics.components.count, 3);
+        assert_eq!(metrics.components.largest, 4);
+        assert_eq!(metrics.components.isolated, 1);
+        assert_eq!(metrics.diameter, Some(3));
+    }
+}
@@ -0,0 +1,151 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::pageviews::PageviewRefreshStats;
+use crate::state::AppState;
+use crate::utils::db_health::DbHealthStats;
+use crate::utils::maintenance::MaintenanceStats;
+use wikiexplorer_core::search::cross_edges::EdgeSource;
+use wikiexplorer_core::search::engine::{EmbeddingCacheStats, SearchParams};
+use wikiexplorer_core::search::query_store::QueryStoreStats;
+use wikiexplorer_core::search::semantic_cache::SemanticCacheStats;
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct HealthResponse {
+    status: String,
+    index_path: String,
+    metadata_path: String,
+    total_articles: i64,
+    index_total_vectors: u64,
+    /// Non-fatal problems, e.g. index size not matching the metadata DB
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    search_params: SearchParams,
+    ranking_weights: RankingWeights,
+    connectivity: Connectivity,
+    signal_coverage: SignalCoverage,
+    candidate_pool_size: usize,
+    default_results: usize,
+    database: DbHealthStats,
+    maintenance: MaintenanceStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pageview_refresh: Option<PageviewRefreshStats>,
+    /// Where query embeddings are computed: cpu, cuda, mps or remote
+    embedding_device: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embedding_cache: Option<EmbeddingCacheStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query_store: Option<QueryStoreStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    semantic_cache: Option<SemanticCacheStats>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct RankingWeights {
+    semantic: f64,
+    pagerank: f64,
+    pageviews: f64,
+    backlinks: f64,
+    title_match: f64,
+    recency: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct Connectivity {
+    threshold: f64,
+    /// Whether searches return cross edges at all: semantic ones, or wikilinks
+    /// from a `links` table in fallback
+    enabled: bool,
+    source: EdgeSource,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct SignalCoverage {
+    pagerank: i64,
+    pageviews: i64,
+    backlinks: i64,
+    last_modified: i64,
+}
+
+pub async fn health_handler(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+    info!("Health check requested");
+    let config = state.config;
+
+    let count = |sql: &'static str| {
+        let db = state.db();
+        async move {
+            sqlx::query_as::<_, (i64,)>(sql)
+                .fetch_one(&db)
+                .await
+                .map(|r| r.0)
+                .unwrap_or(0)
+        }
+    };
+
+    let total_articles = count("SELECT COUNT(*) FROM articles").await;
+
+    let index_total_vectors = state.search_engine.total_vectors();
+    let edge_source = EdgeSource::of(&state.search_engine.index());
+    let has_links = count("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'links'").await > 0;
+
+    let mut warnings = Vec::new();
+    if state.search_engine.is_fallback_index() {
+        warnings.push(format!("Index {} could not be loaded; serving an empty fallback index", config.index_path));
+    }
+    if index_total_vectors as i64 != total_articles {
+        warnings.push(format!(
+            "Index has {} vectors but metadata DB has {} articles",
+            index_total_vectors, total_articles
+        ));
+    }
+
+    let database = state.db_health.stats();
+
+    Json(HealthResponse {
+        status: if database.healthy { "ok" } else { "degraded" }.to_string(),
+        index_path: config.index_path.clone(),
+        metadata_path: state.metadata_path(),
+        total_articles,
+        index_total_vectors,
+        warnings,
+        search_params: state.search_engine.search_params(),
+        ranking_weights: RankingWeights {
+            semantic: config.weight_semantic,
+            pagerank: config.weight_pagerank,
+            pageviews: config.weight_pageviews,
+            backlinks: config.weight_backlinks,
+            title_match: config.weight_title_match,
+            recency: config.weight_recency,
+        },
+        connectivity: Connectivity {
+            threshold: config.cross_edge_threshold,
+            enabled: edge_source == EdgeSource::Semantic || has_links,
+            source: edge_source,
+        },
+        signal_coverage: SignalCoverage {
+            pagerank: count("SELECT COUNT(*) FROM articles WHERE pagerank > 0").await,
+            pageviews: count("SELECT COUNT(*) FROM articles WHERE pageviews > 0").await,
+            backlinks: count("SELECT COUNT(*) FROM articles WHERE backlinks > 0").await,
+            // 0 before `ingest last-modified` added the column
+            last_modified: count("SELECT COUNT(*) FROM articles WHERE last_modified IS NOT NULL").await,
+        },
+        candidate_pool_size: config.candidate_pool_size,
+        default_results: config.results_to_return,
+        database,
+        maintenance: state.maintenance.stats(config.maintenance_interval_secs > 0),
+        pageview_refresh: state.pageview_refresh.stats(config.pageview_refresh_every),
+        embedding_device: state.search_engine.inference.device(),
+        embedding_cache: state.search_engine.embedding_cache_stats(),
+        query_store: match state.search_engine.query_store() {
+            Some(store) => Some(store.stats().await),
+            None => None,
+        },
+        semantic_cache: state.semantic_cache.as_ref().map(|c| c.stats()),
+    })
+}
@@ -0,0 +1,123 @@
+use axum::extract::{Json, Path, Query, State};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+
+#[derive(Deserialize)]
+pub struct InterwikiParams {
+    /// Corpus the ID belongs to (default: primary)
+    #[serde(default)]
+    corpus: Option<String>,
+    /// Or the language whose index it belongs to
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct InterwikiResponse {
+    id: i64,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lang: Option<String>,
+    /// None when the metadata DB has no QID for the article
+    wikidata_qid: Option<String>,
+    /// The same Wikidata item in every other language corpus that has it
+    links: Vec<InterwikiLink>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct InterwikiLink {
+    lang: String,
+    corpus: String,
+    id: i64,
+    title: String,
+}
+
+/// `GET /api/interwiki/:id`: the article with the same Wikidata QID in the
+/// other configured languages (LANGUAGES), so a node can be expanded into
+/// neighbors from another language's index.
+pub async fn interwiki_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(params): Query<InterwikiParams>,
+) -> Result<Json<InterwikiResponse>, ApiError> {
+    let source = match (params.corpus.as_deref(), params.lang.as_deref()) {
+        (Some(_), Some(_)) => return Err(AppError::BadRequest("Pass either corpus or lang, not both".to_string()).into()),
+        (Some(name), None) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+        (None, Some(lang)) => state
+            .corpus_for_lang(lang)
+            .ok_or_else(|| AppError::BadRequest(format!("No index for language '{}'", lang)))?,
+        (None, None) => state.primary_corpus(),
+    };
+
+    let article_id = source.index.ids.article(id).ok_or_else(|| AppError::NotFound(format!("Article {} not found", id)))?;
+    let (title, qid) = lookup_article(&source, article_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Article {} not found", id)))?;
+
+    let mut links = Vec::new();
+    if let Some(qid) = &qid {
+        for corpus in state.all_corpora() {
+            let Some(lang) = corpus.lang.clone() else {
+                continue;
+            };
+            if corpus.name == source.name {
+                continue;
+            }
+            match find_by_qid(&corpus, qid).await {
+                Ok(Some((id, title))) => {
+                    let id = corpus.index.ids.public(id);
+                    links.push(InterwikiLink { lang, corpus: corpus.name.clone(), id, title })
+                }
+                Ok(None) => {}
+                // An older DB without the column just has no links
+                Err(e) => debug!("Interwiki lookup in corpus '{}' failed: {}", corpus.name, e),
+            }
+        }
+    }
+
+    Ok(Json(InterwikiResponse {
+        id,
+        title,
+        lang: source.lang.clone(),
+        wikidata_qid: qid,
+        links,
+    }))
+}
+
+async fn lookup_article(corpus: &Corpus, id: i64) -> Result<Option<(String, Option<String>)>, AppError> {
+    let query = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT title, wikidata_qid FROM articles WHERE article_id = ?",
+    )
+    .bind(id)
+    .fetch_optional(&corpus.db);
+    match with_deadline("interwiki article", query).await {
+        Err(AppError::Database(e)) if e.to_string().contains("no such column") => {
+            let title: Option<String> = sqlx::query_scalar("SELECT title FROM articles WHERE article_id = ?")
+                .bind(id)
+                .fetch_optional(&corpus.db)
+                .await?;
+            Ok(title.map(|title| (title, None)))
+        }
+        result => result,
+    }
+}
+
+async fn find_by_qid(corpus: &Corpus, qid: &str) -> Result<Option<(i64, String)>, AppError> {
+    let query = sqlx::query_as::<_, (i64, String)>(
+        "SELECT article_id, title FROM articles WHERE wikidata_qid = ? LIMIT 1",
+    )
+    .bind(qid)
+    .fetch_optional(&corpus.db);
+    with_deadline("interwiki links", query).await
+}
@@ -0,0 +1,89 @@
+use axum::extract::{Json, State};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::models::Article;
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const MAX_IDS: usize = 1000;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct MetadataRequest {
+    ids: Vec<i64>,
+    #[serde(default)]
+    corpus: Option<String>, // Defaults to the primary corpus
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct MetadataResponse {
+    /// In request order, each ID once
+    articles: Vec<Article>,
+    /// Requested IDs with no row in the metadata DB
+    missing: Vec<i64>,
+}
+
+/// Batch lookup of titles and signals by article ID. Clients use it to fill in
+/// results returned with `hydration: "partial"`.
+pub async fn metadata_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MetadataRequest>,
+) -> Result<Json<MetadataResponse>, ApiError> {
+    if payload.ids.len() > MAX_IDS {
+        return Err(AppError::BadRequest(format!("At most {} ids per request", MAX_IDS)).into());
+    }
+
+    let corpus = match payload.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+
+    let mut seen = HashSet::new();
+    let requested: Vec<i64> = payload.ids.into_iter().filter(|id| seen.insert(*id)).collect();
+    if requested.is_empty() {
+        return Ok(Json(MetadataResponse { articles: vec![], missing: vec![] }));
+    }
+
+    // Public (stable) IDs in and out, article IDs in the DB
+    let ids = corpus.index.ids.articles_of(&requested);
+    if ids.is_empty() {
+        return Ok(Json(MetadataResponse { articles: vec![], missing: requested }));
+    }
+    let params = placeholders(ids.len());
+    let sql = format!(
+        "SELECT article_id, title, pagerank, pageviews, backlinks FROM articles WHERE article_id IN ({})",
+        params
+    );
+
+    let mut query = sqlx::query_as::<_, Article>(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let rows = with_deadline("metadata", query.fetch_all(&corpus.db)).await?;
+    let mut found: HashMap<i64, Article> = rows
+        .into_iter()
+        .map(|mut article| {
+            article.article_id = corpus.index.ids.public(article.article_id);
+            (article.article_id, article)
+        })
+        .collect();
+
+    let mut articles = Vec::with_capacity(found.len());
+    let mut missing = Vec::new();
+    for id in requested {
+        match found.remove(&id) {
+            Some(article) => articles.push(article),
+            None => missing.push(id),
+        }
+    }
+
+    Ok(Json(MetadataResponse { articles, missing }))
+}
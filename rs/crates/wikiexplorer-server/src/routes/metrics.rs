@@ -0,0 +1,19 @@
+use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use crate::utils::runtime_metrics::render_prometheus;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// `GET /metrics`: Prometheus scrape target, 404 unless PROMETHEUS_METRICS is set.
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    if !state.config.prometheus_metrics {
+        return Err(AppError::NotFound("Metrics are disabled".to_string()).into());
+    }
+    let mut body = render_prometheus();
+    state.maintenance.render_prometheus(&mut body);
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response())
+}
@@ -0,0 +1,18 @@
+pub mod admin;
+pub mod article_summary;
+pub mod clusters;
+pub mod export;
+pub mod graph_metrics;
+pub mod health;
+pub mod interwiki;
+pub mod metadata;
+pub mod metrics;
+pub mod resolve;
+pub mod research;
+pub mod search;
+pub mod sessions;
+pub mod suggest;
+pub mod summary;
+pub mod tasks;
+pub mod util;
+pub mod watches;
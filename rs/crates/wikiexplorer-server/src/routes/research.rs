@@ -0,0 +1,336 @@
+use axum::extract::{Json, State};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::routes::search::related_articles;
+use crate::state::AppState;
+use crate::tasks::{spawn_task, Artifact, Task};
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::export::{ExportEdge, ExportGraph, ExportNode};
+use wikiexplorer_core::search::cross_edges::calculate_global_cross_edges;
+use wikiexplorer_core::utils::anonymize::query_digest;
+use wikiexplorer_core::utils::errors::AppError;
+
+const MAX_SEEDS: usize = 50;
+const MAX_DEPTH: usize = 3;
+const MAX_K_PER_SEED: usize = 50;
+const MAX_NODES: usize = 2000;
+/// Node cap for background jobs (semantic edges need an n x n similarity matrix)
+const MAX_JOB_NODES: usize = 5000;
+/// `Retry-After` of a job submitted while MAX_RUNNING_TASKS are running
+const TASK_RETRY_SECS: u64 = 30;
+/// Expansions between progress checkpoints
+const EXPANSION_BATCH: usize = 16;
+/// Expansions searched at once; the inference worker batches their encodes
+const EXPANSION_CONCURRENCY: usize = 4;
+
+#[derive(Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ResearchGraphRequest {
+    seeds: Vec<String>,
+    #[serde(default = "default_depth")]
+    depth: usize,
+    #[serde(default = "default_k_per_seed")]
+    k_per_seed: usize,
+    #[serde(default)]
+    threshold: Option<f32>, // Semantic edge threshold (default CROSS_EDGE_THRESHOLD)
+    #[serde(default)]
+    max_nodes: Option<usize>,
+    #[serde(default)]
+    corpus: Option<String>,
+}
+
+fn default_depth() -> usize {
+    1
+}
+
+fn default_k_per_seed() -> usize {
+    10
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct GraphNode {
+    id: i64,
+    title: String,
+    /// 0 for seed articles, n for articles found by the n-th expansion
+    depth: usize,
+    score: f64,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct GraphEdge {
+    source: i64,
+    target: i64,
+    score: f32,
+    /// "expansion" (target found by searching the source) or "semantic"
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ResearchGraphResponse {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    /// Seeds that matched no article
+    unresolved_seeds: Vec<String>,
+    /// Seed and expansion searches that failed; the graph is built without them
+    failed_searches: usize,
+    truncated: bool,
+    elapsed_ms: u128,
+}
+
+/// Builds a whole exploration graph in one call (see [`build_graph`]).
+pub async fn research_graph_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResearchGraphRequest>,
+) -> Result<Json<ResearchGraphResponse>, ApiError> {
+    Ok(Json(build_graph(&state, &request, MAX_NODES, None).await?))
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct JobSubmitted {
+    task_id: String,
+    status_url: String,
+}
+
+/// Runs the same build as a background task for graphs beyond interactive size.
+/// Poll `/api/tasks/{id}`; the result is a GraphML artifact.
+pub async fn research_graph_job_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResearchGraphRequest>,
+) -> Result<Json<JobSubmitted>, ApiError> {
+    validate(&request)?;
+    let task = state.tasks.create("research_graph").ok_or(AppError::RateLimited(TASK_RETRY_SECS))?;
+    let task_id = task.id.clone();
+    info!("TASK {}: research graph job with {} seeds", task_id, request.seeds.len());
+
+    let state = Arc::clone(&state);
+    spawn_task(Arc::clone(&task), async move {
+        match build_graph(&state, &request, MAX_JOB_NODES, Some(task.as_ref())).await {
+            Ok(graph) => {
+                let written = state
+                    .tasks
+                    .artifact_path(&task, "graphml")
+                    .and_then(|path| std::fs::write(&path, graph.to_export_graph().to_graphml()).map(|_| path));
+                match written {
+                    Ok(path) => {
+                        info!("✓ TASK {}: {} nodes written to {:?}", task.id, graph.nodes.len(), path);
+                        task.complete(Artifact {
+                            path,
+                            content_type: "application/graphml+xml",
+                            file_name: format!("research-graph-{}.graphml", task.id),
+                        });
+                    }
+                    Err(e) => task.fail(format!("Writing artifact failed: {}", e)),
+                }
+            }
+            Err(AppError::Cancelled) => {
+                info!("TASK {}: cancelled", task.id);
+                task.mark_cancelled();
+            }
+            Err(e) => {
+                warn!("⚠ TASK {} failed: {}", task.id, e);
+                task.fail(e.to_string());
+            }
+        }
+    });
+
+    Ok(Json(JobSubmitted { status_url: format!("/api/tasks/{}", task_id), task_id }))
+}
+
+fn validate(request: &ResearchGraphRequest) -> Result<(), AppError> {
+    if request.seeds.is_empty() || request.seeds.len() > MAX_SEEDS {
+        return Err(AppError::BadRequest(format!("Provide 1 to {} seeds", MAX_SEEDS)));
+    }
+    Ok(())
+}
+
+/// Each seed resolves to its top article, then every frontier article is
+/// expanded by searching its title (`k_per_seed` neighbors) for `depth` rounds.
+/// Nodes are deduplicated by ID and semantic edges are computed once over the
+/// final node set. A failed search only loses its own seed or expansion. With a
+/// `task`, progress is reported and cancellation is checked between batches.
+async fn build_graph(
+    state: &AppState,
+    request: &ResearchGraphRequest,
+    node_cap: usize,
+    task: Option<&Task>,
+) -> Result<ResearchGraphResponse, AppError> {
+    let started = Instant::now();
+    validate(request)?;
+
+    let depth = request.depth.min(MAX_DEPTH);
+    let k = request.k_per_seed.clamp(1, MAX_K_PER_SEED);
+    let max_nodes = request.max_nodes.unwrap_or(500).clamp(1, node_cap);
+    let threshold = request.threshold.unwrap_or(state.config.cross_edge_threshold as f32);
+    let corpus = match request.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+    info!("RESEARCH GRAPH: {} seeds, depth {}, k {}", request.seeds.len(), depth, k);
+
+    let mut nodes: Vec<GraphNode> = Vec::new();
+    let mut seen: HashSet<i64> = HashSet::new();
+    let mut edges: Vec<GraphEdge> = Vec::new();
+    let mut edge_pairs: HashSet<(i64, i64)> = HashSet::new();
+    let mut unresolved_seeds = Vec::new();
+    let mut failed_searches = 0;
+    let mut truncated = false;
+
+    // 1. Seeds -> top article each
+    let mut frontier: Vec<(i64, String)> = Vec::new();
+    for chunk in request.seeds.chunks(EXPANSION_BATCH) {
+        checkpoint(task, nodes.len(), "resolving seeds")?;
+        let resolved = search_all(chunk.iter().map(|seed| related_articles(state, &corpus, seed, 1)).collect()).await;
+        for (seed, results) in chunk.iter().zip(resolved) {
+            let Some(results) = tolerate(results, seed, &mut failed_searches)? else {
+                continue;
+            };
+            match results.into_iter().next().filter(|r| !r.title.is_empty()) {
+                Some(top) => {
+                    if seen.insert(top.id) {
+                        frontier.push((top.id, top.title.to_string()));
+                        nodes.push(GraphNode { id: top.id, title: top.title.to_string(), depth: 0, score: top.score_float });
+                    }
+                }
+                None => unresolved_seeds.push(seed.clone()),
+            }
+        }
+    }
+
+    // 2. Breadth-first expansion by title search
+    'levels: for level in 1..=depth {
+        let mut next_frontier = Vec::new();
+
+        for chunk in frontier.chunks(EXPANSION_BATCH) {
+            checkpoint(task, nodes.len(), format!("expanding depth {}/{}", level, depth))?;
+            let expanded = search_all(chunk.iter().map(|(_, title)| related_articles(state, &corpus, title, k + 1)).collect()).await;
+
+            for ((source, title), results) in chunk.iter().zip(expanded) {
+                let Some(results) = tolerate(results, title, &mut failed_searches)? else {
+                    continue;
+                };
+                for result in results.into_iter().filter(|r| r.id != *source).take(k) {
+                    if !seen.contains(&result.id) && nodes.len() >= max_nodes {
+                        truncated = true;
+                        break 'levels;
+                    }
+                    if edge_pairs.insert(ordered(*source, result.id)) {
+                        edges.push(GraphEdge {
+                            source: *source,
+                            target: result.id,
+                            score: result.score_float as f32,
+                            kind: "expansion",
+                        });
+                    }
+                    if !seen.insert(result.id) {
+                        continue;
+                    }
+                    // Partial hydration has no title to expand further
+                    if !result.title.is_empty() {
+                        next_frontier.push((result.id, result.title.to_string()));
+                    }
+                    nodes.push(GraphNode { id: result.id, title: result.title.to_string(), depth: level, score: result.score_float });
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    // 3. Semantic edges over the final node set
+    checkpoint(task, nodes.len(), "computing semantic edges")?;
+    let article_ids: Vec<i64> = nodes.iter().map(|n| n.id).collect();
+    let semantic = calculate_global_cross_edges(&corpus.index, &corpus.db, &article_ids, &[], threshold).await?;
+
+    // Built on article IDs; clients and the artifact get public ones, as semantic edges do
+    let ids = &corpus.index.ids;
+    nodes.iter_mut().for_each(|n| n.id = ids.public(n.id));
+    for edge in &mut edges {
+        edge.source = ids.public(edge.source);
+        edge.target = ids.public(edge.target);
+    }
+    let node_ids: HashSet<i64> = nodes.iter().map(|n| n.id).collect();
+    edge_pairs = edges.iter().map(|e| ordered(e.source, e.target)).collect();
+    for edge in semantic {
+        let (source, target) = (edge.source_id, edge.target_id);
+        if node_ids.contains(&source) && node_ids.contains(&target) && edge_pairs.insert(ordered(source, target)) {
+            edges.push(GraphEdge { source, target, score: edge.score, kind: "semantic" });
+        }
+    }
+
+    info!("✓ Research graph: {} nodes, {} edges in {:?}", nodes.len(), edges.len(), started.elapsed());
+    Ok(ResearchGraphResponse {
+        nodes,
+        edges,
+        unresolved_seeds,
+        failed_searches,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+/// Runs `searches` at most EXPANSION_CONCURRENCY at a time, results in order.
+/// Takes the futures collected: a lazy `map` would keep its closure alive across
+/// the await, which the job's `Send` bound can't prove for every lifetime.
+async fn search_all<F, T>(searches: Vec<F>) -> Vec<Result<T, AppError>>
+where
+    F: std::future::Future<Output = Result<T, AppError>>,
+{
+    stream::iter(searches).buffered(EXPANSION_CONCURRENCY).collect().await
+}
+
+/// The results of one search, or None (counted in `failed`) when it failed.
+/// Cancellation still ends the whole build.
+fn tolerate<T>(results: Result<T, AppError>, query: &str, failed: &mut usize) -> Result<Option<T>, AppError> {
+    match results {
+        Ok(results) => Ok(Some(results)),
+        Err(AppError::Cancelled) => Err(AppError::Cancelled),
+        Err(e) => {
+            warn!("⚠ Research graph: search for query {} failed: {}", query_digest(query), e);
+            *failed += 1;
+            Ok(None)
+        }
+    }
+}
+
+/// Reports progress and bails out if the task was cancelled.
+fn checkpoint(task: Option<&Task>, nodes: usize, message: impl Into<String>) -> Result<(), AppError> {
+    let Some(task) = task else {
+        return Ok(());
+    };
+    if task.is_cancelled() {
+        return Err(AppError::Cancelled);
+    }
+    task.set_progress(nodes as u64, message);
+    Ok(())
+}
+
+impl ResearchGraphResponse {
+    pub fn to_export_graph(&self) -> ExportGraph {
+        ExportGraph {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|n| ExportNode { id: n.id, title: n.title.clone(), depth: Some(n.depth), score: Some(n.score) })
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .map(|e| ExportEdge { source: e.source, target: e.target, score: e.score, kind: Some(e.kind.to_string()) })
+                .collect(),
+        }
+    }
+}
+
+fn ordered(a: i64, b: i64) -> (i64, i64) {
+    if a < b { (a, b) } else { (b, a) }
+}
@@ -0,0 +1,101 @@
+use axum::extract::{Json, State};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::search::titles::articles_by_title;
+use wikiexplorer_core::utils::errors::AppError;
+
+const MAX_TITLES: usize = 500;
+/// Wikipedia caps titles at 255 bytes
+const MAX_TITLE_BYTES: usize = 255;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ResolveRequest {
+    titles: Vec<String>,
+    #[serde(default)]
+    corpus: Option<String>, // Defaults to the primary corpus
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ResolvedTitle {
+    /// As requested
+    query: String,
+    id: i64,
+    /// As stored, e.g. `Alan_Turing` for `alan turing`
+    title: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ResolveResponse {
+    /// In request order, each title once
+    resolved: Vec<ResolvedTitle>,
+    /// Requested titles with no article
+    missing: Vec<String>,
+}
+
+/// Batch lookup of article IDs by title, e.g. to open a graph from a list of
+/// articles. Spaces and underscores are interchangeable and case is ignored
+/// (ASCII only); an exact match wins, otherwise the lowest article ID does, so
+/// the same title always resolves to the same article.
+pub async fn resolve_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ResolveRequest>,
+) -> Result<Json<ResolveResponse>, ApiError> {
+    if payload.titles.len() > MAX_TITLES {
+        return Err(AppError::BadRequest(format!("At most {} titles per request", MAX_TITLES)).into());
+    }
+
+    let corpus = match payload.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+
+    let mut seen = HashSet::new();
+    let requested: Vec<(String, String)> = payload
+        .titles
+        .into_iter()
+        .map(|query| {
+            let title = query.trim().replace(' ', "_");
+            (query, title)
+        })
+        .filter(|(_, title)| !title.is_empty() && title.len() <= MAX_TITLE_BYTES)
+        .filter(|(query, _)| seen.insert(query.clone()))
+        .collect();
+    if requested.is_empty() {
+        return Ok(Json(ResolveResponse { resolved: vec![], missing: vec![] }));
+    }
+
+    let titles: Vec<&str> = requested.iter().map(|(_, title)| title.as_str()).collect();
+    let rows = articles_by_title(&corpus.db, &titles, "resolve").await?;
+
+    // Rows come lowest ID first, so the first case-insensitive match is the fallback
+    let mut exact: HashMap<&str, i64> = HashMap::new();
+    let mut folded: HashMap<String, (i64, &str)> = HashMap::new();
+    for (id, title) in &rows {
+        exact.insert(title.as_str(), *id);
+        folded.entry(title.to_ascii_lowercase()).or_insert((*id, title.as_str()));
+    }
+
+    let mut resolved = Vec::with_capacity(requested.len());
+    let mut missing = Vec::new();
+    for (query, title) in requested {
+        let hit = match exact.get(title.as_str()) {
+            Some(&id) => Some((id, title)),
+            None => folded.get(&title.to_ascii_lowercase()).map(|&(id, t)| (id, t.to_string())),
+        };
+        match hit {
+            Some((id, title)) => resolved.push(ResolvedTitle { query, id: corpus.index.ids.public(id), title }),
+            None => missing.push(query),
+        }
+    }
+
+    Ok(Json(ResolveResponse { resolved, missing }))
+}
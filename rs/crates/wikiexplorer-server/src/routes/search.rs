@@ -0,0 +1,884 @@
+use axum::{
+    extract::{ConnectInfo, State, Json},
+    http::HeaderMap,
+};
+use crate::pageimages::attach_thumbnails;
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use crate::utils::api_version::{rename_in_array, ApiVersion, Versioned, VersionedResponse};
+use crate::utils::features::{Feature, Features};
+use crate::utils::rate_limit::client_ip_from;
+use futures::future::{join_all, try_join_all};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, debug, warn};
+use wikiexplorer_core::categories::CategoryFilter;
+use wikiexplorer_core::config::get_config;
+use wikiexplorer_core::search::clustering::{default_k, kmeans_cosine};
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::search::cross_edges::{calculate_cross_edges, EdgeBudget, EdgeOrigin, EdgeResult, EdgeSource};
+use wikiexplorer_core::search::dedup::{dedup_across_corpora, ArticleFingerprint};
+use wikiexplorer_core::search::disambiguation::{disambiguate, Disambiguation};
+use wikiexplorer_core::search::diversity::mmr_order;
+use wikiexplorer_core::search::embedder::MODEL_VERSION;
+use wikiexplorer_core::search::engine::SearchParams;
+use wikiexplorer_core::search::filter_policy::FilterOverrides;
+use wikiexplorer_core::search::lexical::SearchMode;
+use wikiexplorer_core::search::pipeline::{rank_candidates, Hydration, RankOptions, SearchResult, POOL_DEPTH};
+use wikiexplorer_core::search::query_expansion::{anchor_vectors, pool, QueryInput, Pooling, MAX_ANCHORS, MAX_QUERY_TERMS};
+use wikiexplorer_core::search::ranking::{ObscurityOverrides, ObscurityPenalty, RankingStrategy};
+use wikiexplorer_core::search::response_cache::cache_key;
+use wikiexplorer_core::search::timings::{Stage, StageTimings, Timings};
+use wikiexplorer_core::utils::anonymize::{anonymize_ip, query_digest};
+use wikiexplorer_core::utils::cancel::run_blocking;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::metrics::metrics;
+
+/// Graph context IDs per search, token and `context` merged
+const MAX_CONTEXT: usize = 5000;
+/// Smaller contexts cost less to resend than to keep server-side
+const MIN_TOKEN_CONTEXT: usize = 32;
+/// Results per page (`k`)
+const MAX_SEARCH_K: usize = 200;
+/// Deepest rank a page may reach (`offset + k`); the pool is ranked this deep at most
+const MAX_PAGE_END: usize = 10 * POOL_DEPTH;
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SearchRequest {
+    query: QueryInput, // A text, or several terms searched together
+    #[serde(default)]
+    anchor_ids: Vec<i64>, // Articles whose vectors join the query's
+    #[serde(default)]
+    pooling: Option<Pooling>, // mean (default) | max, combines terms and anchors
+    #[serde(default)]
+    exclude: Option<QueryInput>, // Terms whose articles are ranked down (EXCLUDE_PENALTY)
+    #[serde(default)]
+    context: Vec<i64>, // List of IDs currently on the graph
+    #[serde(default)]
+    context_token: Option<String>, // From a previous response; its context is merged with `context`
+    #[serde(default)]
+    k: Option<usize>, // Results per page, 1 to 200 (default RESULTS_TO_RETURN)
+    #[serde(default)]
+    debug: bool, // Per-signal score breakdown of each result and per-stage `timings`
+    #[serde(default)]
+    rescore: Option<usize>, // Overrides RESCORE_TOP_N for this request
+    #[serde(default)]
+    search_params: Option<SearchParams>, // Per-request nprobe / efSearch
+    #[serde(default)]
+    corpus: Option<String>, // Corpus name, or "all" for federated search (default: primary)
+    #[serde(default)]
+    offset: Option<usize>, // Skip this many ranked results (offset + k at most 3000)
+    #[serde(default)]
+    page_token: Option<String>, // From a previous response; pages through its stored pool
+    #[serde(default)]
+    include_categories: Vec<String>, // Keep only articles in at least one of these
+    #[serde(default)]
+    exclude_categories: Vec<String>, // Drop articles in any of these
+    #[serde(default)]
+    filters: Option<FilterOverrides>, // Overrides of the configured meta-page policy
+    #[serde(default)]
+    search_mode: SearchMode, // semantic (default) | lexical | hybrid
+    #[serde(default)]
+    lang: Option<String>, // Search the index of this language (LANGUAGES); excludes `corpus`
+    #[serde(default)]
+    ranking: Option<RankingStrategy>, // geometric | sum | rrf | linear (default: RANKING)
+    #[serde(default)]
+    diversity: Option<f32>, // 0-1, MMR re-ordering of the top results (0 = by score only)
+    #[serde(default)]
+    recency: Option<f64>, // Weight of recently edited articles, overrides WEIGHT_RECENCY (0 = off)
+    #[serde(default)]
+    obscurity: Option<ObscurityOverrides>, // Overrides of the configured obscurity penalty
+    #[serde(default)]
+    deep_dive: bool, // No obscurity penalty: niche topics rank on relevance alone
+    #[serde(default)]
+    features: Vec<String>, // Experimental response fields to include, like X-Features
+}
+
+impl SearchRequest {
+    /// `corpus` resolves the graph context's public IDs; federated search
+    /// ranks towards the context in the primary corpus only.
+    fn rank_options(
+        &self,
+        depth: usize,
+        page_end: usize,
+        corpus: &Corpus,
+        exclude: &[Vec<f32>],
+        timings: Option<&Arc<StageTimings>>,
+    ) -> RankOptions {
+        RankOptions {
+            rescore: self.rescore,
+            search_params: self.search_params.clone(),
+            include_categories: self.include_categories.clone(),
+            exclude_categories: self.exclude_categories.clone(),
+            filters: self.filters.clone(),
+            search_mode: self.search_mode,
+            debug: self.debug,
+            limit: Some(depth),
+            page_end: Some(page_end),
+            ranking: self.ranking,
+            diversity: self.diversity,
+            context: corpus.index.ids.articles_of(&self.context),
+            exclude: exclude.to_vec(),
+            recency: self.recency,
+            obscurity: self.obscurity,
+            deep_dive: self.deep_dive,
+            timings: timings.cloned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SearchResponse {
+    results: Vec<SearchResult>,
+    cross_edges: Vec<EdgeResult>,
+    /// "fallback" when the index can't reconstruct vectors: no fresh semantic
+    /// edges, wikilinks stand in for them
+    edge_source: EdgeSource,
+    /// Size of the ranked pool; ranking stops at the requested depth, so with
+    /// `total_is_lower_bound` more results follow
+    #[serde(default)]
+    total_results: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    total_is_lower_bound: bool,
+    /// Pass back with a higher `offset` to fetch further pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_page_token: Option<String>,
+    #[serde(default)]
+    hydration: Hydration,
+    /// Cross-edges were cut to the edge budget (CROSS_EDGE_MAX_EDGES / CROSS_EDGE_BUDGET_MS)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// Experimental (`clusters` feature): result ID -> k-means label of its
+    /// embedding among this page's results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clusters: Option<HashMap<i64, usize>>,
+    /// Experimental (`centrality` feature): result ID -> sum of the scores of
+    /// its cross-edges on this page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    centrality: Option<HashMap<i64, f32>>,
+    /// Senses of an ambiguous query ("Mercury"), on the first page only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disambiguation: Option<Disambiguation>,
+    /// The query with typos corrected, when its best result is a weak match
+    /// (DID_YOU_MEAN_THRESHOLD); on the first page only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    did_you_mean: Option<String>,
+    /// Stands for the whole graph context of the request (token and
+    /// `context` merged): send it as `context_token` with only the IDs added
+    /// since, instead of resending them all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context_token: Option<String>,
+    /// Time spent per stage, for `debug` requests (zero for stages a cache
+    /// or page token skipped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timings: Option<Timings>,
+}
+
+impl VersionedResponse for SearchResponse {
+    fn downgrade(value: &mut serde_json::Value, to: u32) {
+        if to == 1 {
+            rename_in_array(value, "results", "relevance", "score_float");
+        }
+    }
+}
+
+/// A ranked result list stored behind a page token.
+pub struct RankedPool {
+    corpus: Option<String>,
+    results: Vec<SearchResult>,
+    hydration: Hydration,
+    /// False when ranking may have stopped at the requested depth: pages past
+    /// the end of `results` need a deeper search
+    complete: bool,
+}
+
+impl RankedPool {
+    /// Whether `offset..end` can be sliced out of the pool
+    fn covers(&self, end: usize) -> bool {
+        self.complete || end <= self.results.len()
+    }
+
+    /// Whether anything may follow `offset..end`
+    fn has_more(&self, end: usize) -> bool {
+        !self.complete || end < self.results.len()
+    }
+}
+
+pub async fn search_handler(
+    State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(mut payload): Json<SearchRequest>,
+) -> Result<Versioned<SearchResponse>, ApiError> {
+    let started = Instant::now();
+    let version = ApiVersion::from_headers(&headers)?;
+    let features = Features::from_request(&headers, &payload.features);
+    let config = &state.config;
+    let query_clean = payload.query.text();
+    let timings = payload.debug.then(|| Arc::new(StageTimings::default()));
+    
+    // 1. Identify Client, never logging more of the IP than allowed, nor the query text
+    let ip = client_ip_from(&headers, peer.map(|ConnectInfo(addr)| addr.ip()))
+        .map(anonymize_ip)
+        .unwrap_or_else(|| "unknown".to_string());
+    info!("SEARCH: query {} ({} chars) from IP: {}", query_digest(&query_clean), query_clean.chars().count(), ip);
+    metrics().record_query(&query_clean);
+
+    let k = payload.k.unwrap_or(config.results_to_return).clamp(1, MAX_SEARCH_K);
+    let offset = payload.offset.unwrap_or(0);
+    if offset > MAX_PAGE_END - k {
+        return Err(AppError::BadRequest(format!("offset + k must be at most {}", MAX_PAGE_END)).into());
+    }
+    if payload.recency.is_some_and(|r| !(r.is_finite() && r >= 0.0)) {
+        return Err(AppError::BadRequest("recency must be a weight of at least 0".to_string()).into());
+    }
+    validate_query(&payload)?;
+    let context_token = merge_context(&state, &mut payload)?;
+
+    // 1a. Later pages are sliced from the pool stored by the first request,
+    // unless they lie past where its ranking stopped
+    if let Some(token) = &payload.page_token {
+        let pool = state.result_pools.get(token).ok_or_else(|| {
+            AppError::BadRequest("Page token expired or unknown, repeat the search".to_string())
+        })?;
+        if pool.covers(offset.saturating_add(k)) {
+            let mut response =
+                page_response(&state, &payload.context, &pool, Some(token.clone()), offset, k, features, timings.as_deref())
+                    .await?;
+            response.context_token = context_token;
+            response.timings = timings.map(|t| t.snapshot(started.elapsed()));
+            return Ok(Versioned { version, features, body: response });
+        }
+        debug!("Page {}..{} is past the stored pool, ranking deeper", offset, offset.saturating_add(k));
+    }
+
+    // 1b. Response cache: same query + graph context + options => same response
+    let response_key = state.response_cache.as_ref().map(|_| {
+        let mut context = payload.context.clone();
+        context.sort_unstable();
+        context.dedup();
+        let weights = [
+            config.weight_semantic,
+            config.weight_pagerank,
+            config.weight_pageviews,
+            config.weight_backlinks,
+            config.weight_title_match,
+            config.weight_recency,
+            config.obscurity_pageviews_below,
+            config.obscurity_pagerank_below,
+            config.obscurity_penalty,
+        ]
+        .map(f64::to_bits);
+        cache_key(&(
+            payload.query.cache_key(),
+            context,
+            k,
+            offset,
+            weights,
+            payload.rescore,
+            format!("{:?}", payload.search_params),
+            payload.debug,
+            payload.corpus.clone(),
+            CategoryFilter::new(&payload.include_categories, &payload.exclude_categories),
+            format!("{:?}", payload.filters),
+            // Hash is implemented for tuples of up to 12
+            (
+                format!("{:?}", payload.search_mode),
+                payload.lang.clone(),
+                payload.ranking,
+                payload.diversity.map(f32::to_bits),
+                {
+                    let mut anchors = payload.anchor_ids.clone();
+                    anchors.sort_unstable();
+                    anchors
+                },
+                payload.pooling,
+                payload.exclude.as_ref().map(QueryInput::cache_key),
+                features,
+                payload.recency.map(f64::to_bits),
+                format!("{:?}", (payload.deep_dive, payload.obscurity)),
+            ),
+        ))
+    });
+    if let (Some(cache), Some(key)) = (&state.response_cache, &response_key) {
+        if let Some(mut response) = cache.get(&state.db(), key).await {
+            debug!("Response cache hit for query {}", query_digest(&query_clean));
+            response.context_token = context_token;
+            response.timings = timings.map(|t| t.snapshot(started.elapsed()));
+            state.search_log.record(&query_clean, !response.results.is_empty());
+            return Ok(Versioned { version, features, body: response });
+        }
+    }
+
+    // 2. Encode Query, with the model of the selected corpus
+    let (corpus, federated) = match &payload.lang {
+        Some(_) if payload.corpus.is_some() => {
+            return Err(AppError::BadRequest("Pass either corpus or lang, not both".to_string()).into());
+        }
+        Some(lang) => {
+            let corpus = state
+                .corpus_for_lang(lang)
+                .ok_or_else(|| AppError::BadRequest(format!("No index for language '{}'", lang)))?;
+            (corpus, false)
+        }
+        None => resolve_corpus(&state, payload.corpus.as_deref())?,
+    };
+    let encode_started = Instant::now();
+    let query_vec = query_vector(&state, &corpus, &payload).await?;
+    let exclude = exclude_vectors(&state, &corpus, &payload).await?;
+    if let Some(timings) = &timings {
+        timings.since(Stage::Encode, encode_started);
+    }
+
+    // 3-5. Candidate search and ranking, optionally served from the semantic cache
+    let page_end = offset.saturating_add(k);
+    let depth = page_end.max(POOL_DEPTH);
+    let context_key = if config.weight_context != 0.0 {
+        let mut context = payload.context.clone();
+        context.sort_unstable();
+        context.dedup();
+        cache_key(&context)
+    } else {
+        String::new()
+    };
+    let variant = format!(
+        "{:?}|{:?}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{}|{}|{:?}|{:?}|{}|{:?}|{:?}|{}|{:?}",
+        payload.rescore,
+        payload.search_params,
+        payload.debug,
+        payload.corpus,
+        payload.lang,
+        CategoryFilter::new(&payload.include_categories, &payload.exclude_categories),
+        payload.filters,
+        payload.search_mode,
+        depth,
+        // The keyword backfill tops the pool up to the page
+        page_end,
+        payload.ranking,
+        payload.diversity,
+        // Results are ranked towards the graph context
+        context_key,
+        payload.exclude.as_ref().map(QueryInput::cache_key),
+        payload.recency,
+        payload.deep_dive,
+        payload.obscurity,
+    );
+    let cached = state
+        .semantic_cache
+        .as_ref()
+        .and_then(|cache| cache.lookup(&query_vec, &variant));
+
+    let (results, hydration) = match cached {
+        Some(results) => {
+            debug!("Semantic cache hit for query {}", query_digest(&query_clean));
+            (results, Hydration::Full)
+        }
+        None => {
+            let (results, hydration) = if federated {
+                let options = payload.rank_options(depth, page_end, &corpus, &exclude, timings.as_ref());
+                federated_rank(&state, &options, &query_clean, &query_vec, depth).await?
+            } else {
+                let options = payload.rank_options(depth, page_end, &corpus, &exclude, timings.as_ref());
+                rank_candidates(&corpus, &options, &query_clean, &query_vec).await?
+            };
+            // Partial results are a degraded answer, never cache them
+            if let (Some(cache), Hydration::Full) = (&state.semantic_cache, hydration) {
+                cache.insert(query_vec.clone(), variant, results.clone());
+            }
+            if hydration == Hydration::Partial {
+                // Don't wait for the next scheduled probe
+                state.db_health.request_check();
+            }
+            (results, hydration)
+        }
+    };
+
+    // Keep the full pool only when there is something beyond this page
+    let pool_corpus = if payload.lang.is_some() { Some(corpus.name.clone()) } else { payload.corpus.clone() };
+    // Ranking stops once the top `depth` are settled, keeping exactly that many
+    let complete = results.len() < depth;
+    let pool = Arc::new(RankedPool { corpus: pool_corpus, results, hydration, complete });
+    let token = pool.has_more(offset.saturating_add(k)).then(|| state.result_pools.insert(Arc::clone(&pool)));
+
+    let mut response = page_response(&state, &payload.context, &pool, token, offset, k, features, timings.as_deref()).await?;
+    if offset == 0 && hydration == Hydration::Full {
+        response.disambiguation = match disambiguate(&corpus, &query_clean, &pool.results).await {
+            Ok(disambiguation) => disambiguation.map(|mut d| {
+                d.senses.iter_mut().for_each(|s| s.id = corpus.index.ids.public(s.id));
+                d
+            }),
+            // Optional, never worth failing the search over
+            Err(e) => {
+                debug!("Disambiguation skipped for query {}: {}", query_digest(&query_clean), e);
+                None
+            }
+        };
+        response.did_you_mean = did_you_mean(&state, &query_clean, &pool.results);
+    }
+    state
+        .search_log
+        .record(&query_clean, hydration == Hydration::Full && !response.results.is_empty());
+    if let (Some(cache), Some(key), Hydration::Full) = (&state.response_cache, response_key, hydration) {
+        cache.put(&state.db(), key, response.clone()).await;
+    }
+    // Not cached: the token expires, the context it stands for is in the key;
+    // timings are this request's own
+    response.context_token = context_token;
+    response.timings = timings.map(|t| t.snapshot(started.elapsed()));
+
+    Ok(Versioned { version, features, body: response })
+}
+
+/// A spelling correction of `query` when even its best result is a weak
+/// semantic match; None before the spelling index is built.
+fn did_you_mean(state: &AppState, query: &str, results: &[SearchResult]) -> Option<String> {
+    let threshold = state.config.did_you_mean_threshold;
+    let best = results.iter().map(|r| r.signals.semantic).fold(0.0, f64::max);
+    if threshold <= 0.0 || best >= threshold {
+        return None;
+    }
+    state.spelling.load().as_ref()?.correct(query)
+}
+
+/// Merges the context stored behind `context_token` with `context` into
+/// `payload.context`, stored first. Returns the token for the merged context:
+/// the request's own when nothing was added, None for small contexts.
+fn merge_context(state: &AppState, payload: &mut SearchRequest) -> Result<Option<String>, AppError> {
+    let mut context: Vec<i64> = match &payload.context_token {
+        Some(token) => {
+            let stored = state.context_tokens.get(token).ok_or_else(|| {
+                AppError::BadRequest("Context token expired or unknown, resend the context IDs".to_string())
+            })?;
+            stored.as_ref().clone()
+        }
+        None => vec![],
+    };
+    let stored = context.len();
+    let mut seen: HashSet<i64> = context.iter().copied().collect();
+    context.extend(payload.context.iter().copied().filter(|id| seen.insert(*id)));
+    if context.len() > MAX_CONTEXT {
+        return Err(AppError::BadRequest(format!("At most {} context IDs", MAX_CONTEXT)));
+    }
+
+    let token = match payload.context_token.take() {
+        Some(token) if context.len() == stored => Some(token),
+        _ if context.len() < MIN_TOKEN_CONTEXT => None,
+        _ => Some(state.context_tokens.insert(Arc::new(context.clone()))),
+    };
+    payload.context = context;
+    Ok(token)
+}
+
+fn validate_query(payload: &SearchRequest) -> Result<(), AppError> {
+    let terms = payload.query.terms();
+    if terms.len() > MAX_QUERY_TERMS {
+        return Err(AppError::BadRequest(format!("At most {} query terms", MAX_QUERY_TERMS)));
+    }
+    if payload.anchor_ids.len() > MAX_ANCHORS {
+        return Err(AppError::BadRequest(format!("At most {} anchor_ids", MAX_ANCHORS)));
+    }
+    if payload.query.is_terms() && terms.is_empty() && payload.anchor_ids.is_empty() {
+        return Err(AppError::BadRequest("query has no terms".to_string()));
+    }
+    if payload.exclude.as_ref().is_some_and(|e| e.terms().len() > MAX_QUERY_TERMS) {
+        return Err(AppError::BadRequest(format!("At most {} exclude terms", MAX_QUERY_TERMS)));
+    }
+    // Non-finite values would reach MMR as full (NaN) or clamped diversity
+    if payload.diversity.is_some_and(|d| !(d.is_finite() && (0.0..=1.0).contains(&d))) {
+        return Err(AppError::BadRequest("diversity must be between 0 and 1".to_string()));
+    }
+    ObscurityPenalty::for_request(payload.deep_dive, payload.obscurity.as_ref())?;
+    Ok(())
+}
+
+/// The vector searched for: the query's embedding, or the pooled embeddings
+/// of its terms and anchor articles (see `query_expansion`).
+async fn query_vector(state: &AppState, corpus: &Corpus, payload: &SearchRequest) -> Result<Vec<f32>, AppError> {
+    let engine = &state.search_engine;
+    let terms = payload.query.terms();
+    if terms.len() == 1 && payload.anchor_ids.is_empty() {
+        return engine.encode_query_with(corpus.model.as_deref(), &terms[0]).await;
+    }
+
+    let mut vectors = try_join_all(terms.iter().map(|term| engine.encode_query_with(corpus.model.as_deref(), term))).await?;
+    let anchors = corpus.index.ids.articles_of(&payload.anchor_ids);
+    if !anchors.is_empty() {
+        let index = Arc::clone(&corpus.index);
+        vectors.extend(run_blocking(move |_| anchor_vectors(&index, &anchors)).await?);
+    }
+    pool(&vectors, payload.pooling.unwrap_or_default())
+        .ok_or_else(|| AppError::BadRequest("None of the query terms or anchor_ids could be embedded".to_string()))
+}
+
+/// Embeddings of the request's `exclude` terms, blank ones skipped.
+async fn exclude_vectors(state: &AppState, corpus: &Corpus, payload: &SearchRequest) -> Result<Vec<Vec<f32>>, AppError> {
+    let terms = payload.exclude.as_ref().map(QueryInput::terms).unwrap_or_default();
+    let terms = terms.iter().filter(|t| !t.trim().is_empty());
+    try_join_all(terms.map(|term| state.search_engine.encode_query_with(corpus.model.as_deref(), term))).await
+}
+
+/// Top `k` ranked articles for `query` with default request options, for
+/// endpoints that expand many queries server-side.
+pub(crate) async fn related_articles(
+    state: &AppState,
+    corpus: &Corpus,
+    query: &str,
+    k: usize,
+) -> Result<Vec<SearchResult>, AppError> {
+    let query_clean = query.replace('_', " ");
+    let query_vec = state.search_engine.encode_query_with(corpus.model.as_deref(), &query_clean).await?;
+    let options = RankOptions { limit: Some(k), ..Default::default() };
+    let (mut results, _) = rank_candidates(corpus, &options, &query_clean, &query_vec).await?;
+    results.truncate(k);
+    Ok(results)
+}
+
+/// `None` selects the primary corpus, `"all"` the primary one in federated mode.
+fn resolve_corpus(state: &AppState, name: Option<&str>) -> Result<(Corpus, bool), AppError> {
+    match name {
+        None => Ok((state.primary_corpus(), false)),
+        Some("all") => Ok((state.primary_corpus(), true)),
+        Some(name) => state
+            .corpus(name)
+            .map(|corpus| (corpus, false))
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name))),
+    }
+}
+
+/// Slices `offset..offset + k` out of a ranked pool and computes cross edges for it
+#[allow(clippy::too_many_arguments)]
+async fn page_response(
+    state: &AppState,
+    context: &[i64],
+    pool: &RankedPool,
+    token: Option<String>,
+    offset: usize,
+    k: usize,
+    features: Features,
+    timings: Option<&StageTimings>,
+) -> Result<SearchResponse, AppError> {
+    let config = get_config();
+    let (corpus, federated) = resolve_corpus(state, pool.corpus.as_deref())?;
+
+    let total_results = pool.results.len();
+    let mut results: Vec<SearchResult> = pool.results.iter().skip(offset).take(k).cloned().collect();
+    // The client's graph holds public (stable) IDs
+    let context = corpus.index.ids.articles_of(context);
+
+    // Edges need titles from the same DB that just timed out
+    if pool.hydration == Hydration::Partial {
+        publish_ids(state, &corpus, &mut results);
+        return Ok(SearchResponse {
+            results,
+            cross_edges: vec![],
+            edge_source: EdgeSource::of(&corpus.index),
+            total_results,
+            total_is_lower_bound: !pool.complete,
+            next_page_token: token.filter(|_| pool.has_more(offset.saturating_add(k))),
+            hydration: Hydration::Partial,
+            truncated: false,
+            clusters: None,
+            centrality: None,
+            disambiguation: None,
+            did_you_mean: None,
+            context_token: None,
+            timings: None,
+        });
+    }
+
+    attach_thumbnails(state, &corpus, &mut results).await;
+
+    // 6. Cross Edges
+    // Graph context IDs belong to the selected corpus (the primary one when federated)
+    let result_ids: Vec<i64> = results
+        .iter()
+        .filter(|r| r.corpus.as_deref().is_none_or(|c| c == corpus.name))
+        .map(|r| r.id)
+        .collect();
+
+    let edges_started = Instant::now();
+    let (mut cross_edges, truncated) = calculate_cross_edges(
+        &corpus.index,
+        &corpus.db,
+        &result_ids,
+        &context,
+        config.cross_edge_threshold as f32,
+        &EdgeBudget::from_config(),
+    ).await?;
+
+    if federated {
+        cross_edges.extend(cross_corpus_edges(state, &results, config.cross_edge_threshold as f32));
+    }
+    if let Some(timings) = timings {
+        timings.since(Stage::CrossEdges, edges_started);
+    }
+    let clusters = if features.has(Feature::Clusters) {
+        Some(result_clusters(&corpus, &result_ids).await?)
+    } else {
+        None
+    };
+    publish_ids(state, &corpus, &mut results);
+    let centrality = features.has(Feature::Centrality).then(|| weighted_degrees(&results, &cross_edges));
+
+    Ok(SearchResponse {
+        results,
+        cross_edges,
+        edge_source: EdgeSource::of(&corpus.index),
+        total_results,
+        total_is_lower_bound: !pool.complete,
+        next_page_token: token.filter(|_| pool.has_more(offset.saturating_add(k))),
+        hydration: Hydration::Full,
+        truncated,
+        clusters,
+        centrality,
+        disambiguation: None,
+        did_you_mean: None,
+        context_token: None,
+        timings: None,
+    })
+}
+
+/// Public ID -> cluster label of the results of `corpus` (article IDs), by
+/// spherical k-means over their vectors; empty if the index can't reconstruct.
+async fn result_clusters(corpus: &Corpus, ids: &[i64]) -> Result<HashMap<i64, usize>, AppError> {
+    if !corpus.index.can_reconstruct || ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let (index, ids) = (Arc::clone(&corpus.index), ids.to_vec());
+    let labels = run_blocking(move |_| {
+        let (ids, vectors): (Vec<i64>, Vec<Vec<f32>>) =
+            ids.into_iter().filter_map(|id| index.reconstruct(id).ok().map(|v| (id, v))).unzip();
+        let clustering = kmeans_cosine(&vectors, default_k(vectors.len()));
+        Ok(ids.into_iter().zip(clustering.labels).collect::<Vec<_>>())
+    })
+    .await?;
+    Ok(labels.into_iter().map(|(id, label)| (corpus.index.ids.public(id), label)).collect())
+}
+
+/// Result ID -> weighted degree in the page's cross-edge graph. Edges name
+/// their endpoints by title, so results are matched by title.
+fn weighted_degrees(results: &[SearchResult], edges: &[EdgeResult]) -> HashMap<i64, f32> {
+    let mut degrees: HashMap<&str, f32> = HashMap::new();
+    for edge in edges {
+        *degrees.entry(&*edge.source).or_default() += edge.score;
+        *degrees.entry(&*edge.target).or_default() += edge.score;
+    }
+    results.iter().map(|r| (r.id, degrees.get(&*r.title).copied().unwrap_or(0.0))).collect()
+}
+
+/// Replaces each result's article ID with the public ID of its corpus.
+fn publish_ids(state: &AppState, corpus: &Corpus, results: &mut [SearchResult]) {
+    for result in results {
+        result.id = match result.corpus.as_deref() {
+            Some(name) if name != corpus.name => match state.corpus(name) {
+                Some(other) => other.index.ids.public(result.id),
+                None => result.id,
+            },
+            _ => corpus.index.ids.public(result.id),
+        };
+    }
+}
+
+/// Ranks every corpus in parallel, scales each corpus's scores by its best score
+/// so they are comparable, merges, and collapses cross-corpus duplicates.
+/// At most `depth` results are taken from each corpus. Corpora with their own
+/// embedding model (per-language indexes) can't use `query_vec` and are left out.
+async fn federated_rank(
+    state: &AppState,
+    options: &RankOptions,
+    query_clean: &str,
+    query_vec: &[f32],
+    depth: usize,
+) -> Result<(Vec<SearchResult>, Hydration), AppError> {
+    let config = get_config();
+    let corpora: Vec<Corpus> = state.all_corpora().into_iter().filter(|c| c.model.is_none()).collect();
+
+    // Diversified once the corpora are merged, not per corpus; the graph
+    // context's IDs belong to the primary corpus
+    let primary = RankOptions { diversity: None, ..options.clone() };
+    let others = RankOptions { context: vec![], ..primary.clone() };
+    let ranked = join_all(corpora.iter().map(|corpus| {
+        let options = if corpus.name == config.corpus_name { &primary } else { &others };
+        rank_candidates(corpus, options, query_clean, query_vec)
+    }))
+    .await;
+
+    let mut hydration = Hydration::Full;
+    let mut merged: Vec<(usize, SearchResult)> = Vec::new();
+    for (corpus_idx, (corpus, results)) in corpora.iter().zip(ranked).enumerate() {
+        let results = match results {
+            Ok((results, corpus_hydration)) => {
+                if corpus_hydration == Hydration::Partial {
+                    hydration = Hydration::Partial;
+                }
+                results
+            }
+            Err(e) => {
+                warn!("Federated search: corpus '{}' failed: {}", corpus.name, e);
+                continue;
+            }
+        };
+
+        let best = results.first().map(|r| r.score_float).unwrap_or(0.0);
+        for mut result in results.into_iter().take(depth) {
+            result.score_float = if best > 0.0 { result.score_float / best } else { 0.0 };
+            result.score = (result.score_float * 100.0) as i32;
+            result.corpus = Some(corpus.name.clone());
+            merged.push((corpus_idx, result));
+        }
+    }
+    merged.sort_by(|a, b| b.1.score_float.total_cmp(&a.1.score_float));
+
+    // Same topic in several corpora: keep the best-scoring copy
+    let fingerprints: Vec<ArticleFingerprint> = merged
+        .iter()
+        .map(|(corpus_idx, r)| {
+            let corpus = &corpora[*corpus_idx];
+            let embedding = corpus.index.reconstruct(r.id).unwrap_or_default();
+            ArticleFingerprint::new(&corpus.name, r.id, &r.title, &embedding)
+        })
+        .collect();
+    let outcome = dedup_across_corpora(&fingerprints, config.dedup_threshold);
+    if !outcome.duplicates.is_empty() {
+        debug!("Federated search: collapsed {} cross-corpus duplicates", outcome.duplicates.len());
+    }
+
+    // MMR over the merged head, on the fingerprints' (unit length) embeddings
+    let mut order: Vec<usize> = outcome.kept;
+    if let Some(diversity) = options.diversity.filter(|d| *d > 0.0) {
+        let window = config.mmr_window.min(order.len());
+        let relevance: Vec<f64> = order[..window].iter().map(|&i| merged[i].1.score_float).collect();
+        let vectors: Vec<Option<Vec<f32>>> = order[..window]
+            .iter()
+            .map(|&i| Some(fingerprints[i].embedding.clone()).filter(|v| v.iter().any(|x| *x != 0.0)))
+            .collect();
+        let head: Vec<usize> = mmr_order(&relevance, &vectors, diversity).into_iter().map(|j| order[j]).collect();
+        order.splice(..window, head);
+    }
+
+    // Duplicates aren't in `order`, so they're dropped here
+    let mut merged: Vec<Option<SearchResult>> = merged.into_iter().map(|(_, r)| Some(r)).collect();
+    let results = order.into_iter().filter_map(|i| merged[i].take()).collect();
+    Ok((results, hydration))
+}
+
+/// Semantic edges between results that come from different corpora.
+fn cross_corpus_edges(state: &AppState, results: &[SearchResult], threshold: f32) -> Vec<EdgeResult> {
+    let corpora = state.all_corpora();
+    let vectors: Vec<Option<Vec<f32>>> = results
+        .iter()
+        .map(|r| {
+            let corpus = corpora.iter().find(|c| Some(c.name.as_str()) == r.corpus.as_deref())?;
+            let v = corpus.index.reconstruct(r.id).ok()?;
+            let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+            Some(v.into_iter().map(|x| x / norm).collect())
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for i in 0..results.len() {
+        for j in (i + 1)..results.len() {
+            if results[i].corpus == results[j].corpus {
+                continue;
+            }
+            let (Some(a), Some(b)) = (&vectors[i], &vectors[j]) else {
+                continue;
+            };
+            let score: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+            if score >= threshold {
+                edges.push(EdgeResult {
+                    source: results[i].title.clone(),
+                    target: results[j].title.clone(),
+                    source_id: results[i].id,
+                    target_id: results[j].id,
+                    score,
+                    source_corpus: results[i].corpus.clone(),
+                    target_corpus: results[j].corpus.clone(),
+                    origin: EdgeOrigin::Computed,
+                    model_version: MODEL_VERSION.to_string(),
+                    wikilink: None,
+                });
+            }
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use serde_json::{json, Map, Value};
+
+    /// Fields of a search request, each given any JSON value
+    const FIELDS: &[&str] = &[
+        "query", "anchor_ids", "pooling", "exclude", "context", "context_token", "k", "debug", "rescore",
+        "search_params", "corpus", "offset", "page_token", "include_categories", "exclude_categories",
+        "filters", "search_mode", "lang", "ranking", "diversity", "recency", "obscurity", "deep_dive", "features",
+    ];
+
+    fn any_json() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            any::<f64>().prop_map(Value::from),
+            "\\PC{0,12}".prop_map(Value::from),
+        ];
+        leaf.prop_recursive(3, 24, 6, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..6).prop_map(Value::Array),
+                prop::collection::btree_map("[a-z_]{1,12}", inner, 0..4)
+                    .prop_map(|fields| Value::Object(fields.into_iter().collect())),
+            ]
+        })
+    }
+
+    fn any_request() -> impl Strategy<Value = Value> {
+        prop::collection::vec((prop::sample::select(FIELDS), any_json()), 0..8).prop_map(|fields| {
+            let mut body: Map<String, Value> = fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+            body.entry("query").or_insert_with(|| json!("quantum"));
+            Value::Object(body)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bodies_are_rejected_or_validated(body in any_request()) {
+            if let Ok(request) = serde_json::from_value::<SearchRequest>(body) {
+                if validate_query(&request).is_ok() {
+                    prop_assert!(request.query.terms().len() <= MAX_QUERY_TERMS);
+                    prop_assert!(request.anchor_ids.len() <= MAX_ANCHORS);
+                }
+                let _ = request.query.cache_key();
+            }
+        }
+
+        #[test]
+        fn term_limits_are_enforced(terms in prop::collection::vec("[a-z]{1,8}", 0..16), anchors in prop::collection::vec(any::<i64>(), 0..48)) {
+            let request: SearchRequest = serde_json::from_value(json!({ "query": terms, "anchor_ids": anchors })).unwrap();
+            let valid = terms.len() <= MAX_QUERY_TERMS
+                && anchors.len() <= MAX_ANCHORS
+                && !(terms.is_empty() && anchors.is_empty());
+            prop_assert_eq!(validate_query(&request).is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn diversity_must_be_a_finite_share() {
+        let mut request: SearchRequest = serde_json::from_value(json!({ "query": "quantum" })).unwrap();
+        for diversity in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -0.1, 1.5] {
+            request.diversity = Some(diversity);
+            assert!(matches!(validate_query(&request), Err(AppError::BadRequest(_))), "{}", diversity);
+        }
+        for diversity in [0.0, 0.5, 1.0] {
+            request.diversity = Some(diversity);
+            assert!(validate_query(&request).is_ok());
+        }
+    }
+}
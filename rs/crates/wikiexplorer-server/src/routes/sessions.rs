@@ -0,0 +1,359 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::http::{HeaderMap, HeaderName};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::routes::admin::require_admin;
+use crate::routes::util::pagination::{decode_cursor, Page, PageLimits, PageParams};
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use crate::utils::api_version::{ApiVersion, Versioned};
+use crate::utils::features::Features;
+use wikiexplorer_core::search::cross_edges::{calculate_cross_edges, EdgeBudget, EdgeResult};
+use wikiexplorer_core::sessions::snapshots::{self, CorpusVersion, EdgeShift, SnapshotInfo};
+use wikiexplorer_core::sessions::thumbnail::{list_sessions, SessionListing};
+use wikiexplorer_core::sessions::{self, Session, SessionGraph};
+use wikiexplorer_core::utils::cancel::run_blocking;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// Carries the edit token a session was created with on `PUT` and `DELETE`
+pub const EDIT_TOKEN_HEADER: HeaderName = HeaderName::from_static("x-edit-token");
+
+const MAX_SESSION_NODES: usize = 5000;
+const MAX_COMPARED_SHIFTS: usize = 200;
+const LISTING_LIMITS: PageLimits = PageLimits::new(50, 200);
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CreateSessionRequest {
+    name: String,
+    #[serde(default)]
+    corpus: Option<String>, // Defaults to the primary corpus
+    graph: SessionGraph,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct UpdateSessionRequest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    graph: Option<SessionGraph>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionQuery {
+    /// Recompute semantic edges between the saved nodes (served from the edge cache where possible)
+    #[serde(default)]
+    cross_edges: bool,
+    /// Clamped to [0, 1]
+    #[serde(default)]
+    threshold: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    /// Snapshot IDs; default to the two most recent
+    #[serde(default)]
+    from: Option<i64>,
+    #[serde(default)]
+    to: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SnapshotComparison {
+    from: i64,
+    to: i64,
+    /// Node ID -> title for every node in `shifts`
+    titles: HashMap<i64, String>,
+    shifts: Vec<EdgeShift>,
+}
+
+/// A new session and the token for changing or deleting it, returned only here
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CreatedSession {
+    #[serde(flatten)]
+    session: Session,
+    edit_token: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SessionResponse {
+    #[serde(flatten)]
+    session: Session,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cross_edges: Option<Vec<EdgeResult>>,
+}
+
+pub async fn create_session_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateSessionRequest>,
+) -> Result<Json<CreatedSession>, ApiError> {
+    let name = validate_name(&request.name)?;
+    validate_graph(&request.graph)?;
+    let corpus = match request.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+
+    let pool = state.user_db();
+    sessions::ensure_sessions_table(&pool).await?;
+    let id = Uuid::new_v4().to_string();
+    let edit_token = Uuid::new_v4().simple().to_string();
+    let id_space = corpus.index.ids.id_space();
+    sessions::insert_session(&pool, &id, &name, &corpus.name, &request.graph, id_space, &edit_token).await?;
+    state.thumbnails.enqueue(&id);
+
+    info!("SESSION {}: saved '{}' ({} nodes)", id, name, request.graph.nodes.len());
+    let session = sessions::fetch_session(&pool, &id).await?;
+    Ok(Json(CreatedSession { session, edit_token }))
+}
+
+/// `GET /api/session?limit=&cursor=`: every saved session, most recently
+/// updated first, with thumbnails instead of full graphs. Admin only: session
+/// UUIDs are the only thing protecting a shared graph, so they aren't listed
+/// publicly. Thumbnails are queued when a graph is saved, not here. v1 clients
+/// get a bare array and page with `offset`.
+pub async fn list_sessions_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<PageParams>,
+) -> Result<Versioned<Page<SessionListing>>, ApiError> {
+    require_admin(&headers, state.config)?;
+    let version = ApiVersion::from_headers(&headers)?;
+    let limit = LISTING_LIMITS.resolve(params.limit);
+    let after: Option<(i64, String)> = decode_cursor(params.cursor.as_deref())?;
+    let offset = if after.is_some() { 0 } else { params.offset.unwrap_or(0) };
+    let pool = state.user_db();
+    let rows = list_sessions(&pool, limit as i64 + 1, after, offset.min(i64::MAX as usize) as i64).await?;
+    let body = Page::from_overfetch(rows, limit, |s| (s.updated_at, s.id.clone()));
+    Ok(Versioned { version, features: Features::default(), body })
+}
+
+/// Reopens a saved session. With `?cross_edges=true` semantic edges between its
+/// nodes are recomputed, hitting the edge cache for anything seen before, within
+/// the interactive cross-edge budget.
+pub async fn get_session_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<SessionQuery>,
+) -> Result<Json<SessionResponse>, ApiError> {
+    let session = load(&state, &id).await?;
+    spawn_snapshot(&state, &session);
+
+    let cross_edges = if params.cross_edges {
+        let corpus = state
+            .corpus(&session.corpus)
+            .ok_or_else(|| AppError::BadRequest(format!("Session corpus '{}' is not loaded", session.corpus)))?;
+        let threshold = params
+            .threshold
+            .filter(|t| t.is_finite())
+            .unwrap_or(state.config.cross_edge_threshold as f32)
+            .clamp(0.0, 1.0);
+        let ids = corpus.index.ids.articles_of(&session.graph.node_ids());
+        let budget = EdgeBudget::from_config();
+        let (edges, _) = calculate_cross_edges(&corpus.index, &corpus.db, &ids, &[], threshold, &budget).await?;
+        Some(edges)
+    } else {
+        None
+    };
+
+    Ok(Json(SessionResponse { session, cross_edges }))
+}
+
+/// Needs the session's edit token (see [`require_edit_token`]).
+pub async fn update_session_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateSessionRequest>,
+) -> Result<Json<Session>, ApiError> {
+    let id = parse_id(&id)?;
+    let name = request.name.as_deref().map(validate_name).transpose()?;
+    if let Some(graph) = &request.graph {
+        validate_graph(graph)?;
+    }
+
+    let pool = state.user_db();
+    sessions::ensure_sessions_table(&pool).await?;
+    require_edit_token(&state, &headers, &id).await?;
+    // A new graph's IDs are in the ID space of the session's corpus as loaded now
+    let graph = match &request.graph {
+        Some(graph) => {
+            let corpus = sessions::fetch_session(&pool, &id).await?.corpus;
+            let corpus = state
+                .corpus(&corpus)
+                .ok_or_else(|| AppError::BadRequest(format!("Session corpus '{}' is not loaded", corpus)))?;
+            Some((graph, corpus.index.ids.id_space()))
+        }
+        None => None,
+    };
+    if !sessions::update_session(&pool, &id, name.as_deref(), graph).await? {
+        return Err(AppError::NotFound(format!("Unknown session '{}'", id)).into());
+    }
+    let session = sessions::fetch_session(&pool, &id).await?;
+    if request.graph.is_some() {
+        state.thumbnails.enqueue(&session.id);
+    }
+    spawn_snapshot(&state, &session);
+    Ok(Json(session))
+}
+
+/// Needs the session's edit token (see [`require_edit_token`]).
+pub async fn delete_session_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Session>, ApiError> {
+    let session = load(&state, &id).await?;
+    require_edit_token(&state, &headers, &session.id).await?;
+    sessions::delete_session(&state.user_db(), &session.id).await?;
+    snapshots::delete_snapshots(&state.user_db(), &session.id).await?;
+    Ok(Json(session))
+}
+
+/// `GET /api/session/{id}/snapshots`: edge-score snapshots, one per index version
+/// the session was opened under.
+pub async fn list_snapshots_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<SnapshotInfo>>, ApiError> {
+    let session = load(&state, &id).await?;
+    let pool = state.user_db();
+    snapshots::ensure_snapshots_table(&pool).await?;
+    Ok(Json(snapshots::list_snapshots(&pool, &session.id).await?))
+}
+
+/// `GET /api/session/{id}/snapshots/compare?from=&to=`: how edge scores between
+/// the session's nodes shifted from one corpus version to another.
+pub async fn compare_snapshots_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<CompareQuery>,
+) -> Result<Json<SnapshotComparison>, ApiError> {
+    let session = load(&state, &id).await?;
+    let pool = state.user_db();
+    snapshots::ensure_snapshots_table(&pool).await?;
+
+    let (from, to) = match (params.from, params.to) {
+        (Some(from), Some(to)) => (from, to),
+        _ => {
+            let list = snapshots::list_snapshots(&pool, &session.id).await?;
+            match list.as_slice() {
+                [.., previous, latest] => (params.from.unwrap_or(previous.id), params.to.unwrap_or(latest.id)),
+                _ => return Err(AppError::BadRequest("Session has fewer than two snapshots".to_string()).into()),
+            }
+        }
+    };
+
+    let before = snapshots::load_snapshot_edges(&pool, &session.id, from).await?;
+    let after = snapshots::load_snapshot_edges(&pool, &session.id, to).await?;
+    let mut shifts = snapshots::compare(&before, &after);
+    shifts.truncate(MAX_COMPARED_SHIFTS);
+
+    let titles = session
+        .graph
+        .nodes
+        .iter()
+        .filter(|n| shifts.iter().any(|s| s.source == n.id || s.target == n.id))
+        .map(|n| (n.id, n.title.clone()))
+        .collect();
+
+    Ok(Json(SnapshotComparison { from, to, titles, shifts }))
+}
+
+/// Records an edge-score snapshot in the background if none exists for the
+/// current index version (i.e. first touch after an index or model refresh).
+fn spawn_snapshot(state: &Arc<AppState>, session: &Session) {
+    let state = Arc::clone(state);
+    let session = session.clone();
+    tokio::spawn(async move {
+        if let Err(e) = record_snapshot(&state, &session).await {
+            warn!("⚠ Snapshot of session {} failed: {}", session.id, e);
+        }
+    });
+}
+
+async fn record_snapshot(state: &AppState, session: &Session) -> Result<(), AppError> {
+    let Some(corpus) = state.corpus(&session.corpus) else { return Ok(()) };
+    if !corpus.index.can_reconstruct || session.graph.nodes.is_empty() {
+        return Ok(());
+    }
+
+    let pool = state.user_db();
+    snapshots::ensure_snapshots_table(&pool).await?;
+    let index = Arc::clone(&corpus.index);
+    let version = tokio::task::spawn_blocking(move || CorpusVersion::of(&index))
+        .await
+        .map_err(|e| AppError::Anyhow(e.into()))?;
+    if snapshots::has_snapshot(&pool, &session.id, &version).await? {
+        return Ok(());
+    }
+
+    let index = Arc::clone(&corpus.index);
+    let graph = session.graph.clone();
+    let edges = run_blocking(move |cancel| snapshots::score_graph(&index, &graph, cancel)).await?;
+    snapshots::insert_snapshot(&pool, &session.id, &version, &edges).await?;
+
+    info!("SESSION {}: snapshot of {} edges for {}", session.id, edges.len(), version.version);
+    Ok(())
+}
+
+/// Fetches a session by UUID (also used by `/api/export?session=`).
+pub(crate) async fn load(state: &AppState, id: &str) -> Result<Session, AppError> {
+    let id = parse_id(id)?;
+    let pool = state.user_db();
+    sessions::ensure_sessions_table(&pool).await?;
+    sessions::fetch_session(&pool, &id).await
+}
+
+/// Checks `X-Edit-Token` against the token issued when session `id` was created.
+/// The UUID is shared to let others read a session; changing or deleting it
+/// takes the edit token, or the admin token (the only way for sessions saved
+/// before edit tokens).
+async fn require_edit_token(state: &AppState, headers: &HeaderMap, id: &str) -> Result<(), AppError> {
+    if require_admin(headers, state.config).is_ok() {
+        return Ok(());
+    }
+    let provided = headers.get(EDIT_TOKEN_HEADER).and_then(|h| h.to_str().ok());
+    let expected = sessions::edit_token_hash(&state.user_db(), id).await?;
+    let matches = match (provided, expected) {
+        (Some(token), Some(expected)) => bool::from(sessions::hash_edit_token(token).as_bytes().ct_eq(expected.as_bytes())),
+        _ => false,
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(AppError::Unauthorized)
+    }
+}
+
+fn parse_id(id: &str) -> Result<String, AppError> {
+    Uuid::parse_str(id)
+        .map(|u| u.to_string())
+        .map_err(|_| AppError::BadRequest(format!("Invalid session id '{}'", id)))
+}
+
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 200 {
+        return Err(AppError::BadRequest("Session name must be 1 to 200 characters".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_graph(graph: &SessionGraph) -> Result<(), AppError> {
+    if graph.nodes.len() > MAX_SESSION_NODES {
+        return Err(AppError::BadRequest(format!("At most {} nodes per session", MAX_SESSION_NODES)));
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::routes::util::pagination::{decode_cursor, encode_cursor, PageLimits};
+use crate::state::AppState;
+use crate::suggestions::{escape_like, load_stats, normalize_query, top_queries_with_prefix};
+use crate::utils::api_error::ApiError;
+use crate::utils::api_version::{ApiVersion, Versioned, VersionedResponse};
+use crate::utils::features::Features;
+use wikiexplorer_core::search::filter_policy::{default_policy, FilterVerdict};
+use wikiexplorer_core::search::ranking::normalize_pagerank;
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const LIMITS: PageLimits = PageLimits::new(10, 50);
+/// Prefix matches ranked, and so suggestions reachable by paging. Fixed, so
+/// every page is cut from the same ranking.
+const MAX_DEPTH: usize = 200;
+/// Learned queries added to the prefix matches
+const LEARNED_CANDIDATES: usize = 20;
+
+#[derive(Deserialize)]
+pub struct SuggestParams {
+    q: String,
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    corpus: Option<String>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Suggestion {
+    id: i64,
+    title: String,
+    pagerank: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SuggestResponse {
+    query: String,
+    suggestions: Vec<Suggestion>,
+    /// Pass as `cursor` for more suggestions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
+impl VersionedResponse for SuggestResponse {
+    /// v1: one page, no cursor
+    fn downgrade(value: &mut Value, to: u32) {
+        if let (1, Some(map)) = (to, value.as_object_mut()) {
+            map.remove("next_cursor");
+        }
+    }
+}
+
+/// Title autocomplete: case-insensitive prefix match in SQLite, exact title
+/// first, then pagerank blended with learned usage (`suggestion_stats`), then
+/// title. Pages up to MAX_DEPTH suggestions deep; the cursor is the position
+/// of the next page (v2 and later). Never touches the FAISS index or the model.
+pub async fn suggest_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<SuggestParams>,
+) -> Result<Versioned<SuggestResponse>, ApiError> {
+    let version = ApiVersion::from_headers(&headers)?;
+    let respond = |body| Ok(Versioned { version, features: Features::default(), body });
+    let query = params.q.trim().to_string();
+    let limit = LIMITS.resolve(params.limit);
+    let offset: usize = decode_cursor(params.cursor.as_deref())?.unwrap_or(0);
+    if query.is_empty() || offset >= MAX_DEPTH {
+        return respond(SuggestResponse { query, suggestions: vec![], next_cursor: None });
+    }
+
+    let corpus = match params.corpus.as_deref() {
+        None => state.primary_corpus(),
+        Some(name) => state
+            .corpus(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown corpus '{}'", name)))?,
+    };
+
+    // Titles are stored with underscores, which are also a LIKE wildcard
+    let title = query.replace(' ', "_");
+    let pattern = format!("{}%", escape_like(&title));
+
+    // Candidates: the pagerank prior's top titles plus the most used learned queries
+    let rows = sqlx::query_as::<_, (i64, String, Option<f64>)>(
+        "SELECT article_id, title, pagerank FROM articles
+         WHERE title LIKE ? ESCAPE '\\'
+         ORDER BY (title = ? COLLATE NOCASE) DESC, pagerank DESC
+         LIMIT ?",
+    )
+    .bind(&pattern)
+    .bind(&title)
+    .bind(MAX_DEPTH as i64)
+    .fetch_all(&corpus.db);
+    let mut rows = with_deadline("suggest prefix", rows).await?;
+
+    let learned = top_queries_with_prefix(&state.user_db(), &normalize_query(&query), LEARNED_CANDIDATES).await;
+    if !learned.is_empty() {
+        let sql = format!(
+            "SELECT article_id, title, pagerank FROM articles
+             WHERE title COLLATE NOCASE IN ({}) GROUP BY title COLLATE NOCASE",
+            placeholders(learned.len())
+        );
+        let mut learned_rows = sqlx::query_as::<_, (i64, String, Option<f64>)>(&sql);
+        for learned_query in &learned {
+            learned_rows = learned_rows.bind(learned_query.replace(' ', "_"));
+        }
+        let learned_rows = with_deadline("suggest learned", learned_rows.fetch_all(&corpus.db)).await?;
+        for row in learned_rows {
+            if rows.iter().all(|(id, _, _)| *id != row.0) {
+                rows.push(row);
+            }
+        }
+    }
+
+    let keys: Vec<String> = rows.iter().map(|(_, t, _)| normalize_query(t)).collect();
+    let usage = load_stats(&state.user_db(), &keys).await;
+    let exact_key = normalize_query(&query);
+    let usage_weight = state.config.suggest_usage_weight;
+
+    let policy = default_policy();
+    let mut ranked: Vec<(bool, f64, Suggestion)> = rows
+        .into_iter()
+        .zip(keys)
+        .filter(|((_, title, _), _)| !matches!(policy.verdict(title), FilterVerdict::Exclude))
+        .map(|((id, title, pagerank), key)| {
+            let learned = usage.get(&key).map(|u| u.score()).unwrap_or(0.0);
+            let score = normalize_pagerank(pagerank) + usage_weight * learned;
+            let id = corpus.index.ids.public(id);
+            (key == exact_key, score, Suggestion { id, title, pagerank })
+        })
+        .collect();
+    // Titles are unique, so the order is total and pages don't overlap
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.total_cmp(&a.1)).then_with(|| a.2.title.cmp(&b.2.title)));
+
+    let end = (offset + limit).min(MAX_DEPTH).min(ranked.len());
+    let next_cursor = (ranked.len() > end && end < MAX_DEPTH).then(|| encode_cursor(&end));
+    let suggestions = ranked.into_iter().skip(offset).take(end.saturating_sub(offset)).map(|(_, _, s)| s).collect();
+
+    respond(SuggestResponse { query, suggestions, next_cursor })
+}
+
+/// Case-insensitive title index so prefix LIKE lookups don't scan `articles`.
+/// Failures (e.g. a read-only DB) only cost speed, so they are logged and ignored.
+pub async fn ensure_title_index(pool: &SqlitePool) {
+    let result = sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_articles_title_nocase ON articles (title COLLATE NOCASE)",
+    )
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(_) => info!("✓ Title index ready for /api/suggest"),
+        Err(e) => warn!("⚠ Could not create title index, suggestions will scan: {:?}", e),
+    }
+}
@@ -0,0 +1,226 @@
+use axum::extract::{Json, Path, State};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::routes::sessions;
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::categories::fetch_categories;
+use wikiexplorer_core::graph::Graph;
+use wikiexplorer_core::search::clustering::{default_k, kmeans_cosine};
+use wikiexplorer_core::search::cross_edges::calculate_global_cross_edges;
+use wikiexplorer_core::utils::cancel::run_blocking;
+use wikiexplorer_core::utils::db_deadline::with_deadline;
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const TOP_NODES: usize = 10;
+const TOP_CATEGORIES: usize = 10;
+const CATEGORIES_PER_CLUSTER: usize = 3;
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct GraphSummary {
+    graph_id: String,
+    name: String,
+    node_count: usize,
+    edge_count: usize,
+    /// "embedding" (k-means over vectors) or "components" (index can't reconstruct)
+    clustering: &'static str,
+    clusters: Vec<ClusterSummary>,
+    central_nodes: Vec<NodeSummary>,
+    bridge_nodes: Vec<NodeSummary>,
+    top_categories: Vec<CategoryCount>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct ClusterSummary {
+    label: usize,
+    size: usize,
+    /// Best-connected member, used as the cluster's name
+    representative_id: i64,
+    representative_title: String,
+    top_categories: Vec<CategoryCount>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct NodeSummary {
+    id: i64,
+    title: String,
+    degree: usize,
+    score: f32,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+struct CategoryCount {
+    category: String,
+    count: usize,
+}
+
+/// `POST /api/graphs/{id}/summary` for a saved session: clusters, the most
+/// central nodes (weighted degree) and bridge nodes linking clusters.
+pub async fn graph_summary_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<GraphSummary>, ApiError> {
+    let session = sessions::load(&state, &id).await?;
+    let corpus = state
+        .corpus(&session.corpus)
+        .ok_or_else(|| AppError::BadRequest(format!("Session corpus '{}' is not loaded", session.corpus)))?;
+
+    // Sessions hold public IDs, and so does the graph below; the index and
+    // the DB are asked by article ID
+    let stable = &corpus.index.ids;
+    let ids = session.graph.node_ids();
+    let mut titles: HashMap<i64, String> = session
+        .graph
+        .nodes
+        .iter()
+        .filter(|n| !n.title.is_empty())
+        .map(|n| (n.id, n.title.clone()))
+        .collect();
+    let missing: Vec<i64> = ids.iter().filter(|id| !titles.contains_key(id)).cloned().collect();
+    let missing = stable.articles_of(&missing);
+    if !missing.is_empty() {
+        let params = placeholders(missing.len());
+        let sql = format!("SELECT article_id, title FROM articles WHERE article_id IN ({})", params);
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+        for id in &missing {
+            query = query.bind(id);
+        }
+        let rows = with_deadline("summary titles", query.fetch_all(&corpus.db)).await?;
+        titles.extend(rows.into_iter().map(|(id, title)| (stable.public(id), title)));
+    }
+
+    // Saved edges, or semantic edges when the session was saved without any
+    let mut edges: Vec<(i64, i64, f32)> =
+        session.graph.edges.iter().map(|e| (e.source, e.target, e.score.unwrap_or(1.0))).collect();
+    if edges.is_empty() && !ids.is_empty() {
+        let title_to_id: HashMap<&str, i64> = titles.iter().map(|(id, t)| (t.as_str(), *id)).collect();
+        let threshold = state.config.cross_edge_threshold as f32;
+        edges = calculate_global_cross_edges(&corpus.index, &corpus.db, &stable.articles_of(&ids), &[], threshold)
+            .await?
+            .into_iter()
+            .filter_map(|e| Some((*title_to_id.get(&*e.source)?, *title_to_id.get(&*e.target)?, e.score)))
+            .collect();
+    }
+    let graph = Graph::new(&ids, edges);
+
+    // Communities: embedding clusters where vectors are available, else connected components
+    let (communities, clustering) = if corpus.index.can_reconstruct && !ids.is_empty() {
+        let index = Arc::clone(&corpus.index);
+        let nodes = graph.nodes().to_vec();
+        let labels = run_blocking(move |cancel| {
+            let mut vectors = Vec::new();
+            let mut valid = Vec::new();
+            for id in nodes {
+                cancel.check()?;
+                let Some(article_id) = index.ids.article(id) else {
+                    continue;
+                };
+                if let Ok(v) = index.reconstruct(article_id) {
+                    vectors.push(v);
+                    valid.push(id);
+                }
+            }
+            let clustering = kmeans_cosine(&vectors, default_k(vectors.len()));
+            Ok(valid.into_iter().zip(clustering.labels).collect::<HashMap<i64, usize>>())
+        })
+        .await?;
+        (labels, "embedding")
+    } else {
+        (graph.connected_components(), "components")
+    };
+
+    let categories: HashMap<i64, Vec<String>> = fetch_categories(&corpus.db, &stable.articles_of(graph.nodes()))
+        .await?
+        .into_iter()
+        .map(|(id, categories)| (stable.public(id), categories))
+        .collect();
+    let node_summary = |id: i64, score: f32| NodeSummary {
+        id,
+        title: titles.get(&id).cloned().unwrap_or_default(),
+        degree: graph.degree(id),
+        score,
+    };
+
+    // Clusters, largest first
+    let mut members: HashMap<usize, Vec<i64>> = HashMap::new();
+    for (&id, &label) in &communities {
+        members.entry(label).or_default().push(id);
+    }
+    let mut clusters: Vec<ClusterSummary> = members
+        .into_values()
+        .map(|members| {
+            let representative = *members
+                .iter()
+                .max_by(|a, b| graph.weighted_degree(**a).total_cmp(&graph.weighted_degree(**b)).then(b.cmp(a)))
+                .expect("clusters are non-empty");
+            ClusterSummary {
+                label: 0,
+                size: members.len(),
+                representative_id: representative,
+                representative_title: titles.get(&representative).cloned().unwrap_or_default(),
+                top_categories: count_categories(&members, &categories, CATEGORIES_PER_CLUSTER),
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.size.cmp(&a.size).then(a.representative_id.cmp(&b.representative_id)));
+    for (label, cluster) in clusters.iter_mut().enumerate() {
+        cluster.label = label;
+    }
+
+    let mut central: Vec<(i64, f32)> = graph.nodes().iter().map(|&id| (id, graph.weighted_degree(id))).collect();
+    central.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut bridges: Vec<(i64, f32)> = graph
+        .nodes()
+        .iter()
+        .map(|&id| (id, graph.bridge_score(id, &communities)))
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    bridges.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let summary = GraphSummary {
+        graph_id: session.id,
+        name: session.name,
+        node_count: graph.nodes().len(),
+        edge_count: graph.edge_count(),
+        clustering,
+        clusters,
+        central_nodes: central.into_iter().take(TOP_NODES).map(|(id, s)| node_summary(id, s)).collect(),
+        bridge_nodes: bridges.into_iter().take(TOP_NODES).map(|(id, s)| node_summary(id, s)).collect(),
+        top_categories: count_categories(graph.nodes(), &categories, TOP_CATEGORIES),
+    };
+
+    info!(
+        "SUMMARY {}: {} nodes, {} edges, {} clusters",
+        summary.graph_id,
+        summary.node_count,
+        summary.edge_count,
+        summary.clusters.len()
+    );
+    Ok(Json(summary))
+}
+
+/// Most frequent categories among `ids`, ties broken alphabetically.
+fn count_categories(ids: &[i64], categories: &HashMap<i64, Vec<String>>, limit: usize) -> Vec<CategoryCount> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for id in ids {
+        for category in categories.get(id).into_iter().flatten() {
+            *counts.entry(category.as_str()).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<CategoryCount> = counts
+        .into_iter()
+        .map(|(category, count)| CategoryCount { category: category.to_string(), count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.category.cmp(&b.category)));
+    counts.truncate(limit);
+    counts
+}
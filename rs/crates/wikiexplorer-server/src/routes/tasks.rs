@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::routes::admin::require_admin;
+use crate::state::AppState;
+use crate::tasks::{TaskInfo, TaskStatus};
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// Every known task; admin only (see [`crate::tasks::TaskRegistry`]).
+pub async fn list_tasks_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<TaskInfo>>, ApiError> {
+    require_admin(&headers, state.config)?;
+    Ok(Json(state.tasks.list()))
+}
+
+pub async fn task_status_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<TaskInfo>, ApiError> {
+    let task = state.tasks.get(&id).ok_or_else(|| unknown_task(&id))?;
+    Ok(Json(task.info()))
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CancelResponse {
+    id: String,
+    status: &'static str,
+}
+
+pub async fn cancel_task_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<CancelResponse>, ApiError> {
+    if !state.tasks.cancel(&id) {
+        return Err(unknown_task(&id).into());
+    }
+    info!("TASK {}: cancellation requested", id);
+    Ok(Json(CancelResponse { id, status: "cancelling" }))
+}
+
+/// Streams the finished artifact as a download.
+pub async fn task_artifact_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let task = state.tasks.get(&id).ok_or_else(|| unknown_task(&id))?;
+    let artifact = match (task.status(), task.artifact()) {
+        (TaskStatus::Completed, Some(artifact)) => artifact,
+        (status, _) => {
+            return Err(AppError::BadRequest(format!("Task {} has no artifact (status {:?})", id, status)).into())
+        }
+    };
+
+    let body = tokio::fs::read(&artifact.path).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, artifact.content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", artifact.file_name)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+fn unknown_task(id: &str) -> AppError {
+    AppError::NotFound(format!("Unknown task '{}'", id))
+}
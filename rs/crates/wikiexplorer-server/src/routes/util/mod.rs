@@ -0,0 +1,3 @@
+//! Helpers shared by route handlers.
+
+pub mod pagination;
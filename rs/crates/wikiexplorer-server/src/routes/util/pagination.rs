@@ -0,0 +1,160 @@
+//! Page size limits and cursor pagination for listing and lookup endpoints.
+//!
+//! Every listing has a default and a maximum page size; larger requests are
+//! clamped to it, as they were before listings had cursors. Listings sort on a
+//! key that ends in a unique column, so the order is total and a cursor (the
+//! opaque encoding of the last returned item's key) resumes right after that
+//! item even while rows are added or removed.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::api_version::VersionedResponse;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// `?limit=&cursor=`, or `?limit=&offset=` for clients predating cursors
+#[derive(Debug, Default, Deserialize)]
+pub struct PageParams {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Items to skip; ignored with a `cursor`
+    #[serde(default)]
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+    pub default: usize,
+    pub max: usize,
+}
+
+impl PageLimits {
+    pub const fn new(default: usize, max: usize) -> Self {
+        Self { default, max }
+    }
+
+    /// The page size for `requested`, clamped to `1..=max`.
+    pub fn resolve(&self, requested: Option<usize>) -> usize {
+        requested.map_or(self.default, |limit| limit.clamp(1, self.max))
+    }
+}
+
+/// One page of a listing.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass as `cursor` for the next page; absent on the last one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// A page from `rows` fetched with `limit + 1`: the extra row only tells
+    /// that there is a next page, whose cursor is the key of the last kept row.
+    pub fn from_overfetch<K: Serialize>(mut rows: Vec<T>, limit: usize, key: impl Fn(&T) -> K) -> Self {
+        let more = rows.len() > limit;
+        rows.truncate(limit);
+        let next_cursor = if more { rows.last().map(|last| encode_cursor(&key(last))) } else { None };
+        Self { items: rows, next_cursor }
+    }
+}
+
+/// v1 listings were bare arrays of the items.
+impl<T: Serialize> VersionedResponse for Page<T> {
+    fn downgrade(value: &mut Value, to: u32) {
+        if to == 1 {
+            *value = value.get_mut("items").map(Value::take).unwrap_or_else(|| Value::Array(vec![]));
+        }
+    }
+}
+
+/// Opaque, URL-safe cursor for a sort key (hex of its JSON).
+pub fn encode_cursor<K: Serialize>(key: &K) -> String {
+    let json = serde_json::to_vec(key).unwrap_or_default();
+    json.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The sort key of a cursor from `encode_cursor`; None without a cursor.
+pub fn decode_cursor<K: DeserializeOwned>(cursor: Option<&str>) -> Result<Option<K>, AppError> {
+    let Some(cursor) = cursor.filter(|c| !c.is_empty()) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+    if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map(Some).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn decode(cursor: &str) -> Result<Option<(i64, String)>, AppError> {
+        decode_cursor(Some(cursor))
+    }
+
+    #[test]
+    fn missing_or_empty_cursors_start_at_the_beginning() {
+        assert!(decode_cursor::<i64>(None).unwrap().is_none());
+        assert!(decode_cursor::<i64>(Some("")).unwrap().is_none());
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        let valid = encode_cursor(&(42i64, "b".to_string()));
+        let truncated = &valid[..valid.len() - 2];
+        // Odd length, not hex, not ASCII (multi-byte chars must not be split), hex but not JSON
+        for cursor in ["abc", "zz", "0g", "éé", "00", truncated, &valid[1..]] {
+            assert!(matches!(decode(cursor), Err(AppError::BadRequest(_))), "{:?} was accepted", cursor);
+        }
+        // A cursor of another listing's key
+        assert!(decode_cursor::<i64>(Some(&valid)).is_err());
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        let limits = PageLimits::new(50, 200);
+        assert_eq!(limits.resolve(None), 50);
+        assert_eq!(limits.resolve(Some(0)), 1);
+        assert_eq!(limits.resolve(Some(20)), 20);
+        assert_eq!(limits.resolve(Some(200)), 200);
+        assert_eq!(limits.resolve(Some(usize::MAX)), 200);
+    }
+
+    #[test]
+    fn overfetched_rows_give_the_next_cursor() {
+        let page = Page::from_overfetch((0..6).collect(), 5, |row: &i64| *row);
+        assert_eq!(page.items, vec![0, 1, 2, 3, 4]);
+        assert_eq!(decode_cursor::<i64>(page.next_cursor.as_deref()).unwrap(), Some(4));
+
+        let last = Page::from_overfetch((0..5).collect(), 5, |row: &i64| *row);
+        assert_eq!(last.items.len(), 5);
+        assert!(last.next_cursor.is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn cursors_round_trip(id in any::<i64>(), name in "\\PC{0,24}") {
+            let cursor = encode_cursor(&(id, name.clone()));
+            prop_assert!(cursor.bytes().all(|b| b.is_ascii_hexdigit()));
+            prop_assert_eq!(decode(&cursor).unwrap(), Some((id, name)));
+        }
+
+        #[test]
+        fn arbitrary_cursors_never_panic(cursor in "\\PC{0,32}") {
+            let _ = decode(&cursor);
+        }
+    }
+}
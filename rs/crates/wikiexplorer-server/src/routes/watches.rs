@@ -0,0 +1,171 @@
+use axum::extract::{Json, Path, Query, State};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::routes::util::pagination::{decode_cursor, Page, PageLimits};
+use crate::state::AppState;
+use crate::utils::api_error::ApiError;
+use crate::watches::{ensure_watch_tables, run_watch, Watch, WatchUpdate};
+use wikiexplorer_core::utils::anonymize::query_digest;
+use wikiexplorer_core::utils::errors::AppError;
+
+const MAX_WATCHES_PER_USER: i64 = 100;
+const MAX_WATCH_K: usize = 200;
+const UPDATE_LIMITS: PageLimits = PageLimits::new(500, 5000);
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CreateWatchRequest {
+    query: String,
+    #[serde(default)]
+    k: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatesQuery {
+    #[serde(default)]
+    since: Option<i64>, // Unix seconds; default: everything
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct WatchUpdatesResponse {
+    user_id: String,
+    updates: Vec<WatchUpdate>,
+    /// Pass as `cursor` for older updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
+fn parse_user(id: &str) -> Result<String, AppError> {
+    Uuid::parse_str(id)
+        .map(|u| u.to_string())
+        .map_err(|_| AppError::BadRequest(format!("Invalid user id '{}'", id)))
+}
+
+/// Saves a query as a watch and records its current top-k as the baseline.
+pub async fn create_watch_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Json(request): Json<CreateWatchRequest>,
+) -> Result<Json<Watch>, ApiError> {
+    let user_id = parse_user(&user_id)?;
+    let query = request.query.trim().to_string();
+    if query.is_empty() {
+        return Err(AppError::BadRequest("Empty query".to_string()).into());
+    }
+    let k = request.k.unwrap_or(state.config.results_to_return).clamp(1, MAX_WATCH_K);
+
+    let pool = state.user_db();
+    ensure_watch_tables(&pool).await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM watches WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_one(&pool)
+        .await?;
+    if count >= MAX_WATCHES_PER_USER {
+        return Err(AppError::BadRequest(format!("At most {} watches per user", MAX_WATCHES_PER_USER)).into());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO watches (id, user_id, query, k) VALUES (?, ?, ?, ?)")
+        .bind(&id)
+        .bind(&user_id)
+        .bind(&query)
+        .bind(k as i64)
+        .execute(&pool)
+        .await?;
+
+    let mut watch = fetch_watch(&pool, &user_id, &id).await?;
+    run_watch(&state, &watch).await?;
+    watch = fetch_watch(&pool, &user_id, &id).await?;
+
+    info!("WATCH {}: user {} watching query {} (k={})", id, user_id, query_digest(&query), k);
+    Ok(Json(watch))
+}
+
+pub async fn list_watches_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> Result<Json<Vec<Watch>>, ApiError> {
+    let user_id = parse_user(&user_id)?;
+    let pool = state.user_db();
+    ensure_watch_tables(&pool).await?;
+
+    let watches = sqlx::query_as::<_, Watch>(
+        "SELECT id, user_id, query, k, last_results, created_at, last_run_at
+         FROM watches WHERE user_id = ? ORDER BY created_at DESC",
+    )
+    .bind(&user_id)
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(watches))
+}
+
+pub async fn delete_watch_handler(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, watch_id)): Path<(String, String)>,
+) -> Result<Json<Watch>, ApiError> {
+    let user_id = parse_user(&user_id)?;
+    let pool = state.user_db();
+    ensure_watch_tables(&pool).await?;
+
+    let watch = fetch_watch(&pool, &user_id, &watch_id).await?;
+    sqlx::query("DELETE FROM watches WHERE id = ?").bind(&watch_id).execute(&pool).await?;
+    sqlx::query("DELETE FROM watch_updates WHERE watch_id = ?").bind(&watch_id).execute(&pool).await?;
+    Ok(Json(watch))
+}
+
+/// Articles that entered any of the user's watches, newest first (then by
+/// rank), paged with `limit` and `cursor`.
+pub async fn watch_updates_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<UpdatesQuery>,
+) -> Result<Json<WatchUpdatesResponse>, ApiError> {
+    let user_id = parse_user(&user_id)?;
+    let limit = UPDATE_LIMITS.resolve(params.limit);
+    // (detected_at, rank, id) of the last update already listed
+    let after: Option<(i64, i64, i64)> = decode_cursor(params.cursor.as_deref())?;
+    let pool = state.user_db();
+    ensure_watch_tables(&pool).await?;
+
+    let (detected_at, rank, id) = after.map_or((None, None, None), |(d, r, i)| (Some(d), Some(r), Some(i)));
+    let rows = sqlx::query_as::<_, WatchUpdate>(
+        "SELECT u.id, u.watch_id, w.query, u.article_id, u.title, u.rank, u.detected_at
+         FROM watch_updates u JOIN watches w ON w.id = u.watch_id
+         WHERE u.user_id = ?1 AND u.detected_at >= ?2
+           AND (?3 IS NULL OR (-u.detected_at, u.rank, u.id) > (-?3, ?4, ?5))
+         ORDER BY u.detected_at DESC, u.rank ASC, u.id ASC
+         LIMIT ?6",
+    )
+    .bind(&user_id)
+    .bind(params.since.unwrap_or(0))
+    .bind(detected_at)
+    .bind(rank)
+    .bind(id)
+    .bind(limit as i64 + 1)
+    .fetch_all(&pool)
+    .await?;
+
+    let page = Page::from_overfetch(rows, limit, |u| (u.detected_at, u.rank, u.id));
+    Ok(Json(WatchUpdatesResponse { user_id, updates: page.items, next_cursor: page.next_cursor }))
+}
+
+async fn fetch_watch(pool: &sqlx::SqlitePool, user_id: &str, id: &str) -> Result<Watch, AppError> {
+    sqlx::query_as::<_, Watch>(
+        "SELECT id, user_id, query, k, last_results, created_at, last_run_at
+         FROM watches WHERE id = ? AND user_id = ?",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Unknown watch '{}'", id)))
+}
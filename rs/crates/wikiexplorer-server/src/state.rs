@@ -0,0 +1,282 @@
+use arc_swap::{ArcSwap, ArcSwapOption};
+use crate::pageimages::ImageQueue;
+use crate::pageviews::{self, PageviewRefresh};
+use crate::query_topics::QueryTopics;
+use crate::routes::search::{RankedPool, SearchResponse};
+use crate::suggestions::SearchLog;
+use crate::tasks::TaskRegistry;
+use crate::utils::db_health::DbHealth;
+use crate::utils::maintenance::Maintenance;
+use crate::wikisummary::SummaryProxy;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use wikiexplorer_core::config::{get_config, Config};
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::search::engine::SearchEngine;
+use wikiexplorer_core::search::pipeline::SearchResult;
+use wikiexplorer_core::search::query_store::QueryStore;
+use wikiexplorer_core::search::response_cache::{ensure_cache_table, ResponseCache};
+use wikiexplorer_core::search::result_pool::ResultPoolCache;
+use wikiexplorer_core::search::semantic_cache::SemanticCache;
+use wikiexplorer_core::search::spelling::SpellIndex;
+use wikiexplorer_core::sessions::thumbnail::ThumbnailQueue;
+use wikiexplorer_core::signals::SignalRegistry;
+
+pub struct AppState {
+    pub config: &'static Config,
+    // Swappable so the metadata DB can be replaced alongside a reloaded index
+    db: ArcSwap<SqlitePool>,
+    metadata_path: ArcSwap<String>,
+    // User-owned data (sessions, watches, ...); outlives every metadata swap
+    user_db: SqlitePool,
+    pub db_health: DbHealth,
+    /// Results of the periodic ANALYZE / vacuum job
+    pub maintenance: Maintenance,
+    /// Last run of the scheduled pageview refresh
+    pub pageview_refresh: PageviewRefresh,
+    /// Latest clustering of stored queries into topics
+    pub query_topics: QueryTopics,
+    /// Cached Wikipedia summaries for `/api/summary`
+    pub summaries: SummaryProxy,
+    /// Searches waiting for the suggestion rollup
+    pub search_log: SearchLog,
+    /// Sessions waiting for the thumbnail writer
+    pub thumbnails: ThumbnailQueue,
+    /// Articles waiting for a thumbnail lookup
+    pub images: ImageQueue,
+    /// Background jobs (bulk research graphs)
+    pub tasks: TaskRegistry,
+    pub search_engine: Arc<SearchEngine>,
+    pub semantic_cache: Option<Arc<SemanticCache<Vec<SearchResult>>>>,
+    pub response_cache: Option<ResponseCache<SearchResponse>>,
+    /// Full ranked pools for `page_token` requests
+    pub result_pools: ResultPoolCache<RankedPool>,
+    /// Graph contexts (public IDs) for `context_token` requests
+    pub context_tokens: ResultPoolCache<Vec<i64>>,
+    /// Title-word typo index for `did_you_mean`; None until built
+    pub spelling: ArcSwapOption<SpellIndex>,
+    /// Builds of `spelling` started so far: only the latest may store its index
+    spelling_generation: parking_lot::Mutex<u64>,
+    // Custom ranking signals registered in the metadata DB (reloaded with it)
+    signals: ArcSwap<SignalRegistry>,
+    /// Additional corpora from CORPORA (searched with `corpus: "<name>"` or `"all"`)
+    pub extra_corpora: Vec<Corpus>,
+    /// Serializes admin reloads
+    pub reload_lock: Mutex<()>,
+}
+
+impl AppState {
+    pub async fn new(db_pool: SqlitePool, user_db: SqlitePool) -> anyhow::Result<Self> {
+        let mut engine = SearchEngine::new()?;
+        
+        // We verify signals here (like Python's _verify_signals)
+        let mut signals = engine.available_signals.clone();
+        
+        // Check columns in DB
+        // Note: This is a simplified check. In Rust/SQLx we usually assume schema is known.
+        // But to match the Python logic of dynamic capability detection:
+        let _row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM articles")
+            .fetch_one(&db_pool)
+            .await?;
+            
+        // Assuming if table exists, we have the columns. 
+        // In a real migration scenario, we might query pragma_table_info.
+        signals.pagerank = true;
+        signals.pageviews = true;
+        signals.backlinks = true;
+        engine.available_signals = signals;
+
+        let config = get_config();
+        if let Some(path) = &config.query_store_path {
+            let ttl = Duration::from_secs(config.query_store_ttl_days * 86_400);
+            match QueryStore::open(path, ttl, config.query_store_keep_text).await {
+                Ok(store) => engine.set_query_store(store),
+                Err(e) => warn!("⚠ Query store at {} unavailable, embeddings won't be persisted: {}", path, e),
+            }
+        }
+
+        let semantic_cache = config.semantic_cache_enabled.then(|| {
+            Arc::new(SemanticCache::new(
+                config.semantic_cache_capacity,
+                config.semantic_cache_max_distance,
+            ))
+        });
+
+        let response_cache = if config.response_cache_enabled {
+            if config.response_cache_persist {
+                ensure_cache_table(&db_pool).await?;
+            }
+            Some(ResponseCache::new(
+                Duration::from_secs(config.response_cache_ttl_secs),
+                config.response_cache_capacity,
+                config.response_cache_persist,
+            ))
+        } else {
+            None
+        };
+
+        let registry = SignalRegistry::load(&db_pool).await;
+
+        let mut extra_corpora = Vec::new();
+        for spec in &config.extra_corpora {
+            extra_corpora.push(Corpus::load(spec).await?);
+        }
+
+        Ok(Self {
+            config,
+            signals: ArcSwap::from_pointee(registry),
+            db: ArcSwap::from_pointee(db_pool),
+            metadata_path: ArcSwap::from_pointee(config.metadata_path.clone()),
+            user_db,
+            db_health: DbHealth::new(),
+            maintenance: Maintenance::new(),
+            pageview_refresh: PageviewRefresh::new(),
+            query_topics: QueryTopics::new(),
+            summaries: SummaryProxy::new(&config.wiki_summary_api, config.summary_cache_ttl_secs, config.summary_cache_max_entries),
+            search_log: SearchLog::new(),
+            thumbnails: ThumbnailQueue::new(),
+            images: ImageQueue::new(),
+            tasks: TaskRegistry::new(
+                config.task_artifact_dir.clone().into(),
+                Duration::from_secs(config.task_retention_secs),
+                config.max_running_tasks,
+            ),
+            search_engine: Arc::new(engine),
+            semantic_cache,
+            response_cache,
+            result_pools: ResultPoolCache::new(
+                Duration::from_secs(config.page_token_ttl_secs),
+                config.page_pool_capacity,
+            ),
+            context_tokens: ResultPoolCache::new(
+                Duration::from_secs(config.context_token_ttl_secs),
+                config.context_token_capacity,
+            ),
+            spelling: ArcSwapOption::empty(),
+            spelling_generation: parking_lot::Mutex::new(0),
+            extra_corpora,
+            reload_lock: Mutex::new(()),
+        })
+    }
+
+    /// The active metadata pool (cheap clone of a shared handle)
+    pub fn db(&self) -> SqlitePool {
+        self.db.load().as_ref().clone()
+    }
+
+    /// The user DB pool: sessions, watches and other data users create
+    pub fn user_db(&self) -> SqlitePool {
+        self.user_db.clone()
+    }
+
+    /// Replaces the metadata pool; requests already holding the old one finish on it.
+    /// The custom signal registry is reloaded from the new DB, which also gets
+    /// the refreshed pageviews.
+    pub async fn swap_db(&self, pool: SqlitePool, metadata_path: String) {
+        if self.config.pageview_refresh_every.is_some() {
+            if let Err(e) = pageviews::apply_refreshed(&self.user_db, &pool).await {
+                warn!("⚠ Refreshed pageviews not applied to the new metadata DB: {}", e);
+            }
+        }
+        let registry = SignalRegistry::load(&pool).await;
+        self.signals.store(Arc::new(registry));
+        self.db.store(Arc::new(pool));
+        self.metadata_path.store(Arc::new(metadata_path));
+    }
+
+    /// Rebuilds the `did_you_mean` index from the active metadata DB in the
+    /// background; searches keep the previous one (or none) until it is done.
+    /// A build overtaken by a later one (reloads in quick succession) is dropped.
+    pub fn spawn_spelling_build(self: &Arc<Self>) {
+        if self.config.did_you_mean_threshold <= 0.0 {
+            return;
+        }
+        let generation = {
+            let mut latest = self.spelling_generation.lock();
+            *latest += 1;
+            *latest
+        };
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            match SpellIndex::build(&state.db(), state.config.spell_min_word_count).await {
+                Ok(index) => {
+                    // Held while storing, so a newer build can't start and finish in between
+                    let latest = state.spelling_generation.lock();
+                    if *latest != generation {
+                        info!("Spelling index build {} superseded by build {}, dropped", generation, *latest);
+                        return;
+                    }
+                    info!("✓ Spelling index: {} words in {:?}", index.len(), started.elapsed());
+                    state.spelling.store(Some(Arc::new(index)));
+                }
+                Err(e) => warn!("⚠ Spelling index unavailable, no did_you_mean suggestions: {}", e),
+            }
+        });
+    }
+
+    /// Path of the active metadata DB (changes on admin reload)
+    pub fn metadata_path(&self) -> String {
+        self.metadata_path.load().as_ref().clone()
+    }
+
+    pub fn signals(&self) -> Arc<SignalRegistry> {
+        self.signals.load_full()
+    }
+
+    /// Snapshot of the primary corpus (current index, DB and signals)
+    pub fn primary_corpus(&self) -> Corpus {
+        Corpus {
+            name: self.config.corpus_name.clone(),
+            index: self.search_engine.index(),
+            db: self.db(),
+            signals: self.signals(),
+            lang: Some(self.config.corpus_lang.clone()),
+            model: None,
+        }
+    }
+
+    /// The corpus serving `lang` (the primary one for CORPUS_LANG, else one of LANGUAGES)
+    pub fn corpus_for_lang(&self, lang: &str) -> Option<Corpus> {
+        let lang = lang.trim().to_lowercase();
+        if lang == self.config.corpus_lang {
+            return Some(self.primary_corpus());
+        }
+        self.extra_corpora.iter().find(|c| c.lang.as_deref() == Some(lang.as_str())).cloned()
+    }
+
+    /// Looks up a corpus by name; `None` for unknown names
+    pub fn corpus(&self, name: &str) -> Option<Corpus> {
+        if name == self.config.corpus_name {
+            return Some(self.primary_corpus());
+        }
+        self.extra_corpora.iter().find(|c| c.name == name).cloned()
+    }
+
+    /// Releases resources on shutdown: stops the inference workers and closes
+    /// every SQLite pool, waiting for open connections to finish.
+    pub async fn shutdown(&self) {
+        let engine = Arc::clone(&self.search_engine);
+        if let Err(e) = tokio::task::spawn_blocking(move || engine.shutdown_inference()).await {
+            warn!("⚠ Inference shutdown failed: {}", e);
+        }
+        for corpus in self.all_corpora() {
+            corpus.db.close().await;
+        }
+        self.user_db.close().await;
+        if let Some(store) = self.search_engine.query_store() {
+            store.close().await;
+        }
+        info!("✓ Database pools closed");
+    }
+
+    /// The primary corpus followed by every extra corpus
+    pub fn all_corpora(&self) -> Vec<Corpus> {
+        std::iter::once(self.primary_corpus())
+            .chain(self.extra_corpora.iter().cloned())
+            .collect()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,206 @@
+use parking_lot::Mutex;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::state::AppState;
+use wikiexplorer_core::utils::privacy::PrivacyPolicy;
+use wikiexplorer_core::utils::sql::placeholders;
+
+/// Events kept in memory between rollups; the oldest are dropped beyond this.
+const MAX_PENDING: usize = 100_000;
+const MAX_QUERY_LEN: usize = 200;
+/// Learned queries read per suggestion wanted, as some are suppressed
+const CANDIDATES_PER_SUGGESTION: usize = 4;
+
+/// Write-ahead buffer of searches. The handler only appends here; the rollup
+/// job aggregates the buffer into `suggestion_stats`, which `/api/suggest`
+/// blends with the pagerank prior.
+pub struct SearchLog {
+    pending: Mutex<VecDeque<(String, bool)>>,
+}
+
+impl SearchLog {
+    pub fn new() -> Self {
+        Self { pending: Mutex::new(VecDeque::new()) }
+    }
+
+    /// `success`: the search returned fully hydrated results.
+    pub fn record(&self, query: &str, success: bool) {
+        let key = normalize_query(query);
+        if key.is_empty() || key.len() > MAX_QUERY_LEN {
+            return;
+        }
+
+        let mut pending = self.pending.lock();
+        if pending.len() >= MAX_PENDING {
+            pending.pop_front();
+        }
+        pending.push_back((key, success));
+    }
+
+    fn drain(&self) -> Vec<(String, bool)> {
+        self.pending.lock().drain(..).collect()
+    }
+
+    /// Puts events back after a failed flush (DB unavailable) so they aren't lost.
+    fn restore(&self, mut events: Vec<(String, bool)>) {
+        let mut pending = self.pending.lock();
+        events.extend(pending.drain(..));
+        let overflow = events.len().saturating_sub(MAX_PENDING);
+        pending.extend(events.drain(..).skip(overflow));
+    }
+}
+
+/// Usage counts for one normalized query / title.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UsageStats {
+    pub searches: i64,
+    pub successes: i64,
+}
+
+impl UsageStats {
+    /// Successful searches count fully, unsuccessful ones a little; log-damped so
+    /// a few very popular queries don't drown the prior.
+    pub fn score(&self) -> f64 {
+        let failures = (self.searches - self.successes).max(0) as f64;
+        (1.0 + self.successes as f64 + 0.25 * failures).ln()
+    }
+}
+
+/// Escapes LIKE wildcards for use with `ESCAPE '\\'`.
+pub fn escape_like(raw: &str) -> String {
+    raw.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Lowercase, underscores as spaces, whitespace collapsed (matches titles too).
+pub fn normalize_query(raw: &str) -> String {
+    raw.replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+pub async fn ensure_stats_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS suggestion_stats (
+            query TEXT PRIMARY KEY,
+            searches INTEGER NOT NULL DEFAULT 0,
+            successes INTEGER NOT NULL DEFAULT 0,
+            last_seen INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Most searched learned queries starting with `prefix` (normalized), as
+/// published under the analytics privacy policy: queries searched by too few
+/// users are never offered, so one user's searches don't show up in everyone's
+/// suggestions.
+pub async fn top_queries_with_prefix(pool: &SqlitePool, prefix: &str, limit: usize) -> Vec<String> {
+    let pattern = format!("{}%", escape_like(prefix));
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        "SELECT query, searches FROM suggestion_stats WHERE query LIKE ? ESCAPE '\\'
+         ORDER BY successes DESC, searches DESC LIMIT ?",
+    )
+    .bind(pattern)
+    .bind((limit * CANDIDATES_PER_SUGGESTION) as i64)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let counts = rows.into_iter().map(|(query, searches)| (query, searches.max(0) as u64)).collect();
+    PrivacyPolicy::from_config()
+        .publish_top(counts)
+        .into_iter()
+        .take(limit)
+        .map(|(query, _)| query)
+        .collect()
+}
+
+/// Usage stats for the given normalized keys (none if the lookup fails).
+pub async fn load_stats(pool: &SqlitePool, keys: &[String]) -> HashMap<String, UsageStats> {
+    let mut stats = HashMap::new();
+    if keys.is_empty() {
+        return stats;
+    }
+
+    let params = placeholders(keys.len());
+    let sql = format!(
+        "SELECT query, searches, successes FROM suggestion_stats WHERE query IN ({})",
+        params
+    );
+    let mut query = sqlx::query_as::<_, (String, i64, i64)>(&sql);
+    for key in keys {
+        query = query.bind(key);
+    }
+
+    match query.fetch_all(pool).await {
+        Ok(rows) => {
+            for (key, searches, successes) in rows {
+                stats.insert(key, UsageStats { searches, successes });
+            }
+        }
+        Err(e) => warn!("Could not load suggestion stats: {:?}", e),
+    }
+    stats
+}
+
+async fn flush(pool: &SqlitePool, events: &[(String, bool)]) -> Result<usize, sqlx::Error> {
+    let mut rollup: HashMap<&str, UsageStats> = HashMap::new();
+    for (key, success) in events {
+        let entry = rollup.entry(key.as_str()).or_default();
+        entry.searches += 1;
+        entry.successes += *success as i64;
+    }
+
+    let mut tx = pool.begin().await?;
+    for (key, stats) in &rollup {
+        sqlx::query(
+            "INSERT INTO suggestion_stats (query, searches, successes, last_seen)
+             VALUES (?, ?, ?, strftime('%s', 'now'))
+             ON CONFLICT(query) DO UPDATE SET
+                 searches = searches + excluded.searches,
+                 successes = successes + excluded.successes,
+                 last_seen = excluded.last_seen",
+        )
+        .bind(key)
+        .bind(stats.searches)
+        .bind(stats.successes)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(rollup.len())
+}
+
+/// Background rollup of the search log into `suggestion_stats` every
+/// `SUGGEST_ROLLUP_SECS`.
+pub fn spawn_rollup(state: Arc<AppState>) {
+    let interval = Duration::from_secs(state.config.suggest_rollup_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let events = state.search_log.drain();
+            if events.is_empty() {
+                continue;
+            }
+
+            match flush(&state.user_db(), &events).await {
+                Ok(queries) => debug!("Suggestion rollup: {} searches over {} queries", events.len(), queries),
+                Err(e) => {
+                    warn!("⚠ Suggestion rollup failed, keeping {} events: {:?}", events.len(), e);
+                    state.search_log.restore(events);
+                }
+            }
+        }
+    });
+    info!("✓ Suggestion learning rollup every {:?}", interval);
+}
@@ -0,0 +1,226 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+use wikiexplorer_core::utils::time::unix_now;
+
+/// Background jobs (e.g. large research graphs) with progress, cancellation and
+/// a file artifact. At most `MAX_RUNNING_TASKS` run at once. Finished tasks and
+/// their artifacts are dropped after `TASK_RETENTION_SECS`.
+///
+/// A task's random ID is what gives access to it, like a session's UUID: only
+/// its submitter learns it, and listing every task is admin only.
+pub struct TaskRegistry {
+    tasks: Mutex<HashMap<String, Arc<Task>>>,
+    artifact_dir: PathBuf,
+    retention: Duration,
+    max_running: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+pub struct Task {
+    pub id: String,
+    pub kind: &'static str,
+    created_at: u64,
+    status: Mutex<TaskStatus>,
+    finished_at: AtomicU64,
+    progress: AtomicU64,
+    progress_message: Mutex<String>,
+    error: Mutex<Option<String>>,
+    cancelled: AtomicBool,
+    artifact: Mutex<Option<Artifact>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub path: PathBuf,
+    pub content_type: &'static str,
+    pub file_name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: &'static str,
+    pub status: TaskStatus,
+    pub progress: u64,
+    pub progress_message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_url: Option<String>,
+    pub created_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl Task {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `progress` is task specific (e.g. nodes discovered so far).
+    pub fn set_progress(&self, progress: u64, message: impl Into<String>) {
+        self.progress.store(progress, Ordering::Relaxed);
+        *self.progress_message.lock() = message.into();
+    }
+
+    pub fn complete(&self, artifact: Artifact) {
+        *self.artifact.lock() = Some(artifact);
+        self.finish(TaskStatus::Completed);
+    }
+
+    pub fn fail(&self, error: String) {
+        *self.error.lock() = Some(error);
+        self.finish(TaskStatus::Failed);
+    }
+
+    pub fn mark_cancelled(&self) {
+        self.finish(TaskStatus::Cancelled);
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        *self.status.lock()
+    }
+
+    pub fn artifact(&self) -> Option<Artifact> {
+        self.artifact.lock().clone()
+    }
+
+    fn finish(&self, status: TaskStatus) {
+        *self.status.lock() = status;
+        self.finished_at.store(unix_now(), Ordering::Relaxed);
+    }
+
+    pub fn info(&self) -> TaskInfo {
+        let status = self.status();
+        let finished_at = self.finished_at.load(Ordering::Relaxed);
+        TaskInfo {
+            id: self.id.clone(),
+            kind: self.kind,
+            status,
+            progress: self.progress.load(Ordering::Relaxed),
+            progress_message: self.progress_message.lock().clone(),
+            error: self.error.lock().clone(),
+            artifact_url: (status == TaskStatus::Completed).then(|| format!("/api/tasks/{}/artifact", self.id)),
+            created_at: self.created_at,
+            finished_at: (finished_at > 0).then_some(finished_at),
+        }
+    }
+}
+
+impl TaskRegistry {
+    pub fn new(artifact_dir: PathBuf, retention: Duration, max_running: usize) -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+            artifact_dir,
+            retention,
+            max_running,
+        }
+    }
+
+    /// Registers a running task; the caller runs the work with [`spawn_task`].
+    /// None when `max_running` tasks are already running.
+    pub fn create(&self, kind: &'static str) -> Option<Arc<Task>> {
+        self.prune();
+
+        let task = Arc::new(Task {
+            id: Uuid::new_v4().simple().to_string(),
+            kind,
+            created_at: unix_now(),
+            status: Mutex::new(TaskStatus::Running),
+            finished_at: AtomicU64::new(0),
+            progress: AtomicU64::new(0),
+            progress_message: Mutex::new("queued".to_string()),
+            error: Mutex::new(None),
+            cancelled: AtomicBool::new(false),
+            artifact: Mutex::new(None),
+        });
+        let mut tasks = self.tasks.lock();
+        let running = tasks.values().filter(|t| t.status() == TaskStatus::Running).count();
+        if running >= self.max_running {
+            return None;
+        }
+        tasks.insert(task.id.clone(), Arc::clone(&task));
+        Some(task)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Task>> {
+        self.tasks.lock().get(id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let mut infos: Vec<TaskInfo> = self.tasks.lock().values().map(|t| t.info()).collect();
+        infos.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        infos
+    }
+
+    /// Requests cancellation; the task stops at its next checkpoint.
+    /// Returns false for unknown tasks.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.get(id) {
+            Some(task) => {
+                task.cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Where a task should write its artifact.
+    pub fn artifact_path(&self, task: &Task, extension: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.artifact_dir)?;
+        Ok(self.artifact_dir.join(format!("{}.{}", task.id, extension)))
+    }
+
+    /// Forgets finished tasks past retention and deletes their artifacts.
+    fn prune(&self) {
+        let cutoff = unix_now().saturating_sub(self.retention.as_secs());
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|_, task| {
+            let finished_at = task.finished_at.load(Ordering::Relaxed);
+            let expired = finished_at > 0 && finished_at < cutoff;
+            if expired {
+                if let Some(artifact) = task.artifact() {
+                    if let Err(e) = std::fs::remove_file(&artifact.path) {
+                        warn!("Could not remove task artifact {:?}: {:?}", artifact.path, e);
+                    }
+                }
+            }
+            !expired
+        });
+    }
+}
+
+/// Runs a task's work in the background. Work that panics leaves the task
+/// `failed` instead of running forever.
+pub fn spawn_task<F>(task: Arc<Task>, work: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let handle = tokio::spawn(work);
+    tokio::spawn(async move {
+        if let Err(e) = handle.await {
+            warn!("⚠ TASK {} panicked: {}", task.id, e);
+            if task.status() == TaskStatus::Running {
+                task.fail("Internal error".to_string());
+            }
+        }
+    });
+}
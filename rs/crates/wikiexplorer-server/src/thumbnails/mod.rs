@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::state::AppState;
+use wikiexplorer_core::search::corpus::Corpus;
+use wikiexplorer_core::sessions::thumbnail::{describe, store_thumbnail};
+use wikiexplorer_core::sessions::{self, Session};
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::sql::placeholders;
+
+const WRITER_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background writer computing thumbnails for sessions queued in
+/// `AppState.thumbnails` (saved, updated, or listed without one).
+pub fn spawn_thumbnail_writer(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(WRITER_INTERVAL).await;
+
+            let ids = state.thumbnails.drain();
+            if ids.is_empty() {
+                continue;
+            }
+
+            let mut failed = Vec::new();
+            for id in ids {
+                match write_thumbnail(&state, &id).await {
+                    Ok(()) => {}
+                    // Deleted since it was queued
+                    Err(AppError::NotFound(_)) => {}
+                    Err(e) => {
+                        warn!("⚠ Thumbnail for session {} failed: {}", id, e);
+                        failed.push(id);
+                    }
+                }
+            }
+            if !failed.is_empty() {
+                state.thumbnails.restore(failed);
+            }
+        }
+    });
+    info!("✓ Session thumbnail writer every {:?}", WRITER_INTERVAL);
+}
+
+async fn write_thumbnail(state: &AppState, id: &str) -> Result<(), AppError> {
+    let pool = state.user_db();
+    let session = sessions::fetch_session(&pool, id).await?;
+    let pageviews = match state.corpus(&session.corpus) {
+        Some(corpus) => fetch_pageviews(&corpus, &session).await?,
+        None => HashMap::new(),
+    };
+
+    let thumbnail = describe(&session.graph, &pageviews);
+    store_thumbnail(&pool, id, &thumbnail).await?;
+    debug!("Thumbnail for session {}: {:?}", id, thumbnail.top_nodes);
+    Ok(())
+}
+
+/// Keyed by the session's (public) node IDs
+async fn fetch_pageviews(corpus: &Corpus, session: &Session) -> Result<HashMap<i64, i64>, AppError> {
+    let ids = corpus.index.ids.articles_of(&session.graph.node_ids());
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let params = placeholders(ids.len());
+    let sql = format!("SELECT article_id, pageviews FROM articles WHERE article_id IN ({})", params);
+    let mut query = sqlx::query_as::<_, (i64, Option<i64>)>(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    Ok(query
+        .fetch_all(&corpus.db)
+        .await?
+        .into_iter()
+        .filter_map(|(id, views)| Some((corpus.index.ids.public(id), views?)))
+        .collect())
+}
@@ -0,0 +1,62 @@
+//! HTTP responses for the core's [`AppError`]: handlers return
+//! `Result<_, ApiError>`, and `?` converts whatever converts into an `AppError`.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use wikiexplorer_core::utils::errors::{AppError, PROBLEM_JSON};
+use wikiexplorer_core::utils::metrics::metrics;
+
+#[derive(Debug)]
+pub struct ApiError(pub AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+// The sources `AppError` converts from, so `?` on them works in handlers too
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        Self(error.into())
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(error: std::io::Error) -> Self {
+        Self(error.into())
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    /// [`AppError::problem`] as `application/problem+json`; the request ID,
+    /// `instance` and content negotiation are added by `problem_details`.
+    fn into_response(self) -> Response {
+        let error = self.0;
+        let status = StatusCode::from_u16(error.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        match &error {
+            AppError::Timeout(_) | AppError::Upstream(_) => tracing::warn!("{}", error),
+            _ if status.is_server_error() => tracing::error!("{}: {:?}", error.title(), error),
+            _ => {}
+        }
+        if status.is_server_error() {
+            metrics().record_error(error.to_string());
+        }
+
+        let mut response = (status, [(header::CONTENT_TYPE, PROBLEM_JSON)], Json(error.problem())).into_response();
+        if let AppError::RateLimited(retry_after) = &error {
+            response.headers_mut().insert(header::RETRY_AFTER, (*retry_after).into());
+        }
+        response
+    }
+}
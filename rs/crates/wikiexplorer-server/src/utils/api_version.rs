@@ -0,0 +1,96 @@
+//! `X-Api-Version` negotiation. Response structs always serialize in the
+//! current schema; clients asking for an older version get it rewritten one
+//! version at a time by [`VersionedResponse::downgrade`], so a schema change
+//! and the frontends depending on it can be rolled out in either order.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use axum::response::{IntoResponse, Json, Response};
+use serde::Serialize;
+use serde_json::Value;
+
+use wikiexplorer_core::utils::errors::AppError;
+
+use crate::utils::api_error::ApiError;
+use crate::utils::features::{Features, FEATURES_HEADER};
+
+pub const API_VERSION_HEADER: HeaderName = HeaderName::from_static("x-api-version");
+
+/// Schema the response structs serialize to.
+/// v2: search results carry `relevance` instead of `score_float`; bodies carry `api_version`.
+pub const CURRENT_API_VERSION: u32 = 2;
+
+/// Oldest schema still served, one version back. Requests without the header
+/// get it: they come from frontends deployed before versioning existed.
+pub const OLDEST_API_VERSION: u32 = CURRENT_API_VERSION - 1;
+
+/// First schema whose bodies carry `api_version`
+const API_VERSION_FIELD_SINCE: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiVersion(pub u32);
+
+impl ApiVersion {
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        let Some(value) = headers.get(API_VERSION_HEADER) else {
+            return Ok(Self(OLDEST_API_VERSION));
+        };
+        match value.to_str().ok().and_then(|v| v.trim().parse::<u32>().ok()) {
+            Some(version) if (OLDEST_API_VERSION..=CURRENT_API_VERSION).contains(&version) => Ok(Self(version)),
+            _ => Err(AppError::BadRequest(format!(
+                "Unsupported X-Api-Version {:?}, this server speaks {}-{}",
+                value, OLDEST_API_VERSION, CURRENT_API_VERSION
+            ))),
+        }
+    }
+}
+
+/// A response body with per-version serde adapters.
+pub trait VersionedResponse: Serialize {
+    /// Rewrites `value`, in the schema of `to + 1`, into the schema of `to`.
+    fn downgrade(value: &mut Value, to: u32);
+}
+
+/// Serializes `body` in the negotiated schema, adding an `api_version` field
+/// from v2 on (v1 bodies keep their original shape) and echoing the version in the `X-Api-Version` response header (and the
+/// honored features, if any, in `X-Features`).
+pub struct Versioned<T> {
+    pub version: ApiVersion,
+    pub features: Features,
+    pub body: T,
+}
+
+impl<T: VersionedResponse> IntoResponse for Versioned<T> {
+    fn into_response(self) -> Response {
+        let mut value = match serde_json::to_value(&self.body) {
+            Ok(value) => value,
+            Err(e) => return ApiError(AppError::Anyhow(e.into())).into_response(),
+        };
+        for to in (self.version.0..CURRENT_API_VERSION).rev() {
+            T::downgrade(&mut value, to);
+        }
+        if let Value::Object(map) = &mut value {
+            if self.version.0 >= API_VERSION_FIELD_SINCE {
+                map.insert("api_version".to_string(), self.version.0.into());
+            }
+        }
+
+        let mut response = Json(value).into_response();
+        response.headers_mut().insert(API_VERSION_HEADER, HeaderValue::from(self.version.0));
+        if !self.features.is_empty() {
+            response.headers_mut().insert(FEATURES_HEADER, self.features.header_value());
+        }
+        response
+    }
+}
+
+/// Renames `from` to `to` in every object of the array at `value[array]`.
+pub fn rename_in_array(value: &mut Value, array: &str, from: &str, to: &str) {
+    let Some(items) = value.get_mut(array).and_then(Value::as_array_mut) else {
+        return;
+    };
+    for item in items.iter_mut().filter_map(Value::as_object_mut) {
+        if let Some(field) = item.remove(from) {
+            item.insert(to.to_string(), field);
+        }
+    }
+}
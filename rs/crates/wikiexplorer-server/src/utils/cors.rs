@@ -0,0 +1,40 @@
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use wikiexplorer_core::utils::cors::OriginPolicy;
+
+use crate::routes::sessions::EDIT_TOKEN_HEADER;
+use crate::utils::api_version::API_VERSION_HEADER;
+use crate::utils::features::FEATURES_HEADER;
+use crate::utils::request_log::REQUEST_ID_HEADER;
+
+/// CORS for the public search API (`ALLOWED_ORIGINS`)
+pub fn public_layer(policy: &OriginPolicy) -> CorsLayer {
+    layer(policy)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::CONTENT_TYPE, header::ACCEPT, API_VERSION_HEADER, FEATURES_HEADER, EDIT_TOKEN_HEADER])
+        .expose_headers([
+            header::RETRY_AFTER,
+            header::CONTENT_DISPOSITION,
+            API_VERSION_HEADER,
+            FEATURES_HEADER,
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+}
+
+/// CORS for admin endpoints (`ADMIN_ALLOWED_ORIGINS`). With no origins
+/// configured, browsers can't call them cross-origin at all.
+pub fn admin_layer(policy: &OriginPolicy) -> CorsLayer {
+    layer(policy)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+}
+
+fn layer(policy: &OriginPolicy) -> CorsLayer {
+    if policy.allows_any() {
+        return CorsLayer::new().allow_origin(tower_http::cors::Any);
+    }
+    let policy = policy.clone();
+    CorsLayer::new().allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+        origin.to_str().is_ok_and(|o| policy.allows(o))
+    }))
+}
@@ -0,0 +1,138 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Liveness of the primary metadata DB, probed in the background. While it is
+/// down, searches degrade to ID-only results (`hydration: "partial"`) and the
+/// monitor reconnects with exponential backoff.
+pub struct DbHealth {
+    healthy: AtomicBool,
+    failed_checks: AtomicU64,
+    reconnects: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    wake: Notify,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct DbHealthStats {
+    pub healthy: bool,
+    pub failed_checks: u64,
+    pub reconnects: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl DbHealth {
+    pub fn new() -> Self {
+        Self {
+            healthy: AtomicBool::new(true),
+            failed_checks: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Asks the monitor to probe now (e.g. after a request fell back to partial results).
+    pub fn request_check(&self) {
+        self.wake.notify_one();
+    }
+
+    pub fn stats(&self) -> DbHealthStats {
+        DbHealthStats {
+            healthy: self.is_healthy(),
+            failed_checks: self.failed_checks.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().clone(),
+        }
+    }
+
+    fn mark_healthy(&self) {
+        if !self.healthy.swap(true, Ordering::Relaxed) {
+            info!("✓ Metadata DB healthy again");
+        }
+        *self.last_error.lock() = None;
+    }
+
+    fn mark_failed(&self, error: &sqlx::Error) {
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            warn!("⚠ Metadata DB health check failed, entering degraded mode: {}", error);
+        }
+        self.failed_checks.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock() = Some(error.to_string());
+    }
+}
+
+async fn probe(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1 FROM articles LIMIT 1").execute(pool).await?;
+    Ok(())
+}
+
+/// Background task: probes the DB every `DB_HEALTH_INTERVAL_SECS` (or on request)
+/// and, once a probe fails, reconnects with backoff until a fresh pool works.
+pub fn spawn_monitor(state: Arc<AppState>) {
+    let interval = Duration::from_secs(state.config.db_health_interval_secs.max(1));
+    let max_backoff = Duration::from_secs(state.config.db_reconnect_max_backoff_secs.max(1));
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = state.db_health.wake.notified() => {}
+            }
+
+            match probe(&state.db()).await {
+                Ok(()) => state.db_health.mark_healthy(),
+                Err(e) => {
+                    state.db_health.mark_failed(&e);
+                    reconnect(&state, max_backoff).await;
+                }
+            }
+        }
+    });
+}
+
+async fn reconnect(state: &AppState, max_backoff: Duration) {
+    let mut backoff = Duration::from_secs(1);
+
+    loop {
+        let path = state.metadata_path();
+        let attempt = async {
+            let pool = SqlitePool::connect(&format!("sqlite:{}", path)).await?;
+            probe(&pool).await?;
+            Ok::<_, sqlx::Error>(pool)
+        };
+
+        match attempt.await {
+            Ok(pool) => {
+                // Don't race an admin reload swapping in a different DB
+                let _guard = state.reload_lock.lock().await;
+                if probe(&state.db()).await.is_err() {
+                    state.swap_db(pool, path.clone()).await;
+                    state.db_health.reconnects.fetch_add(1, Ordering::Relaxed);
+                    info!("✓ Reconnected to metadata DB at {}", path);
+                }
+                state.db_health.mark_healthy();
+                return;
+            }
+            Err(e) => {
+                state.db_health.mark_failed(&e);
+                warn!("⚠ Reconnect to {} failed, retrying in {:?}", path, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+            }
+        }
+    }
+}
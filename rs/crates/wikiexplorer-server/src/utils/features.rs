@@ -0,0 +1,70 @@
+//! `X-Features` negotiation. Experimental response fields ship behind a
+//! feature name and are only computed and serialized for requests that list
+//! it, in the header (comma-separated) or a `features` body field, so strict
+//! clients that reject unknown fields never see them. Names this server
+//! doesn't know are ignored: a client can ask for a feature before every
+//! server it talks to has it. Responses echo the features they honored.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+
+pub const FEATURES_HEADER: HeaderName = HeaderName::from_static("x-features");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `clusters` of a search response: k-means label of each result
+    Clusters,
+    /// `centrality` of a search response: weighted degree of each result
+    Centrality,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 2] = [Feature::Clusters, Feature::Centrality];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Clusters => "clusters",
+            Feature::Centrality => "centrality",
+        }
+    }
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// The features a request opted into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Features(u32);
+
+impl Features {
+    /// Union of the `X-Features` header and the body's `features`.
+    pub fn from_request(headers: &HeaderMap, body: &[String]) -> Self {
+        let header = headers
+            .get_all(FEATURES_HEADER)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','));
+        let mut features = Self::default();
+        for name in header.chain(body.iter().map(String::as_str)) {
+            let name = name.trim();
+            if let Some(feature) = Feature::ALL.into_iter().find(|f| f.name().eq_ignore_ascii_case(name)) {
+                features.0 |= feature.bit();
+            }
+        }
+        features
+    }
+
+    pub fn has(self, feature: Feature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Comma-separated names, for the response header.
+    pub fn header_value(self) -> HeaderValue {
+        let names: Vec<&str> = Feature::ALL.into_iter().filter(|f| self.has(*f)).map(Feature::name).collect();
+        HeaderValue::from_str(&names.join(", ")).expect("feature names are ASCII")
+    }
+}
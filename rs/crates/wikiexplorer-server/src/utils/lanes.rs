@@ -0,0 +1,15 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+use wikiexplorer_core::search::lanes::{in_lane, Lane};
+
+/// Paths served in the interactive lane; everything else (research graphs,
+/// exports, jobs, watches) is batch.
+const INTERACTIVE_PATHS: &[&str] = &["/api/related"];
+
+/// Middleware tagging interactive requests so the pipeline can prioritize them.
+pub async fn assign_lane(request: Request, next: Next) -> Response {
+    if INTERACTIVE_PATHS.contains(&request.uri().path()) {
+        in_lane(Lane::Interactive, next.run(request)).await
+    } else {
+        next.run(request).await
+    }
+}
@@ -0,0 +1,243 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use sqlx::SqliteConnection;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use crate::utils::runtime_metrics::{counter, gauge};
+use wikiexplorer_core::utils::time::unix_now;
+
+/// `PRAGMA auto_vacuum` value for incremental mode
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+/// A DB without incremental auto-vacuum gets one full `VACUUM` (switching it
+/// to incremental) once this share of its pages is free
+const FULL_VACUUM_FREE_SHARE: f64 = 0.25;
+
+/// Periodic upkeep of the DBs the server writes to: the user DB (sessions,
+/// watches, suggestion stats) and the active metadata DB of every corpus (edge
+/// cache, thumbnails, search log). Refreshes planner statistics and returns
+/// free pages to the filesystem so long-running deployments don't slow down or
+/// grow without bound.
+pub struct Maintenance {
+    runs: AtomicU64,
+    errors: AtomicU64,
+    reclaimed_bytes: AtomicU64,
+    last: Mutex<Option<MaintenanceRun>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct MaintenanceRun {
+    pub finished_at: u64,
+    pub duration_ms: u64,
+    pub reclaimed_bytes: u64,
+    /// The user DB, then each corpus' metadata DB
+    pub databases: Vec<DbMaintenance>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct DbMaintenance {
+    /// `user`, or the name of the corpus whose metadata DB this is
+    pub name: String,
+    /// `ANALYZE` (no statistics yet) or `PRAGMA optimize`
+    pub analyze: &'static str,
+    /// `incremental`, `full` (a `VACUUM` switching the DB to incremental
+    /// auto-vacuum) or `none`
+    pub vacuum: &'static str,
+    pub reclaimed_bytes: u64,
+    /// Free pages left after the run
+    pub freelist_pages: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct MaintenanceStats {
+    pub enabled: bool,
+    pub runs: u64,
+    pub reclaimed_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<MaintenanceRun>,
+}
+
+impl Maintenance {
+    pub fn new() -> Self {
+        Self {
+            runs: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            reclaimed_bytes: AtomicU64::new(0),
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn stats(&self, enabled: bool) -> MaintenanceStats {
+        MaintenanceStats {
+            enabled,
+            runs: self.runs.load(Ordering::Relaxed),
+            reclaimed_bytes: self.reclaimed_bytes.load(Ordering::Relaxed),
+            last_run: self.last.lock().clone(),
+        }
+    }
+
+    fn record(&self, run: MaintenanceRun) {
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        let failed = run.databases.iter().filter(|db| db.error.is_some()).count();
+        self.errors.fetch_add(failed as u64, Ordering::Relaxed);
+        self.reclaimed_bytes.fetch_add(run.reclaimed_bytes, Ordering::Relaxed);
+        *self.last.lock() = Some(run);
+    }
+
+    /// Prometheus exposition of the counters and the last run's free pages per DB.
+    pub fn render_prometheus(&self, out: &mut String) {
+        counter(out, "wikiexplorer_maintenance_runs_total", "DB maintenance runs", self.runs.load(Ordering::Relaxed));
+        counter(
+            out,
+            "wikiexplorer_maintenance_errors_total",
+            "DBs whose maintenance failed",
+            self.errors.load(Ordering::Relaxed),
+        );
+        counter(
+            out,
+            "wikiexplorer_maintenance_reclaimed_bytes_total",
+            "Bytes returned to the filesystem by vacuuming",
+            self.reclaimed_bytes.load(Ordering::Relaxed),
+        );
+        let Some(last) = self.last.lock().clone() else {
+            return;
+        };
+        gauge(
+            out,
+            "wikiexplorer_maintenance_last_run_timestamp_seconds",
+            "When the last maintenance run finished",
+            last.finished_at as f64,
+        );
+        gauge(
+            out,
+            "wikiexplorer_maintenance_last_duration_seconds",
+            "How long the last maintenance run took",
+            last.duration_ms as f64 / 1000.0,
+        );
+        let _ = writeln!(out, "# HELP wikiexplorer_db_freelist_pages Free pages left after the last maintenance run");
+        let _ = writeln!(out, "# TYPE wikiexplorer_db_freelist_pages gauge");
+        for db in &last.databases {
+            let _ = writeln!(out, "wikiexplorer_db_freelist_pages{{db=\"{}\"}} {}", db.name, db.freelist_pages);
+        }
+    }
+}
+
+/// Background task: every `MAINTENANCE_INTERVAL_SECS` (0 disables) analyzes the
+/// user DB and each corpus' metadata DB and frees up to
+/// `MAINTENANCE_VACUUM_PAGES` pages in each. Skipped while the metadata DB is
+/// unhealthy. Also drops expired entries from the query store.
+pub fn spawn_maintenance(state: Arc<AppState>) {
+    if state.config.maintenance_interval_secs == 0 {
+        return;
+    }
+    let interval = Duration::from_secs(state.config.maintenance_interval_secs);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if !state.db_health.is_healthy() {
+                continue;
+            }
+
+            let started = Instant::now();
+            // Pools as of now: a reload swapping a metadata DB mid-run leaves the old one done
+            let targets = std::iter::once(("user".to_string(), state.user_db()))
+                .chain(state.all_corpora().into_iter().map(|corpus| (corpus.name.clone(), corpus.db.clone())));
+            let mut databases = Vec::new();
+            for (name, pool) in targets {
+                let mut db = DbMaintenance {
+                    name,
+                    analyze: "PRAGMA optimize",
+                    vacuum: "none",
+                    reclaimed_bytes: 0,
+                    freelist_pages: 0,
+                    error: None,
+                };
+                let result = match pool.acquire().await {
+                    Ok(mut conn) => run_once(&mut conn, state.config.maintenance_vacuum_pages, &mut db).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => info!(
+                        "✓ DB maintenance ({}): {}, {} vacuum, reclaimed {} bytes ({} free pages left)",
+                        db.name, db.analyze, db.vacuum, db.reclaimed_bytes, db.freelist_pages
+                    ),
+                    Err(e) => {
+                        warn!("⚠ DB maintenance ({}) failed: {}", db.name, e);
+                        db.error = Some(e.to_string());
+                    }
+                }
+                databases.push(db);
+            }
+
+            let run = MaintenanceRun {
+                finished_at: unix_now(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                reclaimed_bytes: databases.iter().map(|db| db.reclaimed_bytes).sum(),
+                databases,
+            };
+            state.maintenance.record(run);
+
+            if let Some(store) = state.search_engine.query_store() {
+                match store.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("✓ Query store: purged {} expired embeddings", purged),
+                    Err(e) => warn!("⚠ Query store purge failed: {}", e),
+                }
+            }
+        }
+    });
+}
+
+async fn run_once(conn: &mut SqliteConnection, vacuum_pages: u64, db: &mut DbMaintenance) -> Result<(), sqlx::Error> {
+    // `PRAGMA optimize` only re-analyzes tables it has statistics for
+    let has_stats: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'sqlite_stat1'")
+        .fetch_one(&mut *conn)
+        .await?;
+    if has_stats == 0 {
+        db.analyze = "ANALYZE";
+        sqlx::query("ANALYZE").execute(&mut *conn).await?;
+    } else {
+        sqlx::query("PRAGMA optimize").execute(&mut *conn).await?;
+    }
+
+    let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(&mut *conn).await?;
+    let page_count: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(&mut *conn).await?;
+    let free_before: i64 = sqlx::query_scalar("PRAGMA freelist_count").fetch_one(&mut *conn).await?;
+    let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum").fetch_one(&mut *conn).await?;
+
+    db.freelist_pages = free_before;
+    if free_before == 0 {
+        return Ok(());
+    }
+    if auto_vacuum == AUTO_VACUUM_INCREMENTAL {
+        // Bounded so one run never holds the write lock for long
+        db.vacuum = "incremental";
+        sqlx::query(&format!("PRAGMA incremental_vacuum({})", vacuum_pages))
+            .execute(&mut *conn)
+            .await?;
+    } else if free_before as f64 >= page_count as f64 * FULL_VACUUM_FREE_SHARE {
+        // incremental_vacuum is a no-op without auto-vacuum, which only a
+        // VACUUM can turn on for an existing DB
+        db.vacuum = "full";
+        sqlx::query("PRAGMA auto_vacuum = INCREMENTAL").execute(&mut *conn).await?;
+        sqlx::query("VACUUM").execute(&mut *conn).await?;
+    } else {
+        return Ok(());
+    }
+    let free_after: i64 = sqlx::query_scalar("PRAGMA freelist_count").fetch_one(&mut *conn).await?;
+
+    db.freelist_pages = free_after;
+    db.reclaimed_bytes = ((free_before - free_after).max(0) * page_size) as u64;
+    Ok(())
+}
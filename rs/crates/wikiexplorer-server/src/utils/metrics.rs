@@ -0,0 +1,24 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use wikiexplorer_core::utils::metrics::metrics;
+use wikiexplorer_core::utils::slo::slo_tracker;
+
+/// Middleware recording latency and status of every request, also per route
+/// for routes with an SLO.
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let route = request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string());
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    let is_error = response.status().is_server_error();
+    let latency_ms = started.elapsed().as_millis() as u64;
+    metrics().record_request(latency_ms, is_error);
+    if let Some(route) = route {
+        slo_tracker().record(&route, latency_ms, is_error);
+    }
+    response
+}
@@ -0,0 +1,16 @@
+pub mod api_error;
+pub mod api_version;
+pub mod cors;
+pub mod db_health;
+pub mod features;
+pub mod lanes;
+pub mod maintenance;
+pub mod metrics;
+pub mod problem;
+pub mod rate_limit;
+pub mod request_log;
+pub mod retention;
+pub mod runtime_metrics;
+pub mod slo_alerts;
+pub mod user_db;
+pub mod wikimedia;
@@ -0,0 +1,93 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Path, Request},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{Json, Response},
+};
+use serde_json::{json, Value};
+
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::utils::errors::{AppError, PROBLEM_JSON, PROBLEM_TYPE_BASE};
+
+/// Error bodies are small; anything bigger passes through untouched
+const MAX_PROBLEM_BODY: usize = 64 * 1024;
+
+/// The request's ID, put in the request extensions by `log_requests`
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Completes `AppError` problem details (RFC 7807) with the request path as
+/// `instance` and the `request_id`, and negotiates the media type: clients whose
+/// `Accept` names `application/json` but not `application/problem+json` get the
+/// same body as `application/json`.
+pub async fn problem_details(request: Request, next: Next) -> Response {
+    let wants_problem = accepts_problem_json(request.headers());
+    let request_id = request.extensions().get::<RequestId>().map(|id| id.0.clone());
+    let instance = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    let is_problem = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|ct| ct.as_bytes().starts_with(PROBLEM_JSON.as_bytes()));
+    if !is_problem {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_PROBLEM_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let Ok(Value::Object(mut problem)) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    problem.insert("instance".to_string(), instance.into());
+    if let Some(id) = request_id {
+        problem.insert("request_id".to_string(), id.into());
+    }
+
+    if !wants_problem {
+        parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    }
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&problem).unwrap_or_default();
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(header::ACCEPT).and_then(|h| h.to_str().ok()) else {
+        return true;
+    };
+    let accept = accept.to_ascii_lowercase();
+    accept.contains(PROBLEM_JSON) || !accept.contains("application/json")
+}
+
+/// What each problem `type` means: (kind, title, status, description)
+const PROBLEM_TYPES: &[(&str, &str, u16, &str)] = &[
+    ("bad-request", "Bad Request", 400, "The request is malformed or a parameter is out of range; `detail` says which."),
+    ("unauthorized", "Unauthorized", 401, "An admin endpoint was called without a valid `Authorization: Bearer` token."),
+    ("not-found", "Not Found", 404, "The session, article, task or other resource named in the request does not exist."),
+    ("cancelled", "Cancelled", 409, "The request or task was cancelled before it finished; retrying may succeed."),
+    ("rate-limited", "Too Many Requests", 429, "Too many requests from this client; retry after `retry_after_secs`."),
+    ("internal", "Internal Server Error", 500, "An unexpected server error; the server log holds it under the request ID."),
+    ("database", "Database Error", 500, "The metadata or user database failed; usually transient."),
+    ("vector-index", "Vector Index Error", 500, "The vector index failed to search or reconstruct vectors."),
+    ("model", "ML Model Error", 500, "The embedding model failed to encode the query; usually transient."),
+    ("upstream", "Upstream Unavailable", 502, "An external API (Wikimedia) failed or is unreachable; retrying may succeed."),
+    ("timeout", "Timed Out", 504, "Part of the request exceeded its deadline; retrying may succeed."),
+];
+
+/// `GET /problems/{kind}`: the documentation the problem `type` URIs point at.
+pub async fn problem_type_handler(Path(kind): Path<String>) -> Result<Json<Value>, ApiError> {
+    let Some(&(kind, title, status, description)) = PROBLEM_TYPES.iter().find(|(k, ..)| *k == kind) else {
+        return Err(AppError::NotFound(format!("No problem type '{}'", kind)).into());
+    };
+    Ok(Json(json!({
+        "type": format!("{}{}", PROBLEM_TYPE_BASE, kind),
+        "title": title,
+        "status": status,
+        "description": description,
+    })))
+}
@@ -0,0 +1,243 @@
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::utils::api_error::ApiError;
+use wikiexplorer_core::config::get_config;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// Paths that are never limited (load balancer probes)
+const EXEMPT_PATHS: &[&str] = &["/api/health"];
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// In-memory token buckets, one per client IP. Each bucket holds up to
+/// `RATE_LIMIT_PER_MIN` tokens and refills continuously at that rate.
+pub struct RateLimiter {
+    per_minute: f64,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token for `ip`, or returns the seconds until one is available.
+    pub fn check(&self, ip: IpAddr) -> Result<(), u64> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        let rate = self.per_minute / 60.0;
+
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets.entry(ip).or_insert(Bucket { tokens: self.per_minute, updated: now });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(self.per_minute);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64)
+        }
+    }
+
+    /// Drops buckets idle for a minute; they've refilled and equal a fresh one.
+    fn cleanup(&self) {
+        self.buckets.lock().retain(|_, b| b.updated.elapsed() < Duration::from_secs(60));
+    }
+}
+
+static LIMITER: OnceLock<Option<RateLimiter>> = OnceLock::new();
+
+/// The process-wide limiter, or None when `RATE_LIMIT_PER_MIN=0`.
+pub fn rate_limiter() -> Option<&'static RateLimiter> {
+    LIMITER
+        .get_or_init(|| {
+            let per_minute = get_config().rate_limit_per_min;
+            (per_minute > 0).then(|| RateLimiter::new(per_minute))
+        })
+        .as_ref()
+}
+
+/// Periodically forgets idle clients so the map doesn't grow without bound.
+pub fn spawn_cleanup() {
+    let Some(limiter) = rate_limiter() else { return };
+    info!("✓ Rate limiting enabled ({} requests/min per client)", limiter.per_minute);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+            limiter.cleanup();
+        }
+    });
+}
+
+/// Middleware answering 429 with `Retry-After` once a client's budget is spent.
+pub async fn rate_limit(request: Request, next: Next) -> Response {
+    let Some(limiter) = rate_limiter() else {
+        return next.run(request).await;
+    };
+    if is_exempt(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match client_ip(&request) {
+        Some(ip) => match limiter.check(ip) {
+            Ok(()) => next.run(request).await,
+            Err(retry_after) => ApiError(AppError::RateLimited(retry_after)).into_response(),
+        },
+        None => next.run(request).await,
+    }
+}
+
+fn is_exempt(path: &str) -> bool {
+    EXEMPT_PATHS.contains(&path)
+}
+
+/// The peer address, or with `TRUST_PROXY` set the `X-Forwarded-For` entry
+/// added by the outermost of `TRUSTED_PROXY_HOPS` proxies. Entries further
+/// left come from the client and can be forged, so they are never used.
+pub(crate) fn client_ip(request: &Request) -> Option<IpAddr> {
+    let peer = request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+    client_ip_from(request.headers(), peer)
+}
+
+/// [`client_ip`] for handlers, from the request headers and the peer address.
+pub(crate) fn client_ip_from(headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+    let config = get_config();
+    resolve_client_ip(headers, peer, config.trust_proxy, config.trusted_proxy_hops)
+}
+
+fn resolve_client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trust_proxy: bool, hops: usize) -> Option<IpAddr> {
+    if trust_proxy {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| forwarded_client(h, hops));
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    peer
+}
+
+/// The `hops`-th address from the right of an `X-Forwarded-For` list.
+fn forwarded_client(header: &str, hops: usize) -> Option<IpAddr> {
+    header.rsplit(',').nth(hops.max(1) - 1).and_then(|ip| ip.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(raw: &str) -> IpAddr {
+        raw.parse().unwrap()
+    }
+
+    fn forwarded_for(raw: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(raw).unwrap());
+        headers
+    }
+
+    #[test]
+    fn buckets_refill_at_the_configured_rate() {
+        let limiter = RateLimiter::new(60);
+        let client = ip("192.0.2.1");
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check_at(client, start).is_ok());
+        }
+        assert_eq!(limiter.check_at(client, start), Err(1));
+
+        // One token a second
+        assert!(limiter.check_at(client, start + Duration::from_millis(1500)).is_ok());
+        assert_eq!(limiter.check_at(client, start + Duration::from_millis(1500)), Err(1));
+        // A long pause refills the bucket, but not past its size
+        let later = start + Duration::from_secs(3600);
+        for _ in 0..60 {
+            assert!(limiter.check_at(client, later).is_ok());
+        }
+        assert!(limiter.check_at(client, later).is_err());
+    }
+
+    #[test]
+    fn retry_after_counts_the_seconds_to_the_next_token() {
+        let limiter = RateLimiter::new(6);
+        let client = ip("192.0.2.1");
+        let start = Instant::now();
+        for _ in 0..6 {
+            limiter.check_at(client, start).unwrap();
+        }
+        assert_eq!(limiter.check_at(client, start), Err(10));
+        assert_eq!(limiter.check_at(client, start + Duration::from_secs(4)), Err(6));
+        assert!(limiter.check_at(client, start + Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn clients_have_their_own_buckets() {
+        let limiter = RateLimiter::new(1);
+        let start = Instant::now();
+        assert!(limiter.check_at(ip("192.0.2.1"), start).is_ok());
+        assert!(limiter.check_at(ip("192.0.2.1"), start).is_err());
+        assert!(limiter.check_at(ip("192.0.2.2"), start).is_ok());
+    }
+
+    #[test]
+    fn health_probes_are_exempt() {
+        assert!(is_exempt("/api/health"));
+        assert!(!is_exempt("/api/health/extra"));
+        assert!(!is_exempt("/api/search"));
+    }
+
+    #[test]
+    fn clients_behind_a_trusted_proxy_are_keyed_by_forwarded_address() {
+        let proxy = Some(ip("10.0.0.1"));
+        let a = resolve_client_ip(&forwarded_for("198.51.100.7"), proxy, true, 1);
+        let b = resolve_client_ip(&forwarded_for("198.51.100.8"), proxy, true, 1);
+        assert_eq!(a, Some(ip("198.51.100.7")));
+        assert_eq!(b, Some(ip("198.51.100.8")));
+
+        // Behind one proxy, a client can't exhaust another's budget
+        let limiter = RateLimiter::new(1);
+        let start = Instant::now();
+        assert!(limiter.check_at(a.unwrap(), start).is_ok());
+        assert!(limiter.check_at(a.unwrap(), start).is_err());
+        assert!(limiter.check_at(b.unwrap(), start).is_ok());
+    }
+
+    #[test]
+    fn forged_forwarded_entries_are_ignored() {
+        let proxy = Some(ip("10.0.0.1"));
+        // The client prepended an address; the proxy appended the real one
+        let headers = forwarded_for("203.0.113.66, 198.51.100.7");
+        assert_eq!(resolve_client_ip(&headers, proxy, true, 1), Some(ip("198.51.100.7")));
+        // Two proxies: the outer one's entry is second from the right
+        let headers = forwarded_for("203.0.113.66, 198.51.100.7, 10.0.0.2");
+        assert_eq!(resolve_client_ip(&headers, proxy, true, 2), Some(ip("198.51.100.7")));
+        // Without TRUST_PROXY the header is ignored, and a bad entry falls back to the peer
+        assert_eq!(resolve_client_ip(&headers, proxy, false, 1), proxy);
+        assert_eq!(resolve_client_ip(&forwarded_for("unknown"), proxy, true, 1), proxy);
+        assert_eq!(resolve_client_ip(&HeaderMap::new(), proxy, true, 1), proxy);
+    }
+}
@@ -0,0 +1,86 @@
+use axum::{
+    body::HttpBody,
+    extract::{Query, Request},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::{info, info_span, Instrument};
+use uuid::Uuid;
+
+use crate::utils::problem::RequestId;
+use crate::utils::rate_limit::client_ip;
+use wikiexplorer_core::utils::anonymize::anonymize_ip;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_LOGGED_QUERY_CHARS: usize = 100;
+
+/// Per-request logging: assigns a request ID (or keeps a sane incoming
+/// `X-Request-Id`), runs the request inside a span carrying it, logs one
+/// structured line on completion and echoes the ID in the response. Handlers
+/// and inner middleware find the ID as a [`RequestId`] extension. Bodies are
+/// never read here, only their sizes logged (None when streamed).
+pub async fn log_requests(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let fingerprint = fingerprint(&request);
+    let query = query_text(&request);
+    let request_bytes = request.body().size_hint().exact();
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let span = info_span!("request", request_id = %request_id);
+    let started = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+
+    info!(
+        parent: &span,
+        method = %method,
+        path = %path,
+        status = response.status().as_u16(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        query = query.as_deref().unwrap_or(""),
+        request_bytes,
+        response_bytes = response.body().size_hint().exact(),
+        fingerprint = fingerprint.as_deref().unwrap_or(""),
+        "request completed"
+    );
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// SHA-256 of "ip|user-agent", truncated; identifies a client without logging
+/// its IP. The IP is anonymized first (IP_ANONYMIZATION).
+fn fingerprint(request: &Request) -> Option<String> {
+    let ip = client_ip(request)?;
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    let digest = Sha256::digest(format!("{}|{}", anonymize_ip(ip), user_agent).as_bytes());
+    Some(digest.iter().take(8).map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The search text of a GET request (`?q=`/`?query=`).
+fn query_text(request: &Request) -> Option<String> {
+    let Query(params) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    params.get("q").or_else(|| params.get("query")).map(|query| truncate(query))
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_LOGGED_QUERY_CHARS).collect()
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use wikiexplorer_core::utils::time::unix_now;
+
+const RETENTION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// User DB tables holding search text or per-user activity, with their timestamp column
+const PURGED: &[(&str, &str)] = &[("suggestion_stats", "last_seen"), ("watch_updates", "detected_at")];
+
+/// Background task: once a day deletes stored search text and user activity
+/// older than `PII_RETENTION_DAYS` (0 keeps everything): the tables above, the
+/// text of stored query embeddings and expired persisted responses (which
+/// repeat their query). Watches themselves are kept, they belong to their user
+/// until deleted.
+pub fn spawn_retention(state: Arc<AppState>) {
+    let days = state.config.pii_retention_days;
+    if days == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(RETENTION_INTERVAL).await;
+            purge(&state, days).await;
+        }
+    });
+    info!("✓ Search text and user activity kept for {} days", days);
+}
+
+async fn purge(state: &AppState, days: u64) {
+    let now = unix_now();
+    let cutoff = now.saturating_sub(days * 24 * 60 * 60) as i64;
+    let pool = state.user_db();
+
+    for (table, column) in PURGED {
+        let sql = format!("DELETE FROM {} WHERE {} < ?", table, column);
+        match sqlx::query(&sql).bind(cutoff).execute(&pool).await {
+            Ok(done) if done.rows_affected() > 0 => {
+                info!("✓ Retention: purged {} rows from {}", done.rows_affected(), table)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("⚠ Retention purge of {} failed: {}", table, e),
+        }
+    }
+
+    if let Some(store) = state.search_engine.query_store() {
+        match store.forget_text(cutoff as u64).await {
+            Ok(0) => {}
+            Ok(n) => info!("✓ Retention: forgot the text of {} stored queries", n),
+            Err(e) => warn!("⚠ Retention purge of the query store failed: {}", e),
+        }
+    }
+
+    // Entries are only served until they expire; the rows outlive that
+    if state.config.response_cache_persist {
+        let result = sqlx::query("DELETE FROM response_cache WHERE expires_at < ?")
+            .bind(now as i64)
+            .execute(&state.db())
+            .await;
+        match result {
+            Ok(done) if done.rows_affected() > 0 => {
+                info!("✓ Retention: purged {} expired cached responses", done.rows_affected())
+            }
+            Ok(_) => {}
+            Err(e) => warn!("⚠ Retention purge of response_cache failed: {}", e),
+        }
+    }
+}
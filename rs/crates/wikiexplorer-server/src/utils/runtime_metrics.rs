@@ -0,0 +1,158 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tracing::info;
+
+use wikiexplorer_core::utils::metrics::metrics;
+
+/// How often the probes below run
+const PROBE_EVERY: Duration = Duration::from_millis(250);
+
+/// Histogram upper bounds, in seconds
+const DELAY_BUCKETS: [f64; 9] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0];
+
+/// Executor health for `/metrics` (PROMETHEUS_METRICS). Two probes measure what
+/// a request would feel, without tokio_unstable:
+/// - scheduler delay: how late a timer-woken task gets polled, which grows when
+///   workers are stuck in synchronous calls (model inference, FAISS)
+/// - blocking delay: how long a `spawn_blocking` closure waits for a thread,
+///   which grows once the blocking pool is saturated
+///
+/// Builds with `--cfg tokio_unstable` also export the blocking pool gauges and
+/// worker busy time from tokio's own counters.
+pub struct RuntimeStats {
+    scheduler_delay: Histogram,
+    blocking_delay: Histogram,
+}
+
+struct Histogram {
+    // Cumulative counts per DELAY_BUCKETS bound, then +Inf
+    buckets: [AtomicU64; DELAY_BUCKETS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self { buckets: std::array::from_fn(|_| AtomicU64::new(0)), sum_micros: AtomicU64::new(0) }
+    }
+
+    fn observe(&self, value: Duration) {
+        let secs = value.as_secs_f64();
+        for (bound, bucket) in DELAY_BUCKETS.iter().zip(&self.buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.buckets[DELAY_BUCKETS.len()].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(value.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, bucket) in DELAY_BUCKETS.iter().zip(&self.buckets) {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, bucket.load(Ordering::Relaxed));
+        }
+        let count = self.buckets[DELAY_BUCKETS.len()].load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6);
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+static RUNTIME_STATS: OnceLock<RuntimeStats> = OnceLock::new();
+
+pub fn runtime_stats() -> &'static RuntimeStats {
+    RUNTIME_STATS.get_or_init(|| RuntimeStats {
+        scheduler_delay: Histogram::new(),
+        blocking_delay: Histogram::new(),
+    })
+}
+
+/// Starts the probes; a no-op unless PROMETHEUS_METRICS is set.
+pub fn spawn_runtime_probes(enabled: bool) {
+    if !enabled {
+        return;
+    }
+    let stats = runtime_stats();
+
+    tokio::spawn(async move {
+        loop {
+            let expected = Instant::now() + PROBE_EVERY;
+            tokio::time::sleep_until(expected.into()).await;
+            stats.scheduler_delay.observe(Instant::now().saturating_duration_since(expected));
+
+            let queued = Instant::now();
+            if let Ok(waited) = tokio::task::spawn_blocking(move || queued.elapsed()).await {
+                stats.blocking_delay.observe(waited);
+            }
+        }
+    });
+    info!("✓ Runtime probes running every {:?}", PROBE_EVERY);
+}
+
+/// Prometheus text exposition of request counters and runtime metrics.
+pub fn render_prometheus() -> String {
+    let mut out = String::new();
+    let requests = metrics();
+    gauge(&mut out, "wikiexplorer_uptime_seconds", "Seconds since the server started", requests.uptime_secs() as f64);
+    counter(&mut out, "wikiexplorer_requests_total", "HTTP requests served", requests.total_requests());
+    counter(&mut out, "wikiexplorer_errors_total", "HTTP requests that failed", requests.total_errors());
+
+    let runtime = Handle::current().metrics();
+    gauge(&mut out, "tokio_workers", "Runtime worker threads", runtime.num_workers() as f64);
+    gauge(&mut out, "tokio_alive_tasks", "Tasks spawned and not yet finished", runtime.num_alive_tasks() as f64);
+    gauge(
+        &mut out,
+        "tokio_global_queue_depth",
+        "Tasks waiting in the runtime's injection queue",
+        runtime.global_queue_depth() as f64,
+    );
+
+    #[cfg(tokio_unstable)]
+    {
+        let blocking = runtime.num_blocking_threads();
+        let idle = runtime.num_idle_blocking_threads();
+        gauge(&mut out, "tokio_blocking_threads", "Threads in the blocking pool", blocking as f64);
+        gauge(
+            &mut out,
+            "tokio_blocking_busy_threads",
+            "Blocking pool threads running a task",
+            blocking.saturating_sub(idle) as f64,
+        );
+        gauge(
+            &mut out,
+            "tokio_blocking_queue_depth",
+            "Blocking tasks waiting for a thread",
+            runtime.blocking_queue_depth() as f64,
+        );
+        counter(&mut out, "tokio_spawned_tasks_total", "Tasks spawned since startup", runtime.spawned_tasks_count());
+        let busy: Duration = (0..runtime.num_workers()).map(|w| runtime.worker_total_busy_duration(w)).sum();
+        let _ = writeln!(out, "# HELP tokio_worker_busy_seconds_total Time workers spent polling tasks, summed");
+        let _ = writeln!(out, "# TYPE tokio_worker_busy_seconds_total counter");
+        let _ = writeln!(out, "tokio_worker_busy_seconds_total {}", busy.as_secs_f64());
+    }
+
+    let stats = runtime_stats();
+    stats.scheduler_delay.render(
+        &mut out,
+        "tokio_scheduler_delay_seconds",
+        "How late a timer-woken probe task was polled",
+    );
+    stats.blocking_delay.render(
+        &mut out,
+        "tokio_blocking_delay_seconds",
+        "How long a spawn_blocking probe waited for a thread",
+    );
+    out
+}
+
+pub(crate) fn gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge\n{} {}", name, help, name, name, value);
+}
+
+pub(crate) fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter\n{} {}", name, help, name, name, value);
+}
@@ -0,0 +1,88 @@
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use wikiexplorer_core::utils::slo::{slo_tracker, BurnAlert, SloStatus};
+
+const CHECK_EVERY: Duration = Duration::from_secs(60);
+/// Short, so an unreachable webhook can't stall the next check
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = concat!("WikiExplorer-SLO-alerts/", env!("CARGO_PKG_VERSION"));
+
+/// Background task (SLO_WEBHOOK_URL): checks burn rates every minute and POSTs
+/// a JSON message when a route starts burning its error budget or recovers.
+/// The `text` field makes it readable as a Slack or Mattermost webhook as is.
+pub fn spawn_slo_alerts(state: Arc<AppState>) {
+    let Some(url) = state.config.slo_webhook_url.clone() else {
+        return;
+    };
+    if slo_tracker().is_empty() {
+        warn!("⚠ SLO_WEBHOOK_URL is set but SLOS is empty, no alerts will be sent");
+        return;
+    }
+    let client = match webhook_client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("⚠ SLO alerts disabled, HTTP client failed: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let mut firing: HashMap<String, BurnAlert> = HashMap::new();
+        loop {
+            tokio::time::sleep(CHECK_EVERY).await;
+            for status in slo_tracker().statuses() {
+                let previous = firing.get(&status.route).copied();
+                if status.alert == previous {
+                    continue;
+                }
+                match status.alert {
+                    Some(alert) => firing.insert(status.route.clone(), alert),
+                    None => firing.remove(&status.route),
+                };
+
+                let body = alert_message(&status, previous);
+                match client.post(&url).json(&body).send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => info!("✓ SLO alert sent for {}", status.route),
+                    Err(e) => warn!("⚠ SLO alert for {} failed: {}", status.route, e),
+                }
+            }
+        }
+    });
+    info!("✓ SLO burn alerts enabled");
+}
+
+/// HTTP client for the alert webhook; its own, not the Wikimedia one with that
+/// API's user agent and timeouts. Redirects aren't followed.
+fn webhook_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(WEBHOOK_TIMEOUT)
+        .timeout(WEBHOOK_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+}
+
+fn alert_message(status: &SloStatus, previous: Option<BurnAlert>) -> serde_json::Value {
+    let burn = |window: &str| status.windows.iter().find(|w| w.window == window).map_or(0.0, |w| w.burn_rate);
+    let text = match status.alert {
+        Some(alert) => format!(
+            "🔥 {} is burning its {}% error budget ({:?}): {:.1}x over 1h, {:.1}x over 6h",
+            status.route,
+            status.target_percent,
+            alert,
+            burn("1h"),
+            burn("6h")
+        ),
+        None => format!("✅ {} recovered from a {:?} budget burn", status.route, previous.unwrap_or(BurnAlert::Slow)),
+    };
+    json!({
+        "text": text,
+        "state": if status.alert.is_some() { "firing" } else { "resolved" },
+        "slo": status,
+    })
+}
@@ -0,0 +1,22 @@
+//! The user DB (USER_DB_PATH): data users create through the API, kept apart
+//! from the metadata DB so `/api/admin/reload-index` swapping the latter never
+//! takes sessions, watches or learned suggestion stats with it.
+
+use sqlx::sqlite::{SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+use tracing::info;
+
+/// Opens (creating if needed) the user DB at `path`. New files get
+/// incremental auto-vacuum, so maintenance can hand freed pages back.
+pub async fn open(path: &str) -> Result<SqlitePool, sqlx::Error> {
+    if let Some(dir) = std::path::Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", path))?
+        .create_if_missing(true)
+        .auto_vacuum(SqliteAutoVacuum::Incremental)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new().max_connections(4).connect_with(options).await?;
+    info!("✓ User DB opened at {}", path);
+    Ok(pool)
+}
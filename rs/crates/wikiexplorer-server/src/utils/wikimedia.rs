@@ -0,0 +1,12 @@
+use std::time::Duration;
+
+pub use wikiexplorer_core::images::encode_title;
+
+/// Wikimedia asks API clients to identify themselves
+const USER_AGENT: &str = concat!("WikiExplorer/", env!("CARGO_PKG_VERSION"));
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client for Wikimedia APIs.
+pub fn client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder().user_agent(USER_AGENT).timeout(REQUEST_TIMEOUT).build()
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::routes::search::related_articles;
+use crate::state::AppState;
+use wikiexplorer_core::utils::anonymize::query_digest;
+use wikiexplorer_core::utils::errors::AppError;
+
+/// A saved query whose top-k is re-checked after every index refresh.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Watch {
+    pub id: String,
+    pub user_id: String,
+    pub query: String,
+    pub k: i64,
+    /// JSON array of the article IDs in the last known top-k
+    #[serde(skip)]
+    pub last_results: String,
+    pub created_at: i64,
+    pub last_run_at: Option<i64>,
+}
+
+/// An article that entered a watch's top-k.
+#[derive(Debug, Serialize, FromRow)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct WatchUpdate {
+    /// Row ID, the final tie-break of the updates listing
+    #[serde(skip)]
+    pub id: i64,
+    pub watch_id: String,
+    pub query: String,
+    pub article_id: i64,
+    pub title: String,
+    pub rank: i64,
+    pub detected_at: i64,
+}
+
+pub async fn ensure_watch_tables(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS watches (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            query TEXT NOT NULL,
+            k INTEGER NOT NULL,
+            last_results TEXT NOT NULL DEFAULT '[]',
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            last_run_at INTEGER
+        )",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS watch_updates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            watch_id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            article_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            rank INTEGER NOT NULL,
+            detected_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_watch_updates_user ON watch_updates (user_id, detected_at)")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Re-runs one watch and records articles that weren't in its previous top-k.
+/// The first run only records the baseline. Returns the number of new articles.
+pub async fn run_watch(state: &AppState, watch: &Watch) -> Result<usize, AppError> {
+    let pool = state.user_db();
+    let corpus = state.primary_corpus();
+    let mut results = related_articles(state, &corpus, &watch.query, watch.k as usize).await?;
+    // Public IDs, so the baseline survives an index rebuild
+    results.iter_mut().for_each(|r| r.id = corpus.index.ids.public(r.id));
+
+    let previous: HashSet<i64> = serde_json::from_str::<Vec<i64>>(&watch.last_results)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let first_run = watch.last_run_at.is_none();
+
+    let mut tx = pool.begin().await?;
+    let mut new_articles = 0;
+    if !first_run {
+        for (rank, result) in results.iter().enumerate() {
+            if previous.contains(&result.id) || result.title.is_empty() {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO watch_updates (watch_id, user_id, article_id, title, rank) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&watch.id)
+            .bind(&watch.user_id)
+            .bind(result.id)
+            .bind(&*result.title)
+            .bind(rank as i64 + 1)
+            .execute(&mut *tx)
+            .await?;
+            new_articles += 1;
+        }
+    }
+
+    let ids: Vec<i64> = results.iter().map(|r| r.id).collect();
+    sqlx::query("UPDATE watches SET last_results = ?, last_run_at = strftime('%s', 'now') WHERE id = ?")
+        .bind(serde_json::to_string(&ids).unwrap_or_else(|_| "[]".to_string()))
+        .bind(&watch.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(new_articles)
+}
+
+/// Background job: whenever the index generation changes (admin reload), re-runs
+/// every watch. Checked every `WATCH_CHECK_SECS`.
+pub fn spawn_watch_runner(state: Arc<AppState>) {
+    let interval = Duration::from_secs(state.config.watch_check_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut seen_generation = state.search_engine.index_generation();
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let generation = state.search_engine.index_generation();
+            if generation == seen_generation {
+                continue;
+            }
+
+            match run_all(&state).await {
+                Ok((watches, new_articles)) => {
+                    info!("✓ Watches: re-ran {} after index refresh, {} new articles", watches, new_articles);
+                    seen_generation = generation;
+                }
+                // Retried on the next tick
+                Err(e) => warn!("⚠ Watch run failed: {}", e),
+            }
+        }
+    });
+}
+
+async fn run_all(state: &AppState) -> Result<(usize, usize), AppError> {
+    let pool = state.user_db();
+    ensure_watch_tables(&pool).await?;
+    let watches = sqlx::query_as::<_, Watch>(
+        "SELECT id, user_id, query, k, last_results, created_at, last_run_at FROM watches",
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut new_articles = 0;
+    for watch in &watches {
+        match run_watch(state, watch).await {
+            Ok(n) => new_articles += n,
+            Err(e) => warn!("⚠ Watch {} (query {}) failed: {}", watch.id, query_digest(&watch.query), e),
+        }
+    }
+    Ok((watches.len(), new_articles))
+}
@@ -0,0 +1,198 @@
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+use crate::utils::wikimedia::{self, encode_title};
+use wikiexplorer_core::utils::errors::AppError;
+use wikiexplorer_core::utils::time::unix_now;
+
+/// Where a served summary came from, reported in `X-Cache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    /// Expired, but upstream confirmed it unchanged (304 for our ETag)
+    Revalidated,
+    Miss,
+    /// Expired and upstream unreachable: served anyway so previews work offline
+    Stale,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "hit",
+            CacheStatus::Revalidated => "revalidated",
+            CacheStatus::Miss => "miss",
+            CacheStatus::Stale => "stale",
+        }
+    }
+}
+
+/// Writes between two trims of the cache to SUMMARY_CACHE_MAX_ENTRIES
+const TRIM_EVERY: u64 = 64;
+
+/// Read-through cache in front of the Wikimedia REST summary API
+/// (`WIKI_SUMMARY_API`). Bodies are stored verbatim in the user DB's
+/// `summary_cache` with their ETag and refetched conditionally after
+/// SUMMARY_CACHE_TTL_SECS; upstream 404s are cached as rows without a body.
+pub struct SummaryProxy {
+    client: Option<reqwest::Client>,
+    base_url: String,
+    ttl_secs: u64,
+    max_entries: u64,
+    writes: AtomicU64,
+}
+
+struct CachedSummary {
+    /// None for a title upstream has no summary of
+    body: Option<String>,
+    etag: Option<String>,
+    fetched_at: i64,
+}
+
+impl SummaryProxy {
+    pub fn new(base_url: &str, ttl_secs: u64, max_entries: u64) -> Self {
+        let client = wikimedia::client()
+            .map_err(|e| warn!("⚠ Summary proxy offline, HTTP client failed: {}", e))
+            .ok();
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ttl_secs,
+            max_entries,
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    /// JSON summary of `title` (underscores, as stored in `articles`).
+    pub async fn summary(&self, pool: &SqlitePool, title: &str) -> Result<(String, CacheStatus), AppError> {
+        let cached = self.load(pool, title).await;
+        let now = unix_now() as i64;
+        if let Some(entry) = &cached {
+            if entry.fetched_at + self.ttl_secs as i64 > now {
+                return match &entry.body {
+                    Some(body) => Ok((body.clone(), CacheStatus::Hit)),
+                    None => Err(not_found(title)),
+                };
+            }
+        }
+
+        let fetched = self.fetch(title, cached.as_ref().and_then(|c| c.etag.as_deref())).await;
+        match (fetched, cached) {
+            (Ok(Fetched::NotModified), Some(CachedSummary { body: Some(body), .. })) => {
+                let _ = sqlx::query("UPDATE summary_cache SET fetched_at = ? WHERE title = ?")
+                    .bind(now)
+                    .bind(title)
+                    .execute(pool)
+                    .await;
+                Ok((body, CacheStatus::Revalidated))
+            }
+            (Ok(Fetched::NotModified), _) => Err(AppError::Upstream("304 without a cached copy".to_string())),
+            (Ok(Fetched::Body { body, etag }), _) => {
+                self.store(pool, title, Some(&body), etag.as_deref(), now).await;
+                Ok((body, CacheStatus::Miss))
+            }
+            (Ok(Fetched::Missing), _) => {
+                self.store(pool, title, None, None, now).await;
+                Err(not_found(title))
+            }
+            (Err(AppError::Upstream(e)), Some(entry)) => {
+                debug!("Serving stale summary of {}: {}", title, e);
+                entry.body.map(|body| (body, CacheStatus::Stale)).ok_or_else(|| not_found(title))
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    async fn store(&self, pool: &SqlitePool, title: &str, body: Option<&str>, etag: Option<&str>, now: i64) {
+        let stored = sqlx::query("INSERT OR REPLACE INTO summary_cache (title, body, etag, fetched_at) VALUES (?, ?, ?, ?)")
+            .bind(title)
+            .bind(body)
+            .bind(etag)
+            .bind(now)
+            .execute(pool)
+            .await;
+        if let Err(e) = stored {
+            warn!("Summary cache write failed: {:?}", e);
+            return;
+        }
+        if self.writes.fetch_add(1, Ordering::Relaxed).is_multiple_of(TRIM_EVERY) {
+            let trimmed = sqlx::query(
+                "DELETE FROM summary_cache WHERE title IN (
+                    SELECT title FROM summary_cache ORDER BY fetched_at DESC LIMIT -1 OFFSET ?
+                )",
+            )
+            .bind(self.max_entries as i64)
+            .execute(pool)
+            .await;
+            if let Err(e) = trimmed {
+                warn!("Summary cache trim failed: {:?}", e);
+            }
+        }
+    }
+
+    async fn load(&self, pool: &SqlitePool, title: &str) -> Option<CachedSummary> {
+        sqlx::query_as::<_, (Option<String>, Option<String>, i64)>(
+            "SELECT body, etag, fetched_at FROM summary_cache WHERE title = ?",
+        )
+        .bind(title)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .map(|(body, etag, fetched_at)| CachedSummary { body, etag, fetched_at })
+    }
+
+    async fn fetch(&self, title: &str, etag: Option<&str>) -> Result<Fetched, AppError> {
+        let Some(client) = &self.client else {
+            return Err(AppError::Upstream("no HTTP client".to_string()));
+        };
+        let mut request = client.get(format!("{}/{}", self.base_url, encode_title(title)));
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send().await.map_err(|e| AppError::Upstream(e.to_string()))?;
+        match response.status() {
+            StatusCode::NOT_MODIFIED if etag.is_some() => Ok(Fetched::NotModified),
+            StatusCode::NOT_FOUND => Ok(Fetched::Missing),
+            status if status.is_success() => {
+                let etag = response.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let body = response.text().await.map_err(|e| AppError::Upstream(e.to_string()))?;
+                Ok(Fetched::Body { body, etag })
+            }
+            status => Err(AppError::Upstream(format!("summary API returned {}", status))),
+        }
+    }
+}
+
+enum Fetched {
+    NotModified,
+    Body { body: String, etag: Option<String> },
+    /// Upstream 404
+    Missing,
+}
+
+fn not_found(title: &str) -> AppError {
+    AppError::NotFound(format!("No Wikipedia summary for '{}'", title))
+}
+
+/// In the user DB, so cached previews survive metadata swaps.
+pub async fn ensure_summary_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS summary_cache (
+            title TEXT PRIMARY KEY,
+            body TEXT,
+            etag TEXT,
+            fetched_at INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_summary_cache_fetched ON summary_cache (fetched_at)")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
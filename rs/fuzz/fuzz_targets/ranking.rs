@@ -0,0 +1,90 @@
+//! Query normalization, title matching and score ordering with arbitrary
+//! unicode queries/titles and arbitrary (NaN, infinite, negative) signals.
+//!
+//!     cargo +nightly fuzz run ranking
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use wikiexplorer_core::search::pipeline::sort_by_score;
+use wikiexplorer_core::search::ranking::{
+    apply_custom_signals, calculate_multisignal_score, calculate_title_match_score, normalize_pagerank,
+    normalize_backlinks, normalize_pageviews, title_match_spans,
+};
+use wikiexplorer_core::SearchResult;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    title: String,
+    query: String,
+    similarity: f32,
+    pagerank: f64,
+    pageviews: f64,
+    pageview_count: Option<i64>,
+    backlink_count: Option<i64>,
+    custom_signals: Vec<(f64, f64)>,
+    scores: Vec<f64>,
+}
+
+fuzz_target!(|input: Input| {
+    // Same cleanup the search handler applies to every query
+    let query = input.query.replace('_', " ");
+
+    // Highlights: sorted, non-overlapping, non-empty [start, end) spans inside the title (UTF-16)
+    let title_len = input.title.encode_utf16().count();
+    let spans = title_match_spans(&input.title, &query);
+    for span in &spans {
+        assert!(span[0] < span[1] && span[1] <= title_len, "bad span {:?} for {:?}", span, input.title);
+    }
+    for pair in spans.windows(2) {
+        assert!(pair[0][1] < pair[1][0], "overlapping spans {:?}", spans);
+    }
+
+    let title_score = calculate_title_match_score(&input.title, &query);
+    assert!((0.0..=1.0).contains(&title_score), "title score {}", title_score);
+    if input.title.trim().is_empty() {
+        assert_eq!(title_score, 0.0);
+    }
+
+    let views = normalize_pageviews(input.pageview_count);
+    assert!((0.0..=1.0).contains(&views), "pageview score {}", views);
+
+    assert!(normalize_pagerank(Some(input.pagerank)).is_finite());
+    assert!((0.0..=1.0).contains(&normalize_backlinks(input.backlink_count)));
+
+    // Degenerate signals (zero, negative, NaN, infinite) still give a finite, non-negative score
+    let score = calculate_multisignal_score(input.similarity, input.pagerank, input.pageviews, input.backlink_count, &input.title, &query);
+    assert!(score.is_finite() && score >= 0.0, "score {} for {:?}", score, input);
+    let with_custom = apply_custom_signals(score, &input.custom_signals);
+    assert!(with_custom.is_finite(), "custom signals gave {} for {:?}", with_custom, input.custom_signals);
+
+    // Ranking order: never panics, best first, NaN last
+    let mut results: Vec<SearchResult> = input
+        .scores
+        .iter()
+        .enumerate()
+        .map(|(i, &score_float)| SearchResult {
+            id: i as i64,
+            title: Default::default(),
+            score: (score_float * 100.0) as i32,
+            score_float,
+            corpus: None,
+            highlights: vec![],
+            thumbnail_url: None,
+            debug: None,
+            backfilled: false,
+            signals: Default::default(),
+        })
+        .collect();
+    sort_by_score(&mut results);
+    let ranked: Vec<f64> = results.iter().map(|r| r.score_float).collect();
+    // NaN sorts as -inf, so the two may interleave at the bottom
+    let first_nan = ranked.iter().position(|s| s.is_nan()).unwrap_or(ranked.len());
+    assert!(
+        ranked[first_nan..].iter().all(|s| s.is_nan() || *s == f64::NEG_INFINITY),
+        "NaN ranked above a score: {:?}",
+        ranked
+    );
+    assert!(ranked[..first_nan].windows(2).all(|w| w[0] >= w[1]), "not descending: {:?}", ranked);
+});
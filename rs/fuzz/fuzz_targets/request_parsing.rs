@@ -0,0 +1,45 @@
+//! Request fragments parsed from arbitrary JSON: filter policy overrides,
+//! category filters and FAISS search parameters.
+//!
+//!     cargo +nightly fuzz run request_parsing
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wikiexplorer_core::categories::{normalize_category, CategoryFilter};
+use wikiexplorer_core::search::engine::SearchParams;
+use wikiexplorer_core::search::filter_policy::{default_policy, FilterOverrides};
+
+fuzz_target!(|data: &[u8]| {
+    // `filters` of a search request, applied to titles taken from the same input
+    if let Ok((overrides, titles)) = serde_json::from_slice::<(FilterOverrides, Vec<String>)>(data) {
+        // Invalid patterns must be a BadRequest, not a panic
+        if let Ok(policy) = default_policy().with_overrides(&overrides) {
+            for title in &titles {
+                let _ = policy.verdict(title);
+            }
+        }
+    }
+
+    // `include_categories` / `exclude_categories` against an article's categories
+    if let Ok((include, exclude, categories)) = serde_json::from_slice::<(Vec<String>, Vec<String>, Vec<String>)>(data) {
+        let filter = CategoryFilter::new(&include, &exclude);
+        let categories: Vec<String> = categories.iter().map(|c| normalize_category(c)).collect();
+        let allowed = filter.allows(&categories);
+        if filter.is_empty() {
+            assert!(allowed, "empty filter rejected {:?}", categories);
+        }
+        for category in &filter.exclude {
+            if categories.contains(category) {
+                assert!(!allowed, "excluded category {:?} allowed", category);
+            }
+        }
+    }
+
+    // `search_params` round-trips
+    if let Ok(params) = serde_json::from_slice::<SearchParams>(data) {
+        let encoded = serde_json::to_vec(&params).expect("serializable");
+        let decoded: SearchParams = serde_json::from_slice(&encoded).expect("round trip");
+        assert_eq!(params, decoded);
+    }
+});
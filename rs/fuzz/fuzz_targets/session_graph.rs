@@ -0,0 +1,33 @@
+//! Saved graphs with adversarial node/edge arrays: duplicate and negative IDs,
+//! edges to missing nodes, self-loops, NaN and infinite scores.
+//!
+//!     cargo +nightly fuzz run session_graph
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+use wikiexplorer_core::graph::Graph;
+use wikiexplorer_core::sessions::thumbnail::describe;
+use wikiexplorer_core::sessions::SessionGraph;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok((graph, pageviews)) = serde_json::from_slice::<(SessionGraph, HashMap<i64, i64>)>(data) else {
+        return;
+    };
+
+    let ids = graph.node_ids();
+    let g = Graph::new(&ids, graph.semantic_edges());
+    let components = g.connected_components();
+    assert_eq!(components.len(), g.nodes().len());
+    for &id in g.nodes() {
+        assert!(g.degree(id) < g.nodes().len(), "node {} has more neighbors than nodes", id);
+        let _ = g.bridge_score(id, &components);
+    }
+
+    let thumbnail = describe(&graph, &pageviews);
+    assert_eq!(thumbnail.node_count, g.nodes().len());
+    assert!(thumbnail.top_nodes.len() <= thumbnail.node_count);
+
+    let _ = graph.to_export_graph();
+});